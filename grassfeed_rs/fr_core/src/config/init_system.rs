@@ -1,7 +1,9 @@
 use crate::config::configmanager::ConfigManager;
 use crate::controller::browserpane::BrowserPane;
+use crate::controller::contentdownloader::default_enclosure_folder;
 use crate::controller::contentdownloader::Downloader;
 use crate::controller::contentdownloader::CONF_DOWNLOADER_THREADS;
+use crate::controller::contentdownloader::CONF_ENCLOSURE_FOLDER;
 use crate::controller::contentdownloader::DOWNLOADER_THREADS_DEFAULT;
 use crate::controller::contentlist::ContentList;
 use crate::controller::guiprocessor::GuiProcessor;
@@ -9,6 +11,7 @@ use crate::controller::sourcetree::SourceTreeController;
 use crate::controller::subscriptionmove::SubscriptionMove;
 use crate::controller::timer::ITimer;
 use crate::controller::timer::Timer;
+use crate::controller::undo::UndoHistory;
 use crate::db::errors_repo;
 use crate::db::errors_repo::ErrorRepo;
 use crate::db::icon_repo;
@@ -46,6 +49,7 @@ pub fn start(conf: GrassFeederConfig) -> AppContext {
     appcontext.build::<OpmlReader>();
     appcontext.build::<ErrorRepo>();
     appcontext.build::<Downloader>();
+    appcontext.build::<UndoHistory>();
     appcontext.build::<SourceTreeController>();
     appcontext.build::<SubscriptionMove>();
     appcontext.build::<BrowserPane>();
@@ -115,6 +119,10 @@ pub fn create_system_config(gf_conf: &GrassFeederConfig) -> HashMap<String, Stri
         CONF_DOWNLOADER_THREADS.to_string(),
         DOWNLOADER_THREADS_DEFAULT.to_string(),
     );
+    ret.insert(
+        CONF_ENCLOSURE_FOLDER.to_string(),
+        default_enclosure_folder(),
+    );
     ret
 }
 
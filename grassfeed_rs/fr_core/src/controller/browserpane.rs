@@ -1,12 +1,20 @@
 use crate::config::configmanager::ConfigManager;
 use crate::controller::contentlist::ContentList;
+use crate::controller::htmlsanitizer::parse_tag_list;
+use crate::controller::htmlsanitizer::sanitize_html;
+use crate::controller::htmlsanitizer::SanitizeResult;
+use crate::db::message::decompress;
+use crate::db::message::Enclosure;
 use crate::db::messages_repo::IMessagesRepo;
 use crate::db::messages_repo::MessagesRepo;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
+use crate::downloader::util::resolve_base_url;
+use crate::downloader::util::resolve_relative_urls;
 use crate::ui_select::gui_context::GuiContext;
 use crate::util;
 use crate::util::string_escape_url;
+use crate::util::text_diff_html;
 use context::appcontext::AppContext;
 use context::BuildConfig;
 use context::Buildable;
@@ -24,6 +32,9 @@ use std::rc::Weak;
 const BROWSER_ZOOM_LIMIT_UPPER: u32 = 300;
 const BROWSER_ZOOM_LIMIT_LOWER: u32 = 20;
 
+pub const CONF_BROWSER_REMOTE_CONTENT: &str = "BrowserRemoteContent";
+pub const CONF_BROWSER_ALLOWED_TAGS: &str = "BrowserAllowedTags";
+
 const WEBVIEW_REGULAR: u8 = 0;
 const WEBVIEW_PRELOAD: u8 = 1;
 
@@ -41,6 +52,12 @@ pub trait IBrowserPane {
     fn get_last_selected_link(&self) -> String;
     fn display_short_help(&self);
     fn set_browser_zoom(&self, cmd: BrowserZoomCommand);
+    /// remote images are loaded for all subscriptions
+    fn set_conf_remote_content(&mut self, allow: bool);
+    /// comma separated,  empty for the default list
+    fn set_conf_allowed_tags(&mut self, tags: String);
+    /// the next display of that message loads the remote images
+    fn allow_remote_content_once(&self, msg_id: isize);
 }
 
 #[derive(Debug)]
@@ -61,6 +78,7 @@ pub struct BrowserPane {
     config: Config,
     last_selected_link_text: RefCell<String>,
     last_zoom_level_percent: RefCell<u32>,
+    remote_content_msg_id: RefCell<isize>,
 }
 
 impl BrowserPane {
@@ -79,6 +97,7 @@ impl BrowserPane {
             feedcontents_w: Weak::new(),
             subscriptionrepo_r: (*ac).get_rc::<SubscriptionRepo>().unwrap(),
             last_zoom_level_percent: RefCell::new(100),
+            remote_content_msg_id: RefCell::new(-1),
         }
     }
 
@@ -177,6 +196,125 @@ impl BrowserPane {
             .borrow()
             .update_label(LABEL_BROWSER_MSG_CATEGORIES);
     }
+
+    /// list of all media attachments, shown below the content
+    fn enclosures_block(enclosures: &[Enclosure], allow_remote: bool) -> String {
+        let items: Vec<String> = enclosures
+            .iter()
+            .map(|encl| {
+                let filename = encl.url.rsplit('/').next().unwrap_or(&encl.url).to_string();
+                let mut details: Vec<String> = Vec::default();
+                if !encl.mime_type.is_empty() {
+                    details.push(encl.mime_type.clone());
+                }
+                if encl.length > 0 {
+                    details.push(format!("{:.1} MB", encl.length as f64 / 1_000_000.0));
+                }
+                if encl.duration > 0 {
+                    details.push(format!(
+                        "{}:{:02}:{:02}",
+                        encl.duration / 3600,
+                        (encl.duration / 60) % 60,
+                        encl.duration % 60
+                    ));
+                }
+                let thumbnail = if encl.thumbnail_url.is_empty() || !allow_remote {
+                    String::default()
+                } else {
+                    format!(
+                        "<img src=\"{}\" height=\"48\"/> ",
+                        string_escape_url(encl.thumbnail_url.clone())
+                    )
+                };
+                format!(
+                    "<li>{}<a href=\"{}\">{}</a> ({})</li>",
+                    thumbnail,
+                    string_escape_url(encl.url.clone()),
+                    string_escape_url(filename),
+                    details.join(", ")
+                )
+            })
+            .collect();
+        format!(
+            "<hr/><div class=\"enclosures\"><b>{}</b><ul>{}</ul></div>",
+            t!("BROWSER_ENCLOSURES"),
+            items.join("")
+        )
+    }
+
+    fn sanitize(&self, content: &str, allow_remote: bool) -> SanitizeResult {
+        sanitize_html(
+            content,
+            &parse_tag_list(&self.config.allowed_tags),
+            allow_remote,
+        )
+    }
+
+    /// Messages stored before the urls were resolved at download
+    fn resolve_urls(content: &str, link: &str, website_url: &str) -> String {
+        match resolve_base_url(&[link, website_url]) {
+            Some(base) => resolve_relative_urls(content, &base),
+            None => content.to_string(),
+        }
+    }
+
+    fn remote_allowed(&self, subs_remote_content: bool, msg_id: isize) -> bool {
+        self.config.remote_content
+            || subs_remote_content
+            || *self.remote_content_msg_id.borrow() == msg_id
+    }
+
+    /// What the sanitizer held back, shown above the content
+    fn sanitize_info_block(san: &SanitizeResult) -> Option<String> {
+        let mut parts: Vec<String> = Vec::default();
+        if san.blocked_images > 0 {
+            parts.push(format!(
+                "{} {}",
+                san.blocked_images,
+                t!("BROWSER_SANITIZE_BLOCKED_IMAGES")
+            ));
+        }
+        if san.trackers > 0 {
+            parts.push(format!(
+                "{} {}",
+                san.trackers,
+                t!("BROWSER_SANITIZE_TRACKERS")
+            ));
+        }
+        if san.removed_elements > 0 {
+            parts.push(format!(
+                "{} {}",
+                san.removed_elements,
+                t!("BROWSER_SANITIZE_REMOVED")
+            ));
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        let hint = if san.blocked_images > 0 {
+            format!(" &mdash; {}", t!("BROWSER_SANITIZE_HINT"))
+        } else {
+            String::default()
+        };
+        Some(format!(
+            "<div class=\"sanitize-info\"><small>{}{}</small></div><hr/>",
+            parts.join(", "),
+            hint
+        ))
+    }
+
+    /// For changed messages: the difference to the previous revision, shown above the content
+    fn revision_diff_block(&self, msg_id: isize, content: &str) -> Option<String> {
+        let revisions = (*self.messagesrepo_r).borrow().get_revisions(msg_id);
+        let prev_rev = revisions.first()?;
+        let diff = text_diff_html(&decompress(&prev_rev.content_text), content);
+        Some(format!(
+            "<div class=\"revision-diff\"><p><b>{} {}</b></p><p>{}</p></div><hr/>",
+            t!("BROWSER_REVISION_DIFF"),
+            util::db_time_to_display(prev_rev.replaced_date),
+            diff
+        ))
+    }
 }
 
 impl IBrowserPane for BrowserPane {
@@ -201,6 +339,8 @@ impl IBrowserPane for BrowserPane {
         let mut author = String::default();
         let mut categories = String::default();
         let mut su_title = String::default();
+        let mut subs_remote_content = false;
+        let mut website_url = String::default();
         if let Some(triplet) = co_au_ca_su {
             (content, author, categories) = triplet;
         }
@@ -209,7 +349,10 @@ impl IBrowserPane for BrowserPane {
             .get_by_index(message.subscription_id)
         {
             su_title = sub_e.display_name;
+            subs_remote_content = sub_e.remote_content;
+            website_url = sub_e.website_url;
         }
+        content = Self::resolve_urls(&content, &message.link, &website_url);
         let mut display = title;
         if let Some(_pos) = display.find("http") {
             display = display.split("http").next().unwrap().to_string();
@@ -217,6 +360,21 @@ impl IBrowserPane for BrowserPane {
         }
         self.last_selected_link_text.replace(message.link.clone()); //;
         let srcdate = util::db_time_to_display(message.entry_src_date);
+        if message.is_updated() {
+            if let Some(diff_block) = self.revision_diff_block(message.message_id, &content) {
+                content = format!("{diff_block}{content}");
+            }
+        }
+        let allow_remote = self.remote_allowed(subs_remote_content, message.message_id);
+        let san = self.sanitize(&content, allow_remote);
+        content = match Self::sanitize_info_block(&san) {
+            Some(info) => format!("{info}{}", san.html),
+            None => san.html,
+        };
+        let enclosures = message.get_enclosures();
+        if !enclosures.is_empty() {
+            content.push_str(&Self::enclosures_block(&enclosures, allow_remote));
+        }
         self.set_browser_contents_html(content, WEBVIEW_REGULAR);
         self.set_browser_info_area(display, message.link, srcdate, author, categories, su_title)
     }
@@ -232,10 +390,23 @@ impl IBrowserPane for BrowserPane {
         if o_msg.is_none() {
             return;
         }
+        let message = o_msg.unwrap();
         let mut content = String::default();
         if let Some(triplet) = co_au_ca_su {
             (content, _, _) = triplet;
         }
+        let (subs_remote_content, website_url) = (self.subscriptionrepo_r)
+            .borrow()
+            .get_by_index(message.subscription_id)
+            .map(|se| (se.remote_content, se.website_url))
+            .unwrap_or_default();
+        let content = Self::resolve_urls(&content, &message.link, &website_url);
+        let content = self
+            .sanitize(
+                &content,
+                self.remote_allowed(subs_remote_content, message.message_id),
+            )
+            .html;
         // trace!(            "browser_pre_load : {msg_id}  length_of_content:{} ",            content.len()        );
         self.set_browser_contents_html(content, WEBVIEW_PRELOAD);
     }
@@ -294,6 +465,25 @@ impl IBrowserPane for BrowserPane {
             (*self.gui_updater).borrow().update_web_view(0);
         }
     }
+
+    fn set_conf_remote_content(&mut self, allow: bool) {
+        self.config.remote_content = allow;
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_BROWSER_REMOTE_CONTENT, allow.to_string());
+    }
+
+    fn set_conf_allowed_tags(&mut self, tags: String) {
+        let tags = parse_tag_list(&tags).join(",");
+        self.config.allowed_tags.clone_from(&tags);
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_BROWSER_ALLOWED_TAGS, tags);
+    }
+
+    fn allow_remote_content_once(&self, msg_id: isize) {
+        self.remote_content_msg_id.replace(msg_id);
+    }
 }
 
 impl Buildable for BrowserPane {
@@ -305,6 +495,10 @@ impl Buildable for BrowserPane {
         } else {
             bp.config.browser_bg = 64;
         }
+        bp.config.remote_content = conf.get_bool(CONF_BROWSER_REMOTE_CONTENT);
+        if let Some(tags) = conf.get(CONF_BROWSER_ALLOWED_TAGS) {
+            bp.config.allowed_tags = tags;
+        }
         bp
     }
 }
@@ -319,6 +513,10 @@ impl StartupWithAppContext for BrowserPane {
 #[derive(Default, Clone, Debug)]
 pub struct Config {
     pub browser_bg: u8,
+    /// load remote images of all subscriptions
+    pub remote_content: bool,
+    /// comma separated,  empty for the default list of the sanitizer
+    pub allowed_tags: String,
 }
 
 //------------------------------------------------------
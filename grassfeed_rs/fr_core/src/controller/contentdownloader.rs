@@ -11,21 +11,37 @@ use crate::controller::timer::Timer;
 use crate::db::errors_repo::ErrorRepo;
 use crate::db::icon_repo::IIconRepo;
 use crate::db::icon_repo::IconRepo;
+use crate::db::messages_repo::IMessagesRepo;
 use crate::db::messages_repo::MessagesRepo;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
+use crate::downloader::apply_rules::ApplyRulesInner;
+use crate::downloader::apply_rules::ApplyRulesStart;
+use crate::downloader::article::ArticleInner;
+use crate::downloader::article::ArticleStart;
 use crate::downloader::browserdrag::BrowserEvalStart;
 use crate::downloader::browserdrag::DragInner;
 use crate::downloader::comprehensive::ComprStart;
 use crate::downloader::comprehensive::ComprehensiveInner;
 use crate::downloader::db_clean::CleanerInner;
 use crate::downloader::db_clean::CleanerStart;
+use crate::downloader::enclosures::subscription_folder_name;
+use crate::downloader::enclosures::EnclosureInner;
+use crate::downloader::enclosures::EnclosureStart;
 use crate::downloader::icons::IconInner;
 use crate::downloader::icons::IconLoadStart;
 use crate::downloader::launch_web::LaunchInner;
 use crate::downloader::launch_web::LaunchWebBrowserStart;
+use crate::downloader::linkcleaner::LinkCleaner;
 use crate::downloader::messages::FetchInner;
 use crate::downloader::messages::FetchStart;
+use crate::downloader::messages::WebSubPushStart;
+use crate::downloader::scraper::ScraperSelectors;
+use crate::downloader::websub::WebSubInner;
+use crate::downloader::websub::WebSubListener;
+use crate::downloader::websub::WebSubStart;
+use crate::downloader::websub::WEBSUB_RENEW_MARGIN_S;
+use crate::util::timestamp_now;
 use crate::util::StepResult;
 use crate::web::httpfetcher::HttpFetcher;
 use crate::web::WebFetcherType;
@@ -50,12 +66,17 @@ use std::time::Duration;
 
 pub static KEEPRUNNING: AtomicBool = AtomicBool::new(true);
 pub const CONF_DOWNLOADER_THREADS: &str = "DownloaderThreads";
+pub const CONF_ENCLOSURE_FOLDER: &str = "EnclosureDownloadFolder";
+pub const CONF_WEBSUB_PORT: &str = "WebSubPort";
+pub const CONF_WEBSUB_CALLBACK_URL: &str = "WebSubCallbackUrl";
+pub const CONF_LINK_CLEANING: &str = "LinkCleaning";
+pub const CONF_LINK_CLEAN_PARAMS: &str = "LinkCleanParams";
 
 pub const DOWNLOADER_THREADS_DEFAULT: u8 = 2;
 pub const DOWNLOADER_LOOP_DELAY_S: u8 = 1;
 pub const DOWNLOADER_LOOP_WAIT_MS: u64 = 100; // between downloader queue requests
 pub const DOWNLOADER_JOB_QUEUE: usize = 2000;
-pub const DLKIND_MAX: usize = 7;
+pub const DLKIND_MAX: usize = 12;
 
 pub trait IDownloader {
     fn shutdown(&mut self);
@@ -65,6 +86,8 @@ pub trait IDownloader {
     fn get_kind_list(&self) -> Vec<u8>;
     fn add_update_subscription(&self, f_source_repo_id: isize);
     fn new_feedsource_request(&self, fs_edit_url: &str);
+    /// web page without feed, for the preview in the new-subscription dialog
+    fn scraper_preview_request(&self, page_url: &str, selectors: ScraperSelectors);
     fn load_icon(&self, fs_id: isize, fs_url: String, old_icon_id: usize);
     fn cleanup_db(&self);
     /// size of queue, number of running threads
@@ -72,6 +95,17 @@ pub trait IDownloader {
     fn browser_drag_request(&self, dragged_url: &str);
     fn launch_webbrowser(&self, url: String, cl_id: isize, list_pos: u32);
     fn get_statistics(&self) -> [u32; DLKIND_MAX];
+    /// stores into a sub folder of the enclosure folder, resumes partial downloads
+    fn download_enclosure(&self, subs_id: isize, url: String);
+    fn set_conf_enclosure_folder(&mut self, folder: String);
+    /// fetches the link of the message and stores the main content of that page
+    fn extract_article(&self, msg_id: isize);
+    /// port 0 switches the push subscriptions off.  Callback base: the address the hubs reach us at
+    fn set_conf_websub(&mut self, port: u16, callback_base: String);
+    /// extra_params: comma separated names of tracking parameters, besides the default ones
+    fn set_conf_link_cleaning(&mut self, enabled: bool, extra_params: String);
+    /// runs the stored rules on all messages,  the content list gets  CJob::RulesApplied
+    fn apply_rules_to_existing(&self);
 }
 
 #[derive(Debug, PartialEq)]
@@ -83,6 +117,11 @@ pub enum DLJob {
     CleanDatabase(CleanerInner),
     BrowserDragEvaluation(DragInner),
     LaunchWebBrowser(LaunchInner),
+    EnclosureDownload(EnclosureInner),
+    ArticleExtraction(ArticleInner),
+    WebSubSubscribe(WebSubInner),
+    WebSubPush(FetchInner),
+    ApplyRules(ApplyRulesInner),
 }
 
 pub trait DLKind {
@@ -104,6 +143,11 @@ impl DLKind for DLJob {
             DLJob::CleanDatabase(_) => 4,
             DLJob::BrowserDragEvaluation(_) => 5,
             DLJob::LaunchWebBrowser(_) => 6,
+            DLJob::EnclosureDownload(_) => 7,
+            DLJob::ArticleExtraction(_) => 8,
+            DLJob::WebSubSubscribe(_) => 9,
+            DLJob::WebSubPush(_) => 10,
+            DLJob::ApplyRules(_) => 11,
         }
     }
 
@@ -121,6 +165,9 @@ impl DLKind for DLJob {
                 }
                 Downloader::host_from_url(&icon_inner.feed_url)
             }
+            DLJob::EnclosureDownload(enc_inner) => Downloader::host_from_url(&enc_inner.url),
+            DLJob::ArticleExtraction(art_inner) => Downloader::host_from_url(&art_inner.url),
+            DLJob::WebSubSubscribe(ws_inner) => Downloader::host_from_url(&ws_inner.hub),
             _ => None,
         }
     }
@@ -134,6 +181,11 @@ impl DLKind for DLJob {
             DLJob::CleanDatabase(_) => -3,
             DLJob::BrowserDragEvaluation(_) => -4,
             DLJob::LaunchWebBrowser(_) => -5,
+            DLJob::EnclosureDownload(inner) => inner.subs_id,
+            DLJob::ArticleExtraction(inner) => inner.subs_id,
+            DLJob::WebSubSubscribe(inner) => inner.subs_id,
+            DLJob::WebSubPush(inner) => inner.fs_repo_id,
+            DLJob::ApplyRules(_) => -6,
         }
     }
 }
@@ -153,6 +205,7 @@ pub struct Downloader {
     job_queue: Arc<RwLock<VecDeque<DLJob>>>,
     erro_repo: Rc<RefCell<ErrorRepo>>,
     call_statistic: RefCell<[u32; DLKIND_MAX]>,
+    websub_listener: Option<WebSubListener>,
 }
 
 impl Downloader {
@@ -179,6 +232,7 @@ impl Downloader {
             job_queue: Arc::new(RwLock::new(VecDeque::default())),
             erro_repo: err_repo,
             call_statistic: RefCell::new([0; DLKIND_MAX]),
+            websub_listener: None,
         }
     }
 
@@ -283,6 +337,22 @@ impl Downloader {
             DLJob::LaunchWebBrowser(i) => {
                 let _i = StepResult::start(Box::new(LaunchWebBrowserStart::new(i)));
             }
+            DLJob::EnclosureDownload(mut i) => {
+                i.thread_nr = proc_num;
+                let _i = StepResult::start(Box::new(EnclosureStart::new(i)));
+            }
+            DLJob::ArticleExtraction(i) => {
+                let _i = StepResult::start(Box::new(ArticleStart::new(i)));
+            }
+            DLJob::WebSubSubscribe(i) => {
+                let _i = StepResult::start(Box::new(WebSubStart::new(i)));
+            }
+            DLJob::WebSubPush(i) => {
+                let _i = StepResult::start(Box::new(WebSubPushStart::new(i)));
+            }
+            DLJob::ApplyRules(i) => {
+                let _i = StepResult::start(Box::new(ApplyRulesStart::new(i)));
+            }
         }
         let elapsedms = now.elapsed().as_millis();
         let _r = gp_sender.send(Job::DownloaderJobFinished(
@@ -296,6 +366,110 @@ impl Downloader {
         elapsedms as u64
     }
 
+    fn new_comprehensive_inner(
+        &self,
+        url: &str,
+        scraper: Option<ScraperSelectors>,
+    ) -> ComprehensiveInner {
+        let icon_repo =
+            IconRepo::new_by_connection((*self.iconrepo_r).borrow().get_ctx().get_connection());
+        ComprehensiveInner {
+            feed_url_edit: url.to_string(),
+            redirected_url: String::default(),
+            icon_url: String::default(),
+            iconrepo: icon_repo,
+            web_fetcher: self.web_fetcher.clone(),
+            download_error_happened: false,
+            icon_bytes: Vec::default(),
+            icon_id: -1,
+            sourcetree_job_sender: self.source_c_sender.as_ref().unwrap().clone(),
+            feed_homepage: String::default(),
+            feed_title: String::default(),
+            url_download_text: String::default(),
+            scraper,
+            scraper_preview: String::default(),
+            feed_candidates: Vec::default(),
+        }
+    }
+
+    /// The pushed content is processed by the downloader threads, like a fetched feed
+    fn start_websub_listener(&mut self) {
+        if self.config.websub_port == 0 {
+            return;
+        }
+        let subs_con = (*self.subscriptionrepo_r).borrow().get_connection();
+        let icon_con = (*self.iconrepo_r).borrow().get_ctx().get_connection();
+        let msg_con = (*self.messagesrepo).borrow().get_ctx().get_connection();
+        let err_con = (*self.erro_repo).borrow().get_connection();
+        let cjob_sender = self.contentlist_job_sender.as_ref().unwrap().clone();
+        let sjob_sender = self.source_c_sender.as_ref().unwrap().clone();
+        let web_fetcher = self.web_fetcher.clone();
+        let link_cleaner = self.config.link_cleaner();
+        let queue_a = self.job_queue.clone();
+        let on_push = move |subs_id: isize, content: String| {
+            let subscriptionrepo = SubscriptionRepo::by_existing_connection(subs_con.clone());
+            let url = match subscriptionrepo.get_by_index(subs_id) {
+                Some(se) => se.url,
+                None => return,
+            };
+            let inner = FetchInner {
+                fs_repo_id: subs_id,
+                url,
+                cjob_sender: cjob_sender.clone(),
+                subscriptionrepo,
+                iconrepo: IconRepo::new_by_connection(icon_con.clone()),
+                web_fetcher: web_fetcher.clone(),
+                download_error_happened: false,
+                sourcetree_job_sender: sjob_sender.clone(),
+                timestamp_created: 0,
+                messgesrepo: MessagesRepo::new_by_connection(msg_con.clone()),
+                download_text: content,
+                download_error_text: String::default(),
+                erro_repo: ErrorRepo::by_connection(err_con.clone()),
+                link_cleaner: link_cleaner.clone(),
+            };
+            (*queue_a)
+                .write()
+                .unwrap()
+                .push_back(DLJob::WebSubPush(inner));
+        };
+        let subs_repo = SubscriptionRepo::by_existing_connection(
+            (*self.subscriptionrepo_r).borrow().get_connection(),
+        );
+        match WebSubListener::start(self.config.websub_port, subs_repo, Box::new(on_push)) {
+            Ok(listener) => self.websub_listener = Some(listener),
+            Err(e) => {
+                warn!("WebSub listener not started: {}", e);
+            }
+        }
+    }
+
+    /// new hubs have no lease, so they are subscribed here as well
+    fn websub_check_leases(&self) {
+        if self.websub_listener.is_none() {
+            return;
+        }
+        let expiring = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_websub_expiring(timestamp_now() + WEBSUB_RENEW_MARGIN_S);
+        for se in expiring {
+            let inner = WebSubInner {
+                subs_id: se.subs_id,
+                hub: se.websub_hub,
+                topic: se.websub_topic,
+                secret: se.websub_secret,
+                callback_base: self.config.websub_callback_base.clone(),
+                port: self.config.websub_port,
+                web_fetcher: self.web_fetcher.clone(),
+                subscriptionrepo: SubscriptionRepo::by_existing_connection(
+                    (*self.subscriptionrepo_r).borrow().get_connection(),
+                ),
+                erro_repo: ErrorRepo::by_connection((*self.erro_repo).borrow().get_connection()),
+            };
+            self.add_to_queue(DLJob::WebSubSubscribe(inner));
+        }
+    }
+
     pub fn host_from_url(url: &String) -> Option<String> {
         match url::Url::parse(url) {
             Ok(parsed) => {
@@ -357,6 +531,7 @@ impl IDownloader for Downloader {
             download_text: String::default(),
             download_error_text: String::default(),
             erro_repo: errors_rep,
+            link_cleaner: self.config.link_cleaner(),
         };
         self.add_to_queue(DLJob::Feed(new_fetch_job));
     }
@@ -382,21 +557,12 @@ impl IDownloader for Downloader {
     }
 
     fn new_feedsource_request(&self, fs_edit_url: &str) {
-        let icon_repo =
-            IconRepo::new_by_connection((*self.iconrepo_r).borrow().get_ctx().get_connection());
-        let inner = ComprehensiveInner {
-            feed_url_edit: fs_edit_url.to_string(),
-            icon_url: String::default(),
-            iconrepo: icon_repo,
-            web_fetcher: self.web_fetcher.clone(),
-            download_error_happened: false,
-            icon_bytes: Vec::default(),
-            icon_id: -1,
-            sourcetree_job_sender: self.source_c_sender.as_ref().unwrap().clone(),
-            feed_homepage: String::default(),
-            feed_title: String::default(),
-            url_download_text: String::default(),
-        };
+        let inner = self.new_comprehensive_inner(fs_edit_url, None);
+        self.add_to_queue(DLJob::ComprehensiveFeed(inner));
+    }
+
+    fn scraper_preview_request(&self, page_url: &str, selectors: ScraperSelectors) {
+        let inner = self.new_comprehensive_inner(page_url, Some(selectors));
         self.add_to_queue(DLJob::ComprehensiveFeed(inner));
     }
 
@@ -414,7 +580,7 @@ impl IDownloader for Downloader {
         let icon_repo =
             IconRepo::new_by_connection((*self.iconrepo_r).borrow().get_ctx().get_connection());
         let errors_rep = ErrorRepo::by_connection((*self.erro_repo).borrow().get_connection());
-        let cleaner_i = CleanerInner::new(
+        let mut cleaner_i = CleanerInner::new(
             self.gp_job_sender.as_ref().unwrap().clone(),
             self.source_c_sender.as_ref().unwrap().clone(),
             subs_repo,
@@ -423,10 +589,18 @@ impl IDownloader for Downloader {
             msg_keep_count,
             errors_rep,
         );
+        cleaner_i.trash_retention_days = (*self.configmanager_r)
+            .borrow()
+            .get_val_int(SourceTreeController::CONF_TRASH_RETENTION_DAYS)
+            .unwrap_or(SourceTreeController::CONF_TRASH_RETENTION_DAYS_DEFAULT as isize)
+            as i32;
         self.add_to_queue(DLJob::CleanDatabase(cleaner_i));
     }
 
     fn shutdown(&mut self) {
+        if let Some(mut listener) = self.websub_listener.take() {
+            listener.stop();
+        }
         KEEPRUNNING.store(false, Ordering::Relaxed);
         self.add_to_queue(DLJob::None);
         while !self.joinhandles.is_empty() {
@@ -485,6 +659,7 @@ impl IDownloader for Downloader {
 
     fn launch_webbrowser(&self, url: String, cl_id: isize, list_pos: u32) {
         let cl_sender: Sender<CJob> = self.contentlist_job_sender.as_ref().unwrap().clone();
+        let url = self.config.link_cleaner().clean(&url);
         let inner = LaunchInner::new(url, cl_id, list_pos, cl_sender);
         self.add_to_queue(DLJob::LaunchWebBrowser(inner));
     }
@@ -492,6 +667,113 @@ impl IDownloader for Downloader {
     fn get_statistics(&self) -> [u32; DLKIND_MAX] {
         *self.call_statistic.borrow()
     }
+
+    fn download_enclosure(&self, subs_id: isize, url: String) {
+        let o_subscr = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        if o_subscr.is_none() {
+            warn!("download_enclosure: no subscription {}  ", subs_id);
+            return;
+        }
+        let subs = o_subscr.unwrap();
+        let target_folder = std::path::Path::new(&self.config.enclosure_folder)
+            .join(subscription_folder_name(subs_id, &subs.display_name))
+            .to_string_lossy()
+            .to_string();
+        let errors_rep = ErrorRepo::by_connection((*self.erro_repo).borrow().get_connection());
+        let inner = EnclosureInner::new(
+            subs_id,
+            url,
+            target_folder,
+            subs.enclosure_keep,
+            self.gp_job_sender.as_ref().unwrap().clone(),
+            errors_rep,
+        );
+        self.add_to_queue(DLJob::EnclosureDownload(inner));
+    }
+
+    fn set_conf_enclosure_folder(&mut self, folder: String) {
+        let folder = folder.trim().to_string();
+        if folder.is_empty() {
+            return;
+        }
+        self.config.enclosure_folder.clone_from(&folder);
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_ENCLOSURE_FOLDER, folder);
+    }
+
+    fn extract_article(&self, msg_id: isize) {
+        let o_msg = (*self.messagesrepo).borrow().get_by_index(msg_id);
+        if o_msg.is_none() {
+            warn!("extract_article: no message {}  ", msg_id);
+            return;
+        }
+        let msg = o_msg.unwrap();
+        if msg.link.is_empty() {
+            debug!("extract_article: message {} has no link", msg_id);
+            return;
+        }
+        let msgrepo = MessagesRepo::new_by_connection(
+            (*self.messagesrepo).borrow().get_ctx().get_connection(),
+        );
+        let errors_rep = ErrorRepo::by_connection((*self.erro_repo).borrow().get_connection());
+        let inner = ArticleInner::new(
+            msg_id,
+            msg.subscription_id,
+            msg.link,
+            self.web_fetcher.clone(),
+            msgrepo,
+            self.contentlist_job_sender.as_ref().unwrap().clone(),
+            errors_rep,
+        );
+        self.add_to_queue(DLJob::ArticleExtraction(inner));
+    }
+
+    fn apply_rules_to_existing(&self) {
+        let subs_repo = SubscriptionRepo::by_existing_connection(
+            (*self.subscriptionrepo_r).borrow().get_connection(),
+        );
+        let msgrepo = MessagesRepo::new_by_connection(
+            (*self.messagesrepo).borrow().get_ctx().get_connection(),
+        );
+        let inner = ApplyRulesInner::new(
+            subs_repo,
+            msgrepo,
+            self.contentlist_job_sender.as_ref().unwrap().clone(),
+            self.gp_job_sender.as_ref().unwrap().clone(),
+        );
+        self.add_to_queue(DLJob::ApplyRules(inner));
+    }
+
+    fn set_conf_websub(&mut self, port: u16, callback_base: String) {
+        let callback_base = callback_base.trim().to_string();
+        let port_changed = self.config.websub_port != port;
+        self.config.websub_port = port;
+        self.config.websub_callback_base.clone_from(&callback_base);
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_WEBSUB_PORT, port.to_string());
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_WEBSUB_CALLBACK_URL, callback_base);
+        if port_changed && self.is_running() {
+            if let Some(mut listener) = self.websub_listener.take() {
+                listener.stop();
+            }
+            self.start_websub_listener();
+        }
+    }
+
+    fn set_conf_link_cleaning(&mut self, enabled: bool, extra_params: String) {
+        self.config.link_cleaning = enabled;
+        self.config.link_clean_params.clone_from(&extra_params);
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_LINK_CLEANING, enabled.to_string());
+        (*self.configmanager_r)
+            .borrow()
+            .set_val(CONF_LINK_CLEAN_PARAMS, extra_params);
+    }
 }
 
 impl Buildable for Downloader {
@@ -503,6 +785,23 @@ impl Buildable for Downloader {
         } else {
             dl.config.num_downloader_threads = 1;
         }
+        if let Some(folder) = conf.get(CONF_ENCLOSURE_FOLDER) {
+            if !folder.is_empty() {
+                dl.config.enclosure_folder = folder;
+            }
+        }
+        if let Some(port) = conf.get_int(CONF_WEBSUB_PORT) {
+            dl.config.websub_port = port as u16;
+        }
+        if let Some(base) = conf.get(CONF_WEBSUB_CALLBACK_URL) {
+            dl.config.websub_callback_base = base;
+        }
+        if let Some(enabled) = conf.get(CONF_LINK_CLEANING) {
+            dl.config.link_cleaning = enabled.parse::<bool>().unwrap_or(true);
+        }
+        if let Some(params) = conf.get(CONF_LINK_CLEAN_PARAMS) {
+            dl.config.link_clean_params = params;
+        }
         dl
     }
 }
@@ -523,15 +822,21 @@ impl StartupWithAppContext for Downloader {
         let dl_r = ac.get_rc::<Downloader>().unwrap();
         (*timer_r)
             .borrow_mut()
-            .register(&TimerEvent::Shutdown, dl_r, true);
+            .register(&TimerEvent::Shutdown, dl_r.clone(), true);
+        (*timer_r)
+            .borrow_mut()
+            .register(&TimerEvent::Timer100s, dl_r, true);
         self.startup();
+        self.start_websub_listener();
     }
 }
 
 impl TimerReceiver for Downloader {
     fn trigger_mut(&mut self, event: &TimerEvent) {
-        if event == &TimerEvent::Shutdown {
-            self.shutdown();
+        match event {
+            TimerEvent::Shutdown => self.shutdown(),
+            TimerEvent::Timer100s => self.websub_check_leases(),
+            _ => (),
         }
     }
 }
@@ -539,12 +844,39 @@ impl TimerReceiver for Downloader {
 #[derive(Clone, Debug)]
 pub struct Config {
     pub num_downloader_threads: u8,
+    /// enclosures are stored below, one folder per subscription
+    pub enclosure_folder: String,
+    /// listener for the WebSub hubs, 0: off
+    pub websub_port: u16,
+    /// public address of the listener, empty: localhost
+    pub websub_callback_base: String,
+    /// tracking parameters are removed from the message links
+    pub link_cleaning: bool,
+    /// comma separated, in addition to the default list
+    pub link_clean_params: String,
+}
+
+impl Config {
+    pub fn link_cleaner(&self) -> LinkCleaner {
+        LinkCleaner::new(self.link_cleaning, &self.link_clean_params)
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             num_downloader_threads: 1,
+            enclosure_folder: default_enclosure_folder(),
+            websub_port: 0,
+            websub_callback_base: String::default(),
+            link_cleaning: true,
+            link_clean_params: String::default(),
         }
     }
 }
+
+pub fn default_enclosure_folder() -> String {
+    let home = std::env::var("HOME")
+        .unwrap_or_else(|_| std::env::temp_dir().to_string_lossy().to_string());
+    format!("{home}/Downloads/grassfeeder/")
+}
@@ -4,18 +4,34 @@ use crate::controller::browserpane::IBrowserPane;
 use crate::controller::contentdownloader::Downloader;
 use crate::controller::contentdownloader::IDownloader;
 use crate::controller::isourcetree::ISourceTreeController;
+use crate::controller::messagefilter::MessageFields;
+use crate::controller::messagefilter::MessageQuery;
+use crate::controller::messagefilter::QueryError;
 use crate::controller::sourcetree::SJob;
 use crate::controller::sourcetree::SourceTreeController;
 use crate::controller::timer::Timer;
+use crate::controller::undo::UndoAction;
+use crate::controller::undo::UndoHistory;
+use crate::controller::virtualnode::VirtualNode;
 use crate::db::message::decompress;
 use crate::db::message::MessageRow;
+use crate::db::message_revision::MessageRevision;
 use crate::db::message_state::MessageStateMap;
+use crate::db::message_tag::default_tag_color;
+use crate::db::message_tag::MessageTag;
+use crate::db::message_tag::MessageTagLink;
 use crate::db::messages_repo::IMessagesRepo;
 use crate::db::messages_repo::MessageIterator;
 use crate::db::messages_repo::MessagesRepo;
+use crate::db::saved_search::SavedSearch;
+use crate::db::subscription_repo::ISubscriptionRepo;
+use crate::db::subscription_repo::SubscriptionRepo;
 use crate::downloader::db_clean;
+use crate::downloader::linkcleaner::canonical_link;
 use crate::ui_select::gui_context::GuiContext;
 use crate::util::db_time_to_display;
+use crate::util::string_is_http_url;
+use crate::util::timestamp_now;
 use context::appcontext::AppContext;
 use context::BuildConfig;
 use context::Buildable;
@@ -30,9 +46,10 @@ use gui_layer::abstract_ui::UIAdapterValueStoreType;
 use gui_layer::abstract_ui::UIUpdaterAdapter;
 use gui_layer::gui_values::FontAttributes;
 use gui_layer::gui_values::PropDef;
-use regex::RegexSet;
 use resources::gen_icons;
 use resources::gen_icons::IDX_34_DATA_XP2;
+use resources::id::DIALOG_MESSAGE_TAGS;
+use resources::id::DIALOG_MESSAGE_TAGS_VALUES_PER_TAG;
 use resources::id::LIST0_COL_MSG_ID;
 use resources::id::LISTVIEW0;
 use resources::names::FOCUS_POLICY_NAMES;
@@ -45,6 +62,13 @@ use std::sync::RwLock;
 const JOBQUEUE_SIZE: usize = 1000; // at least as many jobs as there might be subscriptions
 const LIST_SCROLL_POS: i8 = 80; // to 70% of the upper list is visible, the cursor shall go to the lower 30%
 
+/// assignment of a tag in the tags dialog
+pub const TAG_STATE_UNASSIGNED: i32 = 0;
+pub const TAG_STATE_ASSIGNED: i32 = 1;
+/// some of the selected messages, or unchanged
+pub const TAG_STATE_UNCHANGED: i32 = 2;
+pub const TAG_STATE_DELETE: i32 = -1;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CJob {
     /// content_id, newtitle
@@ -53,6 +77,8 @@ pub enum CJob {
     DbUpdatePostId(isize, String),
     /// content_id, entry-date
     DbUpdateEntryDate(isize, u64),
+    /// content_id, content-hash, compressed content
+    DbUpdateContent(isize, String, String),
     ///  list_position, feed_content_id
     UpdateMessageListSome(Vec<(u32, u32)>),
     /// feed_content_id
@@ -64,12 +90,24 @@ pub enum CJob {
     ListSetCursorToMessage(isize),
     ///  list_position, msg_id, Favorite
     SetFavoriteSome(Vec<(u32, u32)>, bool),
+    ///  list_position, msg_id,  tag_id,  assign
+    SetTagSome(Vec<(u32, u32)>, isize, bool),
     ///  db-id,   list-position
     LaunchBrowserSuccess(isize, u32),
     /// subs_id
     CheckMessageCounts(isize),
+    /// subs_id,  enclosure urls
+    DownloadEnclosures(isize, Vec<String>),
+    /// message ids
+    ExtractArticles(Vec<isize>),
+    /// message id, the content is replaced by the article
+    ArticleExtracted(isize),
     // millseconds
     Pause(usize),
+    /// finds the other subscriptions of the duplicate stories in the list
+    UpdateDuplicateInfo,
+    /// subscriptions of the changed messages,  some tag was created
+    RulesApplied(Vec<isize>, bool),
 }
 
 pub trait IContentList {
@@ -94,6 +132,9 @@ pub trait IContentList {
     ///  clicking on the favorite left
     fn toggle_favorite(&self, msg_id: isize, list_position: i32, new_fav: Option<bool>);
     fn set_favorite_multi(&self, msg_id: &[(i32, i32)], new_fav: bool);
+    fn set_tag_multi(&self, msg_id_listpos: &[(i32, i32)], tag_id: isize, assign: bool);
+    /// assignments and changed tag definitions from the tags dialog
+    fn end_message_tags_dialog(&self, values: &[AValue]);
 
     fn get_job_receiver(&self) -> Receiver<CJob>;
     fn get_job_sender(&self) -> Sender<CJob>;
@@ -105,6 +146,8 @@ pub trait IContentList {
 
     fn set_conf_focus_policy(&mut self, n: u8);
     fn set_conf_msg_keep_count(&mut self, n: i32);
+    fn set_conf_mark_unread_on_update(&mut self, n: bool);
+    fn set_conf_enclosure_player(&mut self, cmd: String);
     fn notify_config_update(&mut self);
 
     fn process_list_action(&self, action: String, repoid: Vec<(i32, i32)>);
@@ -120,21 +163,37 @@ pub trait IContentList {
         current_row: Option<&MessageRow>,
     ) -> (String, String, String);
     fn move_list_cursor(&self, c: ListMoveCommand);
-    fn set_messages_filter(&mut self, newtext: &str);
+    /// a query that does not parse leaves the previous filter active
+    fn set_messages_filter(&mut self, newtext: &str) -> Result<(), QueryError>;
     fn launch_browser_single(&self, db_ids: Vec<i32>);
     fn launch_browser_selected(&self);
+    /// starts the configured player with all enclosures of those messages
+    fn launch_enclosure_player(&self, db_ids: Vec<i32>);
+    /// queues all enclosures of those messages at the downloader
+    fn download_enclosures(&self, db_ids: Vec<i32>);
+    /// replaces the content by the main text of the linked web page
+    fn extract_articles(&self, db_ids: Vec<i32>);
 
     /// does not update the message list
     fn set_read_complete_subscription(&mut self, source_repo_id: isize);
+    /// several subscriptions as one undo step,  does not update the message list
+    fn set_read_complete_subscriptions(&mut self, subs_ids: &[isize]);
 
     fn memory_conserve(&mut self, act: bool);
     fn keyboard_delete(&self);
+
+    /// Evaluates the stored rules on all messages in the downloader, only the actions that change messages.
+    fn apply_rules_to_existing(&self);
+
+    /// reverts a recorded message action,  or performs it again
+    fn apply_undo(&self, action: &UndoAction, redo: bool);
 }
 
 /// needs GuiContext  ConfigManager  BrowserPane  Downloader
 pub struct ContentList {
     timer_r: Rc<RefCell<Timer>>,
     messagesrepo_r: Rc<RefCell<dyn IMessagesRepo>>,
+    subscriptionrepo_r: Rc<RefCell<dyn ISubscriptionRepo>>,
     subscriptions_ct_w: Weak<RefCell<SourceTreeController>>,
     configmanager_r: Rc<RefCell<ConfigManager>>,
     browserpane_r: Rc<RefCell<dyn IBrowserPane>>,
@@ -146,16 +205,23 @@ pub struct ContentList {
     config: Config,
     list_selected_ids: RwLock<Vec<i32>>,
     msg_state: RwLock<MessageStateMap>,
-    msg_filter: Option<String>,
+    msg_filter: Option<MessageQuery>,
     ///  subscription-id, number-of-lines, is_folder
     current_subscription: RefCell<(isize, isize, bool)>,
     window_minimized: bool,
+    /// sorted by name
+    tags: RefCell<Vec<MessageTag>>,
+    /// msg-id, list-pos  of the messages in the tags dialog
+    tags_dialog_selection: RefCell<Vec<(i32, i32)>>,
+    undo_r: Rc<RefCell<UndoHistory>>,
 }
 
 impl ContentList {
     pub const CONF_FOCUS_POLICY: &'static str = "MessageSelectFocusPolicy";
     pub const CONF_MSG_KEEP_COUNT: &'static str = "MessagesKeepCount";
     pub const CONF_MSG_KEEP_COUNT_DEFAULT: i32 = 1000;
+    pub const CONF_MARK_UNREAD_ON_UPDATE: &'static str = "MessageMarkUnreadOnUpdate";
+    pub const CONF_ENCLOSURE_PLAYER: &'static str = "EnclosurePlayerCommand";
 
     pub fn new(ac: &AppContext) -> Self {
         let (q_s, q_r) = flume::bounded::<CJob>(JOBQUEUE_SIZE);
@@ -178,11 +244,15 @@ impl ContentList {
             config: Config::default(),
             list_selected_ids: RwLock::new(Vec::default()),
             messagesrepo_r: msg_r,
+            subscriptionrepo_r: (*ac).get_rc::<SubscriptionRepo>().unwrap(),
             msg_state: Default::default(),
             msg_filter: None,
             current_subscription: RefCell::new((-1, -1, false)),
             window_minimized: false,
             downloader_r: dl_r,
+            tags: Default::default(),
+            tags_dialog_selection: Default::default(),
+            undo_r: (*ac).get_rc::<UndoHistory>().unwrap(),
         }
     }
 
@@ -192,6 +262,8 @@ impl ContentList {
         title_d: String,
         debug_mode: bool,
         aggr_icon_id: Option<usize>,
+        also_in: &[String],
+        tag_chips: String,
     ) -> Vec<AValue> {
         let mut newrow: Vec<AValue> = Vec::default();
         let mut nfav = gen_icons::IDX_03_ICON_TRANSPARENT_48;
@@ -199,6 +271,8 @@ impl ContentList {
             None => {
                 if fc.is_favorite() {
                     nfav = gen_icons::IDX_44_ICON_GREEN_D
+                } else if fc.is_updated() {
+                    nfav = gen_icons::IDX_18_RELOAD_32
                 };
             }
             Some(agg_id) => {
@@ -206,7 +280,11 @@ impl ContentList {
             }
         }
         newrow.push(AValue::IIMG(nfav as i32)); // 0
-        newrow.push(AValue::ASTR(title_d)); // 1: message title
+        if also_in.is_empty() {
+            newrow.push(AValue::ASTR(title_d)); // 1: message title
+        } else {
+            newrow.push(AValue::ASTR(format!("{}  (+{})", title_d, also_in.len())));
+        }
         if fc.entry_src_date > 0 {
             let mut displaytime = db_time_to_display(fc.entry_src_date);
             if fc.entry_invalid_pubdate {
@@ -234,18 +312,58 @@ impl ContentList {
                 isdel,
                 crate::util::db_time_to_display(fc.fetch_date)
             )));
+        } else if !also_in.is_empty() {
+            newrow.push(AValue::ASTR(format!(
+                "{} {}",
+                t!("ML_ALSO_IN"),
+                also_in.join(", ")
+            )));
         } else {
             newrow.push(AValue::None);
         } // 6 :  tooltip
         newrow.push(AValue::ABOOL(fc.is_favorite())); //  7 : is-fav
+        newrow.push(AValue::ASTR(tag_chips)); //  8 : tags markup
         newrow
     }
 
+    fn tag_chips_for(&self, st: &MessageStateMap, msg_id: isize) -> String {
+        let tag_ids = st.get_tag_ids(msg_id);
+        let tags = self.tags.borrow();
+        let msg_tags: Vec<&MessageTag> = tags
+            .iter()
+            .filter(|t| tag_ids.contains(&t.tag_id))
+            .collect();
+        tag_chips_markup(&msg_tags)
+    }
+
+    /// assigned tags into the state map
+    fn set_state_tag_ids(&self, links: &[MessageTagLink], msg_ids: &[isize]) {
+        let mut by_msg: HashMap<isize, Vec<isize>> = HashMap::new();
+        links
+            .iter()
+            .for_each(|l| by_msg.entry(l.message_id).or_default().push(l.tag_id));
+        let mut st = self.msg_state.write().unwrap();
+        msg_ids.iter().for_each(|msg_id| {
+            st.set_tag_ids(*msg_id, by_msg.remove(msg_id).unwrap_or_default());
+        });
+    }
+
     fn set_read_many(&self, repoid_listpos: &[(i32, i32)], is_read: bool) {
         if repoid_listpos.is_empty() {
             return;
         }
         let repo_ids: Vec<i32> = repoid_listpos.iter().map(|(r, _p)| *r).collect();
+        let changed_ids: Vec<i32> = {
+            let st = self.msg_state.read().unwrap();
+            repo_ids
+                .iter()
+                .filter(|id| st.get_isread(**id as isize) != is_read)
+                .cloned()
+                .collect()
+        };
+        if !changed_ids.is_empty() {
+            self.record_undo(UndoAction::ReadState(changed_ids, is_read));
+        }
         (*self.messagesrepo_r)
             .borrow()
             .update_is_read_many(&repo_ids, is_read);
@@ -254,6 +372,9 @@ impl ContentList {
             .unwrap()
             .set_read_many(&repo_ids, is_read);
         (*self.messagesrepo_r).borrow_mut().cache_clear();
+        if is_read {
+            self.mark_duplicates_read(&repo_ids);
+        }
         let (subs_id, _num_msg, isfolder) = *self.current_subscription.borrow();
         if isfolder {
             if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
@@ -266,6 +387,16 @@ impl ContentList {
                 }
             }
         }
+        if VirtualNode::from_id(subs_id).is_some() {
+            let msg_subs_ids = self
+                .msg_state
+                .read()
+                .unwrap()
+                .get_subscription_ids(&repo_ids);
+            for m_subs_id in msg_subs_ids {
+                self.addjob(CJob::RequestUnreadAllCount(m_subs_id));
+            }
+        }
         self.addjob(CJob::RequestUnreadAllCount(subs_id));
         let listpos_repoid: Vec<(u32, u32)> = repoid_listpos
             .iter()
@@ -339,17 +470,64 @@ impl ContentList {
             msg.subscription_id,
             subscription_icon_id,
         );
+        if msg.dup_group > 0 {
+            self.msg_state
+                .write()
+                .unwrap()
+                .set_dup_group(msg.message_id, msg.dup_group);
+        }
     }
 
     /// Read from db and put into the list view,
     /// State Map shall contain only the current subscription's messages, for finding the cursor position for the focus policy
     fn update_messagelist_int(&self) {
         let (subs_id, num_msg, isfolder) = *self.current_subscription.borrow();
-        let mut messagelist: Vec<&MessageRow> = Vec::default();
         let mut child_ids: Vec<isize> = Vec::default();
+        let tag_links = (*self.messagesrepo_r).borrow().get_all_tag_links();
+        self.tags
+            .replace((*self.messagesrepo_r).borrow().get_tags());
+        let o_search: Option<(SavedSearch, Vec<isize>, Vec<isize>)> =
+            match VirtualNode::from_id(subs_id) {
+                Some(VirtualNode::Search(search_id)) => {
+                    let o_s = (*self.subscriptionrepo_r)
+                        .borrow()
+                        .get_saved_search(search_id);
+                    match (o_s, self.subscriptions_ct_w.upgrade()) {
+                        (Some(search), Some(feedsources)) => {
+                            let scope = (*feedsources).borrow().get_search_scope(&search);
+                            let inactive = (*self.subscriptionrepo_r).borrow().get_inactive_ids();
+                            Some((search, scope, inactive))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+        let trash_ids: Vec<isize> = match VirtualNode::from_id(subs_id) {
+            Some(VirtualNode::TrashEntry(trashed_id)) => (*self.subscriptionrepo_r)
+                .borrow()
+                .get_subscription_ids_below(trashed_id),
+            _ => Vec::default(),
+        };
         let mut mr_r = self.messagesrepo_r.borrow_mut();
         let mr_i: MessageIterator;
-        if isfolder {
+        if let Some(node) = VirtualNode::from_id(subs_id) {
+            mr_i = match (node, o_search) {
+                (VirtualNode::Tags, _) => (*mr_r).get_by_tag(0),
+                (VirtualNode::Tag(tag_id), _) => (*mr_r).get_by_tag(tag_id),
+                (VirtualNode::Search(_), Some((search, scope, inactive))) => {
+                    (*mr_r).get_by_search(&search, &scope, &inactive)
+                }
+                (VirtualNode::TrashEntry(_), _) => {
+                    (*mr_r).get_by_subscriptions(trash_ids.as_slice(), false)
+                }
+                // the searches folder, the trash, or a deleted search:  no messages
+                _ => {
+                    (*mr_r).cache_clear();
+                    (*mr_r).get_by_subscriptions(&[], false)
+                }
+            };
+        } else if isfolder {
             if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
                 if let Some((_subs_e, child_subs)) =
                     (*feedsources).borrow().get_current_selected_subscription()
@@ -364,17 +542,16 @@ impl ContentList {
         } else {
             mr_i = (*mr_r).get_by_subscription(subs_id);
         }
-        mr_i.clone().for_each(|m| {
-            messagelist.push(m);
-        });
+        let messagelist: Vec<MessageRow> = mr_i.clone().cloned().collect();
         if num_msg != messagelist.len() as isize {
             self.fill_state_map(mr_i.clone());
         }
-        let filtered_msglist: Vec<&MessageRow> = if self.msg_filter.is_some() {
-            let matchtext: &str = self.msg_filter.as_ref().unwrap().as_str();
-            filter_messages2(&self.msg_state, &messagelist, matchtext)
-        } else {
-            messagelist
+        drop(mr_r);
+        let msg_ids: Vec<isize> = messagelist.iter().map(|m| m.message_id).collect();
+        self.set_state_tag_ids(&tag_links, &msg_ids);
+        let filtered_msglist: Vec<&MessageRow> = match self.msg_filter {
+            Some(ref query) => self.filter_messages(&messagelist, query),
+            None => messagelist.iter().collect(),
         };
         let mut valstore = (*self.gui_val_store).write().unwrap();
         valstore.clear_list(LISTVIEW0); // , STORE_LIST_INDEX
@@ -394,6 +571,8 @@ impl ContentList {
                     title_string,
                     self.config.mode_debug,
                     o_icon,
+                    &st.get_also_in(fc.message_id),
+                    self.tag_chips_for(&st, fc.message_id),
                 ),
             );
         });
@@ -401,6 +580,26 @@ impl ContentList {
         self.list_selected_ids.write().unwrap().clear();
     }
 
+    /// the other text fields than the title are only read if the query uses them
+    fn filter_messages<'a>(
+        &self,
+        list_in: &'a [MessageRow],
+        query: &MessageQuery,
+    ) -> Vec<&'a MessageRow> {
+        let mut details: HashMap<isize, MessageRow> = HashMap::new();
+        if query.needs_details() {
+            let msg_ids: Vec<isize> = list_in.iter().map(|m| m.message_id).collect();
+            details = (*self.messagesrepo_r)
+                .borrow()
+                .get_by_indices(&msg_ids)
+                .into_iter()
+                .map(|m| (m.message_id, m))
+                .collect();
+        }
+        let list_refs: Vec<&MessageRow> = list_in.iter().collect();
+        filter_messages2(&self.msg_state, &list_refs, query, &details)
+    }
+
     fn fill_state_map(&self, mr_i: MessageIterator) {
         let (subs_id, _num_msg, isfolder) = *self.current_subscription.borrow();
         let msglist_len = mr_i.len();
@@ -414,23 +613,122 @@ impl ContentList {
                     .get_subs_icon_id(msg.subscription_id);
                 self.insert_state_from_row(msg, Some(n as isize), su_icon);
             });
+            self.addjob(CJob::UpdateDuplicateInfo);
+        }
+    }
+
+    /// the names of the other subscriptions that delivered the same story
+    fn update_duplicate_info(&self) {
+        let msg_groups = self.msg_state.read().unwrap().get_all_dup_groups();
+        if msg_groups.is_empty() {
+            return;
+        }
+        let mut groups: Vec<isize> = msg_groups.iter().map(|(_m, g)| *g).collect();
+        groups.sort();
+        groups.dedup();
+        let members = (*self.messagesrepo_r).borrow().get_by_dup_groups(&groups);
+        let feedsources = match self.subscriptions_ct_w.upgrade() {
+            Some(f) => f,
+            None => return,
+        };
+        let mut names: HashMap<isize, String> = HashMap::new();
+        let mut listpos_msgid: Vec<(u32, u32)> = Vec::default();
+        for (msg_id, group) in msg_groups {
+            let mut st = self.msg_state.write().unwrap();
+            let own_subs_id = members
+                .iter()
+                .find(|m| m.message_id == msg_id)
+                .map(|m| m.subscription_id)
+                .unwrap_or(-1);
+            let mut also_in: Vec<String> = Vec::default();
+            for m in members
+                .iter()
+                .filter(|m| m.dup_group == group && m.subscription_id != own_subs_id)
+            {
+                let name = names.entry(m.subscription_id).or_insert_with(|| {
+                    (*feedsources)
+                        .borrow()
+                        .get_subs_display_name(m.subscription_id)
+                });
+                if !also_in.contains(name) {
+                    also_in.push(name.clone());
+                }
+            }
+            if also_in != st.get_also_in(msg_id) {
+                st.set_also_in(msg_id, also_in);
+                listpos_msgid.push((st.get_gui_pos(msg_id), msg_id as u32));
+            }
+        }
+        if !listpos_msgid.is_empty() {
+            self.addjob(CJob::UpdateMessageListSome(listpos_msgid));
+        }
+    }
+
+    /// Reading one story marks it read in the other subscriptions as well
+    fn mark_duplicates_read(&self, msg_ids: &[i32]) {
+        let groups = self.msg_state.read().unwrap().get_dup_groups(msg_ids);
+        if groups.is_empty() {
+            return;
+        }
+        let others: Vec<MessageRow> = (*self.messagesrepo_r)
+            .borrow()
+            .get_by_dup_groups(&groups)
+            .into_iter()
+            .filter(|m| !m.is_read && !msg_ids.contains(&(m.message_id as i32)))
+            .collect();
+        if others.is_empty() {
+            return;
+        }
+        let other_ids: Vec<i32> = others.iter().map(|m| m.message_id as i32).collect();
+        (*self.messagesrepo_r)
+            .borrow()
+            .update_is_read_many(&other_ids, true);
+        (*self.messagesrepo_r).borrow_mut().cache_clear();
+        let mut subs_ids: Vec<isize> = others.iter().map(|m| m.subscription_id).collect();
+        subs_ids.sort();
+        subs_ids.dedup();
+        for subs_id in subs_ids {
+            self.addjob(CJob::RequestUnreadAllCount(subs_id));
+        }
+        let mut st = self.msg_state.write().unwrap();
+        let visible: Vec<(u32, u32)> = other_ids
+            .iter()
+            .filter(|id| st.contains(**id as isize))
+            .map(|id| (st.get_gui_pos(*id as isize), *id as u32))
+            .collect();
+        st.set_read_many(&other_ids, true);
+        if !visible.is_empty() {
+            self.addjob(CJob::UpdateMessageListSome(visible));
         }
     }
 
     fn delete_messages(&self, del_ids: &[i32]) {
-        let del_ids_i: Vec<isize> = del_ids.iter().map(|i| *i as isize).collect::<Vec<isize>>();
-        let child_subs_ids;
-        {
-            let mut msg_rep = (self.messagesrepo_r).borrow_mut();
-            child_subs_ids = msg_rep.get_subscription_ids(&del_ids_i);
-            msg_rep.update_is_deleted_many(del_ids, true);
+        if del_ids.is_empty() {
+            return;
         }
-        // debug!(            "delete_messages : del_ids_i   {:?}   subs_ids: {:?} ",            del_ids_i, child_subs_ids        );
+        self.record_undo(UndoAction::Delete(del_ids.to_vec()));
         let o_neighbour = self
             .msg_state
             .read()
             .unwrap()
             .find_neighbour_message(del_ids);
+        self.set_deleted_many(del_ids, true);
+        if let Some((msg_id, _gui_list_pos)) = o_neighbour {
+            self.addjob(CJob::ListSetCursorToMessage(msg_id));
+        } else {
+            self.addjob(CJob::ListSetCursorToPolicy);
+        }
+    }
+
+    /// sets the deleted flag and refreshes the counts, the list is redrawn
+    fn set_deleted_many(&self, msg_ids: &[i32], is_deleted: bool) {
+        let msg_ids_i: Vec<isize> = msg_ids.iter().map(|i| *i as isize).collect::<Vec<isize>>();
+        let child_subs_ids;
+        {
+            let mut msg_rep = (self.messagesrepo_r).borrow_mut();
+            child_subs_ids = msg_rep.get_subscription_ids(&msg_ids_i);
+            msg_rep.update_is_deleted_many(msg_ids, is_deleted);
+        }
         let (subs_id, _num_msg, _isfolder) = *self.current_subscription.borrow();
         if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
             feedsources.borrow().clear_read_unread(subs_id);
@@ -440,11 +738,68 @@ impl ContentList {
             self.addjob(CJob::RequestUnreadAllCount(child_subs_id));
         }
         self.addjob(CJob::UpdateMessageList);
-        if let Some((msg_id, _gui_list_pos)) = o_neighbour {
-            self.addjob(CJob::ListSetCursorToMessage(msg_id));
-        } else {
-            self.addjob(CJob::ListSetCursorToPolicy);
+    }
+
+    /// for messages in and outside of the current list
+    fn restore_read_state(&self, msg_ids: &[i32], is_read: bool) {
+        let msg_ids_i: Vec<isize> = msg_ids.iter().map(|i| *i as isize).collect::<Vec<isize>>();
+        let subs_ids;
+        {
+            let mut msg_rep = (self.messagesrepo_r).borrow_mut();
+            subs_ids = msg_rep.get_subscription_ids(&msg_ids_i);
+            msg_rep.update_is_read_many(msg_ids, is_read);
+            msg_rep.cache_clear();
+        }
+        if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
+            for subs_id in &subs_ids {
+                feedsources.borrow().clear_read_unread(*subs_id);
+            }
         }
+        for subs_id in subs_ids {
+            self.addjob(CJob::RequestUnreadAllCount(subs_id));
+        }
+        let (subs_id, _num_msg, _isfolder) = *self.current_subscription.borrow();
+        self.addjob(CJob::RequestUnreadAllCount(subs_id));
+        let mut st = self.msg_state.write().unwrap();
+        let visible: Vec<(u32, u32)> = msg_ids
+            .iter()
+            .filter(|id| st.contains(**id as isize))
+            .map(|id| (st.get_gui_pos(*id as isize), *id as u32))
+            .collect();
+        st.set_read_many(msg_ids, is_read);
+        if !visible.is_empty() {
+            self.addjob(CJob::UpdateMessageListSome(visible));
+        }
+    }
+
+    /// for messages in and outside of the current list
+    fn restore_favorite(&self, msg_ids: &[i32], new_fav: bool) {
+        let mut visible: Vec<(u32, u32)> = Vec::default();
+        {
+            let st = self.msg_state.read().unwrap();
+            for msg_id in msg_ids {
+                if st.contains(*msg_id as isize) {
+                    visible.push((st.get_gui_pos(*msg_id as isize), *msg_id as u32));
+                    continue;
+                }
+                let o_msg = (*self.messagesrepo_r)
+                    .borrow()
+                    .get_by_index(*msg_id as isize);
+                if let Some(mut msg) = o_msg {
+                    msg.set_favorite(new_fav);
+                    (*self.messagesrepo_r)
+                        .borrow()
+                        .update_markers(msg.message_id, msg.markers);
+                }
+            }
+        }
+        if !visible.is_empty() {
+            self.addjob(CJob::SetFavoriteSome(visible, new_fav));
+        }
+    }
+
+    fn record_undo(&self, action: UndoAction) {
+        self.undo_r.borrow_mut().push(action);
     }
 
     fn set_favorite_int(&self, listpos_msgid: &[(u32, u32)], new_fav: bool) {
@@ -472,6 +827,194 @@ impl ContentList {
             .update_list_some(LISTVIEW0, &vec_listpos);
     }
 
+    fn set_tag_int(&self, listpos_msgid: &[(u32, u32)], tag_id: isize, assign: bool) {
+        let msg_ids: Vec<i32> = listpos_msgid.iter().map(|(_p, m)| *m as i32).collect();
+        let num_changed = match assign {
+            true => (*self.messagesrepo_r)
+                .borrow()
+                .add_tag_links(&msg_ids, tag_id),
+            false => (*self.messagesrepo_r)
+                .borrow()
+                .remove_tag_links(&msg_ids, tag_id),
+        };
+        if num_changed == 0 {
+            return;
+        }
+        {
+            let mut st = self.msg_state.write().unwrap();
+            msg_ids.iter().for_each(|msg_id| {
+                let mut tag_ids = st.get_tag_ids(*msg_id as isize);
+                tag_ids.retain(|t| *t != tag_id);
+                if assign {
+                    tag_ids.push(tag_id);
+                }
+                st.set_tag_ids(*msg_id as isize, tag_ids);
+            });
+        }
+        self.update_content_list_some(listpos_msgid);
+        let vec_listpos = listpos_msgid.iter().map(|(p, _d)| *p).collect::<Vec<u32>>();
+        (*self.gui_updater)
+            .borrow()
+            .update_list_some(LISTVIEW0, &vec_listpos);
+        self.notify_tags_changed(false);
+    }
+
+    /// definitions_changed:  tags were created, renamed or removed,  otherwise only assignments
+    fn notify_tags_changed(&self, definitions_changed: bool) {
+        self.tags
+            .replace((*self.messagesrepo_r).borrow().get_tags());
+        if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
+            if definitions_changed {
+                (*feedsources)
+                    .borrow()
+                    .addjob(SJob::FillSubscriptionsAdapter);
+                (*feedsources).borrow().addjob(SJob::GuiUpdateTreeAll);
+            } else {
+                (*feedsources)
+                    .borrow()
+                    .addjob(SJob::GuiUpdateVirtualEntries);
+            }
+        }
+        let (subs_id, _num_msg, isfolder) = *self.current_subscription.borrow();
+        if definitions_changed || VirtualNode::from_id(subs_id).is_some() {
+            self.current_subscription.replace((subs_id, -1, isfolder));
+            self.addjob(CJob::UpdateMessageList);
+        }
+    }
+
+    /// all tags, with the assignment to the selected messages
+    fn start_message_tags_dialog(&self, msgid_listpos: Vec<(i32, i32)>) {
+        if msgid_listpos.is_empty() {
+            return;
+        }
+        let tags = (*self.messagesrepo_r).borrow().get_tags();
+        let mut dd: Vec<AValue> = Vec::default();
+        {
+            let st = self.msg_state.read().unwrap();
+            for tag in &tags {
+                let num_assigned = msgid_listpos
+                    .iter()
+                    .filter(|(msg_id, _p)| st.get_tag_ids(*msg_id as isize).contains(&tag.tag_id))
+                    .count();
+                let state = match num_assigned {
+                    0 => TAG_STATE_UNASSIGNED,
+                    n if n == msgid_listpos.len() => TAG_STATE_ASSIGNED,
+                    _ => TAG_STATE_UNCHANGED,
+                };
+                dd.push(AValue::AI32(tag.tag_id as i32));
+                dd.push(AValue::ASTR(tag.name.clone()));
+                dd.push(AValue::ASTR(tag.color.clone()));
+                dd.push(AValue::AI32(state));
+            }
+        }
+        self.tags.replace(tags);
+        self.tags_dialog_selection.replace(msgid_listpos);
+        (*self.gui_val_store)
+            .write()
+            .unwrap()
+            .set_dialog_data(DIALOG_MESSAGE_TAGS, &dd);
+        (*self.gui_updater)
+            .borrow()
+            .update_dialog(DIALOG_MESSAGE_TAGS);
+        (*self.gui_updater)
+            .borrow()
+            .show_dialog(DIALOG_MESSAGE_TAGS);
+    }
+
+    /// Stores the previous content as revision, if the content hash differs.
+    /// Messages from older versions have no hash yet, they only get the hash.
+    fn update_content_revision(&self, msg_id: isize, new_hash: &str, new_content: &str) {
+        let o_msg = (*self.messagesrepo_r).borrow().get_by_index(msg_id);
+        if o_msg.is_none() {
+            warn!("update_content: msg not found: {}", msg_id);
+            return;
+        }
+        let mut msg = o_msg.unwrap();
+        if msg.content_hash == new_hash {
+            return;
+        }
+        let content_changed = !msg.content_hash.is_empty();
+        let had_article = msg.has_article();
+        if content_changed {
+            let rev = MessageRevision {
+                message_id: msg_id,
+                replaced_date: timestamp_now(),
+                content_hash: msg.content_hash.clone(),
+                content_text: msg.content_text.clone(),
+                ..Default::default()
+            };
+            if let Err(e) = (*self.messagesrepo_r).borrow().insert_revision(&rev) {
+                error!("storing revision of {} : {:?}", msg_id, e);
+                return;
+            }
+            msg.set_updated(true);
+            (*self.messagesrepo_r)
+                .borrow()
+                .update_markers(msg_id, msg.markers);
+            if self.config.mark_unread_on_update && msg.is_read {
+                (*self.messagesrepo_r)
+                    .borrow()
+                    .update_is_read_many(&[msg_id as i32], false);
+                self.msg_state
+                    .write()
+                    .unwrap()
+                    .set_read_many(&[msg_id as i32], false);
+                self.addjob(CJob::RequestUnreadAllCount(msg.subscription_id));
+            }
+        }
+        (*self.messagesrepo_r)
+            .borrow()
+            .update_content(msg_id, new_hash, new_content);
+        if content_changed {
+            self.msg_state
+                .write()
+                .unwrap()
+                .clear_contents_author_categories(msg_id);
+            (*self.messagesrepo_r).borrow_mut().cache_clear();
+            let (subs_id, _num_msg, _isfolder) = *self.current_subscription.borrow();
+            if subs_id == msg.subscription_id {
+                let list_pos = self.msg_state.read().unwrap().get_gui_pos(msg_id);
+                self.addjob(CJob::UpdateMessageListSome(vec![(list_pos, msg_id as u32)]));
+            }
+            if had_article {
+                (self.downloader_r).borrow().extract_article(msg_id);
+            }
+        }
+    }
+
+    /// the rules have been run on the stored messages, the changed subscriptions get recounted
+    fn rules_applied(&self, subs_ids: &[isize], tag_created: bool) {
+        (*self.messagesrepo_r).borrow_mut().cache_clear();
+        self.notify_tags_changed(tag_created);
+        if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
+            for subs_id in subs_ids {
+                feedsources.borrow().clear_read_unread(*subs_id);
+            }
+        }
+        for subs_id in subs_ids {
+            self.addjob(CJob::RequestUnreadAllCount(*subs_id));
+        }
+        let (cur_subs_id, _num_msg, isfolder) = *self.current_subscription.borrow();
+        self.current_subscription
+            .replace((cur_subs_id, -1, isfolder));
+        self.addjob(CJob::UpdateMessageList);
+    }
+
+    fn article_extracted(&self, msg_id: isize) {
+        self.msg_state
+            .write()
+            .unwrap()
+            .clear_contents_author_categories(msg_id);
+        (*self.messagesrepo_r).borrow_mut().cache_clear();
+        if self
+            .get_selected_content_ids()
+            .first()
+            .is_some_and(|id| *id as isize == msg_id)
+        {
+            self.addjob(CJob::SwitchBrowserTabContent(msg_id as i32));
+        }
+    }
+
     fn check_message_counts(&self, subs_id: isize) {
         let msg_keep_count: isize = (*self.configmanager_r)
             .borrow()
@@ -531,6 +1074,9 @@ impl IContentList for ContentList {
                         .borrow()
                         .update_entry_src_date(content_id, newdate as i64);
                 }
+                CJob::DbUpdateContent(content_id, ref hash, ref content) => {
+                    self.update_content_revision(content_id, hash, content);
+                }
                 CJob::UpdateMessageListSome(ref vec_pos_db) => {
                     self.update_content_list_some(vec_pos_db);
                     let list_pos: Vec<u32> =
@@ -575,7 +1121,7 @@ impl IContentList for ContentList {
                     let msg_count = (*self.messagesrepo_r).borrow().get_src_sum(feed_source_id);
                     let read_count = (*self.messagesrepo_r).borrow().get_read_sum(feed_source_id);
                     let unread_count = msg_count - read_count;
-                    if msg_count >= 0 {
+                    if msg_count >= 0 && VirtualNode::from_id(feed_source_id).is_none() {
                         if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
                             (*feedsources).borrow().addjob(SJob::NotifyTreeReadCount(
                                 feed_source_id,
@@ -594,12 +1140,34 @@ impl IContentList for ContentList {
                 CJob::SetFavoriteSome(ref vec_listpos_msgid, new_fav) => {
                     self.set_favorite_int(vec_listpos_msgid, new_fav);
                 }
+                CJob::SetTagSome(ref vec_listpos_msgid, tag_id, assign) => {
+                    self.set_tag_int(vec_listpos_msgid, tag_id, assign);
+                }
                 CJob::LaunchBrowserSuccess(msg_id, list_position) => {
                     self.set_read_many(&[(msg_id as i32, list_position as i32)], true);
                 }
                 CJob::CheckMessageCounts(subs_id) => {
                     self.check_message_counts(subs_id);
                 }
+                CJob::DownloadEnclosures(subs_id, ref urls) => {
+                    urls.iter().for_each(|url| {
+                        (self.downloader_r)
+                            .borrow()
+                            .download_enclosure(subs_id, url.clone())
+                    });
+                }
+                CJob::ExtractArticles(ref msg_ids) => {
+                    msg_ids
+                        .iter()
+                        .for_each(|msg_id| (self.downloader_r).borrow().extract_article(*msg_id));
+                }
+                CJob::ArticleExtracted(msg_id) => {
+                    self.article_extracted(msg_id);
+                }
+                CJob::UpdateDuplicateInfo => self.update_duplicate_info(),
+                CJob::RulesApplied(ref subs_ids, tag_created) => {
+                    self.rules_applied(subs_ids, tag_created);
+                }
                 CJob::Pause(t_ms) => {
                     std::thread::sleep(std::time::Duration::from_millis(t_ms as u64));
                 }
@@ -651,6 +1219,7 @@ impl IContentList for ContentList {
                 .update_is_read_many(&is_unread_ids, true);
             self.addjob(CJob::RequestUnreadAllCount(subs_id));
             (*self.messagesrepo_r).borrow_mut().cache_clear();
+            self.mark_duplicates_read(&is_unread_ids);
         }
         self.addjob(CJob::UpdateMessageListSome(list_pos_dbid));
         if let Some(feedsources) = self.subscriptions_ct_w.upgrade() {
@@ -667,9 +1236,7 @@ impl IContentList for ContentList {
     }
 
     fn set_read_complete_subscription(&mut self, subs_id: isize) {
-        let mut mr_r = (*self.messagesrepo_r).borrow_mut();
-        mr_r.update_is_read_all(subs_id, true);
-        mr_r.cache_clear();
+        self.set_read_complete_subscriptions(&[subs_id]);
         let (current_subs_id, _numlines, _isfolder) = *self.current_subscription.borrow();
         if current_subs_id == subs_id {
             self.update_message_list(subs_id);
@@ -683,6 +1250,23 @@ impl IContentList for ContentList {
         }
     }
 
+    fn set_read_complete_subscriptions(&mut self, subs_ids: &[isize]) {
+        let mut unread_ids: Vec<i32> = Vec::default();
+        let mut mr_r = (*self.messagesrepo_r).borrow_mut();
+        for subs_id in subs_ids {
+            unread_ids.extend(
+                mr_r.get_by_subscription(*subs_id)
+                    .filter(|m| !m.is_read)
+                    .map(|m| m.message_id as i32),
+            );
+            mr_r.update_is_read_all(*subs_id, true);
+        }
+        mr_r.cache_clear();
+        if !unread_ids.is_empty() {
+            self.record_undo(UndoAction::ReadState(unread_ids, true));
+        }
+    }
+
     fn update_message_list(&self, subscription_id: isize) {
         let (old_subs_id, _num_msg, mut isfolder) = *self.current_subscription.borrow();
         if subscription_id != old_subs_id {
@@ -691,6 +1275,9 @@ impl IContentList for ContentList {
                     isfolder = subs_e.0.is_folder;
                 }
             }
+            if VirtualNode::from_id(subscription_id).is_some() {
+                isfolder = true; // messages of several subscriptions, showing their icons
+            }
             self.current_subscription
                 .replace((subscription_id, -1, isfolder));
             self.addjob(CJob::UpdateMessageList);
@@ -730,6 +1317,8 @@ impl IContentList for ContentList {
                 title,
                 self.config.mode_debug,
                 o_icon,
+                &st.get_also_in(msg.message_id),
+                self.tag_chips_for(&st, msg.message_id),
             );
             // trace!(                " update_content_list_some   {} {:?}  {:?} ",                isfolder,                o_icon,                av_list            );
             (*self.gui_val_store).write().unwrap().insert_list_item(
@@ -770,11 +1359,11 @@ impl IContentList for ContentList {
             return;
         }
         let mut msg = o_msg.unwrap();
-        if let Some(f) = new_fav {
-            msg.set_favorite(f);
-        } else {
-            msg.set_favorite(!msg.is_favorite());
+        let fav = new_fav.unwrap_or(!msg.is_favorite());
+        if fav != msg.is_favorite() {
+            self.record_undo(UndoAction::Favorite(vec![msg_id as i32], fav));
         }
+        msg.set_favorite(fav);
         (*(self.messagesrepo_r.borrow_mut())).update_markers(msg_id, msg.markers);
         let vec_pos_db: Vec<(u32, u32)> = vec![(list_position as u32, msg_id as u32)];
         self.update_content_list_some(&vec_pos_db);
@@ -785,6 +1374,19 @@ impl IContentList for ContentList {
 
     /// [  ( msg-id , list-pos ) ]
     fn set_favorite_multi(&self, msg_id_listpos: &[(i32, i32)], new_fav: bool) {
+        let changed_ids: Vec<i32> = msg_id_listpos
+            .iter()
+            .map(|(msg_id, _listpos)| *msg_id)
+            .filter(|msg_id| {
+                (*self.messagesrepo_r)
+                    .borrow()
+                    .get_by_index(*msg_id as isize)
+                    .is_some_and(|m| m.is_favorite() != new_fav)
+            })
+            .collect();
+        if !changed_ids.is_empty() {
+            self.record_undo(UndoAction::Favorite(changed_ids, new_fav));
+        }
         let chunk_size = 7;
         if msg_id_listpos.len() <= chunk_size {
             let mut mod_listpos_db: Vec<(u32, u32)> = Vec::default();
@@ -809,6 +1411,83 @@ impl IContentList for ContentList {
         }
     }
 
+    /// [  ( msg-id , list-pos ) ]
+    fn set_tag_multi(&self, msg_id_listpos: &[(i32, i32)], tag_id: isize, assign: bool) {
+        let chunk_size = 7;
+        msg_id_listpos.chunks(chunk_size).for_each(|chunk| {
+            let listpos_msgid: Vec<(u32, u32)> = chunk
+                .iter()
+                .map(|(msg_id, listpos)| (*listpos as u32, *msg_id as u32))
+                .collect();
+            self.addjob(CJob::SetTagSome(listpos_msgid, tag_id, assign));
+        });
+    }
+
+    fn end_message_tags_dialog(&self, values: &[AValue]) {
+        let selection = self.tags_dialog_selection.take();
+        let existing = (*self.messagesrepo_r).borrow().get_tags();
+        let mut definitions_changed = false;
+        for row in values.chunks(DIALOG_MESSAGE_TAGS_VALUES_PER_TAG) {
+            if row.len() < DIALOG_MESSAGE_TAGS_VALUES_PER_TAG {
+                break;
+            }
+            let mut tag_id = row[0].int().unwrap_or(0) as isize;
+            let name = row[1].str().unwrap_or_default().trim().to_string();
+            let color = row[2].str().unwrap_or_default();
+            let state = row[3].int().unwrap_or(TAG_STATE_UNCHANGED);
+            if state == TAG_STATE_DELETE {
+                if tag_id > 0 {
+                    (*self.messagesrepo_r).borrow().delete_tag(tag_id);
+                    definitions_changed = true;
+                }
+                continue;
+            }
+            if name.is_empty() {
+                continue;
+            }
+            if tag_id <= 0 {
+                match (*self.messagesrepo_r).borrow().get_or_insert_tag(&name) {
+                    Ok((new_id, is_new)) => {
+                        tag_id = new_id;
+                        definitions_changed |= is_new;
+                    }
+                    Err(e) => {
+                        warn!("new tag {} => {:?}", name, e);
+                        continue;
+                    }
+                }
+            }
+            let o_old = existing.iter().find(|t| t.tag_id == tag_id);
+            let changed = match o_old {
+                Some(old) => old.name != name || (!color.is_empty() && old.color != color),
+                None => !color.is_empty(),
+            };
+            if changed {
+                let tag = MessageTag {
+                    tag_id,
+                    name: name.clone(),
+                    color: if color.is_empty() {
+                        default_tag_color(existing.len())
+                    } else {
+                        color
+                    },
+                };
+                if let Err(e) = (*self.messagesrepo_r).borrow().update_tag(&tag) {
+                    warn!("update tag {:?} => {:?}", tag, e);
+                }
+                definitions_changed = true;
+            }
+            match state {
+                TAG_STATE_UNASSIGNED => self.set_tag_multi(&selection, tag_id, false),
+                TAG_STATE_ASSIGNED => self.set_tag_multi(&selection, tag_id, true),
+                _ => (),
+            }
+        }
+        if definitions_changed {
+            self.notify_tags_changed(true);
+        }
+    }
+
     fn get_job_receiver(&self) -> Receiver<CJob> {
         self.job_queue_receiver.clone()
     }
@@ -850,6 +1529,21 @@ impl IContentList for ContentList {
             .set_val(ContentList::CONF_MSG_KEEP_COUNT, n.to_string());
     }
 
+    fn set_conf_mark_unread_on_update(&mut self, n: bool) {
+        self.config.mark_unread_on_update = n;
+        (*self.configmanager_r)
+            .borrow_mut()
+            .set_val(ContentList::CONF_MARK_UNREAD_ON_UPDATE, n.to_string());
+    }
+
+    fn set_conf_enclosure_player(&mut self, cmd: String) {
+        self.config.enclosure_player = cmd.trim().to_string();
+        (*self.configmanager_r).borrow_mut().set_val(
+            ContentList::CONF_ENCLOSURE_PLAYER,
+            self.config.enclosure_player.clone(),
+        );
+    }
+
     fn notify_config_update(&mut self) {
         self.config.list_fontsize = get_font_size_from_config(self.configmanager_r.clone()) as u8;
     }
@@ -893,12 +1587,35 @@ impl IContentList for ContentList {
                     debug!("copy-link : no subs-id !!");
                 }
             }
+            "open-enclosure" => {
+                let db_ids: Vec<i32> = msgid_listpos.iter().map(|(db, _lp)| *db).collect();
+                self.launch_enclosure_player(db_ids);
+            }
+            "download-enclosure" => {
+                let db_ids: Vec<i32> = msgid_listpos.iter().map(|(db, _lp)| *db).collect();
+                self.download_enclosures(db_ids);
+            }
+            "extract-article" => {
+                let db_ids: Vec<i32> = msgid_listpos.iter().map(|(db, _lp)| *db).collect();
+                self.extract_articles(db_ids);
+            }
+            "load-remote-content" => {
+                if let Some((msg_id, _lp)) = msgid_listpos.first() {
+                    (*self.browserpane_r)
+                        .borrow()
+                        .allow_remote_content_once(*msg_id as isize);
+                    self.addjob(CJob::SwitchBrowserTabContent(*msg_id));
+                }
+            }
             "mark-as-favorite" => {
                 self.set_favorite_multi(&msgid_listpos, true);
             }
             "unmark-favorite" => {
                 self.set_favorite_multi(&msgid_listpos, false);
             }
+            "edit-tags" => {
+                self.start_message_tags_dialog(msgid_listpos);
+            }
 
             _ => {
                 warn!("contentlist_action unknown {}", &action);
@@ -943,6 +1660,79 @@ impl IContentList for ContentList {
         self.launch_browser_single(id_list);
     }
 
+    fn launch_enclosure_player(&self, db_ids: Vec<i32>) {
+        let mut urls: Vec<String> = Vec::default();
+        db_ids.iter().for_each(|msg_id| {
+            if let Some(msg) = (*self.messagesrepo_r)
+                .borrow()
+                .get_by_index(*msg_id as isize)
+            {
+                msg.get_enclosures()
+                    .into_iter()
+                    .for_each(|encl| urls.push(encl.url));
+            }
+        });
+        if urls.is_empty() {
+            debug!("launch_enclosure_player: no enclosures in {:?}", db_ids);
+            return;
+        }
+        let mut cmd_parts = self.config.enclosure_player.split_whitespace();
+        let o_program = cmd_parts.next();
+        if o_program.is_none() {
+            let first_id = *db_ids.first().unwrap() as isize;
+            let list_pos = self.msg_state.read().unwrap().get_gui_pos(first_id);
+            (self.downloader_r).borrow().launch_webbrowser(
+                urls.first().unwrap().clone(),
+                first_id,
+                list_pos,
+            );
+            return;
+        }
+        let program = o_program.unwrap();
+        match std::process::Command::new(program)
+            .args(cmd_parts)
+            .args(&urls)
+            .spawn()
+        {
+            Ok(_child) => {
+                let read_ids: Vec<(i32, i32)> = db_ids
+                    .iter()
+                    .map(|id| {
+                        let pos = self.msg_state.read().unwrap().get_gui_pos(*id as isize);
+                        (*id, pos as i32)
+                    })
+                    .collect();
+                self.set_read_many(&read_ids, true);
+            }
+            Err(e) => {
+                warn!("launching {} {:?} => {:?}", program, urls, e);
+            }
+        }
+    }
+
+    fn download_enclosures(&self, db_ids: Vec<i32>) {
+        db_ids.iter().for_each(|msg_id| {
+            if let Some(msg) = (*self.messagesrepo_r)
+                .borrow()
+                .get_by_index(*msg_id as isize)
+            {
+                msg.get_enclosures().into_iter().for_each(|encl| {
+                    (self.downloader_r)
+                        .borrow()
+                        .download_enclosure(msg.subscription_id, encl.url)
+                });
+            }
+        });
+    }
+
+    fn extract_articles(&self, db_ids: Vec<i32>) {
+        db_ids.iter().for_each(|msg_id| {
+            (self.downloader_r)
+                .borrow()
+                .extract_article(*msg_id as isize)
+        });
+    }
+
     fn get_msg_content_author_categories(
         &self,
         msg_id: isize,
@@ -983,7 +1773,7 @@ impl IContentList for ContentList {
             return;
         };
         let (last_subs_id, _num_msg, _isfolder) = *self.current_subscription.borrow();
-        if last_subs_id <= 0 {
+        if last_subs_id <= 0 && VirtualNode::from_id(last_subs_id).is_none() {
             trace!("move_list_cursor last_subs_id={} ", last_subs_id);
             return;
         }
@@ -1014,14 +1804,15 @@ impl IContentList for ContentList {
         }
     }
 
-    fn set_messages_filter(&mut self, newtext: &str) {
+    fn set_messages_filter(&mut self, newtext: &str) -> Result<(), QueryError> {
         let trimmed = newtext.trim();
         if trimmed.is_empty() {
             self.msg_filter = None;
         } else {
-            self.msg_filter.replace(trimmed.to_string());
+            self.msg_filter.replace(MessageQuery::parse(trimmed)?);
         }
         self.addjob(CJob::UpdateMessageList);
+        Ok(())
     }
 
     fn keyboard_delete(&self) {
@@ -1033,6 +1824,25 @@ impl IContentList for ContentList {
         self.window_minimized = act;
     }
 
+    fn apply_undo(&self, action: &UndoAction, redo: bool) {
+        match action {
+            UndoAction::ReadState(msg_ids, is_read) => {
+                self.restore_read_state(msg_ids, *is_read == redo);
+            }
+            UndoAction::Delete(msg_ids) => {
+                self.set_deleted_many(msg_ids, redo);
+            }
+            UndoAction::Favorite(msg_ids, new_fav) => {
+                self.restore_favorite(msg_ids, *new_fav == redo);
+            }
+            _ => (),
+        }
+    }
+
+    fn apply_rules_to_existing(&self) {
+        (*self.downloader_r).borrow().apply_rules_to_existing();
+    }
+
     // impl IContentList
 }
 
@@ -1056,6 +1866,10 @@ impl Buildable for ContentList {
             fc.config.list_sort_column = 0;
         }
         fc.config.list_sort_order_up = conf.get_bool(&PropDef::GuiList0SortAscending.to_string());
+        fc.config.mark_unread_on_update = conf.get_bool(ContentList::CONF_MARK_UNREAD_ON_UPDATE);
+        if let Some(s) = conf.get(ContentList::CONF_ENCLOSURE_PLAYER) {
+            fc.config.enclosure_player = s;
+        }
         fc
     }
 }
@@ -1110,6 +1924,11 @@ pub fn match_messagerow(existing: &MessageRow, new_fce: &MessageRow) -> u8 {
     };
     if !existing.post_id.is_empty() && (existing.post_id == new_fce.post_id) {
         match_bits |= ContentMatchMask::PostId as u8;
+    } else if string_is_http_url(&existing.post_id)
+        && string_is_http_url(&new_fce.post_id)
+        && canonical_link(&existing.post_id) == canonical_link(&new_fce.post_id)
+    {
+        match_bits |= ContentMatchMask::PostId as u8; // same article, other tracking parameters
     };
     if existing.title == new_fce.title {
         match_bits |= ContentMatchMask::Title as u8;
@@ -1145,6 +1964,13 @@ pub fn match_new_entries_to_existing(
         if let Some(pos) = pos_with_max_ones {
             let exi_fce = existing_msg_iter.get_row(*pos).unwrap();
             let matchfield: u8 = match_messagerow(exi_fce, n_fce);
+            if !n_fce.content_hash.is_empty() && exi_fce.content_hash != n_fce.content_hash {
+                let _r = job_sender.send(CJob::DbUpdateContent(
+                    exi_fce.message_id,
+                    n_fce.content_hash.clone(),
+                    n_fce.content_text.clone(),
+                ));
+            }
             if matchfield.count_ones() >= 3 {
                 new_list_delete_indices.push(idx_new); // full match
             }
@@ -1191,6 +2017,10 @@ pub struct Config {
     pub list_sort_order_up: bool,
     pub mode_debug: bool,
     pub list_fontsize: u8,
+    /// changed content sets the message to unread again
+    pub mark_unread_on_update: bool,
+    /// external program for enclosures, with arguments. Empty: the web browser
+    pub enclosure_player: String,
 }
 
 impl Default for Config {
@@ -1202,6 +2032,8 @@ impl Default for Config {
             list_sort_order_up: false,
             mode_debug: false,
             list_fontsize: 10,
+            mark_unread_on_update: false,
+            enclosure_player: String::default(),
         }
     }
 }
@@ -1226,42 +2058,61 @@ pub enum ListMoveCommand {
     PreviousUnreadMessage,
 }
 
+/// titles come from the state map,  author, categories and content from the details, if present
 pub fn filter_messages2<'a>(
-    msgstate: &RwLock<MessageStateMap>,
-    list_in: &'a [&MessageRow],
-    matchtext: &str,
+    msg_state: &RwLock<MessageStateMap>,
+    list_in: &[&'a MessageRow],
+    query: &MessageQuery,
+    details: &HashMap<isize, MessageRow>,
 ) -> Vec<&'a MessageRow> {
-    let rset = create_regex_set(matchtext);
-    // trace!("filter_messages:   {}   {:?} ", matchtext, rset);
-    let out_list: Vec<&MessageRow> = list_in
+    let now = timestamp_now();
+    let st = msg_state.read().unwrap();
+    list_in
         .iter()
         .filter(|m| {
-            let o_title = msgstate.read().unwrap().get_title(m.message_id);
-            if o_title.is_none() {
-                return true;
+            let mut fields = MessageFields {
+                title: st.get_title(m.message_id).unwrap_or_default(),
+                link: m.link.clone(),
+                is_read: m.is_read,
+                is_favorite: m.is_favorite(),
+                entry_date: m.entry_src_date,
+                ..Default::default()
+            };
+            if let Some(full) = details.get(&m.message_id) {
+                fields.author = decompress(&full.author);
+                fields.categories = decompress(&full.categories);
+                fields.content = decompress(&full.content_text);
             }
-            let title_lower = o_title.unwrap().to_lowercase();
-            rset.matches(&title_lower).matched_any()
+            query.matches(&fields, now)
         })
-        .cloned()
-        .collect();
-    out_list
+        .copied()
+        .collect()
 }
 
-// case insensitive, pattern will be all lower case
-pub fn create_regex_set(pattern: &str) -> RegexSet {
-    let pattern_tolower = pattern.to_lowercase();
-    let o_set: Result<RegexSet, _> = if pattern_tolower.contains('|') {
-        let patterns: Vec<&str> = pattern_tolower.split('|').collect::<Vec<&str>>();
-        RegexSet::new(patterns.as_slice())
-    } else {
-        RegexSet::new([pattern_tolower])
-    };
-    if o_set.is_err() {
-        debug!("create_regex_set:  {:?} ", o_set.err());
-        return RegexSet::empty();
-    }
-    o_set.unwrap()
+/// pango markup: one colored label per tag
+pub fn tag_chips_markup(tags: &[&MessageTag]) -> String {
+    tags.iter()
+        .map(|t| {
+            let color = if is_hex_color(&t.color) {
+                t.color.as_str()
+            } else {
+                "#555753"
+            };
+            format!(
+                "<span background=\"{}\" foreground=\"#ffffff\"> {} </span>",
+                color,
+                t.name
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn is_hex_color(c: &str) -> bool {
+    c.len() == 7 && c.starts_with('#') && c[1..].chars().all(|ch| ch.is_ascii_hexdigit())
 }
 
 #[cfg(test)]
@@ -1279,10 +2130,48 @@ pub mod t {
         assert!(ismatch(hay, "yy|xx|ll"));
     }
 
+    #[test]
+    fn match_post_id_canonical() {
+        let existing = MessageRow {
+            post_id: "https://a.example/post?utm_source=rss".to_string(),
+            title: "T".to_string(),
+            ..Default::default()
+        };
+        let new_fce = MessageRow {
+            post_id: "https://a.example/post".to_string(),
+            title: "T".to_string(),
+            entry_src_date: 1,
+            ..Default::default()
+        };
+        assert_eq!(match_messagerow(&existing, &new_fce), 6);
+    }
+
+    #[test]
+    fn tag_chips() {
+        let t1 = MessageTag {
+            tag_id: 1,
+            name: "R&D <new>".to_string(),
+            color: "#3465a4".to_string(),
+        };
+        let t2 = MessageTag {
+            tag_id: 2,
+            name: "x".to_string(),
+            color: "red\" onclick".to_string(),
+        };
+        assert_eq!(
+            tag_chips_markup(&[&t1, &t2]),
+            "<span background=\"#3465a4\" foreground=\"#ffffff\"> R&amp;D &lt;new&gt; </span> \
+             <span background=\"#555753\" foreground=\"#ffffff\"> x </span>"
+        );
+        assert!(tag_chips_markup(&[]).is_empty());
+    }
+
     fn ismatch(haystack: &str, pattern: &str) -> bool {
-        let rset = create_regex_set(pattern);
-        let hay_tolower = haystack.to_lowercase();
-        let matched = rset.matches(&hay_tolower).matched_any();
+        let fields = MessageFields {
+            title: haystack.to_string(),
+            ..Default::default()
+        };
+        let matched = MessageQuery::parse(pattern).unwrap().matches(&fields, 0);
         println!("{} \t{} \t{:?} ", haystack, pattern, matched);
         matched
     }
@@ -11,23 +11,34 @@ use crate::controller::isourcetree::ISourceTreeController;
 use crate::controller::sourcetree::SJob;
 use crate::controller::sourcetree::SourceTreeController;
 use crate::controller::statusbar::StatusBar;
+use crate::controller::subscriptionmove::FolderSortOrder;
 use crate::controller::subscriptionmove::ISubscriptionMove;
 use crate::controller::subscriptionmove::SubscriptionMove;
+use crate::controller::subscriptionmove::TreeMoveCommand;
 use crate::controller::timer::ITimer;
 use crate::controller::timer::Timer;
 use crate::controller::timer::TimerJob;
+use crate::controller::undo::UndoHistory;
+use crate::controller::virtualnode::VirtualNode;
 use crate::db::errorentry::ESRC;
 use crate::db::errors_repo::ErrorRepo;
 use crate::db::icon_repo::IIconRepo;
 use crate::db::icon_repo::IconRepo;
 use crate::db::icon_row::CompressionType;
 use crate::db::icon_row::IconRow;
+use crate::db::message_rule::MessageRule;
 use crate::db::subscription_entry::SubscriptionEntry;
+use crate::db::subscription_entry::SOURCE_KIND_PAGE_MONITOR;
+use crate::db::subscription_entry::SOURCE_KIND_SCRAPER;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
+use crate::db::subscription_state::FeedSourceState;
 use crate::db::subscription_state::ISubscriptionState;
 use crate::db::subscription_state::StatusMask;
 use crate::downloader::db_clean::CLEAN_STEPS_MAX;
+use crate::downloader::pagemonitor::PageMonitorParams;
+use crate::downloader::rules::check_rule;
+use crate::downloader::scraper::ScraperSelectors;
 use crate::opml::opmlreader::OpmlReader;
 use crate::ui_select::gui_context::GuiContext;
 use crate::ui_select::select::ui_select;
@@ -54,6 +65,8 @@ use resources::gen_icons::ICON_LIST;
 use resources::id::DIALOG_ABOUT;
 use resources::id::*;
 use resources::parameter::DOWNLOAD_TOO_LONG_MS;
+use rust_i18n;
+use rust_i18n::t;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -79,6 +92,8 @@ pub enum Job {
     NotifyConfigChanged,
     /// thread-nr,  job-kind
     DownloaderJobStarted(u8, u8),
+    /// thread-nr,  percent
+    DownloaderJobProgress(u8, u8),
     /// thread-nr, job-kind, elapsed_ms , job-description, remote-addr
     DownloaderJobFinished(isize, u8, u8, u32, String, String),
     CheckFocusMarker(u8),
@@ -107,6 +122,7 @@ pub struct GuiProcessor {
     subscriptionmove_r: Rc<RefCell<dyn ISubscriptionMove>>,
     subscriptionrepo_r: Rc<RefCell<dyn ISubscriptionRepo>>,
     iconrepo_r: Rc<RefCell<dyn IIconRepo>>,
+    undo_r: Rc<RefCell<UndoHistory>>,
     statusbar: StatusBar,
     focus_by_tab: RefCell<FocusByTab>,
     currently_minimized: RefCell<bool>,
@@ -152,6 +168,7 @@ impl GuiProcessor {
             gui_context_r: guicontex_r,
             erro_repo_r: err_rep,
             subscriptionmove_r: (*ac).get_rc::<SubscriptionMove>().unwrap(),
+            undo_r: (*ac).get_rc::<UndoHistory>().unwrap(),
             focus_by_tab: RefCell::new(FocusByTab::None),
             currently_minimized: RefCell::new(false),
             statusbar: status_bar,
@@ -262,6 +279,9 @@ impl GuiProcessor {
                 Job::DownloaderJobStarted(threadnr, kind) => {
                     self.statusbar.set_downloader_kind(threadnr, kind);
                 }
+                Job::DownloaderJobProgress(threadnr, percent) => {
+                    self.statusbar.set_downloader_progress(threadnr, percent);
+                }
                 Job::DownloaderJobFinished(
                     subs_id,
                     threadnr,
@@ -431,6 +451,16 @@ impl GuiProcessor {
             AValue::AU32(fontsize_manual),         // 8 : Font size Manual
             AValue::AU32(browser_conf.browser_bg as u32), // 9 : Browser_BG
             AValue::ABOOL(browser_cache_clear),    // 10 : Browser Cache Cleanup
+            AValue::ABOOL(contentlist_conf.mark_unread_on_update), // 11 : Mark unread on content change
+            AValue::ASTR(contentlist_conf.enclosure_player.clone()), // 12 : Enclosure player
+            AValue::ASTR(downloader_conf.enclosure_folder.clone()), // 13 : Enclosure download folder
+            AValue::AU32(downloader_conf.websub_port as u32),       // 14 : WebSub listener port
+            AValue::ASTR(downloader_conf.websub_callback_base.clone()), // 15 : WebSub callback url
+            AValue::ABOOL(browser_conf.remote_content),             // 16 : load remote images
+            AValue::ASTR(browser_conf.allowed_tags.clone()),        // 17 : allowed html tags
+            AValue::ABOOL(downloader_conf.link_cleaning), // 18 : remove tracking parameters
+            AValue::ASTR(downloader_conf.link_clean_params.clone()), // 19 : additional tracking parameters
+            AValue::AU32((sources_conf).borrow().trash_retention_days), // 20 : trash retention days
         ];
         (*self.gui_val_store)
             .write()
@@ -440,6 +470,71 @@ impl GuiProcessor {
         (*self.gui_updater).borrow().show_dialog(DIALOG_SETTINGS);
     }
 
+    /// takes the latest step from the history and lets its controller revert or repeat it
+    fn undo_redo(&self, redo: bool) {
+        let o_action = match redo {
+            true => self.undo_r.borrow_mut().take_redo(),
+            false => self.undo_r.borrow_mut().take_undo(),
+        };
+        let action = match o_action {
+            Some(a) => a,
+            None => {
+                let key = if redo { "REDO_NOTHING" } else { "UNDO_NOTHING" };
+                self.addjob(Job::AddBottomDisplayErrorMessage(t!(key)));
+                return;
+            }
+        };
+        if action.is_tree_action() {
+            (*self.subscriptionmove_r)
+                .borrow()
+                .apply_undo(&action, redo);
+        } else {
+            (*self.contentlist_r).borrow().apply_undo(&action, redo);
+        }
+        let key = if redo { "REDO_DONE" } else { "UNDO_DONE" };
+        self.addjob(Job::AddBottomDisplayErrorMessage(format!(
+            "{} {}",
+            t!(key),
+            t!(action.name_key())
+        )));
+    }
+
+    /// number of subscriptions,  (id, name) for each,  then the values of each rule
+    fn start_rules_dialog(&self) {
+        let mut entries: Vec<SubscriptionEntry> = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_all_entries()
+            .into_iter()
+            .filter(|se| !se.isdeleted() && se.parent_subs_id >= 0)
+            .collect();
+        entries.sort_by_key(|se| se.display_name.to_lowercase());
+        let mut dd: Vec<AValue> = vec![AValue::AU32(entries.len() as u32)];
+        for se in entries.iter() {
+            dd.push(AValue::AI32(se.subs_id as i32));
+            let name = if se.is_folder {
+                format!("{}/", se.display_name)
+            } else {
+                se.display_name.clone()
+            };
+            dd.push(AValue::ASTR(name));
+        }
+        for rule in (*self.subscriptionrepo_r).borrow().get_rules() {
+            dd.push(AValue::ABOOL(rule.enabled));
+            dd.push(AValue::AI32(rule.field as i32));
+            dd.push(AValue::ABOOL(rule.is_regex));
+            dd.push(AValue::ASTR(rule.pattern));
+            dd.push(AValue::AI32(rule.subscription_id as i32));
+            dd.push(AValue::AI32(rule.action as i32));
+            dd.push(AValue::ASTR(rule.action_arg));
+        }
+        (*self.gui_val_store)
+            .write()
+            .unwrap()
+            .set_dialog_data(DIALOG_RULES, &dd);
+        (*self.gui_updater).borrow().update_dialog(DIALOG_RULES);
+        (*self.gui_updater).borrow().show_dialog(DIALOG_RULES);
+    }
+
     pub fn is_systray_enabled(&self) -> bool {
         (*self.configmanager_r)
             .borrow()
@@ -648,6 +743,11 @@ pub fn dl_char_for_kind(kind: u8) -> char {
         4 => char::from_u32(0x26c1).unwrap(), // DatabaseCleanup : database icon
         5 => char::from_u32(0x21d3).unwrap(), // Drag Url eval : double arrow
         6 => char::from_u32(0x2191).unwrap(), // Launch Browser : arrow up
+        7 => char::from_u32(0x21a7).unwrap(), // Enclosure : arrow down from bar
+        8 => char::from_u32(0x21af).unwrap(), // Article extraction : zigzag arrow down
+        9 => char::from_u32(0x21c4).unwrap(), // WebSub subscribe : arrows left right
+        10 => char::from_u32(0x21b4).unwrap(), // WebSub push : arrow down corner
+        11 => char::from_u32(0x21bb).unwrap(), // Apply rules : circle arrow
         _ => '_',
     };
     nc
@@ -776,7 +876,7 @@ impl StartupWithAppContext for GuiProcessor {
         );
         self.add_handler(
             &GuiEvents::TreeEvent(0, 0, String::default()),
-            HandleTreeEvent(self.feedsources_r.clone()),
+            HandleTreeEvent(self.feedsources_r.clone(), self.subscriptionmove_r.clone()),
         );
         self.add_handler(
             &GuiEvents::TreeDragEvent(0, Vec::default(), Vec::default()),
@@ -802,7 +902,7 @@ impl StartupWithAppContext for GuiProcessor {
         );
         self.add_handler(
             &GuiEvents::ToolBarToggle(String::default(), false),
-            HandleToolBarToggle(),
+            HandleToolBarToggle(self.feedsources_r.clone(), self.subscriptionmove_r.clone()),
         );
         self.add_handler(
             &GuiEvents::ColumnWidth(0, 0),
@@ -823,7 +923,11 @@ impl StartupWithAppContext for GuiProcessor {
         self.add_handler(&GuiEvents::KeyPressed(0, None), HandleKeyPressed());
         self.add_handler(
             &GuiEvents::SearchEntryTextChanged(0, String::default()),
-            HandleSearchEntryTextChanged(self.contentlist_r.clone()),
+            HandleSearchEntryTextChanged(
+                self.contentlist_r.clone(),
+                self.feedsources_r.clone(),
+                self.subscriptionmove_r.clone(),
+            ),
         );
         self.add_handler(
             &GuiEvents::WindowThemeChanged(String::default()),
@@ -919,6 +1023,9 @@ impl HandleSingleEvent for HandleMenuActivate {
                 "M_SETTINGS" => {
                     gp.start_settings_dialog();
                 }
+                "M_RULES" => {
+                    gp.start_rules_dialog();
+                }
                 "M_ABOUT" => {
                     gp.start_about_dialog();
                 }
@@ -928,6 +1035,12 @@ impl HandleSingleEvent for HandleMenuActivate {
                 "M_SHORT_HELP" => {
                     gp.browserpane_r.borrow().display_short_help();
                 }
+                "M_UNDO" => {
+                    gp.undo_redo(false);
+                }
+                "M_REDO" => {
+                    gp.undo_redo(true);
+                }
                 _ => warn!("Menu Unprocessed:{:?} ", s),
             }
         }
@@ -1051,16 +1164,82 @@ impl HandleSingleEvent for HandleDialogData {
                             .r_subm
                             .borrow_mut()
                             .add_new_subscription(s0.clone(), s1.clone());
+                        let selectors = scraper_selectors_from_payload(&payload[2..]);
+                        let monitor = payload.get(7).map(|av| av.boo()).unwrap_or(false);
+                        if new_id > 0 && monitor {
+                            let params = PageMonitorParams {
+                                selector: payload
+                                    .get(8)
+                                    .and_then(|av| av.str())
+                                    .map(|s| s.trim().to_string())
+                                    .unwrap_or_default(),
+                            };
+                            (*self.r_subr).borrow().update_source_kind(
+                                new_id,
+                                SOURCE_KIND_PAGE_MONITOR,
+                                params.to_json(),
+                            );
+                        } else if new_id > 0 && selectors.is_valid() {
+                            (*self.r_subr).borrow().update_source_kind(
+                                new_id,
+                                SOURCE_KIND_SCRAPER,
+                                selectors.to_json(),
+                            );
+                        }
                         if new_id > 0 {
                             self.r_stc
                                 .borrow_mut()
                                 .addjob(SJob::ScheduleUpdateFeed(new_id));
                         }
+                        // 9.. : further selected feed candidates,  feed-url and title
+                        payload
+                            .get(9..)
+                            .unwrap_or_default()
+                            .chunks(2)
+                            .filter_map(|ch| match ch {
+                                [AValue::ASTR(url), AValue::ASTR(title)] => Some((url, title)),
+                                _ => None,
+                            })
+                            .filter(|(url, _)| !url.is_empty() && *url != s0)
+                            .filter(|(url, _)| self.r_subm.borrow().find_duplicate(url).is_none())
+                            .for_each(|(url, title)| {
+                                let display = if title.is_empty() { url } else { title };
+                                let cand_id = self
+                                    .r_subm
+                                    .borrow_mut()
+                                    .add_new_subscription(url.clone(), display.clone());
+                                if cand_id > 0 {
+                                    self.r_stc
+                                        .borrow_mut()
+                                        .addjob(SJob::ScheduleUpdateFeed(cand_id));
+                                }
+                            });
+                    }
+                }
+                "scraper-preview" => {
+                    if let Some(AValue::ASTR(ref page_url)) = payload.first() {
+                        let selectors = scraper_selectors_from_payload(&payload[1..]);
+                        self.r_stc
+                            .borrow_mut()
+                            .newsource_scraper_preview(page_url.clone(), selectors);
                     }
                 }
+                "new-feedsource-show-existing" => {
+                    self.r_stc.borrow().newsource_show_existing();
+                }
                 "import-opml" => {
                     if let Some(AValue::ASTR(ref s)) = payload.first() {
-                        self.r_subm.borrow_mut().import_opml(s.to_string());
+                        let skipped = self.r_subm.borrow_mut().import_opml(s.to_string());
+                        if let Some(existing_id) = skipped.first() {
+                            gp.addjob(Job::AddBottomDisplayErrorMessage(format!(
+                                "{} {}",
+                                t!("OPML_IMPORT_DUPLICATES"),
+                                skipped.len()
+                            )));
+                            self.r_stc
+                                .borrow()
+                                .addjob(SJob::SetCursorToSubsID(*existing_id));
+                        }
                     }
                 }
                 "export-opml" => {
@@ -1121,8 +1300,79 @@ impl HandleSingleEvent for HandleDialogData {
                         &PropDef::BrowserClearCache.to_string(),
                         payload.get(10).unwrap().boo().to_string(), // 10 : browser cache cleanup
                     );
+                    if let Some(av) = payload.get(11) {
+                        self.r_cl
+                            .borrow_mut() // 11 : mark unread on content change
+                            .set_conf_mark_unread_on_update(av.boo());
+                    }
+                    if let Some(player) = payload.get(12).and_then(|av| av.str()) {
+                        self.r_cl
+                            .borrow_mut() // 12 : enclosure player
+                            .set_conf_enclosure_player(player);
+                    }
+                    if let Some(folder) = payload.get(13).and_then(|av| av.str()) {
+                        self.r_dl
+                            .borrow_mut() // 13 : enclosure download folder
+                            .set_conf_enclosure_folder(folder);
+                    }
+                    if let (Some(port), Some(callback_base)) = (
+                        payload.get(14).and_then(|av| av.uint()),
+                        payload.get(15).and_then(|av| av.str()),
+                    ) {
+                        self.r_dl
+                            .borrow_mut() // 14, 15 : WebSub listener port, callback url
+                            .set_conf_websub(port as u16, callback_base);
+                    }
+                    if let Some(av) = payload.get(16) {
+                        self.r_brow
+                            .borrow_mut() // 16 : load remote images
+                            .set_conf_remote_content(av.boo());
+                    }
+                    if let Some(tags) = payload.get(17).and_then(|av| av.str()) {
+                        self.r_brow
+                            .borrow_mut() // 17 : allowed html tags
+                            .set_conf_allowed_tags(tags);
+                    }
+                    if let (Some(enabled), Some(params)) = (
+                        payload.get(18).map(|av| av.boo()),
+                        payload.get(19).and_then(|av| av.str()),
+                    ) {
+                        self.r_dl
+                            .borrow_mut() // 18, 19 : link cleaning
+                            .set_conf_link_cleaning(enabled, params);
+                    }
+                    if let Some(days) = payload.get(20).and_then(|av| av.uint()) {
+                        self.r_stc
+                            .borrow_mut() // 20 : trash retention days
+                            .set_conf_trash_retention_days(days);
+                    }
                     gp.addjob(Job::NotifyConfigChanged);
                 }
+                "rules" => {
+                    let apply_existing = payload.first().map(|av| av.boo()).unwrap_or(false); // 0 : apply to stored messages
+                    let rules = rules_from_payload(&payload[1.min(payload.len())..]);
+                    if let Some((rule, err)) =
+                        rules.iter().find_map(|r| check_rule(r).map(|e| (r, e)))
+                    {
+                        gp.addjob(Job::AddBottomDisplayErrorMessage(format!(
+                            "{} {}: {}",
+                            t!("D_RULES_INVALID"),
+                            rule.pattern,
+                            err
+                        )));
+                        return;
+                    }
+                    (*self.r_subr).borrow().store_rules(&rules);
+                    if apply_existing {
+                        (*self.r_cl).borrow().apply_rules_to_existing();
+                    }
+                }
+                "message-tags" => {
+                    (*self.r_cl).borrow().end_message_tags_dialog(payload);
+                }
+                "saved-search" => {
+                    self.r_stc.borrow().end_saved_search_dialog(payload);
+                }
                 _ => {
                     warn!("other DialogData: {:?}  {:?} ", &ident, payload);
                 }
@@ -1131,6 +1381,42 @@ impl HandleSingleEvent for HandleDialogData {
     }
 }
 
+/// enabled, field, is-regex, pattern, subscription-id, action, action-argument  for each rule
+fn rules_from_payload(payload: &[AValue]) -> Vec<MessageRule> {
+    payload
+        .chunks(DIALOG_RULES_VALUES_PER_RULE)
+        .filter(|c| c.len() == DIALOG_RULES_VALUES_PER_RULE)
+        .map(|c| MessageRule {
+            enabled: c[0].boo(),
+            field: c[1].int().unwrap_or(0) as isize,
+            is_regex: c[2].boo(),
+            pattern: c[3].str().unwrap_or_default(),
+            subscription_id: c[4].int().unwrap_or(0) as isize,
+            action: c[5].int().unwrap_or(0) as isize,
+            action_arg: c[6].str().unwrap_or_default().trim().to_string(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// container, title, link, date, body
+fn scraper_selectors_from_payload(payload: &[AValue]) -> ScraperSelectors {
+    let get = |n: usize| -> String {
+        payload
+            .get(n)
+            .and_then(|av| av.str())
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    ScraperSelectors {
+        container: get(0),
+        title: get(1),
+        link: get(2),
+        date: get(3),
+        body: get(4),
+    }
+}
+
 struct HandleDialogEditData(Rc<RefCell<dyn ISourceTreeController>>);
 impl HandleSingleEvent for HandleDialogEditData {
     fn handle(&self, ev: GuiEvents, _gp: &GuiProcessor) {
@@ -1149,9 +1435,12 @@ impl HandleSingleEvent for HandleDialogEditData {
     }
 }
 
-struct HandleTreeEvent(Rc<RefCell<dyn ISourceTreeController>>);
+struct HandleTreeEvent(
+    Rc<RefCell<dyn ISourceTreeController>>,
+    Rc<RefCell<dyn ISubscriptionMove>>,
+);
 impl HandleSingleEvent for HandleTreeEvent {
-    fn handle(&self, ev: GuiEvents, _gp: &GuiProcessor) {
+    fn handle(&self, ev: GuiEvents, gp: &GuiProcessor) {
         if let GuiEvents::TreeEvent(_tree_nr, subscription_id, ref command) = ev {
             match command.as_str() {
                 "feedsource-delete-dialog" => {
@@ -1174,6 +1463,12 @@ impl HandleSingleEvent for HandleTreeEvent {
                         .borrow_mut()
                         .mark_as_read(subscription_id as isize);
                 }
+                "feedsource-pause-toggle" => {
+                    let toggled = (*self.0).borrow().toggle_paused(subscription_id as isize);
+                    if !toggled {
+                        gp.addjob(Job::AddBottomDisplayErrorMessage(t!("PAUSE_INHERITED")));
+                    }
+                }
                 "new-folder-dialog" => {
                     (*self.0)
                         .borrow_mut()
@@ -1190,6 +1485,66 @@ impl HandleSingleEvent for HandleTreeEvent {
                         .borrow_mut()
                         .start_statistic_dialog(subscription_id as isize);
                 }
+                "saved-search-new-dialog" => {
+                    (*self.0).borrow().start_saved_search_dialog(0);
+                }
+                "saved-search-edit-dialog" => {
+                    if let Some(VirtualNode::Search(search_id)) =
+                        VirtualNode::from_id(subscription_id as isize)
+                    {
+                        (*self.0).borrow().start_saved_search_dialog(search_id);
+                    }
+                }
+                "saved-search-delete" => {
+                    if let Some(VirtualNode::Search(search_id)) =
+                        VirtualNode::from_id(subscription_id as isize)
+                    {
+                        (*self.0).borrow().delete_saved_search(search_id);
+                    }
+                }
+                "trash-restore" => {
+                    if let Some(VirtualNode::TrashEntry(subs_id)) =
+                        VirtualNode::from_id(subscription_id as isize)
+                    {
+                        (*self.1).borrow().restore_from_trash(subs_id);
+                    }
+                }
+                "trash-delete" => {
+                    if let Some(VirtualNode::TrashEntry(subs_id)) =
+                        VirtualNode::from_id(subscription_id as isize)
+                    {
+                        (*self.1).borrow().delete_from_trash(subs_id);
+                    }
+                }
+                "trash-empty" => {
+                    (*self.1).borrow().empty_trash();
+                }
+                name if TreeMoveCommand::from_name(name).is_some() => {
+                    // the keyboard does not know the selection
+                    let subs_id = if subscription_id > 0 {
+                        subscription_id as isize
+                    } else {
+                        match (*self.0).borrow().get_current_selected_subscription() {
+                            Some((fse, _)) => fse.subs_id,
+                            None => return,
+                        }
+                    };
+                    let move_cmd = TreeMoveCommand::from_name(name).unwrap();
+                    if !(*self.1).borrow().move_by_command(subs_id, move_cmd) {
+                        debug!("{:?} not possible for {}", move_cmd, subs_id);
+                    }
+                }
+                "tree-filter-jump" => {
+                    if let Some(subs_id) = (*self.1).borrow().get_tree_filter_match() {
+                        (*self.0).borrow().addjob(SJob::SetCursorToSubsID(subs_id));
+                    }
+                }
+                name if FolderSortOrder::from_name(name).is_some() => {
+                    let order = FolderSortOrder::from_name(name).unwrap();
+                    (*self.1)
+                        .borrow()
+                        .sort_folder(subscription_id as isize, order);
+                }
                 _ => {
                     warn!("unknown command for TreeEvent   {}", command);
                 }
@@ -1219,6 +1574,10 @@ struct HandleTreeExpanded(
 impl HandleSingleEvent for HandleTreeExpanded {
     fn handle(&self, ev: GuiEvents, _gp: &GuiProcessor) {
         if let GuiEvents::TreeExpanded(_idx, repo_id) = ev {
+            // the filtered tree opens the folders only for showing
+            if (*self.1).borrow().is_tree_filter_active() {
+                return;
+            }
             let statemap_rc = (*self.1).borrow().get_state_map();
             (*statemap_rc).borrow_mut().set_status(
                 &[repo_id as isize],
@@ -1239,6 +1598,10 @@ struct HandleTreeCollapsed(
 impl HandleSingleEvent for HandleTreeCollapsed {
     fn handle(&self, ev: GuiEvents, _gp: &GuiProcessor) {
         if let GuiEvents::TreeCollapsed(_idx, repo_id) = ev {
+            // the filtered tree opens the folders only for showing
+            if (*self.1).borrow().is_tree_filter_active() {
+                return;
+            }
             let statemap_rc = (*self.1).borrow().get_state_map();
             (*statemap_rc).borrow_mut().set_status(
                 &[repo_id as isize],
@@ -1294,7 +1657,10 @@ impl HandleSingleEvent for HandleToolBarButton {
     }
 }
 
-struct HandleToolBarToggle();
+struct HandleToolBarToggle(
+    Rc<RefCell<dyn ISourceTreeController>>,
+    Rc<RefCell<dyn ISubscriptionMove>>,
+);
 impl HandleSingleEvent for HandleToolBarToggle {
     fn handle(&self, ev: GuiEvents, _gp: &GuiProcessor) {
         if let GuiEvents::ToolBarToggle(ref id, active) = ev {
@@ -1302,6 +1668,10 @@ impl HandleSingleEvent for HandleToolBarToggle {
                 "special1" => {
                     debug!(" ToolBarToggle special1 {} {} ", id, active);
                 }
+                "tree-filter-unread" => {
+                    (*self.1).borrow().set_tree_filter_unread(active);
+                    keep_tree_selection(&self.0);
+                }
                 _ => {
                     warn!("unknown ToolBarToggle {} ", id);
                 }
@@ -1363,11 +1733,40 @@ impl HandleSingleEvent for HandleKeyPressed {
     }
 }
 
-struct HandleSearchEntryTextChanged(Rc<RefCell<dyn IContentList>>);
+struct HandleSearchEntryTextChanged(
+    Rc<RefCell<dyn IContentList>>,
+    Rc<RefCell<dyn ISourceTreeController>>,
+    Rc<RefCell<dyn ISubscriptionMove>>,
+);
 impl HandleSingleEvent for HandleSearchEntryTextChanged {
-    fn handle(&self, ev: GuiEvents, _gp: &GuiProcessor) {
-        if let GuiEvents::SearchEntryTextChanged(_idx, ref newtext) = ev {
-            self.0.borrow_mut().set_messages_filter(newtext);
+    fn handle(&self, ev: GuiEvents, gp: &GuiProcessor) {
+        if let GuiEvents::SearchEntryTextChanged(idx, ref newtext) = ev {
+            if idx == SEARCH_ENTRY_1 {
+                (*self.2).borrow().set_tree_filter_text(newtext);
+                keep_tree_selection(&self.1);
+                return;
+            }
+            if let Err(e) = self.0.borrow_mut().set_messages_filter(newtext) {
+                gp.addjob(Job::AddBottomDisplayErrorMessage(format!(
+                    "{} {}",
+                    t!("FILTER_QUERY_INVALID"),
+                    e
+                )));
+            }
+        }
+    }
+}
+
+/// the selected subscription stays selected in the filled tree, if it is shown
+fn keep_tree_selection(r_stc: &Rc<RefCell<dyn ISourceTreeController>>) {
+    let stc = (*r_stc).borrow();
+    if let Some((fse, _)) = stc.get_current_selected_subscription() {
+        let filtered_out = stc
+            .get_state(fse.subs_id)
+            .map(|st| st.is_filtered_out())
+            .unwrap_or(true);
+        if !filtered_out {
+            stc.addjob(SJob::SetCursorToSubsID(fse.subs_id));
         }
     }
 }
@@ -0,0 +1,451 @@
+//! Cleans the feed-supplied html before it is handed to the browser view.
+//! Tags outside the allowlist are unwrapped, active content is removed with its children,
+//! attributes are reduced to a harmless set. Remote images are kept back unless allowed,
+//! tracking pixels are always removed.
+
+use std::collections::HashSet;
+use tl::Node;
+
+/// used when the configured list is empty
+pub const DEFAULT_ALLOWED_TAGS: [&str; 51] = [
+    "a",
+    "abbr",
+    "address",
+    "article",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "col",
+    "colgroup",
+    "dd",
+    "del",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "section",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "th",
+    "tr",
+];
+
+/// removed together with their content, whatever the allowlist says
+const REMOVE_WITH_CONTENT: [&str; 22] = [
+    "script", "style", "noscript", "iframe", "frame", "frameset", "object", "embed", "applet",
+    "form", "input", "button", "select", "textarea", "template", "svg", "math", "head", "title",
+    "meta", "link", "base",
+];
+
+const VOID_TAGS: [&str; 5] = ["br", "hr", "img", "col", "wbr"];
+
+const ALLOWED_ATTRIBUTES: [&str; 14] = [
+    "href", "src", "alt", "title", "width", "height", "colspan", "rowspan", "cite", "datetime",
+    "start", "reversed", "lang", "dir",
+];
+
+/// parts of image urls of known tracking services
+const TRACKER_PATTERNS: [&str; 13] = [
+    "doubleclick.net",
+    "google-analytics.com",
+    "googletagmanager.com",
+    "stats.wordpress.com",
+    "pixel.wp.com",
+    "feeds.feedburner.com/~r/",
+    "feedburner.com/~ff/",
+    "feedblitz.com/_/",
+    "facebook.com/tr",
+    "scorecardresearch.com",
+    "quantserve.com",
+    "/pixel.gif",
+    "/tracking/",
+];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SanitizeResult {
+    pub html: String,
+    /// scripts, frames, forms and the like
+    pub removed_elements: usize,
+    /// tracking pixels and images of tracking services
+    pub trackers: usize,
+    /// remote images that were not loaded
+    pub blocked_images: usize,
+}
+
+/// allowed_tags: lower case tag names,  empty for the default list
+pub fn sanitize_html(html: &str, allowed_tags: &[String], allow_remote: bool) -> SanitizeResult {
+    let allowed: HashSet<String> = if allowed_tags.is_empty() {
+        DEFAULT_ALLOWED_TAGS.iter().map(|t| t.to_string()).collect()
+    } else {
+        allowed_tags
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect()
+    };
+    let mut result = SanitizeResult::default();
+    let dom = match tl::parse(html, tl::ParserOptions::default()) {
+        Ok(d) => d,
+        Err(e) => {
+            debug!("sanitize_html: parsing {:?}", e);
+            result.html = crate::util::string_escape_url(html.to_string());
+            return result;
+        }
+    };
+    let parser = dom.parser();
+    let mut san = Sanitizer {
+        allowed: &allowed,
+        allow_remote,
+        result: &mut result,
+        out: String::with_capacity(html.len()),
+    };
+    for handle in dom.children() {
+        if let Some(node) = handle.get(parser) {
+            san.render(node, parser);
+        }
+    }
+    result.html = san.out;
+    result
+}
+
+/// comma or whitespace separated list, as it is stored in the config
+pub fn parse_tag_list(list: &str) -> Vec<String> {
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+struct Sanitizer<'a> {
+    allowed: &'a HashSet<String>,
+    allow_remote: bool,
+    result: &'a mut SanitizeResult,
+    out: String,
+}
+
+impl Sanitizer<'_> {
+    fn render(&mut self, node: &Node, parser: &tl::Parser) {
+        match node {
+            Node::Comment(_) => (),
+            Node::Raw(bytes) => self.out.push_str(&bytes.as_utf8_str()),
+            Node::Tag(tag) => {
+                let name = tag.name().as_utf8_str().to_lowercase();
+                if REMOVE_WITH_CONTENT.contains(&name.as_str()) {
+                    self.result.removed_elements += 1;
+                    if name == "iframe" {
+                        self.frame_link(tag);
+                    }
+                    return;
+                }
+                let attributes: Vec<(String, String)> = tag
+                    .attributes()
+                    .iter()
+                    .filter_map(|(k, o_v)| {
+                        let key = k.to_lowercase();
+                        let value = o_v.map(|v| v.to_string()).unwrap_or_default();
+                        match key.as_str() {
+                            "href" if !is_safe_url(&value, false) => None,
+                            "src" if !is_safe_url(&value, true) => None,
+                            _ if ALLOWED_ATTRIBUTES.contains(&key.as_str()) => Some((key, value)),
+                            _ => None,
+                        }
+                    })
+                    .collect();
+                if name == "img" && !self.image_permitted(&attributes) {
+                    return;
+                }
+                let is_allowed = self.allowed.contains(&name);
+                if is_allowed {
+                    self.out.push('<');
+                    self.out.push_str(&name);
+                    for (key, value) in attributes.iter() {
+                        self.out
+                            .push_str(&format!(" {}=\"{}\"", key, escape_attribute(value)));
+                    }
+                    self.out.push('>');
+                }
+                if VOID_TAGS.contains(&name.as_str()) {
+                    return;
+                }
+                for handle in tag.children().top().iter() {
+                    if let Some(child) = handle.get(parser) {
+                        self.render(child, parser);
+                    }
+                }
+                if is_allowed {
+                    self.out.push_str(&format!("</{name}>"));
+                }
+            }
+        }
+    }
+
+    /// Tracking pixels are dropped silently, remote images are replaced by a marker
+    fn image_permitted(&mut self, attributes: &[(String, String)]) -> bool {
+        let attr = |name: &str| {
+            attributes
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.trim().to_string())
+        };
+        let src = attr("src").unwrap_or_default();
+        let dims: Vec<Option<u32>> = ["width", "height"]
+            .iter()
+            .map(|d| attr(d).and_then(|v| v.trim_end_matches("px").parse::<u32>().ok()))
+            .collect();
+        let is_pixel =
+            dims.iter().any(|d| d.is_some()) && dims.iter().all(|d| d.is_none_or(|v| v <= 1));
+        let src_lower = src.to_lowercase();
+        if is_pixel || TRACKER_PATTERNS.iter().any(|p| src_lower.contains(p)) {
+            self.result.trackers += 1;
+            return false;
+        }
+        if self.allow_remote || src_lower.starts_with("data:") || src.is_empty() {
+            return true;
+        }
+        self.result.blocked_images += 1;
+        let alt = attr("alt").unwrap_or_default();
+        self.out.push_str(&format!(
+            "<span class=\"blocked-image\" title=\"{}\">[{}]</span>",
+            escape_attribute(&src),
+            if alt.is_empty() {
+                "&#x1F5BC;".to_string()
+            } else {
+                escape_attribute(&alt)
+            }
+        ));
+        false
+    }
+
+    /// embedded videos and the like remain reachable by a link
+    fn frame_link(&mut self, tag: &tl::HTMLTag) {
+        if let Some(Some(src)) = tag.attributes().get("src") {
+            let src = src.as_utf8_str().to_string();
+            if is_safe_url(&src, false) && src.starts_with("http") {
+                let esc = escape_attribute(&src);
+                self.out
+                    .push_str(&format!("<p><a href=\"{esc}\">{esc}</a></p>"));
+            }
+        }
+    }
+}
+
+/// character references a browser would decode in an attribute,  the scheme check needs the plain text
+const NAMED_URL_REFERENCES: [(&str, char); 8] = [
+    ("colon", ':'),
+    ("tab", '\t'),
+    ("newline", '\n'),
+    ("amp", '&'),
+    ("sol", '/'),
+    ("period", '.'),
+    ("quest", '?'),
+    ("num", '#'),
+];
+
+/// decodes numeric and the url relevant named references,  the closing ';' is optional like in html
+fn decode_char_refs(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(pos) = rest.find('&') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        let (o_char, consumed) = if let Some(num) = rest.strip_prefix('#') {
+            let (digits, radix, prefix_len) = match num.strip_prefix(['x', 'X']) {
+                Some(hex) => (hex, 16, 2),
+                None => (num, 10, 1),
+            };
+            let len = digits
+                .find(|c: char| !c.is_digit(radix))
+                .unwrap_or(digits.len());
+            let o_c = u32::from_str_radix(&digits[..len], radix)
+                .ok()
+                .and_then(char::from_u32);
+            (o_c, prefix_len + len)
+        } else {
+            let len = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let name = rest[..len].to_lowercase();
+            let o_c = NAMED_URL_REFERENCES
+                .iter()
+                .find(|(n, _c)| *n == name)
+                .map(|(_n, c)| *c);
+            (o_c, len)
+        };
+        match o_char {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[consumed..];
+                if let Some(r) = rest.strip_prefix(';') {
+                    rest = r;
+                }
+            }
+            None => out.push('&'),
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// No scripting urls. Images may use embedded data.
+/// The browser ignores encoded characters, tabs and line breaks in the scheme,  so we do that too.
+fn is_safe_url(raw_url: &str, is_image: bool) -> bool {
+    let url: String = decode_char_refs(raw_url)
+        .chars()
+        .filter(|c| !c.is_ascii_control() && !c.is_whitespace())
+        .collect();
+    let scheme = match url.split_once(':') {
+        Some((s, _)) if !s.contains('/') && !s.contains('?') && !s.contains('#') => {
+            s.to_lowercase()
+        }
+        _ => return true, // relative
+    };
+    match scheme.as_str() {
+        "http" | "https" => true,
+        "mailto" => !is_image,
+        "data" => is_image && url[5..].trim_start().to_lowercase().starts_with("image/"),
+        _ => false,
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  controller::htmlsanitizer::t_::removes_active_content  --lib -- --exact --nocapture "
+    #[test]
+    fn removes_active_content() {
+        let html = r#"<p onclick="x()">Hello <script>alert(1)</script><b style="color:red">world</b></p>
+            <iframe src="https://video.example/embed/1"></iframe><form><input/></form>"#;
+        let r = sanitize_html(html, &[], false);
+        assert!(r.html.starts_with("<p>Hello <b>world</b></p>"));
+        assert!(!r.html.contains("script"));
+        assert!(!r.html.contains("onclick"));
+        assert!(r
+            .html
+            .contains("<a href=\"https://video.example/embed/1\">"));
+        assert_eq!(r.removed_elements, 3);
+    }
+
+    #[test]
+    fn unwraps_unknown_tags() {
+        let r = sanitize_html("<font face=x><p>a<blink>b</blink></p></font>", &[], false);
+        assert_eq!(r.html, "<p>ab</p>");
+        let r = sanitize_html("<p>a<b>b</b></p>", &["p".to_string()], false);
+        assert_eq!(r.html, "<p>ab</p>");
+    }
+
+    #[test]
+    fn unsafe_links() {
+        let r = sanitize_html(
+            r#"<a href="javascript:alert(1)">x</a><a href="https://ok.example/">y</a><a href="/rel">z</a>"#,
+            &[],
+            false,
+        );
+        assert_eq!(
+            r.html,
+            r#"<a>x</a><a href="https://ok.example/">y</a><a href="/rel">z</a>"#
+        );
+    }
+
+    #[test]
+    fn encoded_unsafe_links() {
+        let links = [
+            "javascript&#58;alert(1)",
+            "java&#x73;cript:alert(1)",
+            "javascript&colon;alert(1)",
+            "&#x6A;avascript&#x3A;alert(1)",
+            "java\tscript:alert(1)",
+            "java&#9;script:alert(1)",
+            " &#14;javascript:alert(1)",
+            "vbscript&#58;msgbox(1)",
+            "vb&#115;cript:msgbox(1)",
+            "data&#58;text/html,<script>alert(1)</script>",
+            "d&#97;ta:text/html;base64,PHNjcmlwdD4=",
+        ];
+        for link in links {
+            let r = sanitize_html(&format!("<a href=\"{link}\">x</a>"), &[], false);
+            assert_eq!(r.html, "<a>x</a>", "{link}");
+        }
+        let r = sanitize_html(
+            r#"<img src="data&#58;text/html,x"/><img src="d&#x61;ta:image/png;base64,AAAA"/>"#,
+            &[],
+            true,
+        );
+        assert_eq!(
+            r.html,
+            r#"<img><img src="d&#x61;ta:image/png;base64,AAAA">"#
+        );
+        let r = sanitize_html(
+            r#"<a href="https://ok.example/?a=1&amp;b=2">y</a>"#,
+            &[],
+            false,
+        );
+        assert_eq!(r.html, r#"<a href="https://ok.example/?a=1&amp;b=2">y</a>"#);
+    }
+
+    #[test]
+    fn remote_images_and_trackers() {
+        let html = r#"<p><img src="https://img.example/a.jpg" alt="A"/>
+            <img src="https://x.example/p.gif" width="1" height="1"/>
+            <img src="http://feeds.feedburner.com/~r/foo/~4/abc"/>
+            <img src="data:image/png;base64,AAAA"/></p>"#;
+        let r = sanitize_html(html, &[], false);
+        assert_eq!(r.trackers, 2);
+        assert_eq!(r.blocked_images, 1);
+        assert!(!r.html.contains("<img src=\"https://img.example"));
+        assert!(r.html.contains("[A]"));
+        assert!(r.html.contains("<img src=\"data:image/png"));
+        let r = sanitize_html(html, &[], true);
+        assert_eq!(r.trackers, 2);
+        assert_eq!(r.blocked_images, 0);
+        assert!(r
+            .html
+            .contains("<img src=\"https://img.example/a.jpg\" alt=\"A\">"));
+    }
+
+    #[test]
+    fn tag_list() {
+        assert_eq!(
+            parse_tag_list(" P, b  img,,"),
+            vec!["p".to_string(), "b".to_string(), "img".to_string()]
+        );
+    }
+}
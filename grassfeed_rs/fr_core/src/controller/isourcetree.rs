@@ -8,12 +8,17 @@ use crate::controller::sourcetree::SJob;
 use crate::controller::sourcetree::SourceTreeController;
 use crate::controller::sourcetree::JOBQUEUE_SIZE;
 use crate::controller::subscriptionmove::ISubscriptionMove;
+use crate::controller::virtualnode::VirtualNode;
 use crate::db::messages_repo::IMessagesRepo;
+use crate::db::saved_search::date_to_timestamp;
+use crate::db::saved_search::timestamp_to_date;
+use crate::db::saved_search::SavedSearch;
 use crate::db::subscription_entry::SubscriptionEntry;
 use crate::db::subscription_state::FeedSourceState;
 use crate::db::subscription_state::ISubscriptionState;
 use crate::db::subscription_state::StatusMask;
 use crate::db::subscription_state::SubsMapEntry;
+use crate::downloader::scraper::ScraperSelectors;
 use crate::util::db_time_to_display;
 use crate::util::db_time_to_display_nonnull;
 use crate::util::string_is_http_url;
@@ -21,11 +26,16 @@ use flume::Sender;
 use gui_layer::abstract_ui::AValue;
 use resources::id::DIALOG_FOLDER_EDIT;
 use resources::id::DIALOG_FS_DELETE;
+use resources::id::DIALOG_NEW_SUBSCRIPTION;
+use resources::id::DIALOG_SAVED_SEARCH;
+use resources::id::DIALOG_SAVED_SEARCH_VALUES;
 use resources::id::DIALOG_SUBSCRIPTION_STATISTIC;
 use resources::id::DIALOG_SUBS_EDIT;
 use resources::id::LISTVIEW1;
 use resources::id::TOOLBUTTON_RELOAD_ALL;
 use resources::id::TREEVIEW0;
+use rust_i18n;
+use rust_i18n::t;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -41,12 +51,15 @@ pub trait ISourceTreeController {
     fn set_fetch_in_progress(&self, subscription_id: isize);
     fn set_fetch_finished(&self, subscription_id: isize, error_happened: bool);
     fn mark_as_read(&self, src_repo_id: isize);
+    /// flips the paused flag of the entry. Returns false if the entry is only paused by a parent folder
+    fn toggle_paused(&self, subs_id: isize) -> bool;
 
     fn get_config(&self) -> Rc<RefCell<Config>>;
     fn set_conf_load_on_start(&mut self, n: bool);
     fn set_conf_fetch_interval(&mut self, n: i32);
     fn set_conf_fetch_interval_unit(&mut self, n: i32);
     fn set_conf_display_feedcount_all(&mut self, a: bool);
+    fn set_conf_trash_retention_days(&mut self, days: u32);
     fn notify_config_update(&mut self);
 
     fn start_subscription_edit_dialog(&mut self, source_repo_id: isize);
@@ -54,13 +67,23 @@ pub trait ISourceTreeController {
     fn start_new_fol_sub_dialog(&mut self, src_repo_id: isize, dialog_id: u8);
     fn start_delete_dialog(&mut self, src_repo_id: isize);
     fn newsource_dialog_edit(&mut self, edit_feed_url: String);
+    /// new subscription for a web page without feed: shows the items found in the dialog
+    fn newsource_scraper_preview(&mut self, page_url: String, selectors: ScraperSelectors);
+    /// moves the tree cursor to the subscription the new one would duplicate
+    fn newsource_show_existing(&self);
     fn set_ctx_subscription(&self, src_repo_id: isize);
     fn start_statistic_dialog(&self, subscription_id: isize);
 
+    /// search_id 0 for a new saved search
+    fn start_saved_search_dialog(&self, search_id: isize);
+    fn end_saved_search_dialog(&self, values: &[AValue]);
+    fn delete_saved_search(&self, search_id: isize);
+
     /// returns  Subscription,  Non-Folder-Child-IDs
     fn get_current_selected_subscription(&self) -> Option<(SubscriptionEntry, Vec<i32>)>;
     fn set_selected_message_id(&self, subs_id: isize, msg_id: isize);
     fn get_subs_icon_id(&self, subs_id: isize) -> usize;
+    fn get_subs_display_name(&self, subs_id: isize) -> String;
 
     fn move_to_other_subscription(&self, move_up: bool);
 }
@@ -111,19 +134,21 @@ impl ISourceTreeController for SourceTreeController {
             is_folder = st.is_folder();
         }
         if is_folder {
-            let child_fse: Vec<SubscriptionEntry> =
-                (*self.subscriptionrepo_r).borrow().get_children(subs_id);
-            child_fse
+            let child_ids: Vec<isize> = (*self.subscriptionrepo_r)
+                .borrow()
+                .get_children(subs_id)
                 .iter()
                 .filter(|fse| !fse.is_folder)
-                .for_each(|fse| {
-                    if let Some(feedcontents) = self.feedcontents_w.upgrade() {
-                        (feedcontents)
-                            .borrow_mut()
-                            .set_read_complete_subscription(fse.subs_id);
-                    }
-                    self.statemap.borrow_mut().clear_num_all_unread(fse.subs_id);
-                });
+                .map(|fse| fse.subs_id)
+                .collect();
+            if let Some(feedcontents) = self.feedcontents_w.upgrade() {
+                (feedcontents)
+                    .borrow_mut()
+                    .set_read_complete_subscriptions(&child_ids);
+            }
+            child_ids.iter().for_each(|child_id| {
+                self.statemap.borrow_mut().clear_num_all_unread(*child_id);
+            });
             self.addjob(SJob::ScanEmptyUnread);
             if let Some(feedcontents) = self.feedcontents_w.upgrade() {
                 feedcontents.borrow().addjob(CJob::UpdateMessageList);
@@ -136,6 +161,24 @@ impl ISourceTreeController for SourceTreeController {
         }
     }
 
+    fn toggle_paused(&self, subs_id: isize) -> bool {
+        let o_entry = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        if o_entry.is_none() {
+            return false;
+        }
+        let entry = o_entry.unwrap();
+        if !entry.paused && self.statemap.borrow().is_paused(subs_id) {
+            return false;
+        }
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .update_paused(subs_id, !entry.paused);
+        self.addjob(SJob::UpdateTreePaths);
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+        true
+    }
+
     fn set_tree_expanded(&self, subs_id: isize, new_expanded: bool) {
         let src_vec = vec![subs_id];
         (*self.subscriptionrepo_r)
@@ -203,6 +246,8 @@ impl ISourceTreeController for SourceTreeController {
         }
         self.addjob(SJob::ScanEmptyUnread);
         self.tree_store_update_one(source_repo_id);
+        self.resort_kept_sorted_parent(source_repo_id);
+        self.refilter_unread_only();
     }
 
     fn get_job_sender(&self) -> Sender<SJob> {
@@ -233,6 +278,7 @@ impl ISourceTreeController for SourceTreeController {
         dd.push(AValue::ASTR(subscr.display_name.clone())); // 0  url
         if subscr.is_folder {
             dialog_id = DIALOG_FOLDER_EDIT;
+            dd.push(AValue::AI32(subscr.keep_sorted as i32)); // 1
         } else {
             dd.push(AValue::ASTR(subscr.url.clone())); // 1
             dd.push(iconval); // 2
@@ -249,6 +295,10 @@ impl ISourceTreeController for SourceTreeController {
                 .collect();
             let joined = lines.join("\n");
             dd.push(AValue::ASTR(joined)); // 8 error lines
+            dd.push(AValue::ABOOL(subscr.enclosure_autodl)); // 9
+            dd.push(AValue::AI32(subscr.enclosure_keep as i32)); // 10
+            dd.push(AValue::ABOOL(subscr.full_article)); // 11
+            dd.push(AValue::ABOOL(subscr.remote_content)); // 12
         }
         (*self.gui_val_store)
             .write()
@@ -258,6 +308,115 @@ impl ISourceTreeController for SourceTreeController {
         (*self.gui_updater).borrow().show_dialog(dialog_id);
     }
 
+    /// the values of the search,  number of tags, (id, name) for each,  number of subscriptions, (id, name) for each
+    fn start_saved_search_dialog(&self, search_id: isize) {
+        let search = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_saved_search(search_id)
+            .unwrap_or_default();
+        let mut dd: Vec<AValue> = vec![
+            AValue::AI32(search.search_id as i32),                    // 0
+            AValue::ASTR(search.name),                                // 1
+            AValue::ASTR(search.text),                                // 2
+            AValue::AI32(search.tag_id as i32),                       // 3
+            AValue::ABOOL(search.only_unread),                        // 4
+            AValue::ABOOL(search.only_favorite),                      // 5
+            AValue::AI32(search.age_days as i32),                     // 6
+            AValue::ASTR(timestamp_to_date(search.date_from, false)), // 7
+            AValue::ASTR(timestamp_to_date(search.date_to, true)),    // 8
+            AValue::AI32(search.subscription_id as i32),              // 9
+        ];
+        let tags = match self.messagesrepo_w.upgrade() {
+            Some(msg_r) => msg_r.borrow().get_tags(),
+            None => Vec::default(),
+        };
+        dd.push(AValue::AU32(tags.len() as u32));
+        for tag in tags {
+            dd.push(AValue::AI32(tag.tag_id as i32));
+            dd.push(AValue::ASTR(tag.name));
+        }
+        let mut entries: Vec<SubscriptionEntry> = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_all_entries()
+            .into_iter()
+            .filter(|se| !se.isdeleted() && se.parent_subs_id >= 0)
+            .collect();
+        entries.sort_by_key(|se| se.display_name.to_lowercase());
+        dd.push(AValue::AU32(entries.len() as u32));
+        for se in entries.iter() {
+            dd.push(AValue::AI32(se.subs_id as i32));
+            let name = if se.is_folder {
+                format!("{}/", se.display_name)
+            } else {
+                se.display_name.clone()
+            };
+            dd.push(AValue::ASTR(name));
+        }
+        (*self.gui_val_store)
+            .write()
+            .unwrap()
+            .set_dialog_data(DIALOG_SAVED_SEARCH, &dd);
+        (*self.gui_updater)
+            .borrow()
+            .update_dialog(DIALOG_SAVED_SEARCH);
+        (*self.gui_updater)
+            .borrow()
+            .show_dialog(DIALOG_SAVED_SEARCH);
+    }
+
+    fn end_saved_search_dialog(&self, values: &[AValue]) {
+        if values.len() < DIALOG_SAVED_SEARCH_VALUES {
+            error!("end_saved_search_dialog: too few values {}", values.len());
+            return;
+        }
+        let get_int = |n: usize| values[n].int().unwrap_or(0) as isize;
+        let get_str = |n: usize| values[n].str().unwrap_or_default().trim().to_string();
+        let mut search = SavedSearch {
+            search_id: get_int(0),
+            name: get_str(1),
+            text: get_str(2),
+            tag_id: get_int(3),
+            only_unread: values[4].boo(),
+            only_favorite: values[5].boo(),
+            age_days: get_int(6).max(0),
+            date_from: date_to_timestamp(&get_str(7), false),
+            date_to: date_to_timestamp(&get_str(8), true),
+            subscription_id: get_int(9),
+            ..Default::default()
+        };
+        if search.name.is_empty() {
+            search.name = t!("TREE_VIRTUAL_SEARCH_UNNAMED");
+        }
+        let r = match search.search_id {
+            0 => (*self.subscriptionrepo_r)
+                .borrow()
+                .insert_saved_search(&search),
+            _ => (*self.subscriptionrepo_r)
+                .borrow()
+                .update_saved_search(&search),
+        };
+        if let Err(e) = r {
+            error!("end_saved_search_dialog: {:?}", e);
+            return;
+        }
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+        if let Some(feedcontents) = self.feedcontents_w.upgrade() {
+            (*feedcontents).borrow().addjob(CJob::UpdateMessageList);
+        }
+    }
+
+    fn delete_saved_search(&self, search_id: isize) {
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .delete_saved_search(search_id);
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+        if let Some(feedcontents) = self.feedcontents_w.upgrade() {
+            (*feedcontents).borrow().addjob(CJob::UpdateMessageList);
+        }
+    }
+
     fn start_statistic_dialog(&self, subscription_id: isize) {
         let o_fse = (*self.subscriptionrepo_r)
             .borrow()
@@ -300,6 +459,18 @@ impl ISourceTreeController for SourceTreeController {
             AValue::ASTR(db_time_to_display_nonnull(subscription.updated_ext)), // 7
             AValue::AI32(num_favorites),                                        // 8
         ];
+        let meta = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_feed_meta(subscription_id)
+            .unwrap_or_default();
+        dd.push(AValue::ASTR(meta.title)); // 9 feed title
+        dd.push(AValue::ASTR(meta.description)); // 10
+        dd.push(AValue::ASTR(meta.language)); // 11
+        dd.push(AValue::ASTR(meta.generator)); // 12
+        dd.push(AValue::ASTR(meta.authors)); // 13
+        dd.push(AValue::ASTR(meta.logo)); // 14
+        dd.push(AValue::ASTR(meta.rights)); // 15
+        dd.push(AValue::ASTR(meta.categories)); // 16
         if true {
             let err_list = (*self.erro_repo_r)
                 .borrow()
@@ -327,7 +498,7 @@ impl ISourceTreeController for SourceTreeController {
                 .map(errorentry_to_line)
                 .collect();
             let joined = lines.join("\n");
-            dd.push(AValue::ASTR(joined)); // 8 error lines
+            dd.push(AValue::ASTR(joined)); // 17 error lines
         }
         (*self.gui_updater).borrow().update_list(LISTVIEW1);
         (*self.gui_val_store)
@@ -360,8 +531,19 @@ impl ISourceTreeController for SourceTreeController {
                 .borrow()
                 .update_displayname(subscr.subs_id, newname.to_string());
             self.tree_store_update_one(subscr.subs_id);
+            self.resort_kept_sorted_parent(subscr.subs_id);
         }
-        if !subscr.is_folder {
+        if subscr.is_folder {
+            if let Some(keep_sorted) = values.get(2).and_then(|v| v.int()) {
+                let keep_sorted = keep_sorted.max(0) as isize;
+                if keep_sorted != subscr.keep_sorted {
+                    (*self.subscriptionrepo_r)
+                        .borrow()
+                        .update_keep_sorted(subscr.subs_id, keep_sorted);
+                    self.resort_kept_sorted(subscr.subs_id);
+                }
+            }
+        } else {
             let new_url = values.get(1).unwrap().str().unwrap();
             let new_url = (*new_url).trim();
             if !new_url.is_empty() && subscr.url != new_url {
@@ -370,6 +552,29 @@ impl ISourceTreeController for SourceTreeController {
                     .update_url(subscr.subs_id, new_url.to_string());
                 self.addjob(SJob::ScheduleUpdateFeed(subscr.subs_id));
             }
+            if let (Some(autodl), Some(keep)) = (values.get(2), values.get(3)) {
+                let autodl = autodl.boo();
+                let keep = keep.int().unwrap_or(0).max(0) as isize;
+                if autodl != subscr.enclosure_autodl || keep != subscr.enclosure_keep {
+                    (*self.subscriptionrepo_r)
+                        .borrow()
+                        .update_enclosure_settings(subscr.subs_id, autodl, keep);
+                }
+            }
+            if let Some(full_article) = values.get(4) {
+                if full_article.boo() != subscr.full_article {
+                    (*self.subscriptionrepo_r)
+                        .borrow()
+                        .update_full_article(subscr.subs_id, full_article.boo());
+                }
+            }
+            if let Some(remote_content) = values.get(5) {
+                if remote_content.boo() != subscr.remote_content {
+                    (*self.subscriptionrepo_r)
+                        .borrow()
+                        .update_remote_content(subscr.subs_id, remote_content.boo());
+                }
+            }
             (*self.downloader_r)
                 .borrow()
                 .load_icon(subscr.subs_id, subscr.url, subscr.icon_id);
@@ -473,6 +678,21 @@ impl ISourceTreeController for SourceTreeController {
         self.addjob(SJob::SetGuiTreeColumn1Width);
     }
 
+    fn set_conf_trash_retention_days(&mut self, days: u32) {
+        (*self.config).borrow_mut().trash_retention_days = days;
+        (*self.configmanager_r).borrow().set_val(
+            SourceTreeController::CONF_TRASH_RETENTION_DAYS,
+            days.to_string(),
+        );
+    }
+
+    fn newsource_show_existing(&self) {
+        let existing_id = self.new_source.borrow().duplicate_of;
+        if existing_id > 0 {
+            self.addjob(SJob::SetCursorToSubsID(existing_id));
+        }
+    }
+
     fn newsource_dialog_edit(&mut self, edit_feed_url: String) {
         if edit_feed_url != self.new_source.borrow().edit_url {
             self.new_source.borrow_mut().edit_url = edit_feed_url.trim().to_string();
@@ -485,6 +705,32 @@ impl ISourceTreeController for SourceTreeController {
         }
     }
 
+    fn newsource_scraper_preview(&mut self, page_url: String, selectors: ScraperSelectors) {
+        let page_url = page_url.trim().to_string();
+        if !string_is_http_url(&page_url) {
+            return;
+        }
+        self.new_source.borrow_mut().edit_url.clone_from(&page_url);
+        self.new_source.borrow_mut().state = NewSourceState::Requesting;
+        let dd: Vec<AValue> = vec![
+            AValue::None,        // 0:display
+            AValue::None,        // 1:homepage
+            AValue::None,        // 2: icon_str
+            AValue::ABOOL(true), // 3 :spinner
+            AValue::None,        // 4: feed url
+        ];
+        (*self.gui_val_store)
+            .write()
+            .unwrap()
+            .set_dialog_data(DIALOG_NEW_SUBSCRIPTION, &dd);
+        (*self.gui_updater)
+            .borrow()
+            .update_dialog(DIALOG_NEW_SUBSCRIPTION);
+        (*self.downloader_r)
+            .borrow()
+            .scraper_preview_request(&page_url, selectors);
+    }
+
     fn notify_config_update(&mut self) {
         (*self.config).borrow_mut().tree_fontsize =
             get_font_size_from_config(self.configmanager_r.clone()) as u8;
@@ -493,6 +739,18 @@ impl ISourceTreeController for SourceTreeController {
     }
 
     fn set_ctx_subscription(&self, src_repo_id: isize) {
+        if let Some(node) = VirtualNode::from_id(src_repo_id) {
+            let title = self.get_virtual_display_name(&node);
+            if let Some(gui_context) = self.gui_context_w.upgrade() {
+                (*gui_context).borrow_mut().set_window_title(title);
+            }
+            (*self.gui_updater)
+                .borrow()
+                .toolbutton_set_sensitive(TOOLBUTTON_RELOAD_ALL, false);
+            self.current_selected_subscription.replace(None);
+            self.tree_filter.borrow_mut().keep_visible = 0;
+            return;
+        }
         let o_fse = (*self.subscriptionrepo_r)
             .borrow()
             .get_by_index(src_repo_id);
@@ -517,6 +775,7 @@ impl ISourceTreeController for SourceTreeController {
             (*self.gui_updater)
                 .borrow()
                 .toolbutton_set_sensitive(TOOLBUTTON_RELOAD_ALL, activate_reloadbutton);
+            self.tree_filter.borrow_mut().keep_visible = fse.subs_id;
             self.current_selected_subscription
                 .replace(Some((fse, child_ids)));
         }
@@ -595,4 +854,11 @@ impl ISourceTreeController for SourceTreeController {
     fn get_subs_icon_id(&self, subs_id: isize) -> usize {
         self.statemap.borrow().get_icon_id(subs_id)
     }
+
+    fn get_subs_display_name(&self, subs_id: isize) -> String {
+        match (*self.subscriptionrepo_r).borrow().get_by_index(subs_id) {
+            Some(entry) => entry.display_name,
+            None => String::default(),
+        }
+    }
 }
@@ -0,0 +1,542 @@
+//! Query language of the message filter box.
+//!
+//!   rust  "exact words"  author:smith  cat:linux  host:example.org  content:release  title:news
+//!   is:unread  is:read  is:fav  after:2026-01-01  before:2026-02-01  age:<7d  age:>12h
+//!   a AND b   a OR b   NOT a   -a   ( a OR b ) c
+//!
+//! Unquoted words are case-insensitive regular expressions, quoted ones are plain text.
+//! Adjacent terms are combined with AND, which binds stronger than OR.
+
+use crate::db::saved_search::date_to_timestamp;
+use regex::Regex;
+use regex::RegexBuilder;
+use std::fmt;
+use url::Url;
+
+const SECONDS_PER_UNIT: [(char, i64); 4] = [('m', 60), ('h', 3600), ('d', 86400), ('w', 604800)];
+
+/// The message values a query is evaluated on, decompressed
+#[derive(Debug, Clone, Default)]
+pub struct MessageFields {
+    pub title: String,
+    pub author: String,
+    pub categories: String,
+    pub content: String,
+    pub link: String,
+    pub is_read: bool,
+    pub is_favorite: bool,
+    pub entry_date: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    /// character position in the query text
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (@{})", self.message, self.position + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Author,
+    Categories,
+    Content,
+    Host,
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    /// lower case
+    Plain(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Plain(p) => text.to_lowercase().contains(p.as_str()),
+            Matcher::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Text(Field, Matcher),
+    Unread,
+    Read,
+    Favorite,
+    /// timestamp, inclusive
+    After(i64),
+    /// timestamp, exclusive
+    Before(i64),
+    /// seconds
+    YoungerThan(i64),
+    OlderThan(i64),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Term(Term),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, m: &MessageFields, now: i64) -> bool {
+        match self {
+            Expr::Term(t) => eval_term(t, m, now),
+            Expr::Not(e) => !e.eval(m, now),
+            Expr::And(a, b) => a.eval(m, now) && b.eval(m, now),
+            Expr::Or(a, b) => a.eval(m, now) || b.eval(m, now),
+        }
+    }
+
+    fn needs_details(&self) -> bool {
+        match self {
+            Expr::Term(Term::Text(field, _)) => {
+                matches!(field, Field::Author | Field::Categories | Field::Content)
+            }
+            Expr::Term(_) => false,
+            Expr::Not(e) => e.needs_details(),
+            Expr::And(a, b) | Expr::Or(a, b) => a.needs_details() || b.needs_details(),
+        }
+    }
+}
+
+fn eval_term(term: &Term, m: &MessageFields, now: i64) -> bool {
+    match term {
+        Term::Text(field, matcher) => match field {
+            Field::Title => matcher.is_match(&m.title),
+            Field::Author => matcher.is_match(&m.author),
+            Field::Categories => matcher.is_match(&m.categories),
+            Field::Content => matcher.is_match(&m.content),
+            Field::Host => match Url::parse(&m.link) {
+                Ok(u) => matcher.is_match(u.host_str().unwrap_or_default()),
+                Err(_) => false,
+            },
+        },
+        Term::Unread => !m.is_read,
+        Term::Read => m.is_read,
+        Term::Favorite => m.is_favorite,
+        Term::After(ts) => m.entry_date >= *ts,
+        Term::Before(ts) => m.entry_date < *ts,
+        Term::YoungerThan(secs) => m.entry_date > now.saturating_sub(*secs),
+        Term::OlderThan(secs) => m.entry_date <= now.saturating_sub(*secs),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    /// prefix, value, quoted
+    Word(Option<String>, String, bool),
+}
+
+/// token with its start position
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens: Vec<(usize, Token)> = Vec::default();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        match c {
+            '(' => {
+                tokens.push((start, Token::LParen));
+                i += 1;
+                continue;
+            }
+            ')' => {
+                tokens.push((start, Token::RParen));
+                i += 1;
+                continue;
+            }
+            '-' if i + 1 < chars.len() && !chars[i + 1].is_whitespace() => {
+                tokens.push((start, Token::Not));
+                i += 1;
+                continue;
+            }
+            _ => (),
+        }
+        let mut word = String::default();
+        let mut quoted: Option<String> = None;
+        let mut depth = 0;
+        while i < chars.len() {
+            let ch = chars[i];
+            if ch == '"' && (word.is_empty() || word.ends_with(':')) {
+                let (q, next) = read_quoted(&chars, i)?;
+                quoted = Some(q);
+                i = next;
+                break;
+            }
+            if ch.is_whitespace() || (ch == ')' && depth == 0) {
+                break;
+            }
+            match ch {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => (),
+            }
+            word.push(ch);
+            i += 1;
+        }
+        let token = match (word.as_str(), quoted) {
+            ("AND", None) => Token::And,
+            ("OR", None) => Token::Or,
+            ("NOT", None) => Token::Not,
+            (_, Some(q)) => match word.strip_suffix(':') {
+                Some(prefix) => Token::Word(Some(prefix.to_string()), q, true),
+                None => Token::Word(None, q, true),
+            },
+            (_, None) => match word.split_once(':') {
+                Some((prefix, value)) if is_known_prefix(prefix) => {
+                    Token::Word(Some(prefix.to_string()), value.to_string(), false)
+                }
+                _ => Token::Word(None, word.clone(), false),
+            },
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// text between the quotes at  pos,  backslash escapes the next character. Returns the position after it
+fn read_quoted(chars: &[char], pos: usize) -> Result<(String, usize), QueryError> {
+    let mut s = String::default();
+    let mut i = pos + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                s.push(chars[i + 1]);
+                i += 2;
+            }
+            '"' => return Ok((s, i + 1)),
+            ch => {
+                s.push(ch);
+                i += 1;
+            }
+        }
+    }
+    Err(QueryError {
+        position: pos,
+        message: "missing closing quote".to_string(),
+    })
+}
+
+fn is_known_prefix(prefix: &str) -> bool {
+    matches!(
+        prefix,
+        "title" | "author" | "cat" | "host" | "content" | "is" | "after" | "before" | "age"
+    )
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    text_len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(p, _)| *p)
+            .unwrap_or(self.text_len)
+    }
+
+    fn error<T>(&self, message: &str) -> Result<T, QueryError> {
+        Err(QueryError {
+            position: self.position(),
+            message: message.to_string(),
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut left = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                Some(Token::Or) | Some(Token::RParen) | None => return Ok(left),
+                _ => (),
+            }
+            let right = self.parse_unary()?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        let start = self.position();
+        match self.tokens.get(self.pos).map(|(_, t)| t.clone()) {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(QueryError {
+                        position: start,
+                        message: "missing closing parenthesis".to_string(),
+                    });
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(Token::Word(prefix, value, quoted)) => {
+                let term = parse_term(prefix.as_deref(), &value, quoted).map_err(|message| {
+                    QueryError {
+                        position: start,
+                        message,
+                    }
+                })?;
+                self.pos += 1;
+                Ok(Expr::Term(term))
+            }
+            Some(Token::RParen) => self.error("unexpected closing parenthesis"),
+            Some(Token::And) | Some(Token::Or) => self.error("missing term before operator"),
+            None => self.error("missing term"),
+        }
+    }
+}
+
+fn parse_term(prefix: Option<&str>, value: &str, quoted: bool) -> Result<Term, String> {
+    let text_field = |field: Field| -> Result<Term, String> {
+        if value.is_empty() {
+            return Err("empty value".to_string());
+        }
+        if quoted {
+            return Ok(Term::Text(field, Matcher::Plain(value.to_lowercase())));
+        }
+        RegexBuilder::new(value)
+            .case_insensitive(true)
+            .build()
+            .map(|re| Term::Text(field, Matcher::Regex(re)))
+            .map_err(|e| format!("invalid regular expression: {}", last_line(&e.to_string())))
+    };
+    match prefix {
+        None | Some("title") => text_field(Field::Title),
+        Some("author") => text_field(Field::Author),
+        Some("cat") => text_field(Field::Categories),
+        Some("content") => text_field(Field::Content),
+        Some("host") => text_field(Field::Host),
+        Some("is") => match value.to_lowercase().as_str() {
+            "unread" => Ok(Term::Unread),
+            "read" => Ok(Term::Read),
+            "fav" | "favorite" => Ok(Term::Favorite),
+            _ => Err(format!("unknown state '{value}', use unread, read or fav")),
+        },
+        Some("after") => parse_date(value).map(Term::After),
+        Some("before") => parse_date(value).map(Term::Before),
+        Some("age") => parse_age(value),
+        Some(other) => Err(format!("unknown field '{other}'")),
+    }
+}
+
+fn parse_date(value: &str) -> Result<i64, String> {
+    match date_to_timestamp(value, false) {
+        0 => Err(format!("invalid date '{value}', use YYYY-MM-DD")),
+        ts => Ok(ts),
+    }
+}
+
+/// <7d  >12h   a missing comparison means younger than
+fn parse_age(value: &str) -> Result<Term, String> {
+    let (older, rest) = match value.chars().next() {
+        Some('>') => (true, &value[1..]),
+        Some('<') => (false, &value[1..]),
+        _ => (false, value),
+    };
+    let o_unit = rest.chars().last().and_then(|u| {
+        SECONDS_PER_UNIT
+            .iter()
+            .find(|(c, _)| *c == u.to_ascii_lowercase())
+    });
+    let o_num = rest
+        .get(..rest.len().saturating_sub(1))
+        .and_then(|n| n.parse::<i64>().ok());
+    let o_span = match (o_num, o_unit) {
+        (Some(num), Some((_, secs))) if num >= 0 => num.checked_mul(*secs),
+        _ => None,
+    };
+    match o_span {
+        Some(span) => Ok(match older {
+            true => Term::OlderThan(span),
+            false => Term::YoungerThan(span),
+        }),
+        _ => Err(format!(
+            "invalid age '{value}', use for example <7d  >12h  <2w"
+        )),
+    }
+}
+
+/// the regex error messages end with the reason
+fn last_line(s: &str) -> String {
+    s.lines().last().unwrap_or_default().trim().to_string()
+}
+
+/// A parsed filter query
+#[derive(Debug, Clone)]
+pub struct MessageQuery {
+    expr: Expr,
+}
+
+impl MessageQuery {
+    pub fn parse(text: &str) -> Result<MessageQuery, QueryError> {
+        let tokens = tokenize(text)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            text_len: text.chars().count(),
+        };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return parser.error("unexpected closing parenthesis");
+        }
+        Ok(MessageQuery { expr })
+    }
+
+    /// author, categories or content are used, which the message list does not hold
+    pub fn needs_details(&self) -> bool {
+        self.expr.needs_details()
+    }
+
+    pub fn matches(&self, fields: &MessageFields, now: i64) -> bool {
+        self.expr.eval(fields, now)
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn message() -> MessageFields {
+        MessageFields {
+            title: "Rust 1.80 Released".to_string(),
+            author: "Jane Smith".to_string(),
+            categories: "programming, rust".to_string(),
+            content: "The new version brings LazyCell".to_string(),
+            link: "https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html".to_string(),
+            is_read: false,
+            is_favorite: true,
+            entry_date: NOW - 3 * 86400,
+        }
+    }
+
+    fn is_match(query: &str) -> bool {
+        MessageQuery::parse(query).unwrap().matches(&message(), NOW)
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  controller::messagefilter::t::plain_terms  --lib -- --exact --nocapture "
+    #[test]
+    fn plain_terms() {
+        assert!(is_match("rust"));
+        assert!(is_match("RUST released"));
+        assert!(!is_match("rust python"));
+        assert!(is_match("python|released"));
+        assert!(is_match("\"1.80 rel\""));
+        assert!(!is_match("\"1.80  rel\""));
+        assert!(is_match("^rust\\s1\\.8"));
+        assert!(is_match("(python|go|rust)"));
+    }
+
+    #[test]
+    fn fields_and_states() {
+        assert!(is_match("author:smith"));
+        assert!(is_match("author:\"jane smith\""));
+        assert!(!is_match("author:doe"));
+        assert!(is_match("cat:programming"));
+        assert!(is_match("host:rust-lang.org"));
+        assert!(!is_match("host:released"));
+        assert!(is_match("content:lazycell"));
+        assert!(is_match("title:released"));
+        assert!(is_match("is:unread is:fav"));
+        assert!(!is_match("is:read"));
+        assert!(is_match("age:<7d"));
+        assert!(!is_match("age:<2d"));
+        assert!(is_match("age:>48h"));
+        assert!(!is_match("age:>9999999999999d"));
+        assert!(is_match("http://x.org|rust"));
+        assert!(MessageQuery::parse("author:x").unwrap().needs_details());
+        assert!(!MessageQuery::parse("host:x is:fav rust")
+            .unwrap()
+            .needs_details());
+    }
+
+    #[test]
+    fn dates() {
+        let mut m = message();
+        m.entry_date = date_to_timestamp("2026-01-15", false) + 3600;
+        let q = |s: &str| MessageQuery::parse(s).unwrap().matches(&m, NOW);
+        assert!(q("after:2026-01-01"));
+        assert!(q("after:2026-01-15"));
+        assert!(!q("after:2026-01-16"));
+        assert!(q("before:2026-01-16"));
+        assert!(!q("before:2026-01-15"));
+    }
+
+    #[test]
+    fn boolean_operators() {
+        assert!(is_match("python OR rust"));
+        assert!(!is_match("python OR java"));
+        assert!(is_match("NOT python"));
+        assert!(is_match("-python rust"));
+        assert!(!is_match("-rust"));
+        assert!(is_match("rust AND NOT is:read"));
+        // AND binds stronger than OR
+        assert!(is_match("python java OR rust"));
+        assert!(!is_match("python ( java OR rust )"));
+        assert!(is_match("(python OR rust) (is:fav OR is:read)"));
+        // lower case operators are words
+        assert!(!is_match("rust and"));
+    }
+
+    #[test]
+    fn errors() {
+        let err = |s: &str| MessageQuery::parse(s).unwrap_err();
+        assert_eq!(err("rust \"open").position, 5);
+        assert_eq!(err("(rust OR go").position, 0);
+        assert_eq!(err("rust )").position, 5);
+        assert_eq!(err("rust OR").position, 7);
+        assert_eq!(err("OR rust").position, 0);
+        assert!(err("is:new").message.contains("unknown state"));
+        assert!(err("after:yesterday").message.contains("invalid date"));
+        assert!(err("age:<7y").message.contains("invalid age"));
+        assert!(err("age:<999999999999999w").message.contains("invalid age"));
+        assert!(err("rust [a").message.contains("regular expression"));
+        assert_eq!(err("author:").message, "empty value".to_string());
+        assert!(MessageQuery::parse("").is_err());
+    }
+}
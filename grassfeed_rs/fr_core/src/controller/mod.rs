@@ -2,8 +2,13 @@ pub mod browserpane;
 pub mod contentdownloader;
 pub mod contentlist;
 pub mod guiprocessor;
+pub mod htmlsanitizer;
 pub mod isourcetree;
+pub mod messagefilter;
 pub mod sourcetree;
 pub mod statusbar;
 pub mod subscriptionmove;
 pub mod timer;
+pub mod treefilter;
+pub mod undo;
+pub mod virtualnode;
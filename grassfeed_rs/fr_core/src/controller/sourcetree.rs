@@ -9,12 +9,17 @@ use crate::controller::subscriptionmove::ISubscriptionMove;
 use crate::controller::subscriptionmove::SubscriptionMove;
 use crate::controller::timer::ITimer;
 use crate::controller::timer::Timer;
+use crate::controller::treefilter::TreeFilter;
+use crate::controller::virtualnode::VirtualNode;
 use crate::db::errorentry::ErrorEntry;
 use crate::db::errors_repo::ErrorRepo;
 use crate::db::icon_repo::IIconRepo;
 use crate::db::icon_repo::IconRepo;
+use crate::db::messages_repo::IMessagesRepo;
 use crate::db::messages_repo::MessagesRepo;
+use crate::db::saved_search::SavedSearch;
 use crate::db::subscription_entry::SubscriptionEntry;
+use crate::db::subscription_entry::SRC_REPO_ID_DELETED;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
 use crate::db::subscription_state::FeedSourceState;
@@ -22,6 +27,7 @@ use crate::db::subscription_state::ISubscriptionState;
 use crate::db::subscription_state::StatusMask;
 use crate::db::subscription_state::SubsMapEntry;
 use crate::db::subscription_state::SubscriptionState;
+use crate::downloader::util::FeedCandidate;
 use crate::ui_select::gui_context::GuiContext;
 use crate::util::db_time_to_display;
 use crate::util::timestamp_now;
@@ -49,6 +55,7 @@ use resources::gen_icons::IDX_14_ICON_DOWNLOAD_64;
 use resources::gen_icons::IDX_16_DOCUMENT_PROPERTIES_48;
 use resources::gen_icons::IDX_30_ERROR_24X24;
 use resources::gen_icons::IDX_32_FLAG_RED_32;
+use resources::gen_icons::IDX_36_ZOOM_IN;
 use resources::gen_icons::IDX_44_ICON_GREEN_D;
 use resources::id::*;
 use resources::parameter::CHECK_MESSAGE_COUNTS_SET_SIZE;
@@ -95,8 +102,7 @@ pub enum SJob {
     DatabasesCleanup,
     /// subscription_id, timestamp_feed_update,  timestamp_creation
     StoreFeedCreateUpdate(isize, i64, i64),
-    ///  feed-url,  Display-Name, icon-id, Feed-Homepage
-    NewFeedSourceEdit(String, String, isize, String),
+    NewFeedSourceEdit(NewSourceResult),
     /// subscription_id, content_repo_id
     UpdateLastSelectedMessageId(isize, isize),
     UpdateTreePaths,
@@ -106,13 +112,15 @@ pub enum SJob {
     NotifyMessagesCountsChecked(isize, bool, isize, isize),
     ScanEmptyUnread,
     EmptyTreeCreateDefaultSubscriptions,
-    ///  Drag-String   Feed-Url   Error-Message,   Home-Page-Title
-    DragUrlEvaluated(String, String, String, String),
+    ///  Drag-String   Feed-Url   Error-Message,   Home-Page-Title,  Feed candidates
+    DragUrlEvaluated(String, String, String, String, Vec<FeedCandidate>),
     /// subs_id
     SetCursorToSubsID(isize),
     SetGuiTreeColumn1Width,
     /// subs_id
     CheckIconOutdated(isize),
+    /// counts of the entries below the subscriptions
+    GuiUpdateVirtualEntries,
 }
 
 /// needs  GuiContext SubscriptionRepo ConfigManager IconRepo
@@ -140,6 +148,9 @@ pub struct SourceTreeController {
     any_spinner_visible: RefCell<bool>,
     pub(super) new_source: RefCell<NewSourceTempData>,
     pub(super) statemap: Rc<RefCell<SubscriptionState>>, // moved over
+    pub(super) tree_filter: Rc<RefCell<TreeFilter>>,     // moved over
+    /// tree paths of the entries below the subscriptions
+    pub(super) virtual_paths: RefCell<Vec<(VirtualNode, Vec<u16>)>>,
 }
 
 impl SourceTreeController {
@@ -147,6 +158,8 @@ impl SourceTreeController {
     pub const CONF_FETCH_INTERVAL: &'static str = "FetchFeedsInterval";
     pub const CONF_FETCH_INTERVAL_UNIT: &'static str = "FetchFeedsIntervalUnit";
     pub const CONF_DISPLAY_FEEDCOUNT_ALL: &'static str = "DisplayFeedCountAll";
+    pub const CONF_TRASH_RETENTION_DAYS: &'static str = "TrashRetentionDays";
+    pub const CONF_TRASH_RETENTION_DAYS_DEFAULT: i32 = 30;
 
     pub fn new_ac(ac: &AppContext) -> Self {
         let gc_r = (*ac).get_rc::<GuiContext>().unwrap();
@@ -199,8 +212,10 @@ impl SourceTreeController {
             messagesrepo_w: Weak::new(),
             subscriptionmove_w: Weak::new(),
             statemap: Default::default(),
+            tree_filter: Default::default(),
             erro_repo_r: err_rep,
             currently_minimized: false,
+            virtual_paths: Default::default(),
         }
     }
 
@@ -217,6 +232,9 @@ impl SourceTreeController {
             match job {
                 SJob::NotifyTreeReadCount(subs_id, msg_all, msg_unread) => {
                     self.process_tree_read_count(subs_id, msg_all, msg_unread);
+                    if !self.virtual_paths.borrow().is_empty() {
+                        self.addjob(SJob::GuiUpdateVirtualEntries);
+                    }
                 }
                 SJob::UpdateTreePaths => {
                     if let Some(subs_mov) = self.subscriptionmove_w.upgrade() {
@@ -293,13 +311,8 @@ impl SourceTreeController {
                         .borrow()
                         .update_timestamps(src_id, update_now, o_creation);
                 }
-                SJob::NewFeedSourceEdit(ref feed_url, ref display, icon_id, ref homepage) => {
-                    self.process_newsource_request_done(
-                        feed_url.clone(),
-                        display.clone(),
-                        icon_id,
-                        homepage.clone(),
-                    );
+                SJob::NewFeedSourceEdit(ref result) => {
+                    self.process_newsource_request_done(result);
                 }
                 SJob::UpdateLastSelectedMessageId(fs_id, fc_id) => {
                     (*self.subscriptionrepo_r)
@@ -327,12 +340,19 @@ impl SourceTreeController {
                         subs_mov.borrow_mut().empty_create_default_subscriptions();
                     }
                 }
-                SJob::DragUrlEvaluated(ref dragged, ref feed_url, ref err_msg, ref hp_title) => {
+                SJob::DragUrlEvaluated(
+                    ref dragged,
+                    ref feed_url,
+                    ref err_msg,
+                    ref hp_title,
+                    ref candidates,
+                ) => {
                     self.process_drag_url_eval(
                         dragged.to_string(),
                         feed_url.to_string(),
                         err_msg.to_string(),
                         hp_title.to_string(),
+                        candidates,
                     );
                 }
                 SJob::SetCursorToSubsID(subs_id) => {
@@ -383,6 +403,9 @@ impl SourceTreeController {
                 SJob::CheckIconOutdated(subs_id) => {
                     self.check_icon_outdated(subs_id);
                 }
+                SJob::GuiUpdateVirtualEntries => {
+                    self.update_virtual_rows();
+                }
             }
             if (*self.config).borrow().mode_debug {
                 let elapsed_m = now.elapsed().as_millis();
@@ -399,6 +422,7 @@ impl SourceTreeController {
         feed_url: String,
         err_msg: String,
         hp_title: String,
+        candidates: &[FeedCandidate],
     ) {
         if !err_msg.is_empty() {
             debug!(
@@ -412,13 +436,17 @@ impl SourceTreeController {
             AValue::ASTR(hp_title)
         };
         if !feed_url.is_empty() {
-            let dd: Vec<AValue> = vec![
+            let av_dup = self.newsource_check_duplicate(&[&feed_url]);
+            let mut dd: Vec<AValue> = vec![
                 AValue::None,           // 0:display
                 av_ti,                  // 1: homepage
                 AValue::None,           // 2: icon_str
                 AValue::ABOOL(true),    // 3 :spinner
                 AValue::ASTR(feed_url), // 4: feed url
+                AValue::None,           // 5: scraper preview
+                av_dup,                 // 6: duplicate warning
             ];
+            append_feed_candidates(&mut dd, candidates);
             (*self.gui_val_store)
                 .write()
                 .unwrap()
@@ -448,6 +476,9 @@ impl SourceTreeController {
                     .clear_num_all_unread(subs_e.parent_subs_id);
                 self.addjob(SJob::ScanEmptyUnread);
             }
+            if !subs_e.is_folder && su_st.is_filtered_out() == (msg_unread > 0) {
+                self.refilter_unread_only();
+            }
             if !self.tree_update_one(&subs_e, &su_st) {
                 if let Some(subs_mov) = self.subscriptionmove_w.upgrade() {
                     let smb = subs_mov.borrow();
@@ -494,10 +525,192 @@ impl SourceTreeController {
     ///  Read all sources   from db and put into ModelValueAdapter
     pub fn subscriptions_to_gui_adapter(&self) {
         (*self.gui_val_store).write().unwrap().clear_tree(0);
-        self.insert_tree_row(&Vec::<u16>::default(), 0);
+        let num_top = self.insert_tree_row(&Vec::<u16>::default(), 0);
+        self.insert_virtual_rows(num_top as u16);
         self.addjob(SJob::CheckSpinnerActive);
     }
 
+    /// The saved searches, the tags folder with one entry per tag and the trash, below the subscriptions
+    fn insert_virtual_rows(&self, num_top: u16) {
+        self.virtual_paths.borrow_mut().clear();
+        let tags = match self.messagesrepo_w.upgrade() {
+            Some(msg_r) => msg_r.borrow().get_tags(),
+            None => return,
+        };
+        let searches = (*self.subscriptionrepo_r).borrow().get_saved_searches();
+        let mut entries: Vec<(VirtualNode, Vec<u16>)> = Vec::default();
+        let mut folder_pos = num_top;
+        if !searches.is_empty() {
+            entries.push((VirtualNode::Searches, vec![folder_pos]));
+            searches.iter().enumerate().for_each(|(n, search)| {
+                entries.push((
+                    VirtualNode::Search(search.search_id),
+                    vec![folder_pos, n as u16],
+                ));
+            });
+            folder_pos += 1;
+        }
+        if !tags.is_empty() {
+            entries.push((VirtualNode::Tags, vec![folder_pos]));
+            tags.iter().enumerate().for_each(|(n, tag)| {
+                entries.push((VirtualNode::Tag(tag.tag_id), vec![folder_pos, n as u16]));
+            });
+            folder_pos += 1;
+        }
+        let trashed = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_children(SRC_REPO_ID_DELETED);
+        if !trashed.is_empty() {
+            entries.push((VirtualNode::Trash, vec![folder_pos]));
+            trashed.iter().enumerate().for_each(|(n, fse)| {
+                entries.push((
+                    VirtualNode::TrashEntry(fse.subs_id),
+                    vec![folder_pos, n as u16],
+                ));
+            });
+        }
+        for (node, path) in &entries {
+            let treevalues = self.virtual_row_to_values(node);
+            (*self.gui_val_store)
+                .write()
+                .unwrap()
+                .insert_tree_item(path, treevalues.as_slice());
+        }
+        self.virtual_paths.replace(entries);
+    }
+
+    fn update_virtual_rows(&self) {
+        let entries = self.virtual_paths.borrow().clone();
+        for (node, path) in entries {
+            let treevalues = self.virtual_row_to_values(&node);
+            (*self.gui_val_store)
+                .write()
+                .unwrap()
+                .replace_tree_item(&path, &treevalues);
+            (*self.gui_updater)
+                .borrow()
+                .update_tree_single(0, path.as_slice());
+        }
+    }
+
+    pub fn get_virtual_display_name(&self, node: &VirtualNode) -> String {
+        match node {
+            VirtualNode::Tags => t!("TREE_VIRTUAL_TAGS").to_string(),
+            VirtualNode::Tag(tag_id) => self
+                .messagesrepo_w
+                .upgrade()
+                .and_then(|msg_r| {
+                    msg_r
+                        .borrow()
+                        .get_tags()
+                        .into_iter()
+                        .find(|t| t.tag_id == *tag_id)
+                })
+                .map(|t| t.name)
+                .unwrap_or_default(),
+            VirtualNode::Searches => t!("TREE_VIRTUAL_SEARCHES").to_string(),
+            VirtualNode::Search(search_id) => (*self.subscriptionrepo_r)
+                .borrow()
+                .get_saved_search(*search_id)
+                .map(|s| s.name)
+                .unwrap_or_default(),
+            VirtualNode::Trash => t!("TREE_VIRTUAL_TRASH").to_string(),
+            VirtualNode::TrashEntry(subs_id) => (*self.subscriptionrepo_r)
+                .borrow()
+                .get_by_index(*subs_id)
+                .map(|fse| fse.display_name)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// the subscription ids a saved search is limited to,  empty for all
+    pub fn get_search_scope(&self, search: &SavedSearch) -> Vec<isize> {
+        if search.subscription_id <= 0 {
+            return Vec::default();
+        }
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .get_subscription_ids_below(search.subscription_id)
+    }
+
+    /// same columns as  tree_row_to_values
+    fn virtual_row_to_values(&self, node: &VirtualNode) -> Vec<AValue> {
+        let displayname = self.get_virtual_display_name(node);
+        let mut num_all_unread: Option<(isize, isize)> = None;
+        let icon_id = match node {
+            VirtualNode::Tags => IDX_08_GNOME_FOLDER_48,
+            VirtualNode::Tag(tag_id) => {
+                if let Some(msg_r) = self.messagesrepo_w.upgrade() {
+                    num_all_unread = Some(msg_r.borrow().count_by_tag(*tag_id));
+                }
+                IDX_06_CENTER_POINT_GREEN
+            }
+            VirtualNode::Searches => IDX_08_GNOME_FOLDER_48,
+            VirtualNode::Search(search_id) => {
+                let o_search = (*self.subscriptionrepo_r)
+                    .borrow()
+                    .get_saved_search(*search_id);
+                if let (Some(msg_r), Some(search)) = (self.messagesrepo_w.upgrade(), o_search) {
+                    let scope = self.get_search_scope(&search);
+                    let inactive = (*self.subscriptionrepo_r).borrow().get_inactive_ids();
+                    num_all_unread =
+                        Some(msg_r.borrow().count_by_search(&search, &scope, &inactive));
+                }
+                IDX_36_ZOOM_IN
+            }
+            VirtualNode::Trash => IDX_08_GNOME_FOLDER_48,
+            VirtualNode::TrashEntry(subs_id) => {
+                let subs_ids = (*self.subscriptionrepo_r)
+                    .borrow()
+                    .get_subscription_ids_below(*subs_id);
+                if let Some(msg_r) = self.messagesrepo_w.upgrade() {
+                    let (num_all, num_read) = subs_ids.iter().fold((0, 0), |(a, r), id| {
+                        let mr = msg_r.borrow();
+                        (a + mr.get_src_sum(*id), r + mr.get_read_sum(*id))
+                    });
+                    num_all_unread = Some((num_all, num_all - num_read));
+                }
+                (*self.subscriptionrepo_r)
+                    .borrow()
+                    .get_by_index(*subs_id)
+                    .map(|fse| fse.icon_id)
+                    .unwrap_or(IDX_05_RSS_FEEDS_GREY_64_D)
+            }
+        };
+        let mut rightcol_text = String::default();
+        let mut num_msg_unread = 0;
+        if let Some((num_all, num_unread)) = num_all_unread {
+            if (*self.config).borrow().display_feedcount_all && num_unread > 0 {
+                rightcol_text = format!("{num_unread}/{num_all}");
+            } else if (*self.config).borrow().display_feedcount_all {
+                rightcol_text = format!("{num_all}");
+            } else {
+                rightcol_text = format!("{num_unread}");
+            }
+            num_msg_unread = num_unread;
+        }
+        let rightcol_visible = (*self.config).borrow().display_feedcount_all || num_msg_unread > 0;
+        vec![
+            AValue::IIMG(icon_id as i32),                    // 0
+            AValue::ASTR(displayname),                       // 1
+            AValue::ASTR(rightcol_text),                     // 2
+            AValue::IIMG(IDX_03_ICON_TRANSPARENT_48 as i32), // 3
+            AValue::ABOOL(node.is_folder()),                 // 4: is-folder
+            AValue::AU32(node.to_id() as u32),               // 5: negative id
+            AValue::AU32(FontAttributes::to_activation_bits(
+                (*self.config).borrow().tree_fontsize as u32,
+                num_msg_unread <= 0,
+                node.is_folder(),
+                false,
+            )), // 6
+            AValue::AU32(TREE0_COL_STATUS_EXPANDED), // 7: virtual folders are always expanded
+            AValue::None,                            // 8: tooltip
+            AValue::ABOOL(false),                    // 9: spinner
+            AValue::ABOOL(true),                     // 10
+            AValue::ABOOL(rightcol_visible),         // 11
+        ]
+    }
+
     /// Creates the tree, fills the gui_val_store ,  is recursive.
     pub fn insert_tree_row_single(&self, parent_subscr_id: isize) {
         let entries = self
@@ -511,6 +724,9 @@ impl SourceTreeController {
                 return;
             }
             let subs_map = o_subs_map.unwrap();
+            if subs_map.is_filtered_out() {
+                return;
+            }
             if subs_map.tree_path.is_none() {
                 warn!("insert_single : path for {}", subscr.subs_id);
                 return;
@@ -548,12 +764,45 @@ impl SourceTreeController {
         }
     }
 
+    /// the folder of the entry may keep its entries sorted
+    pub fn resort_kept_sorted_parent(&self, subs_id: isize) {
+        let o_subscr = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        if let Some(fse) = o_subscr {
+            self.resort_kept_sorted(fse.parent_subs_id);
+        }
+    }
+
+    /// re-sorts the folder if it keeps its entries sorted, and updates the tree
+    pub fn resort_kept_sorted(&self, folder_id: isize) {
+        if let Some(subs_mov) = self.subscriptionmove_w.upgrade() {
+            if subs_mov.borrow().resort_if_kept_sorted(folder_id) {
+                self.addjob(SJob::UpdateTreePaths);
+                self.addjob(SJob::FillSubscriptionsAdapter);
+                self.addjob(SJob::GuiUpdateTreeAll);
+            }
+        }
+    }
+
+    /// with the unread-only filter,  fetches and read counts change which entries are shown
+    pub fn refilter_unread_only(&self) {
+        if self.tree_filter.borrow().unread_only {
+            self.addjob(SJob::UpdateTreePaths);
+            self.addjob(SJob::FillSubscriptionsAdapter);
+            self.addjob(SJob::GuiUpdateTreeAll);
+        }
+    }
+
     pub fn get_path_for_src(&self, subs_id: isize) -> Option<Vec<u16>> {
         let o_path = self.statemap.borrow().get_tree_path(subs_id);
         if o_path.is_none() {
             if subs_id == 0 {
                 return Some([0].to_vec());
             }
+            if let Some(st) = self.statemap.borrow().get_state(subs_id) {
+                if st.is_filtered_out() {
+                    return None;
+                }
+            }
             if let Some(subs_mov) = self.subscriptionmove_w.upgrade() {
                 subs_mov.borrow_mut().request_check_paths(true);
             }
@@ -605,6 +854,9 @@ impl SourceTreeController {
     }
 
     fn check_icon_outdated(&self, subs_id: isize) {
+        if self.statemap.borrow().is_paused(subs_id) {
+            return;
+        }
         let o_subs = self.subscriptionrepo_r.borrow().get_by_index(subs_id);
         if o_subs.is_none() {
             return;
@@ -632,12 +884,15 @@ impl SourceTreeController {
         if self.new_source.borrow().state == NewSourceState::UrlChanged {
             if self.new_source.borrow().edit_url.starts_with("http") {
                 self.new_source.borrow_mut().state = NewSourceState::Requesting;
+                let edit_url = self.new_source.borrow().edit_url.clone();
                 let dd: Vec<AValue> = vec![
-                    AValue::None,        // 0:display
-                    AValue::None,        // 1:homepage
-                    AValue::None,        // 2: icon_str
-                    AValue::ABOOL(true), // 3 :spinner
-                    AValue::None,        // 4: feed url
+                    AValue::None,                                 // 0:display
+                    AValue::None,                                 // 1:homepage
+                    AValue::None,                                 // 2: icon_str
+                    AValue::ABOOL(true),                          // 3 :spinner
+                    AValue::None,                                 // 4: feed url
+                    AValue::None,                                 // 5: scraper preview
+                    self.newsource_check_duplicate(&[&edit_url]), // 6: duplicate warning
                 ];
                 (*self.gui_val_store)
                     .write()
@@ -654,35 +909,33 @@ impl SourceTreeController {
         }
     }
 
-    pub fn process_newsource_request_done(
-        &self,
-        feed_url_edit: String,
-        display_name: String,
-        icon_id: isize,
-        feed_homepage: String,
-    ) {
+    pub fn process_newsource_request_done(&self, result: &NewSourceResult) {
+        let av_dup = self.newsource_check_duplicate(&[&result.feed_url, &result.redirected_url]);
         let mut icon_str = String::default();
-        if icon_id > 0 {
-            if let Some(ie) = self.iconrepo_r.borrow().get_by_index(icon_id) {
+        if result.icon_id > 0 {
+            if let Some(ie) = self.iconrepo_r.borrow().get_by_index(result.icon_id) {
                 icon_str = ie.icon;
             }
         };
         {
             let mut ns = self.new_source.borrow_mut();
             ns.state = NewSourceState::Completed;
-            ns.edit_url = feed_url_edit;
-            ns.display_name = display_name;
-            ns.icon_id = icon_id;
-            ns.feed_homepage = feed_homepage;
+            ns.edit_url.clone_from(&result.feed_url);
+            ns.display_name.clone_from(&result.display_name);
+            ns.icon_id = result.icon_id;
+            ns.feed_homepage.clone_from(&result.feed_homepage);
             ns.icon_str.clone_from(&icon_str);
         }
-        let dd: Vec<AValue> = vec![
+        let mut dd: Vec<AValue> = vec![
             AValue::ASTR(self.new_source.borrow().display_name.clone()),
             AValue::ASTR(self.new_source.borrow().feed_homepage.clone()),
-            AValue::ASTR(icon_str), // 2: icon_str
-            AValue::ABOOL(false),   // 3: spinner
-            AValue::None,           // 4: feed-url
+            AValue::ASTR(icon_str),                       // 2: icon_str
+            AValue::ABOOL(false),                         // 3: spinner
+            AValue::None,                                 // 4: feed-url
+            AValue::ASTR(result.scraper_preview.clone()), // 5: items found by the scraper
+            av_dup,                                       // 6: duplicate warning
         ];
+        append_feed_candidates(&mut dd, &result.candidates);
         (*self.gui_val_store)
             .write()
             .unwrap()
@@ -692,6 +945,31 @@ impl SourceTreeController {
             .update_dialog(DIALOG_NEW_SUBSCRIPTION);
     }
 
+    /// Remembers an existing subscription for one of the urls.
+    /// returns the warning for the new-subscription dialog,  empty if there is none
+    fn newsource_check_duplicate(&self, urls: &[&str]) -> AValue {
+        let o_existing = self.subscriptionmove_w.upgrade().and_then(|subs_mov| {
+            urls.iter()
+                .filter(|u| !u.is_empty())
+                .find_map(|u| subs_mov.borrow().find_duplicate(u))
+        });
+        let mut ns = self.new_source.borrow_mut();
+        match o_existing {
+            Some(fse) => {
+                ns.duplicate_of = fse.subs_id;
+                AValue::ASTR(format!(
+                    "{} {}",
+                    t!("D_NEW_SUBSCRIPTION_DUPLICATE"),
+                    fse.display_name
+                ))
+            }
+            None => {
+                ns.duplicate_of = 0;
+                AValue::ASTR(String::default())
+            }
+        }
+    }
+
     fn check_feed_update_times(&self) {
         let interval_s = (*self.config).borrow().get_interval_seconds();
         if interval_s <= 0 {
@@ -711,6 +989,7 @@ impl SourceTreeController {
         let check_feed_ids = entries
             .iter()
             .filter(|fse| !fse.is_folder)
+            .filter(|fse| !stm_b.is_paused(fse.subs_id))
             .map(|fse| {
                 let mut fetch_sch = false;
                 let mut fetch_pro = false;
@@ -773,6 +1052,11 @@ impl SourceTreeController {
         (*self.config).borrow_mut().feeds_fetch_at_start = (*self.configmanager_r)
             .borrow()
             .get_val_bool(Self::CONF_FETCH_ON_START);
+        (*self.config).borrow_mut().trash_retention_days = (*self.configmanager_r)
+            .borrow()
+            .get_val_int(Self::CONF_TRASH_RETENTION_DAYS)
+            .unwrap_or(Self::CONF_TRASH_RETENTION_DAYS_DEFAULT as isize)
+            as u32;
         if let Some(s) = (*self.configmanager_r)
             .borrow()
             .get_sys_val(ConfigManager::CONF_MODE_DEBUG)
@@ -803,10 +1087,11 @@ impl SourceTreeController {
             .subscriptionrepo_r
             .borrow()
             .get_children(parent_subs_id as isize);
-        entries.iter().enumerate().for_each(|(n, fse)| {
+        let mut n: u16 = 0;
+        entries.iter().for_each(|fse| {
             let mut path: Vec<u16> = Vec::new();
             path.extend_from_slice(localpath);
-            path.push(n as u16);
+            path.push(n);
             let subs_map = match self.statemap.borrow().get_state(fse.subs_id) {
                 Some(m) => m,
                 None => {
@@ -814,6 +1099,10 @@ impl SourceTreeController {
                     SubsMapEntry::default()
                 }
             };
+            if subs_map.is_filtered_out() {
+                return;
+            }
+            n += 1;
             let treevalues = self.tree_row_to_values(fse, &subs_map);
             (*self.gui_val_store)
                 .write()
@@ -821,7 +1110,7 @@ impl SourceTreeController {
                 .insert_tree_item(&path, treevalues.as_slice());
             self.insert_tree_row(&path, fse.subs_id as i32); // recurse
         });
-        entries.len() as i32
+        n as i32
     }
 
     /// We overlap the  in-mem Folder-expanded with DB-Folder-Expanded
@@ -860,7 +1149,8 @@ impl SourceTreeController {
             None => "".to_string(),
         };
         let mut m_status = su_st.status as u32;
-        if subscr.expanded {
+        // the filtered tree shows the matches inside the folders
+        if subscr.expanded || (subscr.is_folder && self.tree_filter.borrow().is_active()) {
             m_status |= TREE0_COL_STATUS_EXPANDED;
         }
 
@@ -907,7 +1197,7 @@ impl SourceTreeController {
             (*self.config).borrow().tree_fontsize as u32,
             num_msg_unread <= 0,
             subscr.is_folder,
-            false,
+            su_st.is_paused(),
         ))); //  6: num_content_unread
         tv.push(AValue::AU32(m_status)); //	7 : status
         tv.push(tooltip_a); //  : 8 tooltip
@@ -923,6 +1213,9 @@ impl SourceTreeController {
             warn!("tree_update_one:  is_deleted ! {:?}", subscr);
             return false;
         }
+        if su_st.is_filtered_out() {
+            return true; // not in the tree
+        }
         match &su_st.tree_path {
             Some(t_path) => {
                 let treevalues = self.tree_row_to_values(subscr, su_st);
@@ -989,6 +1282,9 @@ impl SourceTreeController {
                 .borrow()
                 .get_by_index(subs.icon_id as isize);
             if o_icon.is_none() {
+                if self.statemap.borrow().is_paused(subs_id) {
+                    continue;
+                }
                 debug!(
                     "No Icon From Repo for subscr {}  s_icon_id {}  '{}'  ...downloading ",
                     subs_id, subs.icon_id, subs.display_name
@@ -1057,6 +1353,7 @@ impl StartupWithAppContext for SourceTreeController {
         let sm_c_r: Rc<RefCell<dyn ISubscriptionMove>> =
             (*ac).get_rc::<SubscriptionMove>().unwrap();
         self.statemap = (*sm_c_r).borrow().get_state_map();
+        self.tree_filter = (*sm_c_r).borrow().get_tree_filter();
         let f_so_r = ac.get_rc::<SourceTreeController>().unwrap();
         {
             let mut t = (*self.timer_r).borrow_mut();
@@ -1094,6 +1391,8 @@ pub struct Config {
     pub display_feedcount_all: bool,
     pub mode_debug: bool,
     pub tree_fontsize: u8,
+    /// deleted subscriptions stay that long in the trash
+    pub trash_retention_days: u32,
 }
 
 impl Config {
@@ -1121,6 +1420,7 @@ impl Default for Config {
             display_feedcount_all: false,
             mode_debug: false,
             tree_fontsize: 1,
+            trash_retention_days: SourceTreeController::CONF_TRASH_RETENTION_DAYS_DEFAULT as u32,
         }
     }
 }
@@ -1133,6 +1433,8 @@ pub(super) struct NewSourceTempData {
     pub(super) icon_str: String,
     pub(super) feed_homepage: String,
     pub(super) state: NewSourceState,
+    /// existing subscription with the same url,  0 for none
+    pub(super) duplicate_of: isize,
 }
 
 impl std::fmt::Debug for NewSourceTempData {
@@ -1144,10 +1446,25 @@ impl std::fmt::Debug for NewSourceTempData {
             .field("icon_id", &self.icon_id)
             .field("homepage", &self.feed_homepage)
             .field("#icon", &self.icon_str.len())
+            .field("duplicate_of", &self.duplicate_of)
             .finish()
     }
 }
 
+/// What the downloader found out about the url of a new subscription
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct NewSourceResult {
+    pub feed_url: String,
+    pub display_name: String,
+    pub icon_id: isize,
+    pub feed_homepage: String,
+    /// items found by the scraper
+    pub scraper_preview: String,
+    /// empty if there was no redirect
+    pub redirected_url: String,
+    pub candidates: Vec<FeedCandidate>,
+}
+
 #[derive(Debug, PartialEq, Eq, Default)]
 pub enum NewSourceState {
     #[default]
@@ -1174,6 +1491,19 @@ pub fn errorentry_to_line(ee: &ErrorEntry) -> String {
     )
 }
 
+/// The  candidates follow the fixed fields of the new-subscription dialog data,  only if there is a choice
+fn append_feed_candidates(dd: &mut Vec<AValue>, candidates: &[FeedCandidate]) {
+    if candidates.len() < 2 {
+        return;
+    }
+    candidates.iter().for_each(|c| {
+        dd.push(AValue::ASTR(c.url.clone()));
+        dd.push(AValue::ASTR(c.title.clone()));
+        dd.push(AValue::ASTR(c.feed_type.clone()));
+        dd.push(AValue::ASTR(c.preview.clone()));
+    });
+}
+
 #[cfg(test)]
 mod t {
 
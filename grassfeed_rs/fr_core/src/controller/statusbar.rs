@@ -75,6 +75,14 @@ impl StatusBar {
     pub fn set_downloader_kind(&self, threadnr: u8, kind: u8) {
         let mut c = self.cache.borrow_mut();
         c.downloader_kind[threadnr as usize] = kind;
+        c.downloader_progress[threadnr as usize] = 0;
+        c.downloader_kind_changed = true;
+    }
+
+    /// for long running jobs:  percent  replaces the kind character by a rising bar
+    pub fn set_downloader_progress(&self, threadnr: u8, percent: u8) {
+        let mut c = self.cache.borrow_mut();
+        c.downloader_progress[threadnr as usize] = percent;
         c.downloader_kind_changed = true;
     }
 
@@ -310,6 +318,7 @@ pub struct CachedData {
     pub num_msg_marked: usize,
 
     pub downloader_kind: [u8; DOWNLOADER_MAX_NUM_THREADS],
+    pub downloader_progress: [u8; DOWNLOADER_MAX_NUM_THREADS],
     pub downloader_kind_changed: bool,
 
     pub num_dl_queue_length: u16,
@@ -360,6 +369,7 @@ impl OnePanel for PanelLeft {
         let is_folder: bool;
         let subs_id_changed: bool;
         let downloader_kind: [u8; DOWNLOADER_MAX_NUM_THREADS];
+        let downloader_progress: [u8; DOWNLOADER_MAX_NUM_THREADS];
         let n_threads: usize;
         let downloader_kind_changed: bool;
         let downloader_queue_length: u16;
@@ -372,6 +382,7 @@ impl OnePanel for PanelLeft {
             num_msg_unread = c.num_msg_unread;
             num_msg_changed = c.num_msg_changed;
             downloader_kind = c.downloader_kind;
+            downloader_progress = c.downloader_progress;
             n_threads = c.num_downloader_threads as usize;
             downloader_kind_changed = c.downloader_kind_changed;
             downloader_queue_length = c.num_dl_queue_length;
@@ -394,8 +405,16 @@ impl OnePanel for PanelLeft {
             return (None, None);
         }
         let mut downloader_display: String = String::default();
-        for k in downloader_kind.iter().take(n_threads) {
-            downloader_display.push(dl_char_for_kind(*k));
+        for (k, p) in downloader_kind
+            .iter()
+            .zip(downloader_progress)
+            .take(n_threads)
+        {
+            if p > 0 {
+                downloader_display.push(get_vertical_block_char(p as usize, 100));
+            } else {
+                downloader_display.push(dl_char_for_kind(*k));
+            }
         }
         let unread_all = format!("{num_msg_unread:5} / {num_msg_all:5}");
         statusbar.cache.borrow_mut().reset_downloader_kind_updated();
@@ -1,6 +1,10 @@
 use crate::controller::isourcetree::ISourceTreeController;
 use crate::controller::sourcetree::SJob;
 use crate::controller::sourcetree::SourceTreeController;
+use crate::controller::treefilter::TreeFilter;
+use crate::controller::treefilter::TreeFilterResult;
+use crate::controller::undo::UndoAction;
+use crate::controller::undo::UndoHistory;
 use crate::db::errorentry::ESRC;
 use crate::db::errors_repo::ErrorRepo;
 use crate::db::messages_repo::IMessagesRepo;
@@ -12,9 +16,11 @@ use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
 use crate::db::subscription_state::ISubscriptionState;
 use crate::db::subscription_state::SubscriptionState;
+use crate::downloader::linkcleaner::normalize_feed_url;
 use crate::opml::opmlreader::OpmlReader;
 use crate::util::filter_by_iso8859_1;
 use crate::util::remove_invalid_chars_from_input;
+use crate::util::timestamp_now;
 use context::appcontext::AppContext;
 use context::BuildConfig;
 use context::Buildable;
@@ -23,15 +29,100 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::rc::Weak;
 
+/// keyboard commands that move an entry inside the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeMoveCommand {
+    Up,
+    Down,
+    /// to the end of the nearest folder above, on the same level
+    IntoPrevFolder,
+    /// to the start of the nearest folder below, on the same level
+    IntoNextFolder,
+    /// one level up, right after its folder
+    OutToParent,
+}
+
+impl TreeMoveCommand {
+    /// the tree event command names
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tree-move-up" => Some(TreeMoveCommand::Up),
+            "tree-move-down" => Some(TreeMoveCommand::Down),
+            "tree-move-into-prev-folder" => Some(TreeMoveCommand::IntoPrevFolder),
+            "tree-move-into-next-folder" => Some(TreeMoveCommand::IntoNextFolder),
+            "tree-move-out" => Some(TreeMoveCommand::OutToParent),
+            _ => None,
+        }
+    }
+}
+
+/// orders for the entries of a folder,  the number is kept in  keep_sorted  of the folder.
+/// Folders stay on top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FolderSortOrder {
+    Name = 1,
+    /// most unread messages first
+    Unread = 2,
+    /// most recently updated feed first
+    LastUpdate = 3,
+}
+
+impl FolderSortOrder {
+    pub fn from_isize(n: isize) -> Option<Self> {
+        match n {
+            1 => Some(FolderSortOrder::Name),
+            2 => Some(FolderSortOrder::Unread),
+            3 => Some(FolderSortOrder::LastUpdate),
+            _ => None,
+        }
+    }
+
+    /// the tree event command names
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "folder-sort-name" => Some(FolderSortOrder::Name),
+            "folder-sort-unread" => Some(FolderSortOrder::Unread),
+            "folder-sort-last-update" => Some(FolderSortOrder::LastUpdate),
+            _ => None,
+        }
+    }
+}
+
 pub trait ISubscriptionMove {
     fn on_subscription_drag(&self, _tree_nr: u8, from_path: Vec<u16>, to_path: Vec<u16>) -> bool;
+    /// returns false if the entry cannot move that way
+    fn move_by_command(&self, subs_id: isize, command: TreeMoveCommand) -> bool;
+    /// sorts the entries of the folder once,  0 for the top level
+    fn sort_folder(&self, folder_id: isize, order: FolderSortOrder);
+    /// sorts again if the folder keeps its entries sorted.
+    /// Returns true if a position changed,  the caller updates the tree.
+    fn resort_if_kept_sorted(&self, folder_id: isize) -> bool;
+
+    /// shows only the entries whose name or url contain the text,  and the folders above them
+    fn set_tree_filter_text(&self, text: &str);
+    /// shows only the subscriptions with unread messages
+    fn set_tree_filter_unread(&self, unread_only: bool);
+    fn is_tree_filter_active(&self) -> bool;
+    /// the first matching entry in tree order
+    fn get_tree_filter_match(&self) -> Option<isize>;
 
     fn get_state_map(&self) -> Rc<RefCell<SubscriptionState>>;
+    fn get_tree_filter(&self) -> Rc<RefCell<TreeFilter>>;
     fn update_cached_paths(&self);
 
     fn set_delete_subscription_id(&mut self, o_fs_id: Option<usize>);
     fn move_subscription_to_trash(&mut self);
 
+    /// reverts a recorded tree move or trash action,  or performs it again
+    fn apply_undo(&self, action: &UndoAction, redo: bool);
+
+    /// back to the parent and folder position it had before
+    fn restore_from_trash(&self, subs_id: isize);
+    /// removes the trashed entry with everything below it,  and marks their messages as deleted
+    fn delete_from_trash(&self, subs_id: isize);
+    /// permanently deletes all entries in the trash
+    fn empty_trash(&self);
+
     /// using internal state for parent id
     fn add_new_folder(&mut self, folder_name: String) -> isize;
     fn add_new_folder_at_parent(&self, folder_name: String, parent_id: isize) -> isize;
@@ -44,7 +135,11 @@ pub trait ISubscriptionMove {
         load_messages: bool,
     ) -> isize;
 
-    fn import_opml(&self, filename: String);
+    /// existing subscription in the tree with the same normalized url
+    fn find_duplicate(&self, url: &str) -> Option<SubscriptionEntry>;
+
+    /// returns the existing subscriptions that were in the file again, those are not imported
+    fn import_opml(&self, filename: String) -> Vec<isize>;
     fn empty_create_default_subscriptions(&mut self);
 
     fn set_fs_delete_id(&mut self, o_fs_id: Option<usize>);
@@ -63,10 +158,14 @@ pub struct SubscriptionMove {
     feedsources_w: Weak<RefCell<SourceTreeController>>,
     erro_repo_r: Rc<RefCell<ErrorRepo>>,
 
+    undo_r: Rc<RefCell<UndoHistory>>,
     statemap: Rc<RefCell<SubscriptionState>>,
     need_check_fs_paths: RefCell<bool>,
     feedsource_delete_id: Option<usize>,
     current_new_folder_parent_id: Option<isize>,
+    tree_filter: Rc<RefCell<TreeFilter>>,
+    /// None while the filter is inactive
+    tree_filter_result: RefCell<Option<TreeFilterResult>>,
 }
 
 impl SubscriptionMove {
@@ -89,10 +188,13 @@ impl SubscriptionMove {
             messagesrepo_r: msg_repo_r,
             feedsources_w: Weak::new(),
             erro_repo_r: err_rep,
+            undo_r: Rc::new(RefCell::new(UndoHistory::default())),
             statemap: statemap_,
             need_check_fs_paths: RefCell::new(true),
             feedsource_delete_id: Default::default(),
             current_new_folder_parent_id: Default::default(),
+            tree_filter: Default::default(),
+            tree_filter_result: Default::default(),
         }
     }
 
@@ -109,6 +211,10 @@ impl SubscriptionMove {
         from_path: &[u16],
         to_path: &[u16],
     ) -> Result<(SubscriptionEntry, isize, isize), String> {
+        if self.tree_filter.borrow().is_active() {
+            // the paths of the filtered tree do not tell the folder positions
+            return Err("tree is filtered".to_string());
+        }
         let o_from_entry = self.get_by_path(from_path);
         if o_from_entry.is_none() {
             self.need_check_fs_paths.replace(true);
@@ -206,6 +312,17 @@ impl SubscriptionMove {
         if from_entry.parent_subs_id == to_parent_id && to_folderpos > from_entry.folder_position {
             to_folderpos_lim -= 1;
         }
+        self.place_entry(from_entry, to_parent_id, to_folderpos_lim);
+    }
+
+    /// moves the entry so it ends up at that folder position of the destination
+    pub fn place_entry(
+        &self,
+        from_entry: SubscriptionEntry,
+        to_parent_id: isize,
+        to_folderpos: isize,
+    ) {
+        let mut to_folderpos_lim = to_folderpos;
         // remove the from-entry, re-write the folder-positions
         (*self.subscriptionrepo_r)
             .borrow()
@@ -235,20 +352,152 @@ impl SubscriptionMove {
                     .update_folder_position(fse.subs_id, n as isize);
             }
         });
+        // a sorted folder does not keep the dropped position
+        if self.get_kept_sort_order(to_parent_id).is_some() {
+            self.resort_parent_list(to_parent_id);
+        }
+    }
+
+    /// returns:  To-Parent-ID,  to-folderpos,  counted like for drag_move
+    pub fn command_calc_positions(
+        &self,
+        entry: &SubscriptionEntry,
+        command: TreeMoveCommand,
+    ) -> Option<(isize, isize)> {
+        let siblings = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_children(entry.parent_subs_id);
+        let index = siblings.iter().position(|s| s.subs_id == entry.subs_id)?;
+        match command {
+            TreeMoveCommand::Up if index > 0 => Some((entry.parent_subs_id, index as isize - 1)),
+            TreeMoveCommand::Down if index + 1 < siblings.len() => {
+                Some((entry.parent_subs_id, index as isize + 2))
+            }
+            TreeMoveCommand::IntoPrevFolder => {
+                let folder = siblings[..index].iter().rev().find(|s| s.is_folder)?;
+                let num_children = (*self.subscriptionrepo_r)
+                    .borrow()
+                    .get_children(folder.subs_id)
+                    .len();
+                Some((folder.subs_id, num_children as isize))
+            }
+            TreeMoveCommand::IntoNextFolder => {
+                let folder = siblings[index + 1..].iter().find(|s| s.is_folder)?;
+                Some((folder.subs_id, 0))
+            }
+            TreeMoveCommand::OutToParent if entry.parent_subs_id > 0 => {
+                let parent = (*self.subscriptionrepo_r)
+                    .borrow()
+                    .get_by_index(entry.parent_subs_id)?;
+                Some((parent.parent_subs_id, parent.folder_position + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// straightens the folder_pos,  in the order the folder is kept sorted by.
+    /// Returns true if a position changed.
+    pub fn resort_parent_list(&self, parent_subs_id: isize) -> bool {
+        let o_order = self.get_kept_sort_order(parent_subs_id);
+        self.sort_parent_list(parent_subs_id, o_order)
     }
 
-    /// straightens the folder_pos
-    pub fn resort_parent_list(&self, parent_subs_id: isize) {
-        let mod_list = (*self.subscriptionrepo_r)
+    /// None keeps the current order,  only straightens the folder_pos
+    fn sort_parent_list(&self, parent_subs_id: isize, o_order: Option<FolderSortOrder>) -> bool {
+        let mut mod_list = (*self.subscriptionrepo_r)
             .borrow()
             .get_children(parent_subs_id);
+        if let Some(order) = o_order {
+            self.sort_entries(&mut mod_list, order);
+        }
+        let mut changed = false;
         mod_list.iter().enumerate().for_each(|(n, fse)| {
             if fse.folder_position != n as isize {
                 (*self.subscriptionrepo_r)
                     .borrow()
                     .update_folder_position(fse.subs_id, n as isize);
+                changed = true;
             }
         });
+        changed
+    }
+
+    /// stable,  equal entries keep their order
+    fn sort_entries(&self, entries: &mut [SubscriptionEntry], order: FolderSortOrder) {
+        let statemap = self.statemap.borrow();
+        entries.sort_by_cached_key(|fse| {
+            let primary: i64 = match order {
+                FolderSortOrder::Name => 0,
+                FolderSortOrder::Unread => -(self.get_num_unread(&statemap, fse) as i64),
+                FolderSortOrder::LastUpdate => -fse.updated_ext,
+            };
+            (!fse.is_folder, primary, fse.display_name.to_lowercase())
+        });
+    }
+
+    /// the counts are cleared after a fetch,  then a feed asks the messages
+    fn get_num_unread(&self, statemap: &SubscriptionState, fse: &SubscriptionEntry) -> isize {
+        if let Some((_all, unread)) = statemap.get_num_all_unread(fse.subs_id) {
+            return unread;
+        }
+        if fse.is_folder {
+            return 0;
+        }
+        let msgrepo = (*self.messagesrepo_r).borrow();
+        msgrepo.get_src_sum(fse.subs_id) - msgrepo.get_read_sum(fse.subs_id)
+    }
+
+    /// collects the matching entries and the folders above them.  Below a matching folder all entries match.
+    /// Returns true if an entry below the parent is visible.
+    fn collect_filter_visible(
+        &self,
+        filter: &TreeFilter,
+        parent_subs_id: isize,
+        parent_matches: bool,
+        result: &mut TreeFilterResult,
+    ) -> bool {
+        let entries = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_children(parent_subs_id);
+        let mut any_visible = false;
+        for entry in &entries {
+            let text_match = parent_matches || filter.matches_text(entry);
+            let visible = if entry.is_folder {
+                let match_pos = result.matches.len();
+                let below = self.collect_filter_visible(filter, entry.subs_id, text_match, result);
+                let shown = below
+                    || (text_match
+                        && (!filter.unread_only || entry.subs_id == filter.keep_visible));
+                if shown && !filter.get_text().is_empty() && filter.matches_text(entry) {
+                    result.matches.insert(match_pos, entry.subs_id);
+                }
+                shown
+            } else {
+                let shown = text_match
+                    && (!filter.unread_only
+                        || entry.subs_id == filter.keep_visible
+                        || self.get_num_unread(&self.statemap.borrow(), entry) > 0);
+                if shown {
+                    result.matches.push(entry.subs_id);
+                }
+                shown
+            };
+            if visible {
+                result.visible.insert(entry.subs_id);
+                any_visible = true;
+            }
+        }
+        any_visible
+    }
+
+    fn get_kept_sort_order(&self, folder_id: isize) -> Option<FolderSortOrder> {
+        if folder_id <= 0 {
+            return None;
+        }
+        let folder = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_by_index(folder_id)?;
+        FolderSortOrder::from_isize(folder.keep_sorted)
     }
 
     /// scans the messages for highest subscription id, if there is a higher one, use next higher subscription id
@@ -270,6 +519,7 @@ impl SubscriptionMove {
         localpath: &[u16],
         parent_subs_id: i32,
         mut is_deleted: bool,
+        parent_paused: bool,
         relative_subs_index: &mut isize,
     ) -> bool {
         if parent_subs_id < 0 {
@@ -278,15 +528,24 @@ impl SubscriptionMove {
         let entries: Vec<SubscriptionEntry> = (*self.subscriptionrepo_r)
             .borrow()
             .get_children(parent_subs_id as isize);
-        entries.iter().enumerate().for_each(|(num, entry)| {
-            *relative_subs_index += 1;
+        let mut num: u16 = 0;
+        entries.iter().for_each(|entry| {
+            let shown = match self.tree_filter_result.borrow().as_ref() {
+                Some(result) => result.visible.contains(&entry.subs_id),
+                None => true,
+            };
             let mut path: Vec<u16> = Vec::new();
             path.extend_from_slice(localpath);
-            path.push(num as u16);
+            path.push(num);
+            if shown {
+                *relative_subs_index += 1;
+                num += 1;
+            }
+            let is_paused = parent_paused || entry.paused;
             //  trace!(                "update_paths_rec  num {}  rel {}   path {:?}     N:{:?}   icon:{} ",                num,                relative_subs_index,                path,                entry.display_name,                entry.icon_id            );
             {
                 let mut smm = self.statemap.borrow_mut();
-                let ri = if entry.is_folder {
+                let ri = if entry.is_folder || !shown {
                     -1
                 } else {
                     *relative_subs_index
@@ -299,12 +558,102 @@ impl SubscriptionMove {
                     entry.icon_id,
                 );
                 smm.set_deleted(entry.subs_id, is_deleted);
+                smm.set_paused(entry.subs_id, is_paused);
+                smm.set_filtered_out(entry.subs_id, !shown);
             }
-            self.update_paths_rec(&path, entry.subs_id as i32, is_deleted, relative_subs_index);
+            self.update_paths_rec(
+                &path,
+                entry.subs_id as i32,
+                is_deleted,
+                is_paused,
+                relative_subs_index,
+            );
         });
         false
     }
 
+    /// returns the entry as it was before.  The latest trashed entry is the first in the trash.
+    fn trash_entry(&self, subs_id: isize) -> Option<SubscriptionEntry> {
+        let fse: SubscriptionEntry = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id)?;
+        self.place_entry(fse.clone(), SRC_REPO_ID_DELETED, 0);
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .set_deleted_rec(fse.subs_id, true);
+        (*self.subscriptionrepo_r).borrow().update_trash_origin(
+            fse.subs_id,
+            fse.parent_subs_id,
+            fse.folder_position,
+            timestamp_now(),
+        );
+        self.addjob(SJob::UpdateTreePaths);
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+        Some(fse)
+    }
+
+    /// takes the entry with its children out of the trash,  to the parent if that still exists
+    fn restore_entry(&self, subs_id: isize, parent_id: isize, folder_pos: isize) {
+        let o_entry = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        if o_entry.is_none() {
+            warn!("restore: subscription {} is gone", subs_id);
+            return;
+        }
+        let parent_exists = parent_id == 0
+            || (*self.subscriptionrepo_r)
+                .borrow()
+                .get_by_index(parent_id)
+                .is_some_and(|p| !p.isdeleted());
+        let to_parent = if parent_exists { parent_id } else { 0 };
+        self.place_entry(o_entry.unwrap(), to_parent, folder_pos);
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .set_deleted_rec(subs_id, false);
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .update_trash_origin(subs_id, 0, 0, 0);
+        self.addjob(SJob::UpdateTreePaths);
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+        self.addjob(SJob::SetCursorToSubsID(subs_id));
+    }
+
+    /// the entry and all entries below it
+    fn get_subtree_ids(&self, subs_id: isize) -> Vec<isize> {
+        let mut ids: Vec<isize> = vec![subs_id];
+        let mut n = 0;
+        while n < ids.len() {
+            let children = (*self.subscriptionrepo_r).borrow().get_children(ids[n]);
+            ids.extend(children.iter().map(|c| c.subs_id));
+            n += 1;
+        }
+        ids
+    }
+
+    /// without updating the tree
+    fn delete_entry_permanently(&self, subs_id: isize) {
+        let subtree_ids = self.get_subtree_ids(subs_id);
+        let msg_ids: Vec<i32> = (*self.messagesrepo_r)
+            .borrow_mut()
+            .get_by_subscriptions(&subtree_ids, false)
+            .map(|m| m.message_id as i32)
+            .collect();
+        if !msg_ids.is_empty() {
+            (*self.messagesrepo_r)
+                .borrow_mut()
+                .update_is_deleted_many(&msg_ids, true);
+        }
+        subtree_ids.iter().for_each(|id| {
+            (*self.subscriptionrepo_r).borrow().delete_by_index(*id);
+        });
+    }
+
+    /// new paths for the remaining entries,  and the tree filled again
+    fn apply_tree_filter(&self) {
+        self.update_cached_paths();
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+    }
+
     pub fn addjob(&self, j: SJob) {
         if let Some(subs_w) = self.feedsources_w.upgrade() {
             (*subs_w).borrow().addjob(j);
@@ -332,6 +681,7 @@ impl ISubscriptionMove for SubscriptionMove {
         match self.drag_calc_positions(&from_path, &to_path) {
             Ok((from_entry, to_parent_id, to_folderpos)) => {
                 from_entry_id = from_entry.subs_id;
+                let before = (from_entry.parent_subs_id, from_entry.folder_position);
                 self.drag_move(from_entry, to_parent_id, to_folderpos);
                 let all2 = (*self.subscriptionrepo_r).borrow().get_all_entries();
                 if all2.len() != length_before {
@@ -340,6 +690,19 @@ impl ISubscriptionMove for SubscriptionMove {
                 } else {
                     success = true;
                 }
+                let o_moved = (*self.subscriptionrepo_r)
+                    .borrow()
+                    .get_by_index(from_entry_id);
+                if let Some(moved) = o_moved {
+                    let after = (moved.parent_subs_id, moved.folder_position);
+                    if success && after != before {
+                        self.undo_r.borrow_mut().push(UndoAction::TreeMove(
+                            from_entry_id,
+                            before,
+                            after,
+                        ));
+                    }
+                }
             }
             Err(msg) => {
                 warn!("DragFail: {:?}=>{:?} --> {} ", from_path, to_path, msg);
@@ -360,34 +723,169 @@ impl ISubscriptionMove for SubscriptionMove {
         success
     }
 
+    fn move_by_command(&self, subs_id: isize, command: TreeMoveCommand) -> bool {
+        let entry = match (*self.subscriptionrepo_r).borrow().get_by_index(subs_id) {
+            Some(fse) if fse.parent_subs_id >= 0 && !fse.isdeleted() => fse,
+            _ => return false,
+        };
+        // the target positions are counted from the list index, gaps in the stored ones must go first
+        self.sort_parent_list(entry.parent_subs_id, None);
+        let o_parent = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_by_index(entry.parent_subs_id);
+        if let Some(parent) = o_parent {
+            self.sort_parent_list(parent.parent_subs_id, None);
+        }
+        let entry = match (*self.subscriptionrepo_r).borrow().get_by_index(subs_id) {
+            Some(fse) => fse,
+            None => return false,
+        };
+        let (to_parent_id, to_folderpos) = match self.command_calc_positions(&entry, command) {
+            Some(target) => target,
+            None => return false,
+        };
+        let before = (entry.parent_subs_id, entry.folder_position);
+        self.drag_move(entry, to_parent_id, to_folderpos);
+        let o_moved = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        if let Some(moved) = o_moved {
+            let after = (moved.parent_subs_id, moved.folder_position);
+            if after != before {
+                self.undo_r
+                    .borrow_mut()
+                    .push(UndoAction::TreeMove(subs_id, before, after));
+            }
+        }
+        // the next key press needs the new paths already
+        self.update_cached_paths();
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+        self.addjob(SJob::SetCursorToSubsID(subs_id));
+        true
+    }
+
+    fn sort_folder(&self, folder_id: isize, order: FolderSortOrder) {
+        if self.sort_parent_list(folder_id, Some(order)) {
+            self.addjob(SJob::UpdateTreePaths);
+            self.addjob(SJob::FillSubscriptionsAdapter);
+            self.addjob(SJob::GuiUpdateTreeAll);
+        }
+    }
+
+    fn resort_if_kept_sorted(&self, folder_id: isize) -> bool {
+        match self.get_kept_sort_order(folder_id) {
+            Some(order) => self.sort_parent_list(folder_id, Some(order)),
+            None => false,
+        }
+    }
+
+    fn set_tree_filter_text(&self, text: &str) {
+        let before = self.tree_filter.borrow().clone();
+        self.tree_filter.borrow_mut().set_text(text);
+        if *self.tree_filter.borrow() != before {
+            self.apply_tree_filter();
+        }
+    }
+
+    fn set_tree_filter_unread(&self, unread_only: bool) {
+        if self.tree_filter.borrow().unread_only != unread_only {
+            self.tree_filter.borrow_mut().unread_only = unread_only;
+            self.apply_tree_filter();
+        }
+    }
+
+    fn is_tree_filter_active(&self) -> bool {
+        self.tree_filter.borrow().is_active()
+    }
+
+    fn get_tree_filter_match(&self) -> Option<isize> {
+        self.tree_filter_result
+            .borrow()
+            .as_ref()
+            .and_then(|result| result.matches.first().cloned())
+    }
+
     fn get_state_map(&self) -> Rc<RefCell<SubscriptionState>> {
         self.statemap.clone()
     }
 
+    fn get_tree_filter(&self) -> Rc<RefCell<TreeFilter>> {
+        self.tree_filter.clone()
+    }
+
     fn move_subscription_to_trash(&mut self) {
         if self.feedsource_delete_id.is_none() {
             return;
         }
-        let fs_id = self.feedsource_delete_id.unwrap();
-        let fse: SubscriptionEntry = (*self.subscriptionrepo_r)
-            .borrow()
-            .get_by_index(fs_id as isize)
-            .unwrap();
-        (*self.subscriptionrepo_r)
-            .borrow()
-            .update_parent_and_folder_position(fse.subs_id, SRC_REPO_ID_DELETED, 0);
-        (*self.subscriptionrepo_r)
-            .borrow()
-            .set_deleted_rec(fse.subs_id);
-        self.resort_parent_list(fse.parent_subs_id);
-        self.feedsource_delete_id = None;
-        if let Some(subs_w) = self.feedsources_w.upgrade() {
-            (*subs_w).borrow().addjob(SJob::UpdateTreePaths);
-            (*subs_w).borrow().addjob(SJob::FillSubscriptionsAdapter);
-            (*subs_w).borrow().addjob(SJob::GuiUpdateTreeAll);
+        let fs_id = self.feedsource_delete_id.take().unwrap();
+        if let Some(fse) = self.trash_entry(fs_id as isize) {
+            self.undo_r.borrow_mut().push(UndoAction::Trash(
+                fse.subs_id,
+                fse.parent_subs_id,
+                fse.folder_position,
+            ));
+        }
+    }
+
+    fn apply_undo(&self, action: &UndoAction, redo: bool) {
+        match *action {
+            UndoAction::TreeMove(subs_id, before, after) => {
+                let (parent_id, folder_pos) = if redo { after } else { before };
+                let o_entry = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+                if let Some(entry) = o_entry {
+                    self.place_entry(entry, parent_id, folder_pos);
+                }
+                self.addjob(SJob::UpdateTreePaths);
+                self.addjob(SJob::FillSubscriptionsAdapter);
+                self.addjob(SJob::GuiUpdateTreeAll);
+                self.addjob(SJob::SetCursorToSubsID(subs_id));
+            }
+            UndoAction::Trash(subs_id, parent_id, folder_pos) => {
+                if redo {
+                    self.trash_entry(subs_id);
+                } else {
+                    self.restore_entry(subs_id, parent_id, folder_pos);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn restore_from_trash(&self, subs_id: isize) {
+        let o_entry = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        match o_entry {
+            Some(fse) if fse.parent_subs_id == SRC_REPO_ID_DELETED => {
+                self.restore_entry(subs_id, fse.trash_parent_id, fse.trash_position);
+            }
+            _ => warn!("restore_from_trash: {} is not in the trash", subs_id),
         }
     }
 
+    fn delete_from_trash(&self, subs_id: isize) {
+        let o_entry = (*self.subscriptionrepo_r).borrow().get_by_index(subs_id);
+        let in_trash = matches!(o_entry, Some(fse) if fse.parent_subs_id == SRC_REPO_ID_DELETED);
+        if !in_trash {
+            warn!("delete_from_trash: {} is not in the trash", subs_id);
+            return;
+        }
+        self.delete_entry_permanently(subs_id);
+        self.resort_parent_list(SRC_REPO_ID_DELETED);
+        self.addjob(SJob::UpdateTreePaths);
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+    }
+
+    fn empty_trash(&self) {
+        let trashed = (*self.subscriptionrepo_r)
+            .borrow()
+            .get_children(SRC_REPO_ID_DELETED);
+        trashed
+            .iter()
+            .for_each(|fse| self.delete_entry_permanently(fse.subs_id));
+        self.addjob(SJob::UpdateTreePaths);
+        self.addjob(SJob::FillSubscriptionsAdapter);
+        self.addjob(SJob::GuiUpdateTreeAll);
+    }
+
     fn set_delete_subscription_id(&mut self, o_fs_id: Option<usize>) {
         self.feedsource_delete_id = o_fs_id;
     }
@@ -435,8 +933,17 @@ impl ISubscriptionMove for SubscriptionMove {
     }
 
     fn update_cached_paths(&self) {
+        let filter = self.tree_filter.borrow().clone();
+        let o_result = if filter.is_active() {
+            let mut result = TreeFilterResult::default();
+            self.collect_filter_visible(&filter, 0, false, &mut result);
+            Some(result)
+        } else {
+            None
+        };
+        self.tree_filter_result.replace(o_result);
         let mut rel_idx: isize = 1;
-        self.update_paths_rec(&Vec::<u16>::default(), 0, false, &mut rel_idx);
+        self.update_paths_rec(&Vec::<u16>::default(), 0, false, false, &mut rel_idx);
     }
 
     fn add_new_subscription(&mut self, newsource: String, display: String) -> isize {
@@ -508,12 +1015,28 @@ impl ISubscriptionMove for SubscriptionMove {
     }
 
     // moving
-    fn import_opml(&self, filename: String) {
+    fn find_duplicate(&self, url: &str) -> Option<SubscriptionEntry> {
+        let normalized = normalize_feed_url(url);
+        if normalized.is_empty() {
+            return None;
+        }
+        let existing_id = *(*self.subscriptionrepo_r)
+            .borrow()
+            .get_feed_url_groups()
+            .get(&normalized)?
+            .first()?;
+        (*self.subscriptionrepo_r)
+            .borrow()
+            .get_by_index(existing_id)
+    }
+
+    fn import_opml(&self, filename: String) -> Vec<isize> {
         let new_folder_id = self.add_new_folder_at_parent("import".to_string(), 0);
         let mut opmlreader = OpmlReader::new(self.subscriptionrepo_r.clone());
+        let mut skipped: Vec<isize> = Vec::default();
         match opmlreader.read_from_file(filename) {
             Ok(_) => {
-                opmlreader.transfer_to_db(new_folder_id);
+                skipped = opmlreader.transfer_to_db(new_folder_id);
                 self.addjob(SJob::UpdateTreePaths);
             }
             Err(e) => {
@@ -523,6 +1046,7 @@ impl ISubscriptionMove for SubscriptionMove {
         self.addjob(SJob::UpdateTreePaths);
         self.addjob(SJob::FillSubscriptionsAdapter);
         self.addjob(SJob::GuiUpdateTreeAll);
+        skipped
     }
 
     fn empty_create_default_subscriptions(&mut self) {
@@ -601,5 +1125,6 @@ impl Buildable for SubscriptionMove {
 impl StartupWithAppContext for SubscriptionMove {
     fn startup(&mut self, ac: &AppContext) {
         self.feedsources_w = Rc::downgrade(&(*ac).get_rc::<SourceTreeController>().unwrap());
+        self.undo_r = (*ac).get_rc::<UndoHistory>().unwrap();
     }
 }
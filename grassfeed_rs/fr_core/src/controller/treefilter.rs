@@ -0,0 +1,74 @@
+//! Filter of the subscription tree:  by display name or url,  optionally only the
+//! subscriptions with unread messages.  The folders above a match stay visible.
+
+use crate::db::subscription_entry::SubscriptionEntry;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeFilter {
+    /// lower case, trimmed
+    text: String,
+    pub unread_only: bool,
+    /// the selected subscription stays visible without unread messages,  until the selection changes
+    pub keep_visible: isize,
+}
+
+impl TreeFilter {
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.trim().to_lowercase();
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty() || self.unread_only
+    }
+
+    /// display name or url contain the text.  Without text every entry matches.
+    pub fn matches_text(&self, entry: &SubscriptionEntry) -> bool {
+        if self.text.is_empty() {
+            return true;
+        }
+        entry.display_name.to_lowercase().contains(&self.text)
+            || (!entry.is_folder && entry.url.to_lowercase().contains(&self.text))
+    }
+}
+
+/// The result of one filter pass over the tree
+#[derive(Debug, Clone, Default)]
+pub struct TreeFilterResult {
+    /// the matching entries and the folders above them
+    pub visible: HashSet<isize>,
+    /// the matching entries in tree order, without the folders that are only shown for their entries
+    pub matches: Vec<isize>,
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    // cargo test  --lib controller::treefilter::t_::match_name_or_url
+    #[test]
+    fn match_name_or_url() {
+        let entry = SubscriptionEntry::from_new_url(
+            "Rust Blog".to_string(),
+            "https://blog.rust-lang.org/feed.xml".to_string(),
+        );
+        let mut filter = TreeFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches_text(&entry));
+        filter.set_text("  BLOG ");
+        assert!(filter.is_active());
+        assert!(filter.matches_text(&entry));
+        filter.set_text("rust-lang");
+        assert!(filter.matches_text(&entry));
+        filter.set_text("python");
+        assert!(!filter.matches_text(&entry));
+        filter.set_text("");
+        filter.unread_only = true;
+        assert!(filter.is_active());
+        assert!(filter.matches_text(&entry));
+    }
+}
@@ -0,0 +1,145 @@
+//! Bounded history of the message and subscription operations that can be reverted.
+//! The controllers record an action after they performed it, the  GuiProcessor  takes
+//! them back out for undo and redo and lets the owning controller apply them.
+
+use context::appcontext::AppContext;
+use context::BuildConfig;
+use context::Buildable;
+use context::StartupWithAppContext;
+use std::collections::VecDeque;
+
+/// number of steps that can be undone
+pub const UNDO_HISTORY_SIZE: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UndoAction {
+    /// message ids whose read state was changed,  the new state
+    ReadState(Vec<i32>, bool),
+    /// message ids
+    Delete(Vec<i32>),
+    /// message ids whose favorite marker was changed,  the new state
+    Favorite(Vec<i32>, bool),
+    /// subs_id,  (parent_id, folder_position) before,  (parent_id, folder_position) after
+    TreeMove(isize, (isize, isize), (isize, isize)),
+    /// subs_id,  parent_id and folder_position before it was moved to the trash
+    Trash(isize, isize, isize),
+}
+
+impl UndoAction {
+    /// handled by  ISubscriptionMove,  the others by  IContentList
+    pub fn is_tree_action(&self) -> bool {
+        matches!(self, UndoAction::TreeMove(..) | UndoAction::Trash(..))
+    }
+
+    /// translation key for the status bar
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            UndoAction::ReadState(_, true) => "UNDO_NAME_MARK_READ",
+            UndoAction::ReadState(_, false) => "UNDO_NAME_MARK_UNREAD",
+            UndoAction::Delete(_) => "UNDO_NAME_DELETE",
+            UndoAction::Favorite(_, _) => "UNDO_NAME_FAVORITE",
+            UndoAction::TreeMove(..) => "UNDO_NAME_TREE_MOVE",
+            UndoAction::Trash(..) => "UNDO_NAME_TRASH",
+        }
+    }
+}
+
+pub struct UndoHistory {
+    /// latest at the back
+    undo: VecDeque<UndoAction>,
+    /// latest at the back
+    redo: Vec<UndoAction>,
+    capacity: usize,
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        UndoHistory::new(UNDO_HISTORY_SIZE)
+    }
+}
+
+impl UndoHistory {
+    pub fn new(capacity: usize) -> Self {
+        UndoHistory {
+            undo: VecDeque::with_capacity(capacity),
+            redo: Vec::default(),
+            capacity,
+        }
+    }
+
+    /// a new action discards the redo steps,  the oldest step falls out when full
+    pub fn push(&mut self, action: UndoAction) {
+        self.redo.clear();
+        if self.undo.len() >= self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(action);
+    }
+
+    /// the action to revert, it is kept for redo
+    pub fn take_undo(&mut self) -> Option<UndoAction> {
+        let action = self.undo.pop_back()?;
+        self.redo.push(action.clone());
+        Some(action)
+    }
+
+    /// the action to perform again, it is kept for undo
+    pub fn take_redo(&mut self) -> Option<UndoAction> {
+        let action = self.redo.pop()?;
+        self.undo.push_back(action.clone());
+        Some(action)
+    }
+
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+
+    pub fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+}
+
+impl Buildable for UndoHistory {
+    type Output = UndoHistory;
+    fn build(_conf: Box<dyn BuildConfig>, _ac: &AppContext) -> Self::Output {
+        UndoHistory::default()
+    }
+}
+
+impl StartupWithAppContext for UndoHistory {}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  controller::undo::t::undo_redo_order  --lib -- --exact --nocapture "
+    #[test]
+    fn undo_redo_order() {
+        let mut h = UndoHistory::default();
+        h.push(UndoAction::Delete(vec![1]));
+        h.push(UndoAction::ReadState(vec![2, 3], true));
+        assert_eq!(h.take_undo(), Some(UndoAction::ReadState(vec![2, 3], true)));
+        assert_eq!(h.take_undo(), Some(UndoAction::Delete(vec![1])));
+        assert_eq!(h.take_undo(), None);
+        assert_eq!(h.take_redo(), Some(UndoAction::Delete(vec![1])));
+        assert_eq!(h.redo_len(), 1);
+        h.push(UndoAction::Favorite(vec![4], true));
+        assert_eq!(h.redo_len(), 0);
+        assert_eq!(h.take_redo(), None);
+        assert_eq!(h.undo_len(), 2);
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  controller::undo::t::bounded  --lib -- --exact --nocapture "
+    #[test]
+    fn bounded() {
+        let mut h = UndoHistory::new(3);
+        for n in 0..5 {
+            h.push(UndoAction::Delete(vec![n]));
+        }
+        assert_eq!(h.undo_len(), 3);
+        assert_eq!(h.take_undo(), Some(UndoAction::Delete(vec![4])));
+        assert_eq!(h.take_undo(), Some(UndoAction::Delete(vec![3])));
+        assert_eq!(h.take_undo(), Some(UndoAction::Delete(vec![2])));
+        assert_eq!(h.take_undo(), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Entries of the subscription tree that are no subscriptions. They list messages
+//! by other criteria, and have negative ids, below the  SRC_REPO_ID_*  range.
+
+use resources::id::TREE0_VIRTUAL_ID_SEARCHES;
+use resources::id::TREE0_VIRTUAL_ID_SEARCH_BASE;
+use resources::id::TREE0_VIRTUAL_ID_TAGS;
+use resources::id::TREE0_VIRTUAL_ID_TAG_BASE;
+use resources::id::TREE0_VIRTUAL_ID_TRASH;
+use resources::id::TREE0_VIRTUAL_ID_TRASH_BASE;
+
+pub const VIRTUAL_ID_TAGS: isize = TREE0_VIRTUAL_ID_TAGS as isize;
+pub const VIRTUAL_ID_SEARCHES: isize = TREE0_VIRTUAL_ID_SEARCHES as isize;
+pub const VIRTUAL_ID_TRASH: isize = TREE0_VIRTUAL_ID_TRASH as isize;
+const VIRTUAL_ID_TAG_BASE: isize = TREE0_VIRTUAL_ID_TAG_BASE as isize;
+const VIRTUAL_ID_TRASH_BASE: isize = TREE0_VIRTUAL_ID_TRASH_BASE as isize;
+const VIRTUAL_ID_SEARCH_BASE: isize = TREE0_VIRTUAL_ID_SEARCH_BASE as isize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VirtualNode {
+    /// folder above all tags
+    Tags,
+    /// tag_id
+    Tag(isize),
+    /// folder above all saved searches
+    Searches,
+    /// search_id
+    Search(isize),
+    /// folder above the deleted subscriptions
+    Trash,
+    /// subs_id of a deleted subscription or folder
+    TrashEntry(isize),
+}
+
+impl VirtualNode {
+    pub fn to_id(&self) -> isize {
+        match self {
+            VirtualNode::Tags => VIRTUAL_ID_TAGS,
+            VirtualNode::Tag(tag_id) => VIRTUAL_ID_TAG_BASE - tag_id,
+            VirtualNode::Searches => VIRTUAL_ID_SEARCHES,
+            VirtualNode::Search(search_id) => VIRTUAL_ID_SEARCH_BASE - search_id,
+            VirtualNode::Trash => VIRTUAL_ID_TRASH,
+            VirtualNode::TrashEntry(subs_id) => VIRTUAL_ID_TRASH_BASE - subs_id,
+        }
+    }
+
+    pub fn from_id(id: isize) -> Option<VirtualNode> {
+        match id {
+            VIRTUAL_ID_TAGS => Some(VirtualNode::Tags),
+            VIRTUAL_ID_SEARCHES => Some(VirtualNode::Searches),
+            VIRTUAL_ID_TRASH => Some(VirtualNode::Trash),
+            i if i < VIRTUAL_ID_SEARCH_BASE => {
+                Some(VirtualNode::Search(VIRTUAL_ID_SEARCH_BASE - i))
+            }
+            i if i < VIRTUAL_ID_TRASH_BASE => {
+                Some(VirtualNode::TrashEntry(VIRTUAL_ID_TRASH_BASE - i))
+            }
+            i if i < VIRTUAL_ID_TAG_BASE => Some(VirtualNode::Tag(VIRTUAL_ID_TAG_BASE - i)),
+            _ => None,
+        }
+    }
+
+    pub fn is_folder(&self) -> bool {
+        matches!(
+            self,
+            VirtualNode::Tags | VirtualNode::Searches | VirtualNode::Trash
+        )
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  controller::virtualnode::t::id_roundtrip  --lib -- --exact --nocapture "
+    #[test]
+    fn id_roundtrip() {
+        for node in [
+            VirtualNode::Tags,
+            VirtualNode::Tag(1),
+            VirtualNode::Tag(77),
+            VirtualNode::Searches,
+            VirtualNode::Search(1),
+            VirtualNode::Search(4000),
+            VirtualNode::Trash,
+            VirtualNode::TrashEntry(10),
+            VirtualNode::TrashEntry(123_456),
+        ] {
+            assert_eq!(VirtualNode::from_id(node.to_id()), Some(node));
+        }
+        assert_eq!(VirtualNode::from_id(-1), None);
+        assert_eq!(VirtualNode::from_id(-3), None);
+        assert_eq!(VirtualNode::from_id(5), None);
+    }
+}
@@ -29,10 +29,15 @@ pub enum ESRC {
     IconNoHomepageFromFeedtext = 19,
     IconsSvgToPng = 20,
     IconDownloadOther = 21,
+    EnclosureDownload = 22,
+    ArticleExtraction = 23,
+    WebSub = 24,
+    ScraperSelect = 25,
+    PageMonitorSelect = 26,
 }
 
 impl ESRC {
-    pub const VALUES: [Self; 22] = [
+    pub const VALUES: [Self; 27] = [
         Self::None, // 0
         Self::GpDlFinished,
         Self::SubsmoveTruncated,
@@ -55,6 +60,11 @@ impl ESRC {
         Self::IconNoHomepageFromFeedtext,
         Self::IconsSvgToPng, // 20
         Self::IconDownloadOther,
+        Self::EnclosureDownload,
+        Self::ArticleExtraction,
+        Self::WebSub,
+        Self::ScraperSelect, // 25
+        Self::PageMonitorSelect,
     ];
 }
 
@@ -0,0 +1,99 @@
+use crate::db::sqlite_context::TableInfo;
+use crate::db::sqlite_context::Wrap;
+
+///
+/// Feed-level information, as announced by the feed at the last successful fetch.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedMeta {
+    pub meta_id: isize,
+    pub subscription_id: isize,
+    pub fetch_date: i64,
+    pub title: String,
+    pub description: String,
+    pub language: String,
+    pub generator: String,
+    /// comma separated
+    pub authors: String,
+    /// url of the logo, or the icon
+    pub logo: String,
+    pub rights: String,
+    /// comma separated
+    pub categories: String,
+}
+
+impl TableInfo for FeedMeta {
+    fn table_name() -> String {
+        "feed_meta".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from(
+            "meta_id  INTEGER  PRIMARY KEY, subscription_id  INTEGER, fetch_date INTEGER,  \
+            title text, description text, language text, generator text, authors text, \
+            logo text, rights text, categories text ",
+        )
+    }
+
+    fn create_indices() -> Vec<String> {
+        vec![
+            "CREATE INDEX IF NOT EXISTS idx_meta_subs ON feed_meta (subscription_id) ; "
+                .to_string(),
+        ]
+    }
+
+    fn index_column_name() -> String {
+        "meta_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![
+            String::from("subscription_id"),
+            String::from("fetch_date"),
+            String::from("title"),
+            String::from("description"),
+            String::from("language"),
+            String::from("generator"),
+            String::from("authors"),
+            String::from("logo"),
+            String::from("rights"),
+            String::from("categories"),
+        ]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![
+            Wrap::INT(self.subscription_id),
+            Wrap::I64(self.fetch_date),
+            Wrap::STR(self.title.clone()),
+            Wrap::STR(self.description.clone()),
+            Wrap::STR(self.language.clone()),
+            Wrap::STR(self.generator.clone()),
+            Wrap::STR(self.authors.clone()),
+            Wrap::STR(self.logo.clone()),
+            Wrap::STR(self.rights.clone()),
+            Wrap::STR(self.categories.clone()),
+        ]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        FeedMeta {
+            meta_id: row.get(0).unwrap(),
+            subscription_id: row.get(1).unwrap(),
+            fetch_date: row.get(2).unwrap_or_default(),
+            title: row.get(3).unwrap_or_default(),
+            description: row.get(4).unwrap_or_default(),
+            language: row.get(5).unwrap_or_default(),
+            generator: row.get(6).unwrap_or_default(),
+            authors: row.get(7).unwrap_or_default(),
+            logo: row.get(8).unwrap_or_default(),
+            rights: row.get(9).unwrap_or_default(),
+            categories: row.get(10).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.meta_id
+    }
+}
@@ -2,8 +2,12 @@ use crate::db::sqlite_context::TableInfo;
 use crate::db::sqlite_context::Wrap;
 use crate::util;
 use lz4_compression::prelude;
+use serde::Deserialize;
+use serde::Serialize;
 
 pub const MARKERS_FAVORITE: u64 = 1;
+/// the content was changed by the feed, after we stored it first
+pub const MARKERS_UPDATED: u64 = 2;
 
 #[derive(Default, PartialEq, Clone, Debug, Eq)]
 pub struct CompWrap(pub String, pub Option<String>);
@@ -33,6 +37,18 @@ impl CompWrap {
     }
 }
 
+/// One media attachment of a message, from  RSS enclosure, Media RSS or iTunes tags
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime_type: String,
+    /// bytes,  0 if unknown
+    pub length: u64,
+    /// seconds,  0 if unknown
+    pub duration: u64,
+    pub thumbnail_url: String,
+}
+
 ///
 /// Stores a content for a  single feed item:  Title, Link, isRead, Date, Feed text  etc.
 ///
@@ -59,6 +75,14 @@ pub struct MessageRow {
     pub author: String,
     pub categories: String,
     pub markers: u64,
+    /// hash of the uncompressed content text, empty on messages from older versions
+    pub content_hash: String,
+    /// list of Enclosure, as compressed json
+    pub enclosures: String,
+    /// compressed feed content, kept when content_text holds the extracted full article
+    pub summary_text: String,
+    /// the same story from other subscriptions has the same group, 0: none
+    pub dup_group: isize,
     /// a copy of the decompressed title, needed for sorting
     pub title_d: Option<String>,
 }
@@ -83,6 +107,47 @@ impl MessageRow {
             self.markers &= !MARKERS_FAVORITE;
         }
     }
+
+    pub fn is_updated(&self) -> bool {
+        self.markers & MARKERS_UPDATED > 0
+    }
+
+    /// content_text holds the full article from the web page
+    pub fn has_article(&self) -> bool {
+        !self.summary_text.is_empty()
+    }
+
+    pub fn set_updated(&mut self, n: bool) {
+        if n {
+            self.markers |= MARKERS_UPDATED
+        } else {
+            self.markers &= !MARKERS_UPDATED;
+        }
+    }
+
+    pub fn get_enclosures(&self) -> Vec<Enclosure> {
+        if self.enclosures.is_empty() {
+            return Vec::default();
+        }
+        match serde_json::from_str::<Vec<Enclosure>>(&decompress(&self.enclosures)) {
+            Ok(list) => list,
+            Err(e) => {
+                warn!("enclosures of {} : {:?}", self.message_id, e);
+                Vec::default()
+            }
+        }
+    }
+
+    pub fn set_enclosures(&mut self, list: &[Enclosure]) {
+        if list.is_empty() {
+            self.enclosures = String::default();
+            return;
+        }
+        match serde_json::to_string(list) {
+            Ok(json) => self.enclosures = compress(&json),
+            Err(e) => warn!("enclosures of {} : {:?}", self.message_id, e),
+        }
+    }
 }
 
 impl std::fmt::Display for MessageRow {
@@ -116,6 +181,16 @@ pub fn decompress(compr_b64: &str) -> String {
     String::default()
 }
 
+/// FNV-1a over the uncompressed content, as hex string.  Stable across program versions.
+pub fn content_hash(uncompressed: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in uncompressed.as_bytes() {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
 ///  Compress the data, then  encode base64  into String
 pub fn compress(uncompressed: &str) -> String {
     let compressed_data = prelude::compress(uncompressed.as_bytes());
@@ -134,7 +209,7 @@ impl TableInfo for MessageRow {
         "message_id  INTEGER  PRIMARY KEY, feed_src_id  INTEGER, title  BLOB, post_id  text,  link  text, \
 		is_deleted BOOLEAN, is_read BOOLEAN , fetch_date  INTEGER , entry_src_date INTEGER,   \
 	 	content_text  BLOB, enclosure_url  text, author BLOB, categories BLOB,  \
-		markers INTEGER  	" )
+		markers INTEGER, content_hash text, enclosures BLOB, summary_text BLOB, dup_group INTEGER DEFAULT 0 " )
     }
 
     fn create_indices() -> Vec<String> {
@@ -163,6 +238,10 @@ impl TableInfo for MessageRow {
             String::from("author"),
             String::from("categories"),
             String::from("markers"),
+            String::from("content_hash"), // 14
+            String::from("enclosures"),
+            String::from("summary_text"),
+            String::from("dup_group"),
         ]
     }
 
@@ -181,6 +260,10 @@ impl TableInfo for MessageRow {
             Wrap::STR(self.author.clone()),
             Wrap::STR(self.categories.clone()),
             Wrap::U64(self.markers),
+            Wrap::STR(self.content_hash.clone()), // 14
+            Wrap::STR(self.enclosures.clone()),
+            Wrap::STR(self.summary_text.clone()),
+            Wrap::INT(self.dup_group),
         ]
     }
 
@@ -200,6 +283,10 @@ impl TableInfo for MessageRow {
             author: row.get(11).unwrap(),
             categories: row.get(12).unwrap(),
             markers: row.get(13).unwrap(),
+            content_hash: row.get(14).unwrap_or_default(),
+            enclosures: row.get(15).unwrap_or_default(),
+            summary_text: row.get(16).unwrap_or_default(),
+            dup_group: row.get(17).unwrap_or_default(),
             ..Default::default()
         }
     }
@@ -220,5 +307,27 @@ mod t {
         assert_eq!(decompress(input).as_str(), "RSS Tutorial two");
     }
 
+    #[test]
+    fn t_enclosures_roundtrip() {
+        let mut msg = MessageRow::default();
+        assert!(msg.get_enclosures().is_empty());
+        let list = vec![Enclosure {
+            url: "https://example.org/ep1.mp3".to_string(),
+            mime_type: "audio/mpeg".to_string(),
+            length: 1234,
+            duration: 61,
+            ..Default::default()
+        }];
+        msg.set_enclosures(&list);
+        assert_eq!(msg.get_enclosures(), list);
+    }
+
+    #[test]
+    fn t_content_hash() {
+        assert_eq!(content_hash("").as_str(), "cbf29ce484222325");
+        assert_eq!(content_hash("a").as_str(), "af63dc4c8601ec8c");
+        assert_ne!(content_hash("Lorem"), content_hash("Lorem."));
+    }
+
     fn setup() {}
 }
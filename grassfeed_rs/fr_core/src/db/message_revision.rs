@@ -0,0 +1,73 @@
+use crate::db::sqlite_context::TableInfo;
+use crate::db::sqlite_context::Wrap;
+
+///
+/// Previous content of a message, stored when the feed delivers a changed content text.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageRevision {
+    pub revision_id: isize,
+    pub message_id: isize,
+    /// When the content got replaced by a newer one
+    pub replaced_date: i64,
+    pub content_hash: String,
+    /// keep compressed data in here
+    pub content_text: String,
+}
+
+impl TableInfo for MessageRevision {
+    fn table_name() -> String {
+        "message_revisions".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from(
+            "revision_id  INTEGER  PRIMARY KEY, message_id  INTEGER, replaced_date INTEGER,  \
+            content_hash  text, content_text  BLOB ",
+        )
+    }
+
+    fn create_indices() -> Vec<String> {
+        vec![
+            "CREATE INDEX IF NOT EXISTS idx_rev_msg ON message_revisions (message_id) ; "
+                .to_string(),
+        ]
+    }
+
+    fn index_column_name() -> String {
+        "revision_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![
+            String::from("message_id"),
+            String::from("replaced_date"),
+            String::from("content_hash"),
+            String::from("content_text"),
+        ]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![
+            Wrap::INT(self.message_id),
+            Wrap::I64(self.replaced_date),
+            Wrap::STR(self.content_hash.clone()),
+            Wrap::STR(self.content_text.clone()),
+        ]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        MessageRevision {
+            revision_id: row.get(0).unwrap(),
+            message_id: row.get(1).unwrap(),
+            replaced_date: row.get(2).unwrap_or_default(),
+            content_hash: row.get(3).unwrap_or_default(),
+            content_text: row.get(4).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.revision_id
+    }
+}
@@ -0,0 +1,104 @@
+use crate::db::sqlite_context::TableInfo;
+use crate::db::sqlite_context::Wrap;
+
+pub const RULE_FIELD_TITLE: isize = 0;
+pub const RULE_FIELD_CONTENT: isize = 1;
+pub const RULE_FIELD_AUTHOR: isize = 2;
+pub const RULE_FIELD_CATEGORIES: isize = 3;
+/// host name of the message link
+pub const RULE_FIELD_HOST: isize = 4;
+
+pub const RULE_ACTION_MARK_READ: isize = 0;
+pub const RULE_ACTION_DELETE: isize = 1;
+pub const RULE_ACTION_FAVORITE: isize = 2;
+/// action_arg: tag name
+pub const RULE_ACTION_TAG: isize = 3;
+/// action_arg: optional summary, the subscription name otherwise
+pub const RULE_ACTION_NOTIFY: isize = 4;
+/// action_arg: command line,  see  rules::expand_command
+pub const RULE_ACTION_COMMAND: isize = 5;
+
+///
+/// User defined rule, evaluated on each new message:  one condition, one action.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageRule {
+    pub rule_id: isize,
+    /// evaluation order
+    pub position: isize,
+    pub enabled: bool,
+    pub field: isize,
+    /// plain text is matched case-insensitive
+    pub is_regex: bool,
+    pub pattern: String,
+    /// subscription or folder the rule is limited to,  0: all
+    pub subscription_id: isize,
+    pub action: isize,
+    pub action_arg: String,
+}
+
+impl TableInfo for MessageRule {
+    fn table_name() -> String {
+        "message_rules".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from(
+            "rule_id  INTEGER  PRIMARY KEY, position INTEGER, enabled BOOLEAN, field INTEGER, \
+            is_regex BOOLEAN, pattern text, subscription_id INTEGER, action INTEGER, action_arg text ",
+        )
+    }
+
+    fn create_indices() -> Vec<String> {
+        Vec::default()
+    }
+
+    fn index_column_name() -> String {
+        "rule_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![
+            String::from("position"),
+            String::from("enabled"),
+            String::from("field"),
+            String::from("is_regex"),
+            String::from("pattern"), // 5
+            String::from("subscription_id"),
+            String::from("action"),
+            String::from("action_arg"),
+        ]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![
+            Wrap::INT(self.position),
+            Wrap::BOO(self.enabled),
+            Wrap::INT(self.field),
+            Wrap::BOO(self.is_regex),
+            Wrap::STR(self.pattern.clone()), // 5
+            Wrap::INT(self.subscription_id),
+            Wrap::INT(self.action),
+            Wrap::STR(self.action_arg.clone()),
+        ]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        MessageRule {
+            rule_id: row.get(0).unwrap(),
+            position: row.get(1).unwrap_or_default(),
+            enabled: row.get(2).unwrap_or_default(),
+            field: row.get(3).unwrap_or_default(),
+            is_regex: row.get(4).unwrap_or_default(),
+            pattern: row.get(5).unwrap_or_default(),
+            subscription_id: row.get(6).unwrap_or_default(),
+            action: row.get(7).unwrap_or_default(),
+            action_arg: row.get(8).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.rule_id
+    }
+}
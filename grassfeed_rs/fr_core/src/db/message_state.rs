@@ -16,6 +16,12 @@ pub struct MessageState {
     pub title_d: String,
     pub subscription_id_copy: isize,
     pub subscription_icon_id: usize,
+    /// duplicate group, 0: none
+    pub dup_group_copy: isize,
+    /// names of the other subscriptions with the same story
+    pub also_in: Vec<String>,
+    /// ids of the assigned tags
+    pub tag_ids: Vec<isize>,
 }
 
 impl std::fmt::Display for MessageState {
@@ -84,6 +90,60 @@ impl MessageStateMap {
         subs_ids
     }
 
+    pub fn set_dup_group(&mut self, msg_id: isize, group: isize) {
+        if let Some(st) = self.msgmap.get_mut(&msg_id) {
+            st.dup_group_copy = group;
+        }
+    }
+
+    /// the distinct duplicate groups of those messages
+    pub fn get_dup_groups(&self, msg_ids: &[i32]) -> Vec<isize> {
+        let mut groups: Vec<isize> = self
+            .msgmap
+            .iter()
+            .filter(|(id, st)| st.dup_group_copy > 0 && msg_ids.contains(&(**id as i32)))
+            .map(|(_id, st)| st.dup_group_copy)
+            .collect();
+        groups.sort();
+        groups.dedup();
+        groups
+    }
+
+    /// message-id, group  of all messages that are in a duplicate group
+    pub fn get_all_dup_groups(&self) -> Vec<(isize, isize)> {
+        self.msgmap
+            .values()
+            .filter(|st| st.dup_group_copy > 0)
+            .map(|st| (st.msg_id, st.dup_group_copy))
+            .collect()
+    }
+
+    pub fn set_also_in(&mut self, msg_id: isize, names: Vec<String>) {
+        if let Some(st) = self.msgmap.get_mut(&msg_id) {
+            st.also_in = names;
+        }
+    }
+
+    pub fn get_also_in(&self, msg_id: isize) -> Vec<String> {
+        if let Some(st) = self.msgmap.get(&msg_id) {
+            return st.also_in.clone();
+        }
+        Vec::default()
+    }
+
+    pub fn set_tag_ids(&mut self, msg_id: isize, tag_ids: Vec<isize>) {
+        if let Some(st) = self.msgmap.get_mut(&msg_id) {
+            st.tag_ids = tag_ids;
+        }
+    }
+
+    pub fn get_tag_ids(&self, msg_id: isize) -> Vec<isize> {
+        if let Some(st) = self.msgmap.get(&msg_id) {
+            return st.tag_ids.clone();
+        }
+        Vec::default()
+    }
+
     pub fn get_isread(&self, msg_id: isize) -> bool {
         if let Some(st) = self.msgmap.get(&msg_id) {
             return st.is_read_copy;
@@ -168,6 +228,13 @@ impl MessageStateMap {
         }
     }
 
+    /// the content changed in the db, next display shall decompress it again
+    pub fn clear_contents_author_categories(&mut self, msg_id: isize) {
+        if let Some(st) = self.msgmap.get_mut(&msg_id) {
+            st.contents_author_categories_d = None;
+        }
+    }
+
     pub fn clear(&mut self) {
         self.msgmap.clear();
     }
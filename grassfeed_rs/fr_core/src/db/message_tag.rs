@@ -0,0 +1,114 @@
+use crate::db::sqlite_context::TableInfo;
+use crate::db::sqlite_context::Wrap;
+
+/// colors for new tags, in turn
+pub const TAG_COLORS: [&str; 8] = [
+    "#3465a4", "#cc0000", "#73d216", "#f57900", "#75507b", "#c17d11", "#06989a", "#555753",
+];
+
+pub fn default_tag_color(num_existing: usize) -> String {
+    TAG_COLORS[num_existing % TAG_COLORS.len()].to_string()
+}
+
+///
+/// User defined label, assigned to any number of messages.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageTag {
+    pub tag_id: isize,
+    pub name: String,
+    /// #rrggbb
+    pub color: String,
+}
+
+impl TableInfo for MessageTag {
+    fn table_name() -> String {
+        "tags".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from("tag_id  INTEGER  PRIMARY KEY, name text, color text ")
+    }
+
+    fn create_indices() -> Vec<String> {
+        Vec::default()
+    }
+
+    fn index_column_name() -> String {
+        "tag_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![String::from("name"), String::from("color")]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![Wrap::STR(self.name.clone()), Wrap::STR(self.color.clone())]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        MessageTag {
+            tag_id: row.get(0).unwrap(),
+            name: row.get(1).unwrap_or_default(),
+            color: row.get(2).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.tag_id
+    }
+}
+
+///
+/// Assignment of one tag to one message
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageTagLink {
+    pub link_id: isize,
+    pub message_id: isize,
+    pub tag_id: isize,
+}
+
+impl TableInfo for MessageTagLink {
+    fn table_name() -> String {
+        "message_tags".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from("link_id  INTEGER  PRIMARY KEY, message_id INTEGER, tag_id INTEGER ")
+    }
+
+    fn create_indices() -> Vec<String> {
+        vec![
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_msgtag ON message_tags (message_id, tag_id) ; "
+                .to_string(),
+            "CREATE INDEX IF NOT EXISTS idx_msgtag_tag ON message_tags (tag_id) ; ".to_string(),
+        ]
+    }
+
+    fn index_column_name() -> String {
+        "link_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![String::from("message_id"), String::from("tag_id")]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![Wrap::INT(self.message_id), Wrap::INT(self.tag_id)]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        MessageTagLink {
+            link_id: row.get(0).unwrap(),
+            message_id: row.get(1).unwrap_or_default(),
+            tag_id: row.get(2).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.link_id
+    }
+}
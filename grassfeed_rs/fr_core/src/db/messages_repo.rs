@@ -1,5 +1,12 @@
 use crate::controller::timer::Timer;
+use crate::db::message::decompress;
 use crate::db::message::MessageRow;
+use crate::db::message_revision::MessageRevision;
+use crate::db::message_tag::default_tag_color;
+use crate::db::message_tag::MessageTag;
+use crate::db::message_tag::MessageTagLink;
+use crate::db::page_snapshot::PageSnapshot;
+use crate::db::saved_search::SavedSearch;
 use crate::db::sqlite_context::rusqlite_error_to_boxed;
 use crate::db::sqlite_context::SqliteContext;
 use crate::db::sqlite_context::TableInfo;
@@ -25,6 +32,12 @@ pub trait IMessagesRepo {
     // returns number of elements
     fn insert_tx(&self, e_list: &[MessageRow]) -> Result<i64, Box<dyn std::error::Error>>;
 
+    /// index values of the inserted rows in list order,  -1 for a row that failed
+    fn insert_tx_ids(
+        &self,
+        e_list: &[MessageRow],
+    ) -> Result<Vec<isize>, Box<dyn std::error::Error>>;
+
     /// returns  the number of read lines for that source id:   -1 for undefined
     fn get_read_sum(&self, src_id: isize) -> isize;
 
@@ -36,6 +49,9 @@ pub trait IMessagesRepo {
 
     fn get_by_index(&self, indexvalue: isize) -> Option<MessageRow>;
 
+    /// complete rows, in no particular order
+    fn get_by_indices(&self, msg_ids: &[isize]) -> Vec<MessageRow>;
+
     /// returns   subscription id  , is_read
     fn get_is_read(&self, repo_id: isize) -> (isize, bool);
 
@@ -55,6 +71,8 @@ pub trait IMessagesRepo {
     fn update_entry_src_date(&self, repo_id: isize, n_src_date: i64) -> usize;
 
     fn update_is_deleted_many(&mut self, repo_ids: &[i32], new_is_del: bool);
+    /// moves the messages to another subscription
+    fn update_subscription_id_many(&mut self, repo_ids: &[i32], new_subs_id: isize);
     fn update_markers(&self, msg_id: isize, n_markers: u64) -> usize;
 
     fn get_ctx(&self) -> &SqliteContext<MessageRow>;
@@ -65,6 +83,7 @@ pub trait IMessagesRepo {
     fn get_src_not_contained(&self, src_repo_id_list: &[i32]) -> Vec<MessageRow>;
 
     ///  deletes really those IDs, if they were set to is_deleted  before. Returns the count really deleted.
+    /// Their revisions and tag links are removed as well.
     fn delete_by_index(&self, indices: &[i32]) -> usize;
 
     fn db_vacuum(&self) -> usize;
@@ -88,10 +107,93 @@ pub trait IMessagesRepo {
     fn get_subscription_ids(&mut self, msg_ids: &[isize]) -> Vec<isize>;
 
     fn count_favorites(&self, subscription_id: isize) -> isize;
+
+    /// content shall be compressed. Returns number of lines
+    fn update_content(&self, msg_id: isize, new_hash: &str, new_content_compr: &str) -> usize;
+
+    /// stores the extracted article as content, the feed content moves to the summary. Both compressed.
+    fn update_article(&self, msg_id: isize, article_compr: &str, summary_compr: &str) -> usize;
+
+    /// returns index value
+    fn insert_revision(&self, rev: &MessageRevision) -> Result<i64, Box<dyn std::error::Error>>;
+
+    /// newest revision first
+    fn get_revisions(&self, msg_id: isize) -> Vec<MessageRevision>;
+
+    /// replaces the previous snapshot of that subscription. Returns index value
+    fn store_page_snapshot(&self, snap: &PageSnapshot) -> Result<i64, Box<dyn std::error::Error>>;
+
+    fn get_page_snapshot(&self, subs_id: isize) -> Option<PageSnapshot>;
+
+    /// for subscriptions that are deleted for good. Returns the number of deleted snapshots
+    fn delete_page_snapshots(&self, subs_ids: &[isize]) -> usize;
+
+    /// messages of the other subscriptions within that time span, not deleted. Reduced columns.
+    fn get_duplicate_candidates(&self, subs_id: isize, from_ts: i64, to_ts: i64)
+        -> Vec<MessageRow>;
+
+    fn update_dup_group(&self, msg_id: isize, group: isize) -> usize;
+
+    /// all messages of those duplicate groups, not deleted. Reduced columns.
+    fn get_by_dup_groups(&self, groups: &[isize]) -> Vec<MessageRow>;
+
+    /// complete rows, not deleted
+    fn get_all_not_deleted(&self) -> Vec<MessageRow>;
+
+    /// sorted by name
+    fn get_tags(&self) -> Vec<MessageTag>;
+
+    /// returns index value
+    fn insert_tag(&self, tag: &MessageTag) -> Result<i64, Box<dyn std::error::Error>>;
+
+    /// the tag of that name, ignoring case, or a new one.  Returns tag-id, is-new
+    fn get_or_insert_tag(&self, name: &str) -> Result<(isize, bool), Box<dyn std::error::Error>>;
+
+    /// replaces name and color
+    fn update_tag(&self, tag: &MessageTag) -> Result<i64, Box<dyn std::error::Error>>;
+
+    /// removes the tag from all messages. Returns number of assignments removed
+    fn delete_tag(&self, tag_id: isize) -> usize;
+
+    /// skips the messages that have the tag already. Returns number of new assignments
+    fn add_tag_links(&self, msg_ids: &[i32], tag_id: isize) -> usize;
+
+    fn remove_tag_links(&self, msg_ids: &[i32], tag_id: isize) -> usize;
+
+    fn get_all_tag_links(&self) -> Vec<MessageTagLink>;
+
+    /// messages of all subscriptions with that tag, not deleted, sorted by  entry_src_date
+    /// tag_id 0:  messages with any tag
+    fn get_by_tag(&mut self, tag_id: isize) -> MessageIterator<'_>;
+
+    /// returns  all, unread  messages with that tag, not deleted
+    fn count_by_tag(&self, tag_id: isize) -> (isize, isize);
+
+    /// messages matching the saved search, not deleted, sorted by  entry_src_date
+    /// subs_ids: the resolved scope of the search,  empty for all subscriptions
+    /// inactive_ids: subscriptions in the trash or deleted,  their messages are left out
+    fn get_by_search(
+        &mut self,
+        search: &SavedSearch,
+        subs_ids: &[isize],
+        inactive_ids: &[isize],
+    ) -> MessageIterator<'_>;
+
+    /// returns  all, unread  messages matching the saved search
+    fn count_by_search(
+        &self,
+        search: &SavedSearch,
+        subs_ids: &[isize],
+        inactive_ids: &[isize],
+    ) -> (isize, isize);
 }
 
 pub struct MessagesRepo {
     ctx: SqliteContext<MessageRow>,
+    revisions: SqliteContext<MessageRevision>,
+    snapshots: SqliteContext<PageSnapshot>,
+    tags: SqliteContext<MessageTag>,
+    tag_links: SqliteContext<MessageTagLink>,
     cached_rows: Vec<MessageRow>,
     // -1: no selection was made        -2  all rows were selected
     cached_subs_id: i64,
@@ -111,15 +213,32 @@ impl MessagesRepo {
 
     pub fn new_by_connection(con_a: Arc<Mutex<Connection>>) -> Self {
         MessagesRepo {
-            ctx: SqliteContext::new_by_connection(con_a),
+            ctx: SqliteContext::new_by_connection(con_a.clone()),
+            revisions: SqliteContext::new_by_connection(con_a.clone()),
+            snapshots: SqliteContext::new_by_connection(con_a.clone()),
+            tags: SqliteContext::new_by_connection(con_a.clone()),
+            tag_links: SqliteContext::new_by_connection(con_a),
             cached_rows: Vec::default(),
             cached_subs_id: -1,
         }
     }
 
     pub fn new_in_mem() -> Self {
+        let ctx = SqliteContext::new_in_memory();
+        let revisions = SqliteContext::new_by_connection(ctx.get_connection());
+        revisions.create_table();
+        let snapshots = SqliteContext::new_by_connection(ctx.get_connection());
+        snapshots.create_table();
+        let tags = SqliteContext::new_by_connection(ctx.get_connection());
+        tags.create_table();
+        let tag_links = SqliteContext::new_by_connection(ctx.get_connection());
+        tag_links.create_table();
         MessagesRepo {
-            ctx: SqliteContext::new_in_memory(),
+            ctx,
+            revisions,
+            snapshots,
+            tags,
+            tag_links,
             cached_rows: Vec::default(),
             cached_subs_id: -1,
         }
@@ -134,18 +253,38 @@ impl MessagesRepo {
     }
 
     pub fn new_by_filename_add_column(filename: &str) -> Self {
-        let insert_column = "markers";
+        let insert_columns: [(&str, &str); 5] = [
+            ("markers", " INTEGER DEFAULT 0  "),
+            ("content_hash", " text DEFAULT ''  "),
+            ("enclosures", " BLOB DEFAULT ''  "),
+            ("summary_text", " BLOB DEFAULT ''  "),
+            ("dup_group", " INTEGER DEFAULT 0  "),
+        ];
         let dbctx = SqliteContext::new(filename);
-        let m_e = dbctx.is_column_present(insert_column);
-        if !m_e {
-            debug!("adding column  {}  ", insert_column);
-            let _num = dbctx.add_column(insert_column, " INTEGER DEFAULT 0  ");
-        }
-        if !dbctx.is_column_present(insert_column) {
-            warn!("could not add column:: {}  ", insert_column);
+        for (insert_column, column_type) in insert_columns {
+            let m_e = dbctx.is_column_present(insert_column);
+            if !m_e {
+                debug!("adding column  {}  ", insert_column);
+                let _num = dbctx.add_column(insert_column, column_type);
+            }
+            if !dbctx.is_column_present(insert_column) {
+                warn!("could not add column:: {}  ", insert_column);
+            }
         }
+        let revisions = SqliteContext::new_by_connection(dbctx.get_connection());
+        revisions.create_table();
+        let snapshots = SqliteContext::new_by_connection(dbctx.get_connection());
+        snapshots.create_table();
+        let tags = SqliteContext::new_by_connection(dbctx.get_connection());
+        tags.create_table();
+        let tag_links = SqliteContext::new_by_connection(dbctx.get_connection());
+        tag_links.create_table();
         MessagesRepo {
             ctx: dbctx,
+            revisions,
+            snapshots,
+            tags,
+            tag_links,
             cached_rows: Vec::default(),
             cached_subs_id: -1,
         }
@@ -167,6 +306,23 @@ impl MessagesRepo {
         }
     }
 
+    /// like  request_messages_reduced, without touching the cache
+    fn query_reduced(&self, sql: &str) -> Vec<MessageRow> {
+        let mut rows: Vec<MessageRow> = Vec::default();
+        if let Ok(mut stmt) = (*self.get_connection()).lock().unwrap().prepare(sql) {
+            match stmt.query_map([], |row| {
+                rows.push(Self::from_row_reduced(row));
+                Ok(())
+            }) {
+                Ok(mr) => {
+                    mr.count();
+                }
+                Err(e) => error!("{} {:?}", sql, e),
+            }
+        }
+        rows
+    }
+
     fn from_row_reduced(row: &Row) -> MessageRow {
         MessageRow {
             message_id: row.get(0).unwrap(),
@@ -178,12 +334,61 @@ impl MessagesRepo {
             is_read: row.get(6).unwrap(),
             entry_src_date: row.get(7).unwrap(),
             markers: row.get(8).unwrap(),
+            content_hash: row.get(9).unwrap_or_default(),
+            dup_group: row.get(10).unwrap_or_default(),
             ..Default::default()
         }
     }
 
+    /// the conditions of a saved search that sql can evaluate, the title text is compressed
+    fn search_condition(
+        search: &SavedSearch,
+        subs_ids: &[isize],
+        inactive_ids: &[isize],
+    ) -> String {
+        let mut cond = String::from(" is_deleted=false ");
+        if search.tag_id > 0 {
+            cond.push_str(&format!(
+                " AND {} IN ( SELECT message_id FROM {} WHERE tag_id = {} ) ",
+                MessageRow::index_column_name(),
+                MessageTagLink::table_name(),
+                search.tag_id
+            ));
+        }
+        if search.only_unread {
+            cond.push_str(" AND is_read=false ");
+        }
+        if search.only_favorite {
+            cond.push_str(&format!(" AND (markers & {MARKERS_FAVORITE} >0) "));
+        }
+        let (date_from, date_to) = search.time_range(chrono::Local::now());
+        if date_from > 0 {
+            cond.push_str(&format!(" AND entry_src_date >= {date_from} "));
+        }
+        if date_to > 0 {
+            cond.push_str(&format!(" AND entry_src_date < {date_to} "));
+        }
+        if !subs_ids.is_empty() {
+            let joined = subs_ids
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            cond.push_str(&format!(" AND feed_src_id in ({joined}) "));
+        }
+        if !inactive_ids.is_empty() {
+            let joined = inactive_ids
+                .iter()
+                .map(|r| r.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            cond.push_str(&format!(" AND feed_src_id NOT IN ({joined}) "));
+        }
+        cond
+    }
+
     fn columns_msg_reduced() -> &'static str {
-        "message_id, feed_src_id, title, post_id, link, is_deleted, is_read, entry_src_date, markers"
+        "message_id, feed_src_id, title, post_id, link, is_deleted, is_read, entry_src_date, markers, content_hash, dup_group"
     }
 }
 
@@ -202,6 +407,16 @@ impl IMessagesRepo for MessagesRepo {
         self.ctx.insert_tx(e_list).map_err(rusqlite_error_to_boxed)
     }
 
+    fn insert_tx_ids(
+        &self,
+        e_list: &[MessageRow],
+    ) -> Result<Vec<isize>, Box<dyn std::error::Error>> {
+        self.ctx
+            .insert_tx_ids(e_list)
+            .map(|ids| ids.into_iter().map(|id| id as isize).collect())
+            .map_err(rusqlite_error_to_boxed)
+    }
+
     /// returns  the number of read lines for that source id:   -1 for undefined
     fn get_read_sum(&self, src_id: isize) -> isize {
         let sql = format!(
@@ -236,6 +451,24 @@ impl IMessagesRepo for MessagesRepo {
         self.ctx.get_by_index(indexvalue)
     }
 
+    fn get_by_indices(&self, msg_ids: &[isize]) -> Vec<MessageRow> {
+        if msg_ids.is_empty() {
+            return Vec::default();
+        }
+        let joined = msg_ids
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let sql = format!(
+            "SELECT * FROM {} WHERE {} in ( {} ) ",
+            MessageRow::table_name(),
+            MessageRow::index_column_name(),
+            joined
+        );
+        self.ctx.get_list(sql)
+    }
+
     /// returns   subscription_id  , is_read
     fn get_is_read(&self, repo_id: isize) -> (isize, bool) {
         let sql = format!(
@@ -371,6 +604,23 @@ impl IMessagesRepo for MessagesRepo {
         self.cache_clear();
     }
 
+    fn update_subscription_id_many(&mut self, repo_ids: &[i32], new_subs_id: isize) {
+        let joined = repo_ids
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let sql = format!(
+            "UPDATE {}  SET  feed_src_id = {} WHERE {} in ( {} )",
+            MessageRow::table_name(),
+            new_subs_id,
+            MessageRow::index_column_name(),
+            joined
+        );
+        self.ctx.execute(sql);
+        self.cache_clear();
+    }
+
     fn update_markers(&self, msg_id: isize, n_markers: u64) -> usize {
         let sql = format!(
             "UPDATE {}  SET  markers = \"{}\"    WHERE {} = {}",
@@ -382,7 +632,7 @@ impl IMessagesRepo for MessagesRepo {
         self.ctx.execute(sql)
     }
 
-    ///  deletes really those IDs, if they were set to is_deleted  before.
+    ///  deletes really those IDs, if they were set to is_deleted  before.  Their tag links go with them.
     fn delete_by_index(&self, indices: &[i32]) -> usize {
         let joined = indices
             .iter()
@@ -395,7 +645,24 @@ impl IMessagesRepo for MessagesRepo {
             MessageRow::index_column_name(),
             joined
         );
-        self.ctx.execute(sql)
+        let num_deleted = self.ctx.execute(sql);
+        let sql = format!(
+            "DELETE FROM {}  WHERE message_id NOT IN ( SELECT {} FROM {} )",
+            MessageRevision::table_name(),
+            MessageRow::index_column_name(),
+            MessageRow::table_name(),
+        );
+        self.revisions.execute(sql);
+        // the tag links of those messages, otherwise they are still counted for the tag
+        let sql = format!(
+            "DELETE FROM {}  WHERE message_id in ( {} ) AND message_id NOT IN ( SELECT {} FROM {} )",
+            MessageTagLink::table_name(),
+            joined,
+            MessageRow::index_column_name(),
+            MessageRow::table_name(),
+        );
+        self.tag_links.execute(sql);
+        num_deleted
     }
 
     fn db_vacuum(&self) -> usize {
@@ -506,6 +773,331 @@ impl IMessagesRepo for MessagesRepo {
         self.ctx.one_number(sql)
     }
 
+    fn update_content(&self, msg_id: isize, new_hash: &str, new_content_compr: &str) -> usize {
+        let sql = format!(
+            "UPDATE {}  SET  content_hash = \"{}\" , content_text = \"{}\" , summary_text = \"\"  WHERE {} = {}",
+            MessageRow::table_name(),
+            new_hash,
+            new_content_compr,
+            MessageRow::index_column_name(),
+            msg_id,
+        );
+        self.ctx.execute(sql)
+    }
+
+    fn update_article(&self, msg_id: isize, article_compr: &str, summary_compr: &str) -> usize {
+        let sql = format!(
+            "UPDATE {}  SET  content_text = \"{}\" , summary_text = \"{}\"   WHERE {} = {}",
+            MessageRow::table_name(),
+            article_compr,
+            summary_compr,
+            MessageRow::index_column_name(),
+            msg_id,
+        );
+        self.ctx.execute(sql)
+    }
+
+    fn insert_revision(&self, rev: &MessageRevision) -> Result<i64, Box<dyn std::error::Error>> {
+        self.revisions
+            .insert(rev, false)
+            .map_err(rusqlite_error_to_boxed)
+    }
+
+    fn get_revisions(&self, msg_id: isize) -> Vec<MessageRevision> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE message_id = {} ORDER BY {} DESC ",
+            MessageRevision::table_name(),
+            msg_id,
+            MessageRevision::index_column_name(),
+        );
+        self.revisions.get_list(sql)
+    }
+
+    fn store_page_snapshot(&self, snap: &PageSnapshot) -> Result<i64, Box<dyn std::error::Error>> {
+        let sql = format!(
+            "DELETE FROM {} WHERE subscription_id = {} ",
+            PageSnapshot::table_name(),
+            snap.subscription_id,
+        );
+        self.snapshots.execute(sql);
+        self.snapshots
+            .insert(snap, false)
+            .map_err(rusqlite_error_to_boxed)
+    }
+
+    fn get_page_snapshot(&self, subs_id: isize) -> Option<PageSnapshot> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE subscription_id = {} ORDER BY {} DESC ",
+            PageSnapshot::table_name(),
+            subs_id,
+            PageSnapshot::index_column_name(),
+        );
+        self.snapshots.get_one(sql)
+    }
+
+    fn delete_page_snapshots(&self, subs_ids: &[isize]) -> usize {
+        if subs_ids.is_empty() {
+            return 0;
+        }
+        let joined = subs_ids
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let sql = format!(
+            "DELETE FROM {} WHERE subscription_id IN ( {} ) ",
+            PageSnapshot::table_name(),
+            joined
+        );
+        self.snapshots.execute(sql)
+    }
+
+    fn get_duplicate_candidates(
+        &self,
+        subs_id: isize,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> Vec<MessageRow> {
+        let sql = format!(
+            "SELECT {} FROM {} WHERE feed_src_id != {} AND is_deleted=false AND entry_src_date >= {} AND entry_src_date <= {} ",
+            Self::columns_msg_reduced(),
+            MessageRow::table_name(),
+            subs_id,
+            from_ts,
+            to_ts
+        );
+        self.query_reduced(&sql)
+    }
+
+    fn update_dup_group(&self, msg_id: isize, group: isize) -> usize {
+        let sql = format!(
+            "UPDATE {} SET dup_group = {} WHERE {} = {} ",
+            MessageRow::table_name(),
+            group,
+            MessageRow::index_column_name(),
+            msg_id
+        );
+        self.ctx.execute(sql)
+    }
+
+    fn get_by_dup_groups(&self, groups: &[isize]) -> Vec<MessageRow> {
+        if groups.is_empty() {
+            return Vec::default();
+        }
+        let joined = groups
+            .iter()
+            .map(|g| g.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let sql = format!(
+            "SELECT {} FROM {} WHERE dup_group in ({}) AND is_deleted=false ",
+            Self::columns_msg_reduced(),
+            MessageRow::table_name(),
+            joined
+        );
+        self.query_reduced(&sql)
+    }
+
+    fn get_all_not_deleted(&self) -> Vec<MessageRow> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE is_deleted=false ",
+            MessageRow::table_name()
+        );
+        self.ctx.get_list(sql)
+    }
+
+    fn get_tags(&self) -> Vec<MessageTag> {
+        let mut tags = self.tags.get_all();
+        tags.sort_by_key(|t| t.name.to_lowercase());
+        tags
+    }
+
+    fn insert_tag(&self, tag: &MessageTag) -> Result<i64, Box<dyn std::error::Error>> {
+        self.tags
+            .insert(tag, false)
+            .map_err(rusqlite_error_to_boxed)
+    }
+
+    fn get_or_insert_tag(&self, name: &str) -> Result<(isize, bool), Box<dyn std::error::Error>> {
+        let tags = self.get_tags();
+        let name_lower = name.trim().to_lowercase();
+        if let Some(existing) = tags.iter().find(|t| t.name.to_lowercase() == name_lower) {
+            return Ok((existing.tag_id, false));
+        }
+        let tag = MessageTag {
+            name: name.trim().to_string(),
+            color: default_tag_color(tags.len()),
+            ..Default::default()
+        };
+        let tag_id = self.insert_tag(&tag)?;
+        Ok((tag_id as isize, true))
+    }
+
+    fn update_tag(&self, tag: &MessageTag) -> Result<i64, Box<dyn std::error::Error>> {
+        let sql = format!(
+            "DELETE FROM {} WHERE {} = {} ",
+            MessageTag::table_name(),
+            MessageTag::index_column_name(),
+            tag.tag_id
+        );
+        self.tags.execute(sql);
+        self.tags.insert(tag, true).map_err(rusqlite_error_to_boxed)
+    }
+
+    fn delete_tag(&self, tag_id: isize) -> usize {
+        let sql = format!(
+            "DELETE FROM {} WHERE {} = {} ",
+            MessageTag::table_name(),
+            MessageTag::index_column_name(),
+            tag_id
+        );
+        self.tags.execute(sql);
+        let sql = format!(
+            "DELETE FROM {} WHERE tag_id = {} ",
+            MessageTagLink::table_name(),
+            tag_id
+        );
+        self.tag_links.execute(sql)
+    }
+
+    fn add_tag_links(&self, msg_ids: &[i32], tag_id: isize) -> usize {
+        if msg_ids.is_empty() {
+            return 0;
+        }
+        let joined = msg_ids
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let sql = format!(
+            "INSERT INTO {} (message_id, tag_id) SELECT {}, {} FROM {} WHERE {} in ( {} ) \
+             AND {} NOT IN ( SELECT message_id FROM {} WHERE tag_id = {} ) ",
+            MessageTagLink::table_name(),
+            MessageRow::index_column_name(),
+            tag_id,
+            MessageRow::table_name(),
+            MessageRow::index_column_name(),
+            joined,
+            MessageRow::index_column_name(),
+            MessageTagLink::table_name(),
+            tag_id
+        );
+        self.tag_links.execute(sql)
+    }
+
+    fn remove_tag_links(&self, msg_ids: &[i32], tag_id: isize) -> usize {
+        if msg_ids.is_empty() {
+            return 0;
+        }
+        let joined = msg_ids
+            .iter()
+            .map(|r| r.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        let sql = format!(
+            "DELETE FROM {} WHERE tag_id = {} AND message_id in ( {} ) ",
+            MessageTagLink::table_name(),
+            tag_id,
+            joined
+        );
+        self.tag_links.execute(sql)
+    }
+
+    fn get_all_tag_links(&self) -> Vec<MessageTagLink> {
+        self.tag_links.get_all()
+    }
+
+    fn get_by_tag(&mut self, tag_id: isize) -> MessageIterator<'_> {
+        let tag_cond = match tag_id {
+            0 => String::default(),
+            _ => format!("WHERE tag_id = {tag_id}"),
+        };
+        let prepared = format!(
+            "SELECT {} FROM {} WHERE is_deleted=false AND {} IN ( SELECT message_id FROM {} {} ) ORDER BY entry_src_date DESC ",
+            Self::columns_msg_reduced(),
+            MessageRow::table_name(),
+            MessageRow::index_column_name(),
+            MessageTagLink::table_name(),
+            tag_cond
+        );
+        self.request_messages_reduced(&prepared);
+        self.cached_subs_id = -1; // not cached: the assignments change without the messages
+        MessageIterator {
+            cache: &self.cached_rows,
+            index: 0,
+        }
+    }
+
+    fn count_by_tag(&self, tag_id: isize) -> (isize, isize) {
+        let sql = format!(
+            "SELECT COUNT({}) FROM {} WHERE is_deleted=false AND {} IN ( SELECT message_id FROM {} WHERE tag_id = {} ) ",
+            MessageRow::index_column_name(),
+            MessageRow::table_name(),
+            MessageRow::index_column_name(),
+            MessageTagLink::table_name(),
+            tag_id
+        );
+        let num_all = self.ctx.one_number(sql.clone());
+        let num_unread = self.ctx.one_number(format!("{sql} AND is_read=false "));
+        (num_all, num_unread)
+    }
+
+    fn get_by_search(
+        &mut self,
+        search: &SavedSearch,
+        subs_ids: &[isize],
+        inactive_ids: &[isize],
+    ) -> MessageIterator<'_> {
+        let prepared = format!(
+            "SELECT {} FROM {} WHERE {} ORDER BY entry_src_date DESC ",
+            Self::columns_msg_reduced(),
+            MessageRow::table_name(),
+            Self::search_condition(search, subs_ids, inactive_ids)
+        );
+        self.request_messages_reduced(&prepared);
+        if !search.text.is_empty() {
+            self.cached_rows
+                .retain(|m| search.text_matches(&decompress(&m.title)));
+        }
+        self.cached_subs_id = -1;
+        MessageIterator {
+            cache: &self.cached_rows,
+            index: 0,
+        }
+    }
+
+    fn count_by_search(
+        &self,
+        search: &SavedSearch,
+        subs_ids: &[isize],
+        inactive_ids: &[isize],
+    ) -> (isize, isize) {
+        let cond = Self::search_condition(search, subs_ids, inactive_ids);
+        if !search.text.is_empty() {
+            let matching: Vec<MessageRow> = self
+                .query_reduced(&format!(
+                    "SELECT {} FROM {} WHERE {} ",
+                    Self::columns_msg_reduced(),
+                    MessageRow::table_name(),
+                    cond
+                ))
+                .into_iter()
+                .filter(|m| search.text_matches(&decompress(&m.title)))
+                .collect();
+            let num_unread = matching.iter().filter(|m| !m.is_read).count();
+            return (matching.len() as isize, num_unread as isize);
+        }
+        let sql = format!(
+            "SELECT COUNT({}) FROM {} WHERE {} ",
+            MessageRow::index_column_name(),
+            MessageRow::table_name(),
+            cond
+        );
+        let num_all = self.ctx.one_number(sql.clone());
+        let num_unread = self.ctx.one_number(format!("{sql} AND is_read=false "));
+        (num_all, num_unread)
+    }
+
     // impl IMessagesRepo
 }
 
@@ -531,6 +1123,10 @@ impl Buildable for MessagesRepo {
 impl StartupWithAppContext for MessagesRepo {
     fn startup(&mut self, ac: &AppContext) {
         self.ctx.create_table();
+        self.revisions.create_table();
+        self.snapshots.create_table();
+        self.tags.create_table();
+        self.tag_links.create_table();
         let timer_r = ac.get_rc::<Timer>().unwrap();
         let mr_r = ac.get_rc::<MessagesRepo>().unwrap();
         {
@@ -635,6 +1231,8 @@ mod t {
         assert_eq!(r.unwrap() as usize, insert.len());
         let sum = (*msg_r).borrow().get_all_sum();
         assert_eq!(sum, 5);
+        let r = (*msg_r).borrow().insert_tx_ids(&insert);
+        assert_eq!(r.unwrap(), vec![6, 7]);
     }
 
     #[test]
@@ -837,5 +1435,151 @@ mod t {
         assert_eq!(e2.categories.as_str(), "cat1 cat2");
     }
 
+    #[test]
+    fn t_update_content_revisions() {
+        let msg_r = prepare_3_rows();
+        assert_eq!((*msg_r).borrow().update_content(2, "h2", "c2"), 1);
+        let msg = (*msg_r).borrow().get_by_index(2).unwrap();
+        assert_eq!(msg.content_hash.as_str(), "h2");
+        assert_eq!(msg.content_text.as_str(), "c2");
+        for n in 0..2 {
+            let rev = MessageRevision {
+                message_id: 2,
+                replaced_date: n,
+                content_hash: format!("h{n}"),
+                ..Default::default()
+            };
+            assert!((*msg_r).borrow().insert_revision(&rev).is_ok());
+        }
+        let revs = (*msg_r).borrow().get_revisions(2);
+        assert_eq!(revs.len(), 2);
+        assert_eq!(revs[0].content_hash.as_str(), "h1");
+        (*msg_r).borrow_mut().update_is_deleted_many(&[2], true);
+        (*msg_r).borrow().delete_by_index(&[2]);
+        assert!((*msg_r).borrow().get_revisions(2).is_empty());
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::messages_repo::t_::t_update_article  --lib -- --exact --nocapture "
+    #[test]
+    fn t_update_article() {
+        let msg_r = prepare_3_rows();
+        assert_eq!((*msg_r).borrow().update_article(2, "article", "summary"), 1);
+        let msg = (*msg_r).borrow().get_by_index(2).unwrap();
+        assert_eq!(msg.content_text.as_str(), "article");
+        assert!(msg.has_article());
+        assert_eq!((*msg_r).borrow().update_content(2, "h2", "c2"), 1);
+        let msg = (*msg_r).borrow().get_by_index(2).unwrap();
+        assert!(!msg.has_article());
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::messages_repo::t::t_page_snapshot  --lib -- --exact --nocapture "
+    #[test]
+    fn t_page_snapshot() {
+        let msgrepo = MessagesRepo::new_in_mem();
+        assert!(msgrepo.get_page_snapshot(5).is_none());
+        for n in 0..2 {
+            let snap = PageSnapshot {
+                subscription_id: 5,
+                fetch_date: n,
+                content_hash: format!("h{n}"),
+                ..Default::default()
+            };
+            assert!(msgrepo.store_page_snapshot(&snap).is_ok());
+        }
+        let snap = msgrepo.get_page_snapshot(5).unwrap();
+        assert_eq!(snap.content_hash.as_str(), "h1");
+        assert_eq!(msgrepo.snapshots.count_all(), 1);
+        assert_eq!(msgrepo.delete_page_snapshots(&[4]), 0);
+        assert_eq!(msgrepo.delete_page_snapshots(&[4, 5]), 1);
+        assert!(msgrepo.get_page_snapshot(5).is_none());
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::messages_repo::t::t_tags  --lib -- --exact --nocapture "
+    #[test]
+    fn t_tags() {
+        let msg_r = prepare_3_rows();
+        let tag = MessageTag {
+            name: "to read".to_string(),
+            color: "#3465a4".to_string(),
+            ..Default::default()
+        };
+        let tag_id = (*msg_r).borrow().insert_tag(&tag).unwrap() as isize;
+        assert_eq!(
+            (*msg_r).borrow().get_or_insert_tag("To Read ").unwrap(),
+            (tag_id, false)
+        );
+        assert_eq!((*msg_r).borrow().add_tag_links(&[1, 2], tag_id), 2);
+        assert_eq!((*msg_r).borrow().add_tag_links(&[2, 3], tag_id), 1);
+        assert_eq!((*msg_r).borrow().get_all_tag_links().len(), 3);
+        (*msg_r).borrow().update_is_read_many(&[1], true);
+        assert_eq!((*msg_r).borrow().count_by_tag(tag_id), (3, 1));
+        assert_eq!((*msg_r).borrow().remove_tag_links(&[1], tag_id), 1);
+        assert_eq!((*msg_r).borrow_mut().get_by_tag(tag_id).len(), 2);
+        assert_eq!((*msg_r).borrow_mut().get_by_tag(0).len(), 2);
+        let renamed = MessageTag {
+            tag_id,
+            name: "reference".to_string(),
+            ..tag
+        };
+        assert!((*msg_r).borrow().update_tag(&renamed).is_ok());
+        assert_eq!((*msg_r).borrow().get_tags(), vec![renamed]);
+        (*msg_r).borrow_mut().update_is_deleted_many(&[3], true);
+        (*msg_r).borrow().delete_by_index(&[3]);
+        assert_eq!((*msg_r).borrow().get_all_tag_links().len(), 1);
+        assert_eq!((*msg_r).borrow().delete_tag(tag_id), 1);
+        assert!((*msg_r).borrow().get_tags().is_empty());
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::messages_repo::t::t_saved_search  --lib -- --exact --nocapture "
+    #[test]
+    fn t_saved_search() {
+        setup();
+        let messagesrepo = MessagesRepo::new_in_mem();
+        messagesrepo.get_ctx().create_table();
+        messagesrepo.tag_links.create_table();
+        let now = crate::util::timestamp_now();
+        for (subs_id, title, is_read, markers, age) in [
+            (1, "Rust release", false, 0, 0),
+            (1, "Weather", true, MARKERS_FAVORITE, 0),
+            (2, "rust meetup", false, 0, 86400 * 3),
+            (3, "Rust old", false, MARKERS_FAVORITE, 86400 * 30),
+        ] {
+            let m = MessageRow {
+                subscription_id: subs_id,
+                title: crate::db::message::compress(title),
+                is_read,
+                markers,
+                entry_src_date: now - age,
+                ..Default::default()
+            };
+            let _r = messagesrepo.insert(&m);
+        }
+        let mut msg_r = messagesrepo;
+        let unread = SavedSearch {
+            only_unread: true,
+            ..Default::default()
+        };
+        assert_eq!(msg_r.count_by_search(&unread, &[], &[]), (3, 3));
+        assert_eq!(msg_r.count_by_search(&unread, &[], &[3]), (2, 2));
+        assert_eq!(msg_r.get_by_search(&unread, &[1, 2], &[]).len(), 2);
+        assert_eq!(msg_r.get_by_search(&unread, &[], &[2, 3]).len(), 1);
+        let fav = SavedSearch {
+            only_favorite: true,
+            ..Default::default()
+        };
+        assert_eq!(msg_r.count_by_search(&fav, &[], &[]), (2, 1));
+        let rust_week = SavedSearch {
+            text: "RUST".to_string(),
+            age_days: 7,
+            ..Default::default()
+        };
+        assert_eq!(msg_r.count_by_search(&rust_week, &[], &[]), (2, 2));
+        let titles: Vec<String> = msg_r
+            .get_by_search(&rust_week, &[2], &[])
+            .map(|m| decompress(&m.title))
+            .collect();
+        assert_eq!(titles, vec!["rust meetup".to_string()]);
+    }
+
     fn setup() {} // dummy
 }
@@ -1,11 +1,17 @@
 pub mod check_consistency;
 pub mod errorentry;
 pub mod errors_repo;
+pub mod feed_meta;
 pub mod icon_repo;
 pub mod icon_row;
 pub mod message;
+pub mod message_revision;
+pub mod message_rule;
 pub mod message_state;
+pub mod message_tag;
 pub mod messages_repo;
+pub mod page_snapshot;
+pub mod saved_search;
 pub mod sqlite_context;
 pub mod subscription_entry;
 pub mod subscription_repo;
@@ -0,0 +1,72 @@
+use crate::db::sqlite_context::TableInfo;
+use crate::db::sqlite_context::Wrap;
+
+///
+/// Normalized text of a monitored web page, as it was at the last change.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageSnapshot {
+    pub snapshot_id: isize,
+    pub subscription_id: isize,
+    pub fetch_date: i64,
+    pub content_hash: String,
+    /// keep compressed data in here
+    pub content_text: String,
+}
+
+impl TableInfo for PageSnapshot {
+    fn table_name() -> String {
+        "page_snapshots".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from(
+            "snapshot_id  INTEGER  PRIMARY KEY, subscription_id  INTEGER, fetch_date INTEGER,  \
+            content_hash  text, content_text  BLOB ",
+        )
+    }
+
+    fn create_indices() -> Vec<String> {
+        vec![
+            "CREATE INDEX IF NOT EXISTS idx_snap_subs ON page_snapshots (subscription_id) ; "
+                .to_string(),
+        ]
+    }
+
+    fn index_column_name() -> String {
+        "snapshot_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![
+            String::from("subscription_id"),
+            String::from("fetch_date"),
+            String::from("content_hash"),
+            String::from("content_text"),
+        ]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![
+            Wrap::INT(self.subscription_id),
+            Wrap::I64(self.fetch_date),
+            Wrap::STR(self.content_hash.clone()),
+            Wrap::STR(self.content_text.clone()),
+        ]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        PageSnapshot {
+            snapshot_id: row.get(0).unwrap(),
+            subscription_id: row.get(1).unwrap(),
+            fetch_date: row.get(2).unwrap_or_default(),
+            content_hash: row.get(3).unwrap_or_default(),
+            content_text: row.get(4).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.snapshot_id
+    }
+}
@@ -0,0 +1,195 @@
+use crate::db::sqlite_context::TableInfo;
+use crate::db::sqlite_context::Wrap;
+use chrono::DateTime;
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::TimeZone;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+///
+/// Stored message query, shown as entry of the subscription tree. All conditions are combined.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavedSearch {
+    pub search_id: isize,
+    /// display order
+    pub position: isize,
+    pub name: String,
+    /// contained in the title, case-insensitive.  empty: any
+    pub text: String,
+    /// 0: any
+    pub tag_id: isize,
+    pub only_unread: bool,
+    pub only_favorite: bool,
+    /// 1: since midnight,  2: since yesterday midnight, ...    0: no limit
+    pub age_days: isize,
+    /// timestamp, 0: open
+    pub date_from: i64,
+    /// timestamp, 0: open
+    pub date_to: i64,
+    /// subscription or folder the search is limited to,  0: all
+    pub subscription_id: isize,
+}
+
+impl SavedSearch {
+    /// lower and upper limit for the message date,  0 stands for open
+    pub fn time_range(&self, now: DateTime<Local>) -> (i64, i64) {
+        let mut from = self.date_from;
+        if self.age_days > 0 {
+            let start_day = now.date_naive() - Duration::days(self.age_days as i64 - 1);
+            if let Some(midnight) = start_day
+                .and_hms_opt(0, 0, 0)
+                .and_then(|nd| Local.from_local_datetime(&nd).earliest())
+            {
+                from = std::cmp::max(from, midnight.timestamp());
+            }
+        }
+        (from, self.date_to)
+    }
+
+    /// ignores case,  an empty text matches all
+    pub fn text_matches(&self, title: &str) -> bool {
+        self.text.is_empty() || title.to_lowercase().contains(&self.text.to_lowercase())
+    }
+}
+
+/// "YYYY-MM-DD"  to the local midnight,  for the end of a range the following midnight.  Empty or invalid: 0
+pub fn date_to_timestamp(date: &str, range_end: bool) -> i64 {
+    let o_day = NaiveDate::parse_from_str(date.trim(), DATE_FORMAT).ok();
+    let o_day = match range_end {
+        true => o_day.and_then(|d| d.succ_opt()),
+        false => o_day,
+    };
+    o_day
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .and_then(|nd| Local.from_local_datetime(&nd).earliest())
+        .map(|dt| dt.timestamp())
+        .unwrap_or(0)
+}
+
+/// reverse of  date_to_timestamp,  empty for 0
+pub fn timestamp_to_date(ts: i64, range_end: bool) -> String {
+    if ts == 0 {
+        return String::default();
+    }
+    let o_dt = Local.timestamp_opt(ts, 0).single();
+    let o_day = match range_end {
+        true => o_dt.and_then(|dt| dt.date_naive().pred_opt()),
+        false => o_dt.map(|dt| dt.date_naive()),
+    };
+    o_day
+        .map(|d| d.format(DATE_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+impl TableInfo for SavedSearch {
+    fn table_name() -> String {
+        "saved_searches".to_string()
+    }
+
+    // INTEGER REAL  TEXT  BLOB		BOOLEAN
+    fn create_string() -> String {
+        String::from(
+            "search_id  INTEGER  PRIMARY KEY, position INTEGER, name text, text text, tag_id INTEGER, \
+            only_unread BOOLEAN, only_favorite BOOLEAN, age_days INTEGER, date_from INTEGER, date_to INTEGER, \
+            subscription_id INTEGER ",
+        )
+    }
+
+    fn create_indices() -> Vec<String> {
+        Vec::default()
+    }
+
+    fn index_column_name() -> String {
+        "search_id".to_string()
+    }
+
+    fn get_insert_columns(&self) -> Vec<String> {
+        vec![
+            String::from("position"),
+            String::from("name"),
+            String::from("text"),
+            String::from("tag_id"),
+            String::from("only_unread"), // 5
+            String::from("only_favorite"),
+            String::from("age_days"),
+            String::from("date_from"),
+            String::from("date_to"),
+            String::from("subscription_id"), // 10
+        ]
+    }
+
+    fn get_insert_values(&self) -> Vec<Wrap> {
+        vec![
+            Wrap::INT(self.position),
+            Wrap::STR(self.name.clone()),
+            Wrap::STR(self.text.clone()),
+            Wrap::INT(self.tag_id),
+            Wrap::BOO(self.only_unread), // 5
+            Wrap::BOO(self.only_favorite),
+            Wrap::INT(self.age_days),
+            Wrap::I64(self.date_from),
+            Wrap::I64(self.date_to),
+            Wrap::INT(self.subscription_id), // 10
+        ]
+    }
+
+    fn from_row(row: &rusqlite::Row) -> Self {
+        SavedSearch {
+            search_id: row.get(0).unwrap(),
+            position: row.get(1).unwrap_or_default(),
+            name: row.get(2).unwrap_or_default(),
+            text: row.get(3).unwrap_or_default(),
+            tag_id: row.get(4).unwrap_or_default(),
+            only_unread: row.get(5).unwrap_or_default(),
+            only_favorite: row.get(6).unwrap_or_default(),
+            age_days: row.get(7).unwrap_or_default(),
+            date_from: row.get(8).unwrap_or_default(),
+            date_to: row.get(9).unwrap_or_default(),
+            subscription_id: row.get(10).unwrap_or_default(),
+        }
+    }
+
+    fn get_index_value(&self) -> isize {
+        self.search_id
+    }
+}
+
+#[cfg(test)]
+mod t {
+    use super::*;
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::saved_search::t::time_range_today  --lib -- --exact --nocapture "
+    #[test]
+    fn time_range_today() {
+        let now = Local.with_ymd_and_hms(2024, 3, 10, 15, 30, 0).unwrap();
+        let midnight = Local.with_ymd_and_hms(2024, 3, 10, 0, 0, 0).unwrap();
+        let today = SavedSearch {
+            age_days: 1,
+            ..Default::default()
+        };
+        assert_eq!(today.time_range(now), (midnight.timestamp(), 0));
+        let week = SavedSearch {
+            age_days: 7,
+            date_from: midnight.timestamp(),
+            date_to: 5,
+            ..Default::default()
+        };
+        assert_eq!(week.time_range(now), (midnight.timestamp(), 5));
+        assert_eq!(SavedSearch::default().time_range(now), (0, 0));
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::saved_search::t::date_conversion  --lib -- --exact --nocapture "
+    #[test]
+    fn date_conversion() {
+        let from = date_to_timestamp("2024-06-12", false);
+        let to = date_to_timestamp(" 2024-06-12 ", true);
+        assert_eq!(to - from, 86400);
+        assert_eq!(timestamp_to_date(from, false).as_str(), "2024-06-12");
+        assert_eq!(timestamp_to_date(to, true).as_str(), "2024-06-12");
+        assert_eq!(date_to_timestamp("10.3.2024", false), 0);
+        assert!(timestamp_to_date(0, true).is_empty());
+    }
+}
@@ -130,8 +130,14 @@ impl<T: TableInfo> SqliteContext<T> {
     }
 
     pub fn insert_tx(&self, list: &[T]) -> Result<i64, rusqlite::Error> {
+        self.insert_tx_ids(list)
+            .map(|ids| ids.iter().filter(|id| **id >= 0).count() as i64)
+    }
+
+    /// returns the index values of the new rows in list order,  -1 for a row that could not be inserted
+    pub fn insert_tx_ids(&self, list: &[T]) -> Result<Vec<i64>, rusqlite::Error> {
         if list.is_empty() {
-            return Ok(0);
+            return Ok(Vec::default());
         }
         let e0 = list.first().unwrap();
         let col_names = e0.get_insert_columns();
@@ -144,7 +150,7 @@ impl<T: TableInfo> SqliteContext<T> {
         );
         let mut conn = (*self.connection).lock().unwrap();
         let tx = conn.transaction().unwrap();
-        let mut num_success: i64 = 0;
+        let mut ids: Vec<i64> = Vec::with_capacity(list.len());
         {
             let mut stmt = tx.prepare_cached(&prep_sql).unwrap();
             list.iter().for_each(|e| {
@@ -154,16 +160,17 @@ impl<T: TableInfo> SqliteContext<T> {
                     .map(|w| w.to_dyn_tosql())
                     .collect::<Vec<&dyn ToSql>>();
                 let params_fi: ParamsFromIter<&Vec<&dyn ToSql>> = params_from_iter(&vec_dyn_tosql);
-                match stmt.execute(params_fi) {
-                    Ok(num) => num_success += num as i64,
+                match stmt.insert(params_fi) {
+                    Ok(id) => ids.push(id),
                     Err(e) => {
-                        error!("{} => {:?}", prep_sql, e)
+                        error!("{} => {:?}", prep_sql, e);
+                        ids.push(-1);
                     }
                 };
             });
         }
         match tx.commit() {
-            Ok(_) => Ok(num_success),
+            Ok(_) => Ok(ids),
             Err(e) => Err(e),
         }
     }
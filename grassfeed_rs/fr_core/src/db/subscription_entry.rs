@@ -12,6 +12,13 @@ pub const SRC_REPO_ID_MOVING: isize = -3;
 #[allow(dead_code)]
 pub const SRC_REPO_ID_DUMMY: isize = 9;
 
+/// regular RSS / Atom / JSON feed
+pub const SOURCE_KIND_FEED: isize = 0;
+/// web page without feed, the items are found with selectors
+pub const SOURCE_KIND_SCRAPER: isize = 1;
+/// plain web page, each change of its text becomes a message
+pub const SOURCE_KIND_PAGE_MONITOR: isize = 2;
+
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SubscriptionEntry {
     pub subs_id: isize, // 1
@@ -31,6 +38,36 @@ pub struct SubscriptionEntry {
     pub display_name: String,
     pub url: String,         // xml_url
     pub website_url: String, // 14
+    /// download new enclosures automatically
+    pub enclosure_autodl: bool,
+    /// number of downloaded enclosure files to keep,  0: keep all
+    pub enclosure_keep: isize,
+    /// fetch the linked web page and store the extracted article as message content
+    pub full_article: bool,
+    /// see  SOURCE_KIND_FEED ...
+    pub source_kind: isize,
+    /// settings of the source kind, json
+    pub source_params: String,
+    /// WebSub hub announced by the feed, empty if there is none
+    pub websub_hub: String,
+    /// feed url the hub knows,  the self link of the feed
+    pub websub_topic: String,
+    /// part of the callback path, to recognize the hub requests
+    pub websub_secret: String,
+    /// timestamp until the push subscription is valid,  0: not subscribed
+    pub websub_lease: i64,
+    /// remote images of the messages are loaded without asking
+    pub remote_content: bool,
+    /// parent before it was moved to the trash
+    pub trash_parent_id: isize,
+    /// folder position before it was moved to the trash
+    pub trash_position: isize,
+    /// timestamp of moving to the trash,  0: not in the trash
+    pub trashed_at: i64,
+    /// not fetched,  for folders also all entries below
+    pub paused: bool,
+    /// folders only: the sort order that is kept for the entries below,  0: manual order
+    pub keep_sorted: isize,
 }
 
 impl SubscriptionEntry {
@@ -50,6 +87,21 @@ impl SubscriptionEntry {
             website_url: String::default(),
             last_selected_msg: -1,
             deleted: false,
+            enclosure_autodl: false,
+            enclosure_keep: 0,
+            full_article: false,
+            source_kind: SOURCE_KIND_FEED,
+            source_params: String::default(),
+            websub_hub: String::default(),
+            websub_topic: String::default(),
+            websub_secret: String::default(),
+            websub_lease: 0,
+            remote_content: false,
+            trash_parent_id: 0,
+            trash_position: 0,
+            trashed_at: 0,
+            paused: false,
+            keep_sorted: 0,
         }
     }
 
@@ -69,6 +121,21 @@ impl SubscriptionEntry {
             website_url: String::default(),
             last_selected_msg: -1,
             deleted: false,
+            enclosure_autodl: false,
+            enclosure_keep: 0,
+            full_article: false,
+            source_kind: SOURCE_KIND_FEED,
+            source_params: String::default(),
+            websub_hub: String::default(),
+            websub_topic: String::default(),
+            websub_secret: String::default(),
+            websub_lease: 0,
+            remote_content: false,
+            trash_parent_id: 0,
+            trash_position: 0,
+            trashed_at: 0,
+            paused: false,
+            keep_sorted: 0,
         }
     }
 
@@ -96,6 +163,16 @@ impl fmt::Debug for SubscriptionEntry {
             .field("u_icn", &self.updated_icon)
             .field("XP", &self.expanded)
             .field("web", &self.website_url)
+            .field("e_dl", &self.enclosure_autodl)
+            .field("e_keep", &self.enclosure_keep)
+            .field("f_art", &self.full_article)
+            .field("kind", &self.source_kind)
+            .field("hub", &self.websub_hub)
+            .field("lease", &self.websub_lease)
+            .field("remote", &self.remote_content)
+            .field("trashed", &self.trashed_at)
+            .field("paused", &self.paused)
+            .field("keep_sorted", &self.keep_sorted)
             .finish()
     }
 }
@@ -125,7 +202,13 @@ impl TableInfo for SubscriptionEntry {
 			last_selected_msg  INTEGER, icon_id INTEGER,   \
 			updated_ext INTEGER, updated_int INTEGER, updated_icon INTEGER, \
 		 	is_folder BOOL,  expanded BOOL, deleted BOOL, \
-			display_name TEXT, url TEXT, website_url TEXT ",
+			display_name TEXT, url TEXT, website_url TEXT, \
+			enclosure_autodl BOOL DEFAULT 0, enclosure_keep INTEGER DEFAULT 0, full_article BOOL DEFAULT 0, \
+			source_kind INTEGER DEFAULT 0, source_params TEXT DEFAULT '', \
+			websub_hub TEXT DEFAULT '', websub_topic TEXT DEFAULT '', websub_secret TEXT DEFAULT '', \
+			websub_lease INTEGER DEFAULT 0, remote_content BOOL DEFAULT 0, \
+			trash_parent_id INTEGER DEFAULT 0, trash_position INTEGER DEFAULT 0, trashed_at INTEGER DEFAULT 0, \
+			paused BOOL DEFAULT 0, keep_sorted INTEGER DEFAULT 0 ",
         )
     }
     fn index_column_name() -> String {
@@ -151,6 +234,21 @@ impl TableInfo for SubscriptionEntry {
             String::from("display_name"),
             String::from("url"),
             String::from("website_url"), // 13
+            String::from("enclosure_autodl"),
+            String::from("enclosure_keep"), // 15
+            String::from("full_article"),
+            String::from("source_kind"),
+            String::from("source_params"),
+            String::from("websub_hub"), // 20
+            String::from("websub_topic"),
+            String::from("websub_secret"),
+            String::from("websub_lease"),
+            String::from("remote_content"),
+            String::from("trash_parent_id"), // 25
+            String::from("trash_position"),
+            String::from("trashed_at"),
+            String::from("paused"),
+            String::from("keep_sorted"),
         ]
     }
 
@@ -169,6 +267,21 @@ impl TableInfo for SubscriptionEntry {
             Wrap::STR(self.display_name.clone()),
             Wrap::STR(self.url.clone()),
             Wrap::STR(self.website_url.clone()), // 13
+            Wrap::BOO(self.enclosure_autodl),
+            Wrap::INT(self.enclosure_keep), // 15
+            Wrap::BOO(self.full_article),
+            Wrap::INT(self.source_kind),
+            Wrap::STR(self.source_params.clone()),
+            Wrap::STR(self.websub_hub.clone()), // 20
+            Wrap::STR(self.websub_topic.clone()),
+            Wrap::STR(self.websub_secret.clone()),
+            Wrap::I64(self.websub_lease),
+            Wrap::BOO(self.remote_content),
+            Wrap::INT(self.trash_parent_id), // 25
+            Wrap::INT(self.trash_position),
+            Wrap::I64(self.trashed_at),
+            Wrap::BOO(self.paused),
+            Wrap::INT(self.keep_sorted),
         ]
     }
 
@@ -188,7 +301,21 @@ impl TableInfo for SubscriptionEntry {
             display_name: row.get(11).unwrap(),
             url: row.get(12).unwrap(),
             website_url: row.get(13).unwrap(), // 13
-                                               // ..Default::default()
+            enclosure_autodl: row.get(14).unwrap_or_default(),
+            enclosure_keep: row.get(15).unwrap_or_default(), // 15
+            full_article: row.get(16).unwrap_or_default(),
+            source_kind: row.get(17).unwrap_or_default(),
+            source_params: row.get(18).unwrap_or_default(),
+            websub_hub: row.get(19).unwrap_or_default(),
+            websub_topic: row.get(20).unwrap_or_default(),
+            websub_secret: row.get(21).unwrap_or_default(),
+            websub_lease: row.get(22).unwrap_or_default(),
+            remote_content: row.get(23).unwrap_or_default(),
+            trash_parent_id: row.get(24).unwrap_or_default(),
+            trash_position: row.get(25).unwrap_or_default(),
+            trashed_at: row.get(26).unwrap_or_default(),
+            paused: row.get(27).unwrap_or_default(),
+            keep_sorted: row.get(28).unwrap_or_default(),
         }
     }
 
@@ -1,11 +1,17 @@
 use crate::controller::timer::Timer;
 use crate::db::errors_repo;
+use crate::db::feed_meta::FeedMeta;
+use crate::db::message_rule::MessageRule;
+use crate::db::saved_search::SavedSearch;
+use crate::db::sqlite_context::rusqlite_error_to_boxed;
 use crate::db::sqlite_context::SqliteContext;
 use crate::db::sqlite_context::TableInfo;
 use crate::db::subscription_entry::SubscriptionEntry;
+use crate::db::subscription_entry::SOURCE_KIND_FEED;
 use crate::db::subscription_entry::SRC_REPO_ID_DELETED;
 use crate::db::subscription_entry::SRC_REPO_ID_DUMMY;
 use crate::db::subscription_entry::SRC_REPO_ID_MOVING;
+use crate::downloader::linkcleaner::normalize_feed_url;
 use crate::util::file_exists;
 use context::appcontext::AppContext;
 use context::BuildConfig;
@@ -15,6 +21,7 @@ use context::TimerEvent;
 use context::TimerReceiver;
 use context::TimerRegistry;
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -25,12 +32,23 @@ pub trait ISubscriptionRepo {
     /// sorts by folder_position
     fn get_children(&self, parent_subs_id: isize) -> Vec<SubscriptionEntry>;
 
+    /// the subscription itself, or for a folder all subscriptions below it, at any depth
+    fn get_subscription_ids_below(&self, subs_id: isize) -> Vec<isize>;
+
+    /// deleted subscriptions,  and those in the trash or below a trashed folder
+    fn get_inactive_ids(&self) -> Vec<isize>;
+
     /// get by parent_subs_id  and folder_position
     fn get_by_pri_fp(&self, parent_subs_id: isize, folder_pos: isize) -> Vec<SubscriptionEntry>;
 
     /// sorts by folder_position
     fn get_all_nonfolder(&self) -> Vec<SubscriptionEntry>;
 
+    /// normalized feed url -> ids of the subscriptions in the tree with that url, ascending.
+    /// Entries in the trash are not contained. Only feeds: scrapers and page monitors of the same page
+    /// differ by their parameters and are no duplicates.
+    fn get_feed_url_groups(&self) -> HashMap<String, Vec<isize>>;
+
     /// checks for  updated_int,  retrieves those earlier than the given date
     fn get_by_fetch_time(&self, updated_time_s: i64) -> Vec<SubscriptionEntry>;
 
@@ -73,6 +91,59 @@ pub trait ISubscriptionRepo {
 
     fn update_homepage(&self, src_id: isize, new_url: &str);
 
+    /// automatic download of new enclosures,  number of files to keep
+    fn update_enclosure_settings(&self, src_id: isize, autodl: bool, keep_count: isize);
+    /// extract the full article from the web page for new messages
+    fn update_full_article(&self, src_id: isize, full_article: bool);
+
+    fn update_remote_content(&self, src_id: isize, remote_content: bool);
+    /// origin in the tree and time of moving to the trash.  All 0 when restored.
+    fn update_trash_origin(
+        &self,
+        src_id: isize,
+        parent_id: isize,
+        position: isize,
+        trashed_at: i64,
+    );
+    /// paused subscriptions and folders are not fetched
+    fn update_paused(&self, src_id: isize, paused: bool);
+    /// paused by itself or by a folder above
+    fn is_paused_in_tree(&self, src_id: isize) -> bool;
+    /// sort order kept for the entries of the folder,  0: manual order
+    fn update_keep_sorted(&self, src_id: isize, sort_order: isize);
+    /// parameters as json, depending on the kind
+    fn update_source_kind(&self, src_id: isize, source_kind: isize, source_params: String);
+    /// a changed hub invalidates the push subscription
+    fn update_websub_hub(&self, src_id: isize, hub: String, topic: String);
+    /// lease end timestamp,  0: not subscribed
+    fn update_websub_lease(&self, src_id: isize, secret: String, lease: i64);
+    /// subscriptions announcing a hub, whose lease ends before that timestamp. Paused ones are left out.
+    fn get_websub_expiring(&self, before: i64) -> Vec<SubscriptionEntry>;
+
+    /// replaces the previous feed information of that subscription. Returns index value
+    fn store_feed_meta(&self, meta: &FeedMeta) -> Result<i64, Box<dyn std::error::Error>>;
+
+    fn get_feed_meta(&self, subs_id: isize) -> Option<FeedMeta>;
+
+    /// sorted by position
+    fn get_rules(&self) -> Vec<MessageRule>;
+
+    /// replaces all rules, the positions are taken from the list order
+    fn store_rules(&self, rules: &[MessageRule]);
+
+    /// sorted by position
+    fn get_saved_searches(&self) -> Vec<SavedSearch>;
+
+    fn get_saved_search(&self, search_id: isize) -> Option<SavedSearch>;
+
+    /// new searches are appended. Returns index value
+    fn insert_saved_search(&self, search: &SavedSearch) -> Result<i64, Box<dyn std::error::Error>>;
+
+    /// replaces all fields except the position
+    fn update_saved_search(&self, search: &SavedSearch) -> Result<i64, Box<dyn std::error::Error>>;
+
+    fn delete_saved_search(&self, search_id: isize);
+
     fn delete_by_index(&self, del_index: isize);
 
     /// clear:   deletes the table, and recreates it. Use only inside tests.
@@ -83,7 +154,8 @@ pub trait ISubscriptionRepo {
     fn get_highest_src_id(&self) -> isize;
 
     ///  put the topmost entry to deleted-parent,  set the deleted flag on all entries below
-    fn set_deleted_rec(&self, del_index: isize);
+    /// sets the deleted flag of the entry and all below it
+    fn set_deleted_rec(&self, del_index: isize, is_deleted: bool);
 
     fn store_default_db_entries(&self);
 
@@ -98,6 +170,9 @@ pub trait ISubscriptionRepo {
 pub struct SubscriptionRepo {
     folder_name: String,
     ctx: SqliteContext<SubscriptionEntry>,
+    feedmeta: SqliteContext<FeedMeta>,
+    rules: SqliteContext<MessageRule>,
+    searches: SqliteContext<SavedSearch>,
 }
 
 impl SubscriptionRepo {
@@ -118,9 +193,16 @@ impl SubscriptionRepo {
             }
         }
 
+        let ctx = SqliteContext::new(&reg_filename);
+        let feedmeta = SqliteContext::new_by_connection(ctx.get_connection());
+        let rules = SqliteContext::new_by_connection(ctx.get_connection());
+        let searches = SqliteContext::new_by_connection(ctx.get_connection());
         SubscriptionRepo {
             folder_name: folder_conf.to_string(),
-            ctx: SqliteContext::new(&reg_filename),
+            ctx,
+            feedmeta,
+            rules,
+            searches,
         }
     }
 
@@ -129,23 +211,44 @@ impl SubscriptionRepo {
     }
 
     pub fn by_file(filename: &str) -> Self {
+        let ctx = SqliteContext::new(filename);
+        let feedmeta = SqliteContext::new_by_connection(ctx.get_connection());
+        let rules = SqliteContext::new_by_connection(ctx.get_connection());
+        let searches = SqliteContext::new_by_connection(ctx.get_connection());
         SubscriptionRepo {
             folder_name: String::default(),
-            ctx: SqliteContext::new(filename),
+            ctx,
+            feedmeta,
+            rules,
+            searches,
         }
     }
 
     pub fn by_existing_connection(con: Arc<Mutex<Connection>>) -> Self {
+        let ctx = SqliteContext::new_by_connection(con);
+        let feedmeta = SqliteContext::new_by_connection(ctx.get_connection());
+        let rules = SqliteContext::new_by_connection(ctx.get_connection());
+        let searches = SqliteContext::new_by_connection(ctx.get_connection());
         SubscriptionRepo {
             folder_name: String::default(),
-            ctx: SqliteContext::new_by_connection(con),
+            ctx,
+            feedmeta,
+            rules,
+            searches,
         }
     }
 
     pub fn new_inmem() -> Self {
+        let ctx = SqliteContext::new_in_memory();
+        let feedmeta = SqliteContext::new_by_connection(ctx.get_connection());
+        let rules = SqliteContext::new_by_connection(ctx.get_connection());
+        let searches = SqliteContext::new_by_connection(ctx.get_connection());
         SubscriptionRepo {
             folder_name: String::default(),
-            ctx: SqliteContext::new_in_memory(),
+            ctx,
+            feedmeta,
+            rules,
+            searches,
         }
     }
 
@@ -161,10 +264,77 @@ impl SubscriptionRepo {
             }
         }
         self.ctx.create_table();
+        self.feedmeta.create_table();
+        self.rules.create_table();
+        let searches_existed = self.searches.one_number(format!(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='{}' ",
+            SavedSearch::table_name()
+        )) > 0;
+        self.searches.create_table();
+        self.add_missing_columns();
         self.store_default_db_entries();
+        if !searches_existed {
+            self.store_default_searches();
+        }
         true
     }
 
+    /// once, when the table is created.  The user may delete them later.
+    fn store_default_searches(&self) {
+        let defaults = [
+            SavedSearch {
+                name: t!("SEARCH_DEFAULT_TODAY"),
+                age_days: 1,
+                ..Default::default()
+            },
+            SavedSearch {
+                name: t!("SEARCH_DEFAULT_UNREAD"),
+                only_unread: true,
+                ..Default::default()
+            },
+            SavedSearch {
+                name: t!("SEARCH_DEFAULT_FAVORITES"),
+                only_favorite: true,
+                ..Default::default()
+            },
+        ];
+        for d in defaults.iter() {
+            if let Err(e) = self.insert_saved_search(d) {
+                error!("store_default_searches: {:?}", e);
+            }
+        }
+    }
+
+    /// databases from older versions lack the later added columns
+    fn add_missing_columns(&self) {
+        let insert_columns: [(&str, &str); 15] = [
+            ("enclosure_autodl", " BOOL DEFAULT 0 "),
+            ("enclosure_keep", " INTEGER DEFAULT 0 "),
+            ("full_article", " BOOL DEFAULT 0 "),
+            ("source_kind", " INTEGER DEFAULT 0 "),
+            ("source_params", " TEXT DEFAULT '' "),
+            ("websub_hub", " TEXT DEFAULT '' "),
+            ("websub_topic", " TEXT DEFAULT '' "),
+            ("websub_secret", " TEXT DEFAULT '' "),
+            ("websub_lease", " INTEGER DEFAULT 0 "),
+            ("remote_content", " BOOL DEFAULT 0 "),
+            ("trash_parent_id", " INTEGER DEFAULT 0 "),
+            ("trash_position", " INTEGER DEFAULT 0 "),
+            ("trashed_at", " INTEGER DEFAULT 0 "),
+            ("paused", " BOOL DEFAULT 0 "),
+            ("keep_sorted", " INTEGER DEFAULT 0 "),
+        ];
+        for (insert_column, column_type) in insert_columns {
+            if !self.ctx.is_column_present(insert_column) {
+                debug!("adding column  {}  ", insert_column);
+                let _num = self.ctx.add_column(insert_column, column_type);
+                if !self.ctx.is_column_present(insert_column) {
+                    warn!("could not add column:: {}  ", insert_column);
+                }
+            }
+        }
+    }
+
     /// recursive, depth-first
     pub fn dump_tree_rec(&self, lpath: &[u16], parent_subs_id: isize, ident: &str) {
         let entries = self.get_children(parent_subs_id);
@@ -206,6 +376,34 @@ impl ISubscriptionRepo for SubscriptionRepo {
         self.ctx.get_list(prepared)
     }
 
+    fn get_subscription_ids_below(&self, subs_id: isize) -> Vec<isize> {
+        match self.get_by_index(subs_id) {
+            Some(fse) if fse.is_folder => self
+                .get_children(subs_id)
+                .iter()
+                .flat_map(|child| self.get_subscription_ids_below(child.subs_id))
+                .collect(),
+            _ => vec![subs_id],
+        }
+    }
+
+    fn get_inactive_ids(&self) -> Vec<isize> {
+        let mut active: HashSet<isize> = HashSet::default();
+        let mut work: Vec<isize> = vec![0];
+        while let Some(parent_id) = work.pop() {
+            for se in self.get_children(parent_id) {
+                if !se.deleted && active.insert(se.subs_id) {
+                    work.push(se.subs_id);
+                }
+            }
+        }
+        self.get_all_entries()
+            .iter()
+            .filter(|se| !active.contains(&se.subs_id))
+            .map(|se| se.subs_id)
+            .collect()
+    }
+
     /// get by parent_subs_id  and folder_position
     fn get_by_pri_fp(&self, parent_subs_id: isize, folder_pos: isize) -> Vec<SubscriptionEntry> {
         let prepared = format!(
@@ -223,6 +421,29 @@ impl ISubscriptionRepo for SubscriptionRepo {
         self.ctx.get_list(prepared)
     }
 
+    fn get_feed_url_groups(&self) -> HashMap<String, Vec<isize>> {
+        let mut groups: HashMap<String, Vec<isize>> = HashMap::new();
+        let mut visited: HashSet<isize> = HashSet::default();
+        let mut work: Vec<isize> = vec![0];
+        while let Some(parent_id) = work.pop() {
+            if !visited.insert(parent_id) {
+                continue;
+            }
+            for se in self.get_children(parent_id) {
+                if se.is_folder {
+                    work.push(se.subs_id);
+                } else if se.source_kind == SOURCE_KIND_FEED && !se.url.trim().is_empty() {
+                    groups
+                        .entry(normalize_feed_url(&se.url))
+                        .or_default()
+                        .push(se.subs_id);
+                }
+            }
+        }
+        groups.values_mut().for_each(|ids| ids.sort());
+        groups
+    }
+
     /// checks for  updated_int,  retrieves those earlier than the given date
     /// returns the list   order by updated-time
     fn get_by_fetch_time(&self, updated_time_s: i64) -> Vec<SubscriptionEntry> {
@@ -373,6 +594,248 @@ impl ISubscriptionRepo for SubscriptionRepo {
         self.ctx.execute(sql);
     }
 
+    fn update_enclosure_settings(&self, src_id: isize, autodl: bool, keep_count: isize) {
+        let sql = format!(
+            "UPDATE {}  SET   enclosure_autodl={}, enclosure_keep={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            autodl,
+            keep_count,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_full_article(&self, src_id: isize, full_article: bool) {
+        let sql = format!(
+            "UPDATE {}  SET   full_article={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            full_article,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_remote_content(&self, src_id: isize, remote_content: bool) {
+        let sql = format!(
+            "UPDATE {}  SET   remote_content={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            remote_content,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_paused(&self, src_id: isize, paused: bool) {
+        let sql = format!(
+            "UPDATE {}  SET   paused={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            paused,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn is_paused_in_tree(&self, src_id: isize) -> bool {
+        let mut visited: HashSet<isize> = HashSet::default();
+        let mut o_entry = self.get_by_index(src_id);
+        while let Some(se) = o_entry {
+            if se.paused {
+                return true;
+            }
+            if se.parent_subs_id <= 0 || !visited.insert(se.subs_id) {
+                break;
+            }
+            o_entry = self.get_by_index(se.parent_subs_id);
+        }
+        false
+    }
+
+    fn update_keep_sorted(&self, src_id: isize, sort_order: isize) {
+        let sql = format!(
+            "UPDATE {}  SET   keep_sorted={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            sort_order,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_trash_origin(
+        &self,
+        src_id: isize,
+        parent_id: isize,
+        position: isize,
+        trashed_at: i64,
+    ) {
+        let sql = format!(
+            "UPDATE {}  SET   trash_parent_id={}, trash_position={}, trashed_at={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            parent_id,
+            position,
+            trashed_at,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_source_kind(&self, src_id: isize, source_kind: isize, source_params: String) {
+        let sql = format!(
+            "UPDATE {}  SET   source_kind={}, source_params='{}'  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            source_kind,
+            source_params.replace('\'', "''"),
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_websub_hub(&self, src_id: isize, hub: String, topic: String) {
+        let sql = format!(
+            "UPDATE {}  SET   websub_hub='{}', websub_topic='{}', websub_lease=0  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            hub.replace('\'', "''"),
+            topic.replace('\'', "''"),
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn update_websub_lease(&self, src_id: isize, secret: String, lease: i64) {
+        let sql = format!(
+            "UPDATE {}  SET   websub_secret='{}', websub_lease={}  WHERE {}={} ",
+            SubscriptionEntry::table_name(),
+            secret.replace('\'', "''"),
+            lease,
+            SubscriptionEntry::index_column_name(),
+            src_id
+        );
+        self.ctx.execute(sql);
+    }
+
+    fn get_websub_expiring(&self, before: i64) -> Vec<SubscriptionEntry> {
+        let sql = format!(
+            "SELECT * FROM {}  WHERE websub_hub != '' AND websub_lease < {} AND is_folder = false \
+             AND deleted = false AND paused = false AND parent_subs_id >= 0 ",
+            SubscriptionEntry::table_name(),
+            before
+        );
+        self.ctx
+            .get_list(sql)
+            .into_iter()
+            .filter(|se| !self.is_paused_in_tree(se.parent_subs_id))
+            .collect()
+    }
+
+    fn store_feed_meta(&self, meta: &FeedMeta) -> Result<i64, Box<dyn std::error::Error>> {
+        let sql = format!(
+            "DELETE FROM {} WHERE subscription_id = {} ",
+            FeedMeta::table_name(),
+            meta.subscription_id,
+        );
+        self.feedmeta.execute(sql);
+        self.feedmeta
+            .insert(meta, false)
+            .map_err(rusqlite_error_to_boxed)
+    }
+
+    fn get_feed_meta(&self, subs_id: isize) -> Option<FeedMeta> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE subscription_id = {} ORDER BY {} DESC ",
+            FeedMeta::table_name(),
+            subs_id,
+            FeedMeta::index_column_name(),
+        );
+        self.feedmeta.get_one(sql)
+    }
+
+    fn get_rules(&self) -> Vec<MessageRule> {
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY position ",
+            MessageRule::table_name()
+        );
+        self.rules.get_list(sql)
+    }
+
+    fn store_rules(&self, rules: &[MessageRule]) {
+        self.rules
+            .execute(format!("DELETE FROM {} ", MessageRule::table_name()));
+        let positioned: Vec<MessageRule> = rules
+            .iter()
+            .enumerate()
+            .map(|(n, r)| MessageRule {
+                position: n as isize,
+                ..r.clone()
+            })
+            .collect();
+        if let Err(e) = self.rules.insert_tx(&positioned) {
+            error!("store_rules: {:?}", e);
+        }
+    }
+
+    fn get_saved_searches(&self) -> Vec<SavedSearch> {
+        let sql = format!(
+            "SELECT * FROM {} ORDER BY position ",
+            SavedSearch::table_name()
+        );
+        self.searches.get_list(sql)
+    }
+
+    fn get_saved_search(&self, search_id: isize) -> Option<SavedSearch> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE {} = {} ",
+            SavedSearch::table_name(),
+            SavedSearch::index_column_name(),
+            search_id
+        );
+        self.searches.get_one(sql)
+    }
+
+    fn insert_saved_search(&self, search: &SavedSearch) -> Result<i64, Box<dyn std::error::Error>> {
+        let max_pos = self.searches.one_number(format!(
+            "SELECT IFNULL(MAX(position), -1) FROM {} ",
+            SavedSearch::table_name()
+        ));
+        let positioned = SavedSearch {
+            position: max_pos + 1,
+            ..search.clone()
+        };
+        self.searches
+            .insert(&positioned, false)
+            .map_err(rusqlite_error_to_boxed)
+    }
+
+    fn update_saved_search(&self, search: &SavedSearch) -> Result<i64, Box<dyn std::error::Error>> {
+        let position = match self.get_saved_search(search.search_id) {
+            Some(existing) => existing.position,
+            None => search.position,
+        };
+        self.delete_saved_search(search.search_id);
+        let positioned = SavedSearch {
+            position,
+            ..search.clone()
+        };
+        self.searches
+            .insert(&positioned, true)
+            .map_err(rusqlite_error_to_boxed)
+    }
+
+    fn delete_saved_search(&self, search_id: isize) {
+        self.searches.execute(format!(
+            "DELETE FROM {} WHERE {} = {} ",
+            SavedSearch::table_name(),
+            SavedSearch::index_column_name(),
+            search_id
+        ));
+    }
+
     fn update_timestamps(&self, src_id: isize, updated_int: i64, updated_ext: Option<i64>) {
         let upd_ext_s = if let Some(ue) = updated_ext {
             format!(", updated_ext={ue}")
@@ -426,9 +889,15 @@ impl ISubscriptionRepo for SubscriptionRepo {
             del_index
         );
         self.ctx.execute(sql);
+        let sql = format!(
+            "DELETE FROM {} WHERE subscription_id = {} ",
+            FeedMeta::table_name(),
+            del_index
+        );
+        self.feedmeta.execute(sql);
     }
 
-    fn set_deleted_rec(&self, del_index: isize) {
+    fn set_deleted_rec(&self, del_index: isize, is_deleted: bool) {
         let mut to_delete_list: HashSet<isize> = HashSet::default();
         to_delete_list.insert(del_index);
         let mut scan_list: Vec<isize> = Vec::default();
@@ -440,7 +909,7 @@ impl ISubscriptionRepo for SubscriptionRepo {
                 to_delete_list.insert(se.subs_id);
             }
         }
-        self.update_deleted_list(to_delete_list.into_iter().collect(), true);
+        self.update_deleted_list(to_delete_list.into_iter().collect(), is_deleted);
     }
 
     fn debug_dump_tree(&self, ident: &str) {
@@ -538,6 +1007,7 @@ impl TimerReceiver for SubscriptionRepo {
 #[cfg(test)]
 mod ut {
     use super::*;
+    use crate::db::subscription_entry::SOURCE_KIND_SCRAPER;
 
     #[test]
     fn t_update_last_selected() {
@@ -559,6 +1029,195 @@ mod ut {
         assert_eq!(sr.get_by_index(10).unwrap().url, "hhttps:".to_string());
     }
 
+    #[test]
+    fn t_update_enclosure_settings() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        assert!(sr.store_entry(&SubscriptionEntry::default()).is_ok());
+        sr.update_enclosure_settings(10, true, 5);
+        let se = sr.get_by_index(10).unwrap();
+        assert!(se.enclosure_autodl);
+        assert_eq!(se.enclosure_keep, 5);
+        assert!(!se.full_article);
+        sr.update_full_article(10, true);
+        assert!(sr.get_by_index(10).unwrap().full_article);
+        assert!(!se.remote_content);
+        sr.update_remote_content(10, true);
+        assert!(sr.get_by_index(10).unwrap().remote_content);
+    }
+
+    #[test]
+    fn t_update_source_kind() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        assert!(sr.store_entry(&SubscriptionEntry::default()).is_ok());
+        let params = r#"{"container":"div.item","title":"h2"}"#.to_string();
+        sr.update_source_kind(10, SOURCE_KIND_SCRAPER, params.clone());
+        let se = sr.get_by_index(10).unwrap();
+        assert_eq!(se.source_kind, SOURCE_KIND_SCRAPER);
+        assert_eq!(se.source_params, params);
+    }
+
+    #[test]
+    fn t_update_websub() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        assert!(sr.store_entry(&SubscriptionEntry::default()).is_ok());
+        assert!(sr.get_websub_expiring(1000).is_empty());
+        sr.update_websub_hub(10, "http://hub/".to_string(), "http://feed/".to_string());
+        assert_eq!(sr.get_websub_expiring(1000).len(), 1);
+        sr.update_websub_lease(10, "s3cr3t".to_string(), 2000);
+        assert!(sr.get_websub_expiring(1000).is_empty());
+        let se = sr.get_by_index(10).unwrap();
+        assert_eq!(se.websub_topic.as_str(), "http://feed/");
+        assert_eq!(se.websub_secret.as_str(), "s3cr3t");
+        sr.update_websub_hub(10, "http://hub2/".to_string(), "http://feed/".to_string());
+        assert_eq!(sr.get_by_index(10).unwrap().websub_lease, 0);
+    }
+
+    #[test]
+    fn t_websub_expiring_paused() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        let folder = SubscriptionEntry {
+            is_folder: true,
+            ..Default::default()
+        };
+        assert!(sr.store_entry(&folder).is_ok()); // 10
+        let feed = SubscriptionEntry {
+            parent_subs_id: 10,
+            ..Default::default()
+        };
+        assert!(sr.store_entry(&feed).is_ok()); // 11
+        sr.update_websub_hub(11, "http://hub/".to_string(), "http://feed/".to_string());
+        assert_eq!(sr.get_websub_expiring(1000).len(), 1);
+        sr.update_paused(10, true);
+        assert!(sr.is_paused_in_tree(11));
+        assert!(sr.get_websub_expiring(1000).is_empty());
+        sr.update_paused(10, false);
+        sr.update_paused(11, true);
+        assert!(sr.get_websub_expiring(1000).is_empty());
+        sr.update_paused(11, false);
+        assert!(!sr.is_paused_in_tree(11));
+        assert_eq!(sr.get_websub_expiring(1000).len(), 1);
+    }
+
+    #[test]
+    fn t_inactive_ids() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        let folder = SubscriptionEntry {
+            is_folder: true,
+            ..Default::default()
+        };
+        assert!(sr.store_entry(&folder).is_ok()); // 10
+        for parent in [10, 10, 0] {
+            let feed = SubscriptionEntry {
+                parent_subs_id: parent,
+                ..Default::default()
+            };
+            assert!(sr.store_entry(&feed).is_ok()); // 11, 12, 13
+        }
+        assert!(!sr.get_inactive_ids().contains(&11));
+        sr.update_parent_and_folder_position(10, SRC_REPO_ID_DELETED, 0);
+        sr.update_deleted_list(vec![13], true);
+        let inactive = sr.get_inactive_ids();
+        for subs_id in [10, 11, 12, 13] {
+            assert!(inactive.contains(&subs_id));
+        }
+    }
+
+    #[test]
+    fn t_feed_meta() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        assert!(sr.store_entry(&SubscriptionEntry::default()).is_ok());
+        assert!(sr.get_feed_meta(10).is_none());
+        for lang in ["en", "de"] {
+            let meta = FeedMeta {
+                subscription_id: 10,
+                language: lang.to_string(),
+                title: "It's a feed".to_string(),
+                ..Default::default()
+            };
+            assert!(sr.store_feed_meta(&meta).is_ok());
+        }
+        let meta = sr.get_feed_meta(10).unwrap();
+        assert_eq!(meta.language.as_str(), "de");
+        assert_eq!(meta.title.as_str(), "It's a feed");
+        assert_eq!(sr.feedmeta.count_all(), 1);
+        sr.delete_by_index(10);
+        assert!(sr.get_feed_meta(10).is_none());
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::subscription_repo::ut::t_rules  --lib -- --exact --nocapture "
+    #[test]
+    fn t_rules() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        assert!(sr.get_rules().is_empty());
+        let rules = vec![
+            MessageRule {
+                pattern: "b".to_string(),
+                position: 7,
+                ..Default::default()
+            },
+            MessageRule {
+                pattern: "a".to_string(),
+                enabled: true,
+                ..Default::default()
+            },
+        ];
+        sr.store_rules(&rules);
+        sr.store_rules(&rules);
+        let stored = sr.get_rules();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].pattern.as_str(), "b");
+        assert_eq!(stored[1].position, 1);
+        assert!(stored[1].enabled);
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  db::subscription_repo::ut::t_saved_searches  --lib -- --exact --nocapture "
+    #[test]
+    fn t_saved_searches() {
+        setup();
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        let defaults = sr.get_saved_searches();
+        assert_eq!(defaults.len(), 3);
+        assert_eq!(defaults[0].age_days, 1);
+        assert!(defaults[1].only_unread);
+        assert!(defaults[2].only_favorite);
+        sr.startup_int();
+        assert_eq!(sr.get_saved_searches().len(), 3);
+
+        let r = sr.insert_saved_search(&SavedSearch {
+            name: "rust".to_string(),
+            text: "Rust".to_string(),
+            ..Default::default()
+        });
+        let new_id = r.unwrap() as isize;
+        let mut stored = sr.get_saved_search(new_id).unwrap();
+        assert_eq!(stored.position, 3);
+        stored.name = "renamed".to_string();
+        stored.position = 0;
+        assert!(sr.update_saved_search(&stored).is_ok());
+        let updated = sr.get_saved_search(new_id).unwrap();
+        assert_eq!(updated.name.as_str(), "renamed");
+        assert_eq!(updated.position, 3);
+        sr.delete_saved_search(defaults[0].search_id);
+        let remaining = sr.get_saved_searches();
+        assert_eq!(remaining.len(), 3);
+        assert_eq!(remaining[2].search_id, new_id);
+    }
+
     #[test]
     fn t_update_displayname() {
         setup();
@@ -718,7 +1377,7 @@ mod ut {
         let _r = sr.store_entry(&e);
         e.parent_subs_id = 10;
         let _r = sr.store_entry(&e);
-        sr.set_deleted_rec(10);
+        sr.set_deleted_rec(10, true);
         let all = sr.get_all_entries();
         // all.iter().for_each(|e| debug!("## {:?}", &e));
         assert!(all.get(3).unwrap().deleted);
@@ -43,6 +43,13 @@ pub trait ISubscriptionState {
 
     fn set_deleted(&mut self, subs_id: isize, new_del: bool);
 
+    /// paused by itself or by a folder above
+    fn set_paused(&mut self, subs_id: isize, new_paused: bool);
+    fn is_paused(&self, subs_id: isize) -> bool;
+
+    /// hidden by the tree filter,  those have no tree path
+    fn set_filtered_out(&mut self, subs_id: isize, filtered_out: bool);
+
     fn get_length(&self) -> usize;
 
     fn dump(&self);
@@ -67,6 +74,28 @@ impl ISubscriptionState for SubscriptionState {
         }
     }
 
+    fn set_paused(&mut self, subs_id: isize, new_paused: bool) {
+        if let Some(st) = self.statemap.get_mut(&subs_id) {
+            st.set_paused(new_paused);
+        }
+    }
+
+    fn set_filtered_out(&mut self, subs_id: isize, filtered_out: bool) {
+        if let Some(st) = self.statemap.get_mut(&subs_id) {
+            st.set_filtered_out(filtered_out);
+            if filtered_out {
+                st.tree_path = None;
+            }
+        }
+    }
+
+    fn is_paused(&self, subs_id: isize) -> bool {
+        self.statemap
+            .get(&subs_id)
+            .map(|st| st.is_paused())
+            .unwrap_or(false)
+    }
+
     fn set_tree_path(
         &mut self,
         db_id: isize,
@@ -199,6 +228,7 @@ impl ISubscriptionState for SubscriptionState {
         self.statemap
             .iter_mut()
             .filter(|(_id, entry)| !entry.is_folder() && !entry.is_deleted())
+            .filter(|(_id, entry)| !entry.is_paused())
             .for_each(|(_id, entry)| {
                 entry.set_fetch_scheduled(true);
             });
@@ -265,6 +295,8 @@ pub enum StatusMask {
     IsDeletedCopy = 512,
     IsExpandedCopy = 1024,
     MessageCountsChecked = 2048,
+    IsPausedCopy = 4096,
+    IsFilteredOut = 8192,
 }
 
 #[allow(dead_code)]
@@ -297,6 +329,12 @@ pub trait FeedSourceState {
     fn is_deleted(&self) -> bool;
     fn set_deleted(&mut self, n: bool);
 
+    fn is_paused(&self) -> bool;
+    fn set_paused(&mut self, n: bool);
+
+    fn is_filtered_out(&self) -> bool;
+    fn set_filtered_out(&mut self, n: bool);
+
     fn check_bitmask(&self, bitmask: usize) -> bool;
     fn change_bitmask(&mut self, bitmask: usize, new_state: bool);
 
@@ -354,6 +392,20 @@ impl FeedSourceState for SubsMapEntry {
         self.change_bitmask(StatusMask::IsDeletedCopy as usize, n)
     }
 
+    fn is_paused(&self) -> bool {
+        self.check_bitmask(StatusMask::IsPausedCopy as usize)
+    }
+    fn set_paused(&mut self, n: bool) {
+        self.change_bitmask(StatusMask::IsPausedCopy as usize, n)
+    }
+
+    fn is_filtered_out(&self) -> bool {
+        self.check_bitmask(StatusMask::IsFilteredOut as usize)
+    }
+    fn set_filtered_out(&mut self, n: bool) {
+        self.change_bitmask(StatusMask::IsFilteredOut as usize, n)
+    }
+
     fn is_expanded(&self) -> bool {
         self.check_bitmask(StatusMask::IsExpandedCopy as usize)
     }
@@ -443,4 +495,17 @@ pub mod t {
         println!("scan: {:?}", r);
         assert!(r.contains(&(lim + 3, false)));
     }
+
+    //cargo watch -s "cargo test db::subscription_state::t::t_schedule_fetch_all_skips_paused  --lib -- --exact --nocapture"
+    #[test]
+    fn t_schedule_fetch_all_skips_paused() {
+        let mut ss = SubscriptionState::default();
+        ss.statemap.insert(1, SubsMapEntry::default());
+        ss.statemap.insert(2, SubsMapEntry::default());
+        ss.set_paused(2, true);
+        ss.set_schedule_fetch_all();
+        assert_eq!(ss.get_fetch_scheduled(), vec![1]);
+        assert!(ss.is_paused(2));
+        assert!(!ss.is_paused(3));
+    }
 }
@@ -0,0 +1,176 @@
+//! Runs the stored rules on all messages in the database, outside the gui thread.
+//! Only the actions that change messages are applied.
+
+use crate::controller::contentlist::CJob;
+use crate::controller::guiprocessor::Job;
+use crate::db::message_rule::RULE_ACTION_DELETE;
+use crate::db::message_rule::RULE_ACTION_FAVORITE;
+use crate::db::message_rule::RULE_ACTION_MARK_READ;
+use crate::db::message_rule::RULE_ACTION_TAG;
+use crate::db::messages_repo::IMessagesRepo;
+use crate::db::messages_repo::MessagesRepo;
+use crate::db::subscription_repo::ISubscriptionRepo;
+use crate::db::subscription_repo::SubscriptionRepo;
+use crate::downloader::rules::subscription_path;
+use crate::downloader::rules::RuleEngine;
+use crate::util::Step;
+use crate::util::StepResult;
+use flume::Sender;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+pub struct ApplyRulesInner {
+    pub subscriptionrepo: SubscriptionRepo,
+    pub messagesrepo: MessagesRepo,
+    pub cjob_sender: Sender<CJob>,
+    pub gp_job_sender: Sender<Job>,
+    pub num_changed: usize,
+    /// subscriptions of the changed messages
+    pub changed_subs_ids: Vec<isize>,
+    /// some tag was created by a rule
+    pub tag_created: bool,
+}
+
+impl ApplyRulesInner {
+    pub fn new(
+        sub_re: SubscriptionRepo,
+        msg_re: MessagesRepo,
+        c_se: Sender<CJob>,
+        gp_se: Sender<Job>,
+    ) -> Self {
+        ApplyRulesInner {
+            subscriptionrepo: sub_re,
+            messagesrepo: msg_re,
+            cjob_sender: c_se,
+            gp_job_sender: gp_se,
+            num_changed: 0,
+            changed_subs_ids: Vec::default(),
+            tag_created: false,
+        }
+    }
+}
+
+impl PartialEq for ApplyRulesInner {
+    fn eq(&self, _other: &Self) -> bool {
+        true // only one element shall be in the queue
+    }
+}
+
+impl std::fmt::Debug for ApplyRulesInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("")
+            .field("num_changed", &self.num_changed)
+            .finish()
+    }
+}
+
+pub struct ApplyRulesStart(pub ApplyRulesInner);
+impl ApplyRulesStart {
+    pub fn new(i: ApplyRulesInner) -> Self {
+        ApplyRulesStart(i)
+    }
+}
+impl Step<ApplyRulesInner> for ApplyRulesStart {
+    fn step(self: Box<Self>) -> StepResult<ApplyRulesInner> {
+        let mut inner = self.0;
+        let engine = RuleEngine::new(&inner.subscriptionrepo.get_rules());
+        if engine.is_empty() {
+            return StepResult::Continue(Box::new(ApplyRulesNotify(inner)));
+        }
+        let messages = inner.messagesrepo.get_all_not_deleted();
+        let mut paths: HashMap<isize, Vec<isize>> = HashMap::new();
+        let mut read_ids: Vec<i32> = Vec::default();
+        let mut delete_ids: Vec<i32> = Vec::default();
+        let mut favorites: Vec<(isize, u64)> = Vec::default();
+        let mut tag_assign: Vec<(String, i32)> = Vec::default();
+        let existing_tags = inner.messagesrepo.get_tags();
+        let existing_links: HashSet<(isize, isize)> = inner
+            .messagesrepo
+            .get_all_tag_links()
+            .iter()
+            .map(|l| (l.message_id, l.tag_id))
+            .collect();
+        let mut subs_ids: HashSet<isize> = HashSet::default();
+        for msg in &messages {
+            let subs_path = paths
+                .entry(msg.subscription_id)
+                .or_insert_with(|| subscription_path(&inner.subscriptionrepo, msg.subscription_id));
+            let mut changed = false;
+            for rule in engine.matching(msg, subs_path) {
+                match rule.action {
+                    RULE_ACTION_MARK_READ if !msg.is_read => {
+                        read_ids.push(msg.message_id as i32);
+                        changed = true;
+                    }
+                    RULE_ACTION_DELETE => {
+                        delete_ids.push(msg.message_id as i32);
+                        changed = true;
+                    }
+                    RULE_ACTION_FAVORITE if !msg.is_favorite() => {
+                        let mut fav = msg.clone();
+                        fav.set_favorite(true);
+                        favorites.push((msg.message_id, fav.markers));
+                        changed = true;
+                    }
+                    RULE_ACTION_TAG => {
+                        let name_lower = rule.action_arg.trim().to_lowercase();
+                        let has_tag = existing_tags
+                            .iter()
+                            .find(|t| t.name.to_lowercase() == name_lower)
+                            .is_some_and(|t| existing_links.contains(&(msg.message_id, t.tag_id)));
+                        if !has_tag {
+                            tag_assign.push((rule.action_arg.clone(), msg.message_id as i32));
+                            changed = true;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            if changed {
+                inner.num_changed += 1;
+                subs_ids.insert(msg.subscription_id);
+            }
+        }
+        read_ids.dedup();
+        delete_ids.dedup();
+        if !read_ids.is_empty() {
+            inner.messagesrepo.update_is_read_many(&read_ids, true);
+        }
+        if !delete_ids.is_empty() {
+            inner.messagesrepo.update_is_deleted_many(&delete_ids, true);
+        }
+        favorites.iter().for_each(|(msg_id, markers)| {
+            inner.messagesrepo.update_markers(*msg_id, *markers);
+        });
+        for (name, msg_id) in &tag_assign {
+            match inner.messagesrepo.get_or_insert_tag(name) {
+                Ok((tag_id, is_new)) => {
+                    inner.tag_created |= is_new;
+                    inner.messagesrepo.add_tag_links(&[*msg_id], tag_id);
+                }
+                Err(e) => warn!("rule tag {} => {:?}", name, e),
+            }
+        }
+        inner.changed_subs_ids = subs_ids.into_iter().collect();
+        StepResult::Continue(Box::new(ApplyRulesNotify(inner)))
+    }
+}
+
+pub struct ApplyRulesNotify(pub ApplyRulesInner);
+impl Step<ApplyRulesInner> for ApplyRulesNotify {
+    fn step(self: Box<Self>) -> StepResult<ApplyRulesInner> {
+        let inner = self.0;
+        let _r = inner.cjob_sender.send(CJob::RulesApplied(
+            inner.changed_subs_ids.clone(),
+            inner.tag_created,
+        ));
+        let _r = inner
+            .gp_job_sender
+            .send(Job::AddBottomDisplayErrorMessage(format!(
+                "{} {}",
+                t!("D_RULES_APPLIED"),
+                inner.num_changed
+            )));
+        StepResult::Stop(inner)
+    }
+}
@@ -0,0 +1,388 @@
+use crate::controller::contentlist::CJob;
+use crate::db::errorentry::ESRC;
+use crate::db::errors_repo::ErrorRepo;
+use crate::db::message::compress;
+use crate::db::messages_repo::IMessagesRepo;
+use crate::db::messages_repo::MessagesRepo;
+use crate::util::Step;
+use crate::util::StepResult;
+use crate::web::WebFetcherType;
+use flume::Sender;
+use std::collections::HashMap;
+use tl::Node;
+
+/// shorter results are most likely navigation or a cookie banner, not the article
+const MIN_ARTICLE_LENGTH: usize = 250;
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+const CLASS_WEIGHT: f32 = 25.0;
+
+/// these tags never belong to the article text
+const UNLIKELY_TAGS: [&str; 12] = [
+    "script", "style", "nav", "aside", "form", "footer", "header", "noscript", "iframe", "button",
+    "svg", "select",
+];
+const NEGATIVE_NAMES: [&str; 16] = [
+    "comment",
+    "comments",
+    "meta",
+    "footer",
+    "sidebar",
+    "share",
+    "social",
+    "related",
+    "nav",
+    "navigation",
+    "ad",
+    "ads",
+    "promo",
+    "menu",
+    "banner",
+    "cookie",
+];
+const POSITIVE_NAMES: [&str; 9] = [
+    "article", "body", "content", "entry", "main", "post", "text", "story", "blog",
+];
+
+pub struct ArticleInner {
+    pub msg_id: isize,
+    pub subs_id: isize,
+    /// the link of the message
+    pub url: String,
+    pub web_fetcher: WebFetcherType,
+    pub messgesrepo: MessagesRepo,
+    pub cjob_sender: Sender<CJob>,
+    pub erro_repo: ErrorRepo,
+    pub download_text: String,
+    pub article_text: String,
+}
+
+impl ArticleInner {
+    pub fn new(
+        msg_id: isize,
+        subs_id: isize,
+        url: String,
+        fetcher: WebFetcherType,
+        msgrepo: MessagesRepo,
+        cjob_sender: Sender<CJob>,
+        err_repo: ErrorRepo,
+    ) -> Self {
+        ArticleInner {
+            msg_id,
+            subs_id,
+            url,
+            web_fetcher: fetcher,
+            messgesrepo: msgrepo,
+            cjob_sender,
+            erro_repo: err_repo,
+            download_text: String::default(),
+            article_text: String::default(),
+        }
+    }
+
+    fn add_error(&self, e_val: isize, text: String) {
+        self.erro_repo.add_error(
+            self.subs_id,
+            ESRC::ArticleExtraction,
+            e_val,
+            self.url.clone(),
+            text,
+        );
+    }
+}
+
+impl std::fmt::Debug for ArticleInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("")
+            .field("msg_id", &self.msg_id)
+            .field("url", &self.url)
+            .finish()
+    }
+}
+
+impl PartialEq for ArticleInner {
+    fn eq(&self, other: &Self) -> bool {
+        self.msg_id == other.msg_id
+    }
+}
+
+pub struct ArticleStart(ArticleInner);
+impl ArticleStart {
+    pub fn new(i: ArticleInner) -> Self {
+        ArticleStart(i)
+    }
+}
+
+impl Step<ArticleInner> for ArticleStart {
+    fn step(self: Box<Self>) -> StepResult<ArticleInner> {
+        let mut inner = self.0;
+        let r = (*inner.web_fetcher).request_url(&inner.url);
+        if r.http_status != 200 {
+            inner.add_error(
+                r.get_combined_error(),
+                format!("article.rs {} {}", r.http_status, r.error_description),
+            );
+            return StepResult::Stop(inner);
+        }
+        inner.download_text = r.content;
+        StepResult::Continue(Box::new(ArticleExtract(inner)))
+    }
+}
+
+struct ArticleExtract(ArticleInner);
+impl Step<ArticleInner> for ArticleExtract {
+    fn step(self: Box<Self>) -> StepResult<ArticleInner> {
+        let mut inner = self.0;
+        match extract_article(&inner.download_text) {
+            Some(article) => {
+                inner.download_text.clear();
+                inner.article_text = article;
+                StepResult::Continue(Box::new(ArticleStore(inner)))
+            }
+            None => {
+                inner.add_error(0, "no article found".to_string());
+                StepResult::Stop(inner)
+            }
+        }
+    }
+}
+
+/// The feed content moves into the summary, unless a previous extraction did that already.
+struct ArticleStore(ArticleInner);
+impl Step<ArticleInner> for ArticleStore {
+    fn step(self: Box<Self>) -> StepResult<ArticleInner> {
+        let inner = self.0;
+        let o_msg = inner.messgesrepo.get_by_index(inner.msg_id);
+        if o_msg.is_none() {
+            return StepResult::Stop(inner);
+        }
+        let msg = o_msg.unwrap();
+        let summary_compr = if msg.has_article() {
+            msg.summary_text
+        } else {
+            msg.content_text
+        };
+        inner.messgesrepo.update_article(
+            inner.msg_id,
+            &compress(&inner.article_text),
+            &summary_compr,
+        );
+        let _r = inner.cjob_sender.send(CJob::ArticleExtracted(inner.msg_id));
+        StepResult::Stop(inner)
+    }
+}
+
+/// Readability-style: paragraphs are scored by length and commas, their parent gets the score,
+/// the grandparent half of it. Class and id names adjust the score, links reduce it.
+/// Returns the html of the best scored element, without the unlikely parts.
+pub fn extract_article(html: &str) -> Option<String> {
+    let dom: tl::VDom = match tl::parse(html, tl::ParserOptions::default()) {
+        Ok(d) => d,
+        Err(e) => {
+            debug!("extract_article: parsing {:?}", e);
+            return None;
+        }
+    };
+    let parser = dom.parser();
+    let nodes = dom.nodes();
+    let mut parents: Vec<Option<usize>> = vec![None; nodes.len()];
+    for (idx, node) in nodes.iter().enumerate() {
+        if let Some(children) = node.children() {
+            for handle in children.top().iter() {
+                if let Some(p) = parents.get_mut(handle.get_inner() as usize) {
+                    *p = Some(idx);
+                }
+            }
+        }
+    }
+    let is_excluded = |idx: usize| -> bool {
+        let mut o_cur = Some(idx);
+        while let Some(cur) = o_cur {
+            if is_unlikely(&nodes[cur]) {
+                return true;
+            }
+            o_cur = parents[cur];
+        }
+        false
+    };
+    let mut scores: HashMap<usize, f32> = HashMap::default();
+    for (idx, node) in nodes.iter().enumerate() {
+        let tag_name = match node.as_tag() {
+            Some(tag) => tag.name().as_utf8_str().to_lowercase(),
+            None => continue,
+        };
+        if tag_name != "p" && tag_name != "pre" {
+            continue;
+        }
+        let text = node.inner_text(parser);
+        let text = text.trim();
+        let text_len = text.chars().count();
+        if text_len < MIN_PARAGRAPH_LENGTH || is_excluded(idx) {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f32 + (text_len / 100).min(3) as f32;
+        if let Some(parent) = parents[idx] {
+            *scores.entry(parent).or_default() += score;
+            if let Some(grandparent) = parents[parent] {
+                *scores.entry(grandparent).or_default() += score / 2.0;
+            }
+        }
+    }
+    let (best_idx, _best_score) = scores
+        .iter()
+        .map(|(idx, score)| {
+            let node = &nodes[*idx];
+            let weighted = (score + class_weight(node)) * (1.0 - link_density(node, parser));
+            (*idx, weighted)
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let best = &nodes[best_idx];
+    if best.inner_text(parser).trim().chars().count() < MIN_ARTICLE_LENGTH {
+        return None;
+    }
+    let mut article = String::default();
+    if let Some(children) = best.children() {
+        for handle in children.top().iter() {
+            if let Some(child) = handle.get(parser) {
+                article.push_str(&render_node(child, parser));
+            }
+        }
+    }
+    Some(article.trim().to_string())
+}
+
+/// outer html, leaving out unlikely descendants
+fn render_node(node: &Node, parser: &tl::Parser) -> String {
+    match node {
+        Node::Comment(_) => String::default(),
+        Node::Raw(bytes) => bytes.as_utf8_str().to_string(),
+        Node::Tag(tag) => {
+            if is_unlikely(node) {
+                return String::default();
+            }
+            let has_unlikely = tag.children().all(parser).iter().any(is_unlikely);
+            if !has_unlikely {
+                return tag.outer_html(parser);
+            }
+            let name = tag.name().as_utf8_str();
+            let inner: String = tag
+                .children()
+                .top()
+                .iter()
+                .filter_map(|h| h.get(parser))
+                .map(|n| render_node(n, parser))
+                .collect();
+            format!("<{name}>{inner}</{name}>")
+        }
+    }
+}
+
+fn is_unlikely(node: &Node) -> bool {
+    let tag = match node.as_tag() {
+        Some(t) => t,
+        None => return false,
+    };
+    let tag_name = tag.name().as_utf8_str().to_lowercase();
+    if UNLIKELY_TAGS.contains(&tag_name.as_str()) {
+        return true;
+    }
+    let names = class_id_names(node);
+    names.iter().any(|n| NEGATIVE_NAMES.contains(&n.as_str()))
+        && !names.iter().any(|n| POSITIVE_NAMES.contains(&n.as_str()))
+}
+
+fn class_weight(node: &Node) -> f32 {
+    let names = class_id_names(node);
+    let mut weight: f32 = 0.0;
+    if names.iter().any(|n| POSITIVE_NAMES.contains(&n.as_str())) {
+        weight += CLASS_WEIGHT;
+    }
+    if names.iter().any(|n| NEGATIVE_NAMES.contains(&n.as_str())) {
+        weight -= CLASS_WEIGHT;
+    }
+    weight
+}
+
+/// words of the class and id attributes, lowercase, split at  space - _
+fn class_id_names(node: &Node) -> Vec<String> {
+    let mut names: Vec<String> = Vec::default();
+    if let Some(tag) = node.as_tag() {
+        let attributes = tag.attributes();
+        for bytes in [attributes.class(), attributes.id()].into_iter().flatten() {
+            bytes
+                .as_utf8_str()
+                .to_lowercase()
+                .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+                .filter(|s| !s.is_empty())
+                .for_each(|s| names.push(s.to_string()));
+        }
+    }
+    names
+}
+
+/// share of the text that is inside links,  0..1
+fn link_density(node: &Node, parser: &tl::Parser) -> f32 {
+    let text_len = node.inner_text(parser).trim().chars().count();
+    if text_len == 0 {
+        return 1.0;
+    }
+    let link_len: usize = match node.children() {
+        Some(children) => children
+            .all(parser)
+            .iter()
+            .filter_map(|n| n.as_tag())
+            .filter(|t| t.name().as_utf8_str().eq_ignore_ascii_case("a"))
+            .map(|t| t.inner_text(parser).trim().chars().count())
+            .sum(),
+        None => 0,
+    };
+    (link_len as f32 / text_len as f32).min(1.0)
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    const PARAGRAPH: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.";
+
+    fn page(article_class: &str) -> String {
+        format!(
+            "<html><head><title>T</title><script>var x=1;</script></head><body>\
+            <header><p>{PARAGRAPH}</p></header>\
+            <div class=\"sidebar\"><p>{PARAGRAPH}</p></div>\
+            <div class=\"{article_class}\"><h1>Headline</h1><p>{PARAGRAPH}</p><p>{PARAGRAPH}</p>\
+            <p>{PARAGRAPH}</p><script>track();</script><div class=\"share-buttons\">Share</div></div>\
+            <footer><a href=\"/imprint\">Imprint</a></footer></body></html>"
+        )
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::article::t_::extract_main_content  --lib -- --exact --nocapture "
+    #[test]
+    fn extract_main_content() {
+        let article = extract_article(&page("post-content")).unwrap();
+        assert!(article.starts_with("<h1>Headline</h1>"));
+        assert_eq!(article.matches(PARAGRAPH).count(), 3);
+        assert!(!article.contains("track()"));
+        assert!(!article.contains("Share"));
+        assert!(!article.contains("Imprint"));
+    }
+
+    #[test]
+    fn extract_too_short() {
+        let html = "<html><body><div><p>Only a teaser, nothing more here.</p></div></body></html>";
+        assert!(extract_article(html).is_none());
+        assert!(extract_article("").is_none());
+    }
+
+    #[test]
+    fn link_list_is_not_article() {
+        let links: String = (0..20)
+            .map(|n| format!("<p><a href=\"/p{n}\">{PARAGRAPH}</a></p>"))
+            .collect();
+        let html = format!(
+            "<html><body><div id=\"linklist\">{links}</div><div id=\"main\"><p>{PARAGRAPH}</p>\
+            <p>{PARAGRAPH}</p><p>{PARAGRAPH}</p></div></body></html>"
+        );
+        let article = extract_article(&html).unwrap();
+        assert!(!article.contains("href"));
+    }
+}
@@ -2,14 +2,22 @@ use crate::controller::guiprocessor::Job;
 use crate::controller::sourcetree::SJob;
 use crate::db::errorentry::ESRC;
 use crate::db::errors_repo::ErrorRepo;
+use crate::downloader::siteresolver::resolve_site_feed;
 use crate::downloader::util::extract_feed_from_website;
+use crate::downloader::util::feed_preview_titles;
 use crate::downloader::util::go_to_homepage;
+use crate::downloader::util::FeedCandidate;
 use crate::util::Step;
 use crate::util::StepResult;
 use crate::web::WebFetcherType;
 use feed_rs::parser;
 use flume::Sender;
 
+/// more candidates are offered without preview
+pub const CANDIDATES_PREVIEW_MAX: usize = 8;
+/// item titles per candidate preview
+pub const CANDIDATE_PREVIEW_ITEMS: usize = 5;
+
 pub struct DragInner {
     pub web_fetcher: WebFetcherType,
     pub sourcetree_job_sender: Sender<SJob>,
@@ -17,6 +25,8 @@ pub struct DragInner {
     pub dragged_url: String,
     pub dragged_url_content: String,
     pub found_feed_url: String,
+    /// all feeds the page advertises, the found_feed_url is the first
+    pub found_candidates: Vec<FeedCandidate>,
     pub found_homepage: String,
     pub error_message: String,
     pub erro_repo: ErrorRepo,
@@ -40,6 +50,7 @@ impl DragInner {
             guiproc_job_sender: gp_sender,
             dragged_url_content: Default::default(),
             found_feed_url: Default::default(),
+            found_candidates: Default::default(),
             found_homepage: Default::default(),
             error_message: Default::default(),
             testing_base_url: Default::default(),
@@ -83,7 +94,7 @@ impl Step<DragInner> for BrowserEvalStart {
             inner.dragged_url.clone(),
             result.error_description,
         );
-        StepResult::Continue(Box::new(CompleteRelativeUrl(inner)))
+        StepResult::Continue(Box::new(ResolveSiteFeed(inner)))
     }
 }
 
@@ -97,7 +108,8 @@ impl Step<DragInner> for ParseWebpage {
             inner.error_message = err_msg;
             return StepResult::Continue(Box::new(CheckContentIsFeed(inner)));
         }
-        inner.found_feed_url = extr_r.unwrap();
+        inner.found_candidates = extr_r.unwrap();
+        inner.found_feed_url = inner.found_candidates[0].url.clone();
         StepResult::Continue(Box::new(CompleteRelativeUrl(inner)))
     }
 }
@@ -109,7 +121,7 @@ impl Step<DragInner> for CheckContentIsFeed {
         let parse_r = parser::parse(inner.dragged_url_content.as_bytes());
         if parse_r.is_err() {
             inner.error_message += &parse_r.err().unwrap().to_string();
-            return StepResult::Continue(Box::new(AnalyzeContentSloppy(inner)));
+            return StepResult::Continue(Box::new(ResolveSiteFeed(inner)));
         }
         let parsed = parse_r.unwrap();
         if let Some(t_t) = parsed.title {
@@ -120,6 +132,26 @@ impl Step<DragInner> for CheckContentIsFeed {
     }
 }
 
+/// Known platforms whose pages do not announce their feed,  also when the page could not be loaded
+struct ResolveSiteFeed(DragInner);
+impl Step<DragInner> for ResolveSiteFeed {
+    fn step(self: Box<Self>) -> StepResult<DragInner> {
+        let mut inner: DragInner = self.0;
+        let page_url = if inner.dragged_url.starts_with("http") {
+            inner.dragged_url.clone()
+        } else {
+            inner.testing_base_url.clone()
+        };
+        if let Some((resolver, feed_url)) = resolve_site_feed(&page_url, &inner.dragged_url_content)
+        {
+            debug!("feed adress by resolver {}:   {}", resolver, feed_url);
+            inner.found_feed_url = feed_url;
+            return StepResult::Continue(Box::new(CompleteRelativeUrl(inner)));
+        }
+        StepResult::Continue(Box::new(AnalyzeContentSloppy(inner)))
+    }
+}
+
 struct AnalyzeContentSloppy(DragInner);
 impl Step<DragInner> for AnalyzeContentSloppy {
     fn step(self: Box<Self>) -> StepResult<DragInner> {
@@ -128,6 +160,14 @@ impl Step<DragInner> for AnalyzeContentSloppy {
         if !extracted.is_empty() {
             debug!("feed adresses found by sloppy:   {:?}", extracted);
             inner.found_feed_url.clone_from(extracted.first().unwrap());
+            extracted.iter().for_each(|url| {
+                if !inner.found_candidates.iter().any(|c| c.url == *url) {
+                    inner.found_candidates.push(FeedCandidate {
+                        url: url.clone(),
+                        ..Default::default()
+                    });
+                }
+            });
         }
         StepResult::Continue(Box::new(CompleteRelativeUrl(inner)))
     }
@@ -141,21 +181,57 @@ impl Step<DragInner> for CompleteRelativeUrl {
         if !drag_url.starts_with("http") {
             drag_url.clone_from(&inner.testing_base_url);
         }
-        if !inner.found_feed_url.is_empty() && !inner.found_feed_url.starts_with("http") {
-            let o_homepage_addr = go_to_homepage(&drag_url);
-            if let Some(base_url) = o_homepage_addr {
-                if !base_url.ends_with('/') && !inner.found_feed_url.starts_with('/') {
-                    inner.found_feed_url = format!("{}/{}", base_url, inner.found_feed_url);
-                } else {
-                    inner.found_feed_url = format!("{}{}", base_url, inner.found_feed_url);
-                }
-                // trace!("CompleteRelativeUrl modified  {} ", inner.found_feed_url);
+        inner.found_feed_url = complete_relative_url(&inner.found_feed_url, &drag_url);
+        inner
+            .found_candidates
+            .iter_mut()
+            .for_each(|c| c.url = complete_relative_url(&c.url, &drag_url));
+        StepResult::Continue(Box::new(PreviewCandidates(inner)))
+    }
+}
+
+fn complete_relative_url(found_url: &str, drag_url: &str) -> String {
+    if found_url.is_empty() || found_url.starts_with("http") {
+        return found_url.to_string();
+    }
+    match go_to_homepage(drag_url) {
+        Some(base_url) => {
+            if !base_url.ends_with('/') && !found_url.starts_with('/') {
+                format!("{}/{}", base_url, found_url)
+            } else {
+                format!("{}{}", base_url, found_url)
             }
         }
+        None => found_url.to_string(),
+    }
+}
+
+/// Loads the candidates to show their latest item titles,  when there is a choice
+struct PreviewCandidates(DragInner);
+impl Step<DragInner> for PreviewCandidates {
+    fn step(self: Box<Self>) -> StepResult<DragInner> {
+        let mut inner: DragInner = self.0;
+        preview_feed_candidates(&inner.web_fetcher, &mut inner.found_candidates);
         StepResult::Continue(Box::new(Notify(inner)))
     }
 }
 
+/// Fills the preview of the candidates,  when there is a choice
+pub fn preview_feed_candidates(web_fetcher: &WebFetcherType, candidates: &mut [FeedCandidate]) {
+    if candidates.len() < 2 {
+        return;
+    }
+    candidates
+        .iter_mut()
+        .take(CANDIDATES_PREVIEW_MAX)
+        .for_each(|c| {
+            let result = (*web_fetcher).request_url(&c.url);
+            if result.http_status == 200 {
+                c.preview = feed_preview_titles(&result.content, CANDIDATE_PREVIEW_ITEMS);
+            }
+        });
+}
+
 struct Notify(DragInner);
 impl Step<DragInner> for Notify {
     fn step(self: Box<Self>) -> StepResult<DragInner> {
@@ -165,6 +241,7 @@ impl Step<DragInner> for Notify {
             inner.found_feed_url.clone(),
             inner.error_message.clone(),
             inner.feed_display_title.clone(),
+            inner.found_candidates.clone(),
         ));
         if inner.found_feed_url.is_empty() {
             let _r = inner
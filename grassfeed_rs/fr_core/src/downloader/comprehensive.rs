@@ -1,8 +1,15 @@
+use crate::controller::sourcetree::NewSourceResult;
 use crate::controller::sourcetree::SJob;
 use crate::db::icon_repo::IIconRepo;
 use crate::db::icon_repo::IconRepo;
 use crate::db::icon_row::IconRow;
+use crate::downloader::browserdrag::preview_feed_candidates;
+use crate::downloader::scraper::page_title;
+use crate::downloader::scraper::scrape_items;
+use crate::downloader::scraper::scraper_preview_text;
+use crate::downloader::scraper::ScraperSelectors;
 use crate::downloader::util;
+use crate::downloader::util::FeedCandidate;
 use crate::util::downscale_image;
 use crate::util::Step;
 use crate::util::StepResult;
@@ -16,6 +23,8 @@ use super::icons::ICON_CONVERT_TO_WIDTH;
 /// for new-source dialog
 pub struct ComprehensiveInner {
     pub feed_url_edit: String,
+    /// where the feed url redirected to,  empty if it did not
+    pub redirected_url: String,
     pub url_download_text: String,
     pub feed_title: String,
     pub feed_homepage: String,
@@ -26,6 +35,12 @@ pub struct ComprehensiveInner {
     pub download_error_happened: bool,
     pub sourcetree_job_sender: Sender<SJob>,
     pub icon_id: isize,
+    /// only for web pages without feed
+    pub scraper: Option<ScraperSelectors>,
+    /// items found by the scraper, one per line
+    pub scraper_preview: String,
+    /// the feeds a web page advertises,  if the url was not a feed
+    pub feed_candidates: Vec<FeedCandidate>,
 }
 
 impl std::fmt::Debug for ComprehensiveInner {
@@ -60,7 +75,12 @@ impl Step<ComprehensiveInner> for ComprStart {
         match result.http_status {
             200 => {
                 inner.url_download_text = result.content;
-                StepResult::Continue(Box::new(ParseFeedString(inner)))
+                inner.redirected_url = result.final_url;
+                if inner.scraper.is_some() {
+                    StepResult::Continue(Box::new(ComprScrapePreview(inner)))
+                } else {
+                    StepResult::Continue(Box::new(ParseFeedString(inner)))
+                }
             }
             _ => {
                 inner.download_error_happened = true;
@@ -81,10 +101,19 @@ pub struct ParseFeedString(ComprehensiveInner);
 impl Step<ComprehensiveInner> for ParseFeedString {
     fn step(self: Box<Self>) -> StepResult<ComprehensiveInner> {
         let mut inner: ComprehensiveInner = self.0;
-        let (homepage, feed_title, _err_msg) = util::retrieve_homepage_from_feed_text(
+        let (homepage, feed_title, err_msg) = util::retrieve_homepage_from_feed_text(
             inner.url_download_text.as_bytes(),
             &inner.feed_url_edit,
         );
+        if !err_msg.is_empty() {
+            if let Ok(mut candidates) = util::extract_feed_from_website(&inner.url_download_text) {
+                candidates
+                    .iter_mut()
+                    .for_each(|c| c.url = util::resolve_url(&c.url, &inner.feed_url_edit));
+                preview_feed_candidates(&inner.web_fetcher, &mut candidates);
+                inner.feed_candidates = candidates;
+            }
+        }
         if !homepage.is_empty() {
             inner.feed_homepage = homepage;
         }
@@ -100,6 +129,34 @@ impl Step<ComprehensiveInner> for ParseFeedString {
     }
 }
 
+/// Web page without feed: the page is the homepage, the found items are shown in the dialog
+pub struct ComprScrapePreview(ComprehensiveInner);
+impl Step<ComprehensiveInner> for ComprScrapePreview {
+    fn step(self: Box<Self>) -> StepResult<ComprehensiveInner> {
+        let mut inner: ComprehensiveInner = self.0;
+        let selectors = inner.scraper.clone().unwrap_or_default();
+        inner.scraper_preview =
+            match scrape_items(&inner.url_download_text, &selectors, &inner.feed_url_edit) {
+                Ok(items) => scraper_preview_text(&items),
+                Err(e) => e,
+            };
+        inner.feed_homepage.clone_from(&inner.feed_url_edit);
+        inner.feed_title = page_title(&inner.url_download_text);
+        match util::extract_icon_from_homepage(
+            inner.url_download_text.clone(),
+            &inner.feed_homepage,
+        ) {
+            Ok(icon_url) => {
+                inner.icon_url = icon_url;
+            }
+            Err(descr) => {
+                debug!("XI: {} {}", inner.feed_homepage, descr);
+            }
+        }
+        StepResult::Continue(Box::new(ComprLoadIcon(inner)))
+    }
+}
+
 pub struct ComprAnalyzeHomepage(ComprehensiveInner);
 impl Step<ComprehensiveInner> for ComprAnalyzeHomepage {
     fn step(self: Box<Self>) -> StepResult<ComprehensiveInner> {
@@ -212,12 +269,17 @@ pub struct ComprFinal(ComprehensiveInner);
 impl Step<ComprehensiveInner> for ComprFinal {
     fn step(self: Box<Self>) -> StepResult<ComprehensiveInner> {
         let inner: ComprehensiveInner = self.0;
-        let _r = inner.sourcetree_job_sender.send(SJob::NewFeedSourceEdit(
-            inner.feed_url_edit.clone(),
-            inner.feed_title.clone(),
-            inner.icon_id,
-            inner.feed_homepage.clone(),
-        ));
+        let _r = inner
+            .sourcetree_job_sender
+            .send(SJob::NewFeedSourceEdit(NewSourceResult {
+                feed_url: inner.feed_url_edit.clone(),
+                display_name: inner.feed_title.clone(),
+                icon_id: inner.icon_id,
+                feed_homepage: inner.feed_homepage.clone(),
+                scraper_preview: inner.scraper_preview.clone(),
+                redirected_url: inner.redirected_url.clone(),
+                candidates: inner.feed_candidates.clone(),
+            }));
         StepResult::Stop(inner)
     }
 }
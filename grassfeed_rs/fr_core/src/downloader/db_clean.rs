@@ -1,5 +1,6 @@
 use crate::controller::guiprocessor::Job;
 use crate::controller::sourcetree::SJob;
+use crate::controller::sourcetree::SourceTreeController;
 use crate::db::errorentry::ErrorEntry;
 use crate::db::errorentry::ESRC;
 use crate::db::errors_repo::ErrorRepo;
@@ -10,9 +11,11 @@ use crate::db::message::MessageRow;
 use crate::db::messages_repo::IMessagesRepo;
 use crate::db::messages_repo::MessagesRepo;
 use crate::db::subscription_entry::SubscriptionEntry;
+use crate::db::subscription_entry::SRC_REPO_ID_DELETED;
 use crate::db::subscription_entry::SRC_REPO_ID_DUMMY;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
+use crate::downloader::linkcleaner::canonical_link;
 use crate::util::filter_by_iso8859_1;
 use crate::util::timestamp_now;
 use crate::util::Step;
@@ -47,6 +50,8 @@ pub struct CleanerInner {
     pub need_update_messages: bool,
     /// -1 : do not check
     pub max_messages_per_subscription: i32,
+    /// trashed subscriptions and their messages are kept that long
+    pub trash_retention_days: i32,
     pub starttime: Instant,
     pub stepmarker: u8,
 }
@@ -72,6 +77,7 @@ impl CleanerInner {
             need_update_messages: false,
             need_update_subscriptions: false,
             max_messages_per_subscription: max_msg,
+            trash_retention_days: SourceTreeController::CONF_TRASH_RETENTION_DAYS_DEFAULT,
             error_repo: err_re,
             starttime: Instant::now(),
             stepmarker: 0,
@@ -92,6 +98,26 @@ impl CleanerInner {
         std::thread::sleep(std::time::Duration::from_millis(ARTIFICIAL_STEP_DELAY));
     }
 
+    /// the trashed entries within the retention period,  with all entries below them
+    fn get_retained_trash_ids(&self) -> HashSet<isize> {
+        let keep_after = timestamp_now() - self.trash_retention_days as i64 * 60 * 60 * 24;
+        let mut retained: HashSet<isize> = HashSet::default();
+        let mut work: Vec<isize> = self
+            .subscriptionrepo
+            .get_children(SRC_REPO_ID_DELETED)
+            .iter()
+            .filter(|se| se.trashed_at > 0 && se.trashed_at > keep_after)
+            .map(|se| se.subs_id)
+            .collect();
+        while let Some(subs_id) = work.pop() {
+            if retained.insert(subs_id) {
+                let childs = self.subscriptionrepo.get_children(subs_id);
+                work.extend(childs.iter().map(|se| se.subs_id));
+            }
+        }
+        retained
+    }
+
     fn advance_step(&mut self) {
         self.stepmarker += 1;
     }
@@ -147,6 +173,7 @@ impl Step<CleanerInner> for RemoveNonConnectedSubscriptions {
         inner.advance_step();
         inner.send_gp(Some(t!("D_SETTINGS_TAB1")));
         let all_subs = inner.subscriptionrepo.get_all_entries();
+        let retained_trash = inner.get_retained_trash_ids();
         let mut connected_child_list: HashSet<isize> = HashSet::default();
         let mut folder_work: Vec<isize> = Vec::default();
         folder_work.push(0);
@@ -161,6 +188,9 @@ impl Step<CleanerInner> for RemoveNonConnectedSubscriptions {
         }
         let mut delete_list: HashSet<isize> = HashSet::default();
         all_subs.iter().for_each(|se| {
+            if retained_trash.contains(&se.subs_id) {
+                return;
+            }
             if se.deleted || se.parent_subs_id < 0 {
                 delete_list.insert(se.subs_id);
             } else if !connected_child_list.contains(&se.subs_id) {
@@ -180,10 +210,108 @@ impl Step<CleanerInner> for RemoveNonConnectedSubscriptions {
             delete_list
                 .iter()
                 .for_each(|id| inner.subscriptionrepo.delete_by_index(*id));
+            let deleted_ids: Vec<isize> = delete_list.iter().cloned().collect();
+            inner.messagesrepo.delete_page_snapshots(&deleted_ids);
             inner.need_update_subscriptions = true;
         }
+        StepResult::Continue(Box::new(MergeDuplicateSubscriptions(inner)))
+    }
+}
+
+/// Subscriptions with the same normalized url: the messages go to the oldest subscription,
+/// those it has already are deleted. The emptied subscriptions are moved into the trash.
+pub struct MergeDuplicateSubscriptions(pub CleanerInner);
+impl Step<CleanerInner> for MergeDuplicateSubscriptions {
+    fn step(self: Box<Self>) -> StepResult<CleanerInner> {
+        let mut inner = self.0;
+        let groups: Vec<Vec<isize>> = inner
+            .subscriptionrepo
+            .get_feed_url_groups()
+            .into_values()
+            .filter(|ids| ids.len() > 1)
+            .collect();
+        for ids in groups {
+            let keep_id = ids[0];
+            let mut known: HashSet<String> = HashSet::default();
+            inner
+                .messagesrepo
+                .get_by_subscriptions(&[keep_id], false)
+                .for_each(|msg| {
+                    known.extend(message_keys(msg));
+                });
+            for dup_id in ids.iter().skip(1) {
+                let mut move_list: Vec<i32> = Vec::default();
+                let mut delete_list: Vec<i32> = Vec::default();
+                inner
+                    .messagesrepo
+                    .get_by_subscriptions(&[*dup_id], false)
+                    .for_each(|msg| {
+                        let keys = message_keys(msg);
+                        if keys.iter().any(|k| known.contains(k)) {
+                            delete_list.push(msg.message_id as i32);
+                        } else {
+                            move_list.push(msg.message_id as i32);
+                            known.extend(keys);
+                        }
+                    });
+                if !move_list.is_empty() {
+                    inner
+                        .messagesrepo
+                        .update_subscription_id_many(&move_list, keep_id);
+                }
+                if !delete_list.is_empty() {
+                    inner
+                        .messagesrepo
+                        .update_is_deleted_many(&delete_list, true);
+                }
+                if let Some(dup) = inner.subscriptionrepo.get_by_index(*dup_id) {
+                    let trash_pos = inner
+                        .subscriptionrepo
+                        .get_children(SRC_REPO_ID_DELETED)
+                        .len() as isize;
+                    inner.subscriptionrepo.update_trash_origin(
+                        *dup_id,
+                        dup.parent_subs_id,
+                        dup.folder_position,
+                        timestamp_now(),
+                    );
+                    inner.subscriptionrepo.update_parent_and_folder_position(
+                        *dup_id,
+                        SRC_REPO_ID_DELETED,
+                        trash_pos,
+                    );
+                    inner.subscriptionrepo.set_deleted_rec(*dup_id, true);
+                    inner.send_gp(Some(format!(
+                        "Duplicate subscription {} '{}' merged into {}: moved {} deleted {} messages",
+                        dup_id,
+                        dup.display_name,
+                        keep_id,
+                        move_list.len(),
+                        delete_list.len()
+                    )));
+                }
+                inner.need_update_subscriptions = true;
+                inner.need_update_messages = true;
+            }
+        }
         StepResult::Continue(Box::new(AnalyzeFolderPositions(inner)))
     }
+
+    fn take(self: Box<Self>) -> CleanerInner {
+        self.0
+    }
+}
+
+/// post id and link, what recognizes the same message in another subscription
+fn message_keys(msg: &MessageRow) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::default();
+    if !msg.post_id.is_empty() {
+        keys.push(format!("p:{}", msg.post_id));
+    }
+    if !msg.link.is_empty() {
+        keys.push(format!("l:{}", canonical_link(&msg.link)));
+    }
+    keys
 }
 
 pub struct AnalyzeFolderPositions(pub CleanerInner);
@@ -492,11 +620,12 @@ impl Step<CleanerInner> for MarkUnconnectedMessages {
         let mut inner = self.0;
         inner.advance_step();
         inner.send_gp(Some("MarkUnconnectedMessages".to_string()));
+        let retained_trash = inner.get_retained_trash_ids();
         let parent_ids_active: Vec<i32> = inner
             .subscriptionrepo
             .get_all_nonfolder()
             .iter()
-            .filter(|se| !se.isdeleted())
+            .filter(|se| !se.isdeleted() || retained_trash.contains(&se.subs_id))
             .map(|se| se.subs_id as i32)
             .collect();
         inner.send_gp(None);
@@ -567,6 +696,10 @@ impl Step<CleanerInner> for ReduceTooManyMessages {
         inner.send_gp(None);
         StepResult::Continue(Box::new(DeleteDoubleSameMessages(inner)))
     }
+
+    fn take(self: Box<Self>) -> CleanerInner {
+        self.0
+    }
 }
 
 ///  Consumes the user-configured max-messages value
@@ -765,6 +898,9 @@ impl Step<CleanerInner> for CheckErrorLog {
         inner.error_repo.delete_by_index(&delete_list);
         StepResult::Continue(Box::new(ShortenDatabases(inner)))
     }
+    fn take(self: Box<Self>) -> CleanerInner {
+        self.0
+    }
 }
 
 pub struct ShortenDatabases(pub CleanerInner);
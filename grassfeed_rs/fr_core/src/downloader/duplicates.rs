@@ -0,0 +1,176 @@
+//! The same story, delivered by several subscriptions. Recognized by the canonical link,
+//! or by a very similar title published around the same time.
+//! Duplicates share a group id, the message id of the first stored one.
+
+use crate::db::message::decompress;
+use crate::db::message::MessageRow;
+use crate::downloader::linkcleaner::canonical_link;
+use std::collections::HashSet;
+
+/// messages further apart in time are not compared
+pub const DUP_TIME_WINDOW_S: i64 = 3 * 24 * 3600;
+
+/// share of common title words
+const TITLE_SIMILARITY_MIN: f32 = 0.8;
+
+/// shorter titles are compared by link only
+const TITLE_MIN_WORDS: usize = 4;
+
+struct Candidate {
+    msg_id: isize,
+    group: isize,
+    is_read: bool,
+    date: i64,
+    canonical: String,
+    words: HashSet<String>,
+}
+
+/// lower case words, without punctuation and single letters
+pub fn title_words(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 1)
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// common words divided by all words
+pub fn title_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.len() < TITLE_MIN_WORDS || b.len() < TITLE_MIN_WORDS {
+        return 0.0;
+    }
+    let common = a.intersection(b).count();
+    let all = a.union(b).count();
+    common as f32 / all as f32
+}
+
+/// Sets the group of new messages that duplicate one of the existing messages of other subscriptions.
+/// A new message is stored as read if the existing one was read.
+/// existing: messages with compressed titles, as from the repo
+/// returns  existing message id, group  for those that become the first of a group
+pub fn assign_duplicate_groups(
+    new_list: &mut [MessageRow],
+    existing: &[MessageRow],
+) -> Vec<(isize, isize)> {
+    let candidates: Vec<Candidate> = existing
+        .iter()
+        .map(|m| Candidate {
+            msg_id: m.message_id,
+            group: m.dup_group,
+            is_read: m.is_read,
+            date: m.entry_src_date,
+            canonical: if m.link.is_empty() {
+                String::default()
+            } else {
+                canonical_link(&m.link)
+            },
+            words: title_words(&decompress(&m.title)),
+        })
+        .collect();
+    let mut new_heads: Vec<(isize, isize)> = Vec::default();
+    for msg in new_list.iter_mut() {
+        let canonical = if msg.link.is_empty() {
+            String::default()
+        } else {
+            canonical_link(&msg.link)
+        };
+        let words = title_words(&decompress(&msg.title));
+        let o_cand = candidates
+            .iter()
+            .filter(|c| (c.date - msg.entry_src_date).abs() <= DUP_TIME_WINDOW_S)
+            .find(|c| {
+                (!canonical.is_empty() && c.canonical == canonical)
+                    || title_similarity(&c.words, &words) >= TITLE_SIMILARITY_MIN
+            });
+        if let Some(cand) = o_cand {
+            let group = if cand.group > 0 {
+                cand.group
+            } else {
+                if !new_heads.iter().any(|(id, _)| *id == cand.msg_id) {
+                    new_heads.push((cand.msg_id, cand.msg_id));
+                }
+                cand.msg_id
+            };
+            msg.dup_group = group;
+            if cand.is_read {
+                msg.is_read = true;
+            }
+        }
+    }
+    new_heads
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+    use crate::db::message::compress;
+
+    fn msg(id: isize, title: &str, link: &str, date: i64) -> MessageRow {
+        MessageRow {
+            message_id: id,
+            title: compress(title),
+            link: link.to_string(),
+            entry_src_date: date,
+            ..Default::default()
+        }
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::duplicates::t_::groups_by_link_and_title  --lib -- --exact --nocapture "
+    #[test]
+    fn groups_by_link_and_title() {
+        let mut existing = vec![
+            msg(
+                10,
+                "Something else entirely",
+                "https://a.example/story?utm_source=x",
+                1000,
+            ),
+            msg(
+                11,
+                "Rust 2.0 released with many new features",
+                "https://b.example/1",
+                1000,
+            ),
+            msg(
+                12,
+                "Old news about the same topic here",
+                "https://c.example/1",
+                1000,
+            ),
+        ];
+        existing[1].dup_group = 5;
+        existing[1].is_read = true;
+        let mut new_list = vec![
+            msg(-1, "Other title", "http://www.a.example/story", 2000),
+            msg(
+                -1,
+                "Rust 2.0 Released, with many new features!",
+                "https://d.example/9",
+                2000,
+            ),
+            msg(-1, "Short", "https://e.example/", 2000),
+            msg(
+                -1,
+                "Old news about the same topic here",
+                "https://f.example/1",
+                1000 + 2 * DUP_TIME_WINDOW_S,
+            ),
+        ];
+        let heads = assign_duplicate_groups(&mut new_list, &existing);
+        assert_eq!(heads, vec![(10, 10)]);
+        assert_eq!(new_list[0].dup_group, 10);
+        assert_eq!(new_list[1].dup_group, 5);
+        assert!(new_list[1].is_read);
+        assert_eq!(new_list[2].dup_group, 0);
+        assert_eq!(new_list[3].dup_group, 0);
+    }
+
+    #[test]
+    fn similarity() {
+        let a = title_words("The quick brown fox jumps");
+        let b = title_words("the quick brown fox jumps.");
+        assert_eq!(title_similarity(&a, &b), 1.0);
+        let c = title_words("a b c");
+        assert_eq!(title_similarity(&c, &c), 0.0);
+    }
+}
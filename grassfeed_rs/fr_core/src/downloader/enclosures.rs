@@ -0,0 +1,409 @@
+use crate::controller::guiprocessor::Job;
+use crate::db::errorentry::ESRC;
+use crate::db::errors_repo::ErrorRepo;
+use crate::db::message::content_hash;
+use crate::util::Step;
+use crate::util::StepResult;
+use flume::Sender;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// unfinished downloads carry this suffix, they get resumed on the next try
+pub const PARTIAL_SUFFIX: &str = ".part";
+const READ_BUFFER_SIZE: usize = 65536;
+const MAX_FILENAME_LENGTH: usize = 120;
+/// hex digits of the url hash in front of the file name
+const URL_HASH_PREFIX_LENGTH: usize = 8;
+
+pub struct EnclosureInner {
+    pub subs_id: isize,
+    pub url: String,
+    /// folder for this subscription, is created if necessary
+    pub target_folder: String,
+    /// number of files to keep inside the target folder,  0: keep all
+    pub keep_count: isize,
+    pub gp_job_sender: Sender<Job>,
+    pub erro_repo: ErrorRepo,
+    /// downloader thread, for the progress display
+    pub thread_nr: u8,
+    pub file_name: String,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub download_error_happened: bool,
+}
+
+impl EnclosureInner {
+    pub fn new(
+        subs_id: isize,
+        url: String,
+        target_folder: String,
+        keep_count: isize,
+        gp_sender: Sender<Job>,
+        err_repo: ErrorRepo,
+    ) -> Self {
+        EnclosureInner {
+            subs_id,
+            url,
+            target_folder,
+            keep_count,
+            gp_job_sender: gp_sender,
+            erro_repo: err_repo,
+            thread_nr: 0,
+            file_name: String::default(),
+            bytes_done: 0,
+            bytes_total: 0,
+            download_error_happened: false,
+        }
+    }
+
+    fn target_path(&self) -> PathBuf {
+        Path::new(&self.target_folder).join(&self.file_name)
+    }
+
+    fn partial_path(&self) -> PathBuf {
+        Path::new(&self.target_folder).join(format!("{}{}", self.file_name, PARTIAL_SUFFIX))
+    }
+
+    fn add_error(&mut self, e_val: isize, text: String) {
+        self.download_error_happened = true;
+        self.erro_repo.add_error(
+            self.subs_id,
+            ESRC::EnclosureDownload,
+            e_val,
+            self.url.clone(),
+            text,
+        );
+    }
+}
+
+impl std::fmt::Debug for EnclosureInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("")
+            .field("subs_id", &self.subs_id)
+            .field("url", &self.url)
+            .field("folder", &self.target_folder)
+            .finish()
+    }
+}
+
+impl PartialEq for EnclosureInner {
+    fn eq(&self, other: &Self) -> bool {
+        self.url == other.url
+    }
+}
+
+pub struct EnclosureStart(EnclosureInner);
+impl EnclosureStart {
+    pub fn new(i: EnclosureInner) -> Self {
+        EnclosureStart(i)
+    }
+}
+
+impl Step<EnclosureInner> for EnclosureStart {
+    fn step(self: Box<Self>) -> StepResult<EnclosureInner> {
+        let mut inner = self.0;
+        if let Err(e) = std::fs::create_dir_all(&inner.target_folder) {
+            inner.add_error(0, format!("create {} : {:?}", inner.target_folder, e));
+            return StepResult::Stop(inner);
+        }
+        inner.file_name = enclosure_file_name(&inner.url);
+        if inner.target_path().exists() {
+            trace!("enclosure already downloaded: {:?}", inner.target_path());
+            return StepResult::Stop(inner);
+        }
+        StepResult::Continue(Box::new(EnclosureDownload(inner)))
+    }
+}
+
+/// Continues a previous partial download with a Range request.
+/// If the server ignores the range, the partial file is written from the beginning.
+struct EnclosureDownload(EnclosureInner);
+impl Step<EnclosureInner> for EnclosureDownload {
+    fn step(self: Box<Self>) -> StepResult<EnclosureInner> {
+        let mut inner = self.0;
+        let part_path = inner.partial_path();
+        let existing_len: u64 = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+        let agent = ureq::builder().user_agent("ferris/1.0").build();
+        let mut request = agent.get(&inner.url);
+        if existing_len > 0 {
+            request = request.set("Range", &range_header_value(existing_len));
+        }
+        let response = match request.call() {
+            Ok(r) => r,
+            Err(ureq::Error::Status(416, _)) => {
+                // the partial file holds the whole content already
+                return StepResult::Continue(Box::new(EnclosureFinish(inner)));
+            }
+            Err(ureq::Error::Status(status, r)) => {
+                inner.add_error(status as isize, r.status_text().to_string());
+                return StepResult::Continue(Box::new(EnclosureNotify(inner)));
+            }
+            Err(ureq::Error::Transport(transp)) => {
+                inner.add_error(-1, format!("transport:{:?}", transp.kind()));
+                return StepResult::Continue(Box::new(EnclosureNotify(inner)));
+            }
+        };
+        let is_resumed = response.status() == 206;
+        let content_length: u64 = response
+            .header("Content-Length")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+        inner.bytes_done = if is_resumed { existing_len } else { 0 };
+        inner.bytes_total = if content_length > 0 {
+            inner.bytes_done + content_length
+        } else {
+            0
+        };
+        let o_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(is_resumed)
+            .truncate(!is_resumed)
+            .open(&part_path);
+        let mut file = match o_file {
+            Ok(f) => f,
+            Err(e) => {
+                inner.add_error(0, format!("open {:?} : {:?}", &part_path, e));
+                return StepResult::Continue(Box::new(EnclosureNotify(inner)));
+            }
+        };
+        let mut reader = response.into_reader();
+        let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+        let mut last_percent: u8 = 0;
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if let Err(e) = file.write_all(&buffer[..n]) {
+                        inner.add_error(0, format!("write {:?} : {:?}", &part_path, e));
+                        return StepResult::Continue(Box::new(EnclosureNotify(inner)));
+                    }
+                    inner.bytes_done += n as u64;
+                    let percent = progress_percent(inner.bytes_done, inner.bytes_total);
+                    if percent != last_percent {
+                        last_percent = percent;
+                        let _r = inner
+                            .gp_job_sender
+                            .send(Job::DownloaderJobProgress(inner.thread_nr, percent));
+                    }
+                }
+                Err(e) => {
+                    // keep the partial file for the next attempt
+                    inner.add_error(0, format!("read {} : {:?}", inner.bytes_done, e));
+                    return StepResult::Continue(Box::new(EnclosureNotify(inner)));
+                }
+            }
+        }
+        StepResult::Continue(Box::new(EnclosureFinish(inner)))
+    }
+}
+
+struct EnclosureFinish(EnclosureInner);
+impl Step<EnclosureInner> for EnclosureFinish {
+    fn step(self: Box<Self>) -> StepResult<EnclosureInner> {
+        let mut inner = self.0;
+        if let Err(e) = std::fs::rename(inner.partial_path(), inner.target_path()) {
+            inner.add_error(0, format!("rename {:?} : {:?}", inner.partial_path(), e));
+        } else if inner.keep_count > 0 {
+            let removed = apply_retention(&inner.target_folder, inner.keep_count as usize);
+            if !removed.is_empty() {
+                debug!("enclosures removed: {:?}", removed);
+            }
+        }
+        StepResult::Continue(Box::new(EnclosureNotify(inner)))
+    }
+}
+
+struct EnclosureNotify(EnclosureInner);
+impl Step<EnclosureInner> for EnclosureNotify {
+    fn step(self: Box<Self>) -> StepResult<EnclosureInner> {
+        let inner = self.0;
+        let msg = if inner.download_error_happened {
+            format!("{} {}", t!("STATUSBAR_ENCLOSURE_FAILED"), inner.file_name)
+        } else {
+            format!("{} {}", t!("STATUSBAR_ENCLOSURE_DONE"), inner.file_name)
+        };
+        let _r = inner
+            .gp_job_sender
+            .send(Job::AddBottomDisplayErrorMessage(msg));
+        StepResult::Stop(inner)
+    }
+}
+
+/// Last path segment of the url, without query, behind a short hash of the whole url.
+/// Episodes often share the segment,  like  ep1/audio.mp3  and  ep2/audio.mp3.
+/// Falls back to a hash of the url.
+pub fn enclosure_file_name(url: &str) -> String {
+    let mut name = String::default();
+    if let Ok(parsed) = url::Url::parse(url) {
+        if let Some(segment) = parsed.path().rsplit('/').next() {
+            name = percent_decode(segment);
+        }
+    }
+    let mut name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name = name.trim_start_matches('.').to_string();
+    let url_hash = content_hash(url);
+    if name.is_empty() {
+        return format!("enclosure-{url_hash}");
+    }
+    let prefix = &url_hash[url_hash.len() - URL_HASH_PREFIX_LENGTH..];
+    let max_name = MAX_FILENAME_LENGTH - URL_HASH_PREFIX_LENGTH - 1;
+    if name.chars().count() > max_name {
+        name = name.chars().rev().take(max_name).collect();
+        name = name.chars().rev().collect();
+    }
+    format!("{prefix}-{name}")
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(b) = std::str::from_utf8(&bytes[i + 1..i + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// folder name for one subscription, below the enclosure download folder
+pub fn subscription_folder_name(subs_id: isize, display_name: &str) -> String {
+    let cleaned: String = display_name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        format!("{subs_id}")
+    } else {
+        cleaned.to_string()
+    }
+}
+
+pub fn range_header_value(existing_len: u64) -> String {
+    format!("bytes={existing_len}-")
+}
+
+/// 0..100 , zero when the total size is unknown
+pub fn progress_percent(done: u64, total: u64) -> u8 {
+    if total == 0 {
+        return 0;
+    }
+    std::cmp::min(done * 100 / total, 100) as u8
+}
+
+/// Removes the oldest completed files, so that  keep_count  files remain.
+/// Partial downloads are not touched.  Returns the removed file names.
+pub fn apply_retention(folder: &str, keep_count: usize) -> Vec<String> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = Vec::default();
+    if let Ok(rd) = std::fs::read_dir(folder) {
+        for de in rd.flatten() {
+            let path = de.path();
+            if !path.is_file() || path.to_string_lossy().ends_with(PARTIAL_SUFFIX) {
+                continue;
+            }
+            if let Ok(modified) = de.metadata().and_then(|m| m.modified()) {
+                files.push((modified, path));
+            }
+        }
+    }
+    files.sort_by_key(|f| std::cmp::Reverse(f.0));
+    let mut removed: Vec<String> = Vec::default();
+    for (_t, path) in files.iter().skip(keep_count) {
+        match std::fs::remove_file(path) {
+            Ok(()) => removed.push(path.file_name().unwrap().to_string_lossy().to_string()),
+            Err(e) => warn!("remove {:?} : {:?}", path, e),
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+    use std::time::Duration;
+    use std::time::SystemTime;
+
+    #[test]
+    fn file_name_from_url() {
+        assert!(
+            enclosure_file_name("https://cdn.example.org/talks/episode%2042.mp3?src=rss")
+                .ends_with("-episode_42.mp3")
+        );
+        let video = enclosure_file_name("https://example.org/media/video-1.mp4");
+        assert_eq!(video.len(), URL_HASH_PREFIX_LENGTH + "-video-1.mp4".len());
+        assert!(video.ends_with("-video-1.mp4"));
+        assert_eq!(
+            video,
+            enclosure_file_name("https://example.org/media/video-1.mp4")
+        );
+        let long_name = format!("https://example.org/{}.mp3", "x".repeat(300));
+        assert_eq!(
+            enclosure_file_name(&long_name).chars().count(),
+            MAX_FILENAME_LENGTH
+        );
+        assert!(enclosure_file_name("https://example.org/").starts_with("enclosure-"));
+        assert!(enclosure_file_name("https://example.org/..").starts_with("enclosure-"));
+    }
+
+    #[test]
+    fn file_name_same_basename() {
+        let ep1 = enclosure_file_name("https://podcast.example.org/ep1/audio.mp3");
+        let ep2 = enclosure_file_name("https://podcast.example.org/ep2/audio.mp3");
+        assert!(ep1.ends_with("-audio.mp3"));
+        assert!(ep2.ends_with("-audio.mp3"));
+        assert_ne!(ep1, ep2);
+    }
+
+    #[test]
+    fn range_and_progress() {
+        assert_eq!(range_header_value(1234), "bytes=1234-");
+        assert_eq!(progress_percent(50, 200), 25);
+        assert_eq!(progress_percent(50, 0), 0);
+        assert_eq!(progress_percent(300, 200), 100);
+    }
+
+    // cargo test  downloader::enclosures::t_::retention_keeps_newest --lib -- --exact --nocapture
+    #[test]
+    fn retention_keeps_newest() {
+        let folder = std::env::temp_dir().join(format!("gf_enclosures_{}", std::process::id()));
+        let _r = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        let now = SystemTime::now();
+        for n in 0..4 {
+            let path = folder.join(format!("e{n}.mp3"));
+            let f = std::fs::File::create(&path).unwrap();
+            f.set_modified(now - Duration::from_secs(100 - n * 10))
+                .unwrap();
+        }
+        std::fs::File::create(folder.join(format!("e9.mp3{PARTIAL_SUFFIX}"))).unwrap();
+        let mut removed = apply_retention(&folder.to_string_lossy(), 2);
+        removed.sort();
+        assert_eq!(removed, vec!["e0.mp3".to_string(), "e1.mp3".to_string()]);
+        assert!(folder.join("e3.mp3").exists());
+        assert!(folder.join(format!("e9.mp3{PARTIAL_SUFFIX}")).exists());
+        let _r = std::fs::remove_dir_all(&folder);
+    }
+}
@@ -0,0 +1,327 @@
+//! Message links without tracking parameters and redirect services.
+//! The canonical form of a link recognizes the same article behind differently decorated links.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+use url::Url;
+
+/// query parameter names,  a trailing  *  matches the beginning of the name
+pub const DEFAULT_TRACKING_PARAMS: [&str; 24] = [
+    "utm_*",
+    "fbclid",
+    "gclid",
+    "gclsrc",
+    "dclid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "ref_src",
+    "ref_url",
+    "xtor",
+    "wt_mc",
+    "wt.mc_id",
+    "_openstat",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+    "__twitter_impression",
+];
+
+/// host, path, parameter that holds the target
+const REDIRECT_SERVICES: [(&str, &str, &str); 10] = [
+    ("www.google.com", "/url", "q"),
+    ("www.google.com", "/url", "url"),
+    ("google.com", "/url", "q"),
+    ("l.facebook.com", "/l.php", "u"),
+    ("lm.facebook.com", "/l.php", "u"),
+    ("out.reddit.com", "/", "url"),
+    ("t.umblr.com", "/redirect", "z"),
+    ("www.youtube.com", "/redirect", "q"),
+    ("getpocket.com", "/redirect", "url"),
+    ("www.linkedin.com", "/redir/redirect", "url"),
+];
+
+/// nested redirects are followed this often
+const MAX_UNWRAP: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkCleaner {
+    enabled: bool,
+    params: Vec<String>,
+}
+
+impl Default for LinkCleaner {
+    fn default() -> Self {
+        LinkCleaner::new(true, "")
+    }
+}
+
+impl LinkCleaner {
+    /// extra_params: comma separated parameter names, added to the default list
+    pub fn new(enabled: bool, extra_params: &str) -> Self {
+        let mut params: Vec<String> = DEFAULT_TRACKING_PARAMS
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        for p in extra_params
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(|p| p.trim().to_lowercase())
+        {
+            if !p.is_empty() && !params.contains(&p) {
+                params.push(p);
+            }
+        }
+        LinkCleaner { enabled, params }
+    }
+
+    /// Unwraps redirect services, removes the tracking parameters. Unknown links stay as they are.
+    pub fn clean(&self, link: &str) -> String {
+        if !self.enabled {
+            return link.to_string();
+        }
+        let mut current = link.trim().to_string();
+        for _ in 0..MAX_UNWRAP {
+            match unwrap_redirect(&current) {
+                Some(target) => current = target,
+                None => break,
+            }
+        }
+        let mut url = match Url::parse(&current) {
+            Ok(u) if u.scheme() == "http" || u.scheme() == "https" => u,
+            _ => return current,
+        };
+        if let Some(query) = url.query() {
+            if let Some(filtered) = self.filter_pairs(query) {
+                url.set_query(if filtered.is_empty() {
+                    None
+                } else {
+                    Some(&filtered)
+                });
+            }
+        }
+        if let Some(fragment) = url.fragment() {
+            if fragment.split('&').all(|p| p.contains('=')) {
+                if let Some(filtered) = self.filter_pairs(fragment) {
+                    url.set_fragment(if filtered.is_empty() {
+                        None
+                    } else {
+                        Some(&filtered)
+                    });
+                }
+            }
+        }
+        if url.as_str() == current.as_str() {
+            return current;
+        }
+        url.to_string()
+    }
+
+    /// None if nothing is removed, the raw pairs are kept as they are
+    fn filter_pairs(&self, query: &str) -> Option<String> {
+        let pairs: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+        let kept: Vec<&str> = pairs
+            .iter()
+            .filter(|pair| !self.is_tracking(pair.split('=').next().unwrap_or_default()))
+            .copied()
+            .collect();
+        if kept.len() == pairs.len() {
+            return None;
+        }
+        Some(kept.join("&"))
+    }
+
+    fn is_tracking(&self, raw_name: &str) -> bool {
+        let name = percent_decode(raw_name).to_lowercase();
+        self.params.iter().any(|p| match p.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == *p,
+        })
+    }
+}
+
+fn unwrap_redirect(link: &str) -> Option<String> {
+    let url = Url::parse(link).ok()?;
+    let host = url.host_str()?.to_lowercase();
+    let (_, _, param) = REDIRECT_SERVICES.iter().find(|(h, p, param)| {
+        *h == host && url.path() == *p && url.query_pairs().any(|(k, _)| k == *param)
+    })?;
+    let target = url
+        .query_pairs()
+        .find(|(k, _)| k == *param)
+        .map(|(_, v)| v.to_string())?;
+    match Url::parse(&target) {
+        Ok(t) if t.scheme() == "http" || t.scheme() == "https" => Some(target),
+        _ => None,
+    }
+}
+
+fn percent_decode(raw: &str) -> String {
+    url::form_urlencoded::parse(format!("{raw}=").as_bytes())
+        .next()
+        .map(|(k, _)| k.to_string())
+        .unwrap_or(raw.to_string())
+}
+
+/// Cleaned with the default rules, without scheme, www. prefix, fragment and trailing slash.
+/// Only for comparing links, not for opening them.
+pub fn canonical_link(link: &str) -> String {
+    let cleaned = LinkCleaner::default().clean(link);
+    let url = match Url::parse(&cleaned) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => u,
+        _ => return cleaned,
+    };
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let port = url.port().map(|p| format!(":{p}")).unwrap_or_default();
+    let path = url.path().trim_end_matches('/');
+    match url.query() {
+        Some(q) => format!("{host}{port}{path}?{q}"),
+        None => format!("{host}{port}{path}"),
+    }
+}
+
+/// Subscription urls for the same feed have the same normalized form.
+/// Canonical link, the  feed:  pseudo scheme is accepted.
+pub fn normalize_feed_url(url: &str) -> String {
+    let trimmed = url.trim();
+    let unprefixed = match trimmed.strip_prefix("feed:") {
+        Some(rest) if rest.starts_with("//") => format!("http:{rest}"),
+        Some(rest) => rest.to_string(),
+        None => trimmed.to_string(),
+    };
+    canonical_link(&unprefixed)
+}
+
+static RE_FEED_ITEM: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<(item|entry)[\s>].*?</(item|entry)>").unwrap());
+static RE_ORIG_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"<feedburner:origLink>\s*([^<\s]+)\s*</feedburner:origLink>").unwrap()
+});
+static RE_ITEM_LINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<link>\s*([^<\s]+)\s*</link>|<link[^>]*\shref=["']([^"']+)["']"#).unwrap()
+});
+
+/// Feedburner puts its own link into the item and the original one into  feedburner:origLink
+/// returns  proxy link -> original link
+pub fn feedburner_orig_links(feed_text: &str) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    if !feed_text.contains("origLink") {
+        return map;
+    }
+    for item in RE_FEED_ITEM.find_iter(feed_text) {
+        let o_orig = RE_ORIG_LINK
+            .captures(item.as_str())
+            .map(|c| c[1].to_string());
+        let o_link = RE_ITEM_LINK
+            .captures(item.as_str())
+            .and_then(|c| c.get(1).or(c.get(2)))
+            .map(|m| m.as_str().to_string());
+        if let (Some(orig), Some(link)) = (o_orig, o_link) {
+            map.insert(link.replace("&amp;", "&"), orig.replace("&amp;", "&"));
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::linkcleaner::t_::strip_tracking  --lib -- --exact --nocapture "
+    #[test]
+    fn strip_tracking() {
+        let lc = LinkCleaner::default();
+        assert_eq!(
+            lc.clean("https://a.example/p?id=3&utm_source=rss&utm_medium=feed&fbclid=X"),
+            "https://a.example/p?id=3"
+        );
+        assert_eq!(
+            lc.clean("https://a.example/p?utm_campaign=x#xtor=RSS-1"),
+            "https://a.example/p"
+        );
+        assert_eq!(
+            lc.clean("https://a.example/p?a=1+2&b=%20"),
+            "https://a.example/p?a=1+2&b=%20"
+        );
+        assert_eq!(
+            lc.clean("https://a.example/p#section"),
+            "https://a.example/p#section"
+        );
+        assert_eq!(
+            LinkCleaner::new(true, "source, ref").clean("https://a.example/?ref=x&source=y&k=1"),
+            "https://a.example/?k=1"
+        );
+        assert_eq!(
+            LinkCleaner::new(false, "").clean("https://a.example/?utm_source=x"),
+            "https://a.example/?utm_source=x"
+        );
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::linkcleaner::t_::normalize_feed_urls  --lib -- --exact --nocapture "
+    #[test]
+    fn normalize_feed_urls() {
+        let n = normalize_feed_url("https://www.example.com/feed/");
+        assert_eq!(n, "example.com/feed");
+        assert_eq!(normalize_feed_url("http://example.com/feed"), n);
+        assert_eq!(normalize_feed_url(" HTTPS://Example.COM:443/feed#top "), n);
+        assert_eq!(normalize_feed_url("feed://example.com/feed"), n);
+        assert_eq!(normalize_feed_url("feed:https://example.com/feed/"), n);
+        assert_eq!(
+            normalize_feed_url("https://example.com/feed?utm_source=x"),
+            n
+        );
+        assert_ne!(normalize_feed_url("https://example.com/Feed"), n);
+        assert_ne!(normalize_feed_url("https://example.com:8080/feed"), n);
+        assert_ne!(normalize_feed_url("https://example.com/feed?page=2"), n);
+    }
+
+    #[test]
+    fn unwrap_redirects() {
+        let lc = LinkCleaner::default();
+        assert_eq!(
+            lc.clean("https://www.google.com/url?q=https%3A%2F%2Fb.example%2Fx%3Futm_source%3Dg%26k%3D2&sa=D"),
+            "https://b.example/x?k=2"
+        );
+        assert_eq!(
+            lc.clean("https://l.facebook.com/l.php?u=https%3A%2F%2Fc.example%2F&h=AT0"),
+            "https://c.example/"
+        );
+        assert_eq!(
+            lc.clean("https://www.google.com/url?q=javascript:alert(1)"),
+            "https://www.google.com/url?q=javascript:alert(1)"
+        );
+    }
+
+    #[test]
+    fn canonical() {
+        assert_eq!(
+            canonical_link("http://www.a.example/post/?utm_source=rss#comments"),
+            canonical_link("https://a.example/post")
+        );
+        assert_ne!(
+            canonical_link("https://a.example/post?id=1"),
+            canonical_link("https://a.example/post?id=2")
+        );
+    }
+
+    #[test]
+    fn feedburner_orig() {
+        let text = r#"<rss><channel><item><title>A</title>
+            <link>http://feedproxy.google.com/~r/blogspot/cwWR/~3/wFtNHz9TStU/warning.html</link>
+            <feedburner:origLink>http://financearmageddon.blogspot.com/2021/11/warning.html</feedburner:origLink>
+            </item><item><link>http://x.example/2</link></item></channel></rss>"#;
+        let map = feedburner_orig_links(text);
+        assert_eq!(map.len(), 1);
+        assert_eq!(
+            map.get("http://feedproxy.google.com/~r/blogspot/cwWR/~3/wFtNHz9TStU/warning.html"),
+            Some(&"http://financearmageddon.blogspot.com/2021/11/warning.html".to_string())
+        );
+    }
+}
@@ -3,15 +3,44 @@ use crate::controller::contentlist::CJob;
 use crate::controller::sourcetree::SJob;
 use crate::db::errorentry::ESRC;
 use crate::db::errors_repo::ErrorRepo;
+use crate::db::feed_meta::FeedMeta;
 use crate::db::icon_repo::IconRepo;
 use crate::db::message::compress;
+use crate::db::message::content_hash;
+use crate::db::message::decompress;
+use crate::db::message::Enclosure;
 use crate::db::message::MessageRow;
+use crate::db::message_rule::MessageRule;
+use crate::db::message_rule::RULE_ACTION_TAG;
 use crate::db::messages_repo::IMessagesRepo;
 use crate::db::messages_repo::MessageIterator;
 use crate::db::messages_repo::MessagesRepo;
+use crate::db::page_snapshot::PageSnapshot;
+use crate::db::subscription_entry::SOURCE_KIND_PAGE_MONITOR;
+use crate::db::subscription_entry::SOURCE_KIND_SCRAPER;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
+use crate::downloader::duplicates::assign_duplicate_groups;
+use crate::downloader::duplicates::DUP_TIME_WINDOW_S;
+use crate::downloader::linkcleaner::feedburner_orig_links;
+use crate::downloader::linkcleaner::LinkCleaner;
+use crate::downloader::pagemonitor::monitor_message;
+use crate::downloader::pagemonitor::page_snapshot_text;
+use crate::downloader::pagemonitor::snapshot_diff_html;
+use crate::downloader::pagemonitor::snapshot_to_html;
+use crate::downloader::pagemonitor::PageMonitorParams;
+use crate::downloader::rules::run_after_store;
+use crate::downloader::rules::subscription_path;
+use crate::downloader::rules::RuleEngine;
+use crate::downloader::scraper::scrape_items;
+use crate::downloader::scraper::scraped_to_messages;
+use crate::downloader::scraper::ScraperSelectors;
+use crate::downloader::util::resolve_base_url;
+use crate::downloader::util::resolve_relative_urls;
+use crate::downloader::util::resolve_url;
 use crate::downloader::util::workaround_https_declaration;
+use crate::downloader::websub::feed_websub_links;
+use crate::downloader::websub::WebSubLinks;
 use crate::util::remove_invalid_chars_from_input;
 use crate::util::timestamp_from_utc;
 use crate::util::timestamp_now;
@@ -26,6 +55,8 @@ use feed_rs::parser::ParseFeedError;
 use flume::Sender;
 use regex::Regex;
 use resources::parameter::DOWNLOAD_TOO_LONG_MS;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::time::Instant;
 
 pub struct FetchInner {
@@ -42,6 +73,7 @@ pub struct FetchInner {
     pub timestamp_created: i64,
     pub messgesrepo: MessagesRepo,
     pub erro_repo: ErrorRepo,
+    pub link_cleaner: LinkCleaner,
 }
 
 impl std::fmt::Debug for FetchInner {
@@ -99,7 +131,18 @@ impl Step<FetchInner> for DownloadStart {
                         String::default(),
                     );
                 }
-                StepResult::Continue(Box::new(EvalStringAndFilter(inner)))
+                let source_kind = inner
+                    .subscriptionrepo
+                    .get_by_index(inner.fs_repo_id)
+                    .map(|se| se.source_kind)
+                    .unwrap_or_default();
+                match source_kind {
+                    SOURCE_KIND_SCRAPER => StepResult::Continue(Box::new(EvalScrapedPage(inner))),
+                    SOURCE_KIND_PAGE_MONITOR => {
+                        StepResult::Continue(Box::new(EvalMonitoredPage(inner)))
+                    }
+                    _ => StepResult::Continue(Box::new(EvalStringAndFilter(inner))),
+                }
             }
             _ => {
                 inner.download_error_happened = true;
@@ -124,8 +167,19 @@ impl Step<FetchInner> for EvalStringAndFilter {
     fn step(self: Box<Self>) -> StepResult<FetchInner> {
         let mut inner = self.0;
         let dl_text = workaround_https_declaration(&inner.download_text);
-        let (mut new_list, ts_created, err_text): (Vec<MessageRow>, i64, String) =
-            feed_text_to_entries(dl_text, inner.fs_repo_id, inner.url.clone());
+        let website_url = inner
+            .subscriptionrepo
+            .get_by_index(inner.fs_repo_id)
+            .map(|se| se.website_url)
+            .unwrap_or_default();
+        let (mut new_list, ts_created, err_text, o_header) =
+            feed_text_to_entries_header(dl_text, inner.fs_repo_id, inner.url.clone(), &website_url);
+        if let Some(header) = o_header {
+            update_websub_links(&inner, header.websub);
+            if let Err(e) = inner.subscriptionrepo.store_feed_meta(&header.meta) {
+                warn!("store_feed_meta {} : {:?}", inner.fs_repo_id, e);
+            }
+        }
         if !err_text.is_empty() {
             inner.erro_repo.add_error(
                 inner.fs_repo_id,
@@ -145,23 +199,352 @@ impl Step<FetchInner> for EvalStringAndFilter {
                 err_msg,
             );
         }
+        clean_message_links(
+            &mut new_list,
+            &feedburner_orig_links(&inner.download_text),
+            &inner.link_cleaner,
+        );
         inner.timestamp_created = ts_created;
-        let mr_i: MessageIterator = inner.messgesrepo.get_by_subscription(inner.fs_repo_id);
-        let filtered_list =
-            match_new_entries_to_existing(&new_list, mr_i, inner.cjob_sender.clone());
-        match inner.messgesrepo.insert_tx(&filtered_list) {
-            Ok(_num) => {
-                inner.download_text.clear();
-                StepResult::Continue(Box::new(SetSourceUpdatedExt(inner)))
+        filter_and_store(inner, &new_list)
+    }
+}
+
+/// feedburner proxy links are replaced by the original ones, then the tracking parameters are removed
+fn clean_message_links(
+    list: &mut [MessageRow],
+    orig_links: &HashMap<String, String>,
+    cleaner: &LinkCleaner,
+) {
+    for msg in list.iter_mut() {
+        if let Some(orig) = orig_links.get(&msg.link) {
+            msg.link.clone_from(orig);
+        }
+        msg.link = cleaner.clean(&msg.link);
+    }
+}
+
+/// A changed hub starts a new push subscription, see  [crate::downloader::websub]
+fn update_websub_links(inner: &FetchInner, o_links: Option<WebSubLinks>) {
+    let links = o_links.unwrap_or_default();
+    if let Some(se) = inner.subscriptionrepo.get_by_index(inner.fs_repo_id) {
+        if se.websub_hub != links.hub || se.websub_topic != links.topic {
+            inner
+                .subscriptionrepo
+                .update_websub_hub(inner.fs_repo_id, links.hub, links.topic);
+        }
+    }
+}
+
+/// Feed content the hub pushed to us, instead of downloading it
+pub struct WebSubPushStart(FetchInner);
+
+impl WebSubPushStart {
+    pub fn new(i: FetchInner) -> Self {
+        WebSubPushStart(i)
+    }
+}
+
+impl Step<FetchInner> for WebSubPushStart {
+    fn step(self: Box<Self>) -> StepResult<FetchInner> {
+        let inner = self.0;
+        let _r = inner
+            .sourcetree_job_sender
+            .send(SJob::SetFetchInProgress(inner.fs_repo_id));
+        StepResult::Continue(Box::new(EvalStringAndFilter(inner)))
+    }
+}
+
+/// Web page without feed: the items are found by the selectors of the subscription
+struct EvalScrapedPage(FetchInner);
+impl Step<FetchInner> for EvalScrapedPage {
+    fn step(self: Box<Self>) -> StepResult<FetchInner> {
+        let mut inner = self.0;
+        let o_selectors = inner
+            .subscriptionrepo
+            .get_by_index(inner.fs_repo_id)
+            .and_then(|se| ScraperSelectors::from_json(&se.source_params));
+        let items = match o_selectors
+            .ok_or_else(|| "no selectors".to_string())
+            .and_then(|sel| scrape_items(&inner.download_text, &sel, &inner.url))
+        {
+            Ok(items) => items,
+            Err(err_text) => {
+                inner.erro_repo.add_error(
+                    inner.fs_repo_id,
+                    ESRC::ScraperSelect,
+                    0,
+                    inner.url.to_string(),
+                    err_text,
+                );
+                return StepResult::Continue(Box::new(NotifyDlStop(inner)));
             }
-            Err(e) => {
-                error!("storing filtered content entries: {:?}", e);
-                StepResult::Continue(Box::new(NotifyDlStop(inner)))
+        };
+        let existing_dates: HashMap<String, i64> = inner
+            .messgesrepo
+            .get_by_subscription(inner.fs_repo_id)
+            .map(|m| (m.post_id.clone(), m.entry_src_date))
+            .collect();
+        let mut new_list = scraped_to_messages(&items, inner.fs_repo_id, &|post_id| {
+            existing_dates.get(post_id).copied()
+        });
+        clean_message_links(&mut new_list, &HashMap::new(), &inner.link_cleaner);
+        filter_and_store(inner, &new_list)
+    }
+}
+
+/// Plain web page: compares the text with the last snapshot, a change becomes a message with the difference
+struct EvalMonitoredPage(FetchInner);
+impl Step<FetchInner> for EvalMonitoredPage {
+    fn step(self: Box<Self>) -> StepResult<FetchInner> {
+        let mut inner = self.0;
+        let o_subs = inner.subscriptionrepo.get_by_index(inner.fs_repo_id);
+        let params = o_subs
+            .as_ref()
+            .map(|se| PageMonitorParams::from_json(&se.source_params))
+            .unwrap_or_default();
+        let display_name = o_subs.map(|se| se.display_name).unwrap_or_default();
+        let snapshot_text = match page_snapshot_text(&inner.download_text, &params.selector) {
+            Ok(t) => t,
+            Err(err_text) => {
+                inner.erro_repo.add_error(
+                    inner.fs_repo_id,
+                    ESRC::PageMonitorSelect,
+                    0,
+                    inner.url.to_string(),
+                    err_text,
+                );
+                return StepResult::Continue(Box::new(NotifyDlStop(inner)));
+            }
+        };
+        let new_hash = content_hash(&snapshot_text);
+        let o_previous = inner.messgesrepo.get_page_snapshot(inner.fs_repo_id);
+        if let Some(ref prev) = o_previous {
+            inner.timestamp_created = prev.fetch_date;
+            if prev.content_hash == new_hash {
+                return filter_and_store(inner, &[]);
+            }
+        }
+        let now = timestamp_now();
+        let new_message = match o_previous {
+            Some(prev) => {
+                let (diff_html, num_added, num_removed) =
+                    snapshot_diff_html(&decompress(&prev.content_text), &snapshot_text);
+                monitor_message(
+                    inner.fs_repo_id,
+                    &inner.url,
+                    &format!("{display_name} : +{num_added} -{num_removed}"),
+                    &diff_html,
+                    now,
+                )
             }
+            None => monitor_message(
+                inner.fs_repo_id,
+                &inner.url,
+                &display_name,
+                &snapshot_to_html(&snapshot_text),
+                now,
+            ),
+        };
+        let snapshot = PageSnapshot {
+            subscription_id: inner.fs_repo_id,
+            fetch_date: now,
+            content_hash: new_hash,
+            content_text: compress(&snapshot_text),
+            ..Default::default()
+        };
+        if let Err(e) = inner.messgesrepo.store_page_snapshot(&snapshot) {
+            error!("storing page snapshot {} : {:?}", inner.fs_repo_id, e);
+        }
+        inner.timestamp_created = now;
+        filter_and_store(inner, &[new_message])
+    }
+}
+
+/// drops the already known messages, stores the new ones
+fn filter_and_store(mut inner: FetchInner, new_list: &[MessageRow]) -> StepResult<FetchInner> {
+    let mr_i: MessageIterator = inner.messgesrepo.get_by_subscription(inner.fs_repo_id);
+    let mut filtered_list =
+        match_new_entries_to_existing(new_list, mr_i, inner.cjob_sender.clone());
+    let after_store = apply_rules(&inner, &mut filtered_list);
+    group_duplicates(&inner, &mut filtered_list);
+    match inner.messgesrepo.insert_tx_ids(&filtered_list) {
+        Ok(ids) => {
+            filtered_list
+                .iter_mut()
+                .zip(ids)
+                .for_each(|(m, id)| m.message_id = id);
+            inner.download_text.clear();
+            run_rules_after_store(&mut inner, &filtered_list, &after_store);
+            let kept: Vec<MessageRow> = filtered_list
+                .into_iter()
+                .filter(|m| !m.is_deleted)
+                .collect();
+            request_enclosure_autodownload(&inner, &kept);
+            request_article_extraction(&inner, &kept);
+            StepResult::Continue(Box::new(SetSourceUpdatedExt(inner)))
+        }
+        Err(e) => {
+            error!("storing filtered content entries: {:?}", e);
+            StepResult::Continue(Box::new(NotifyDlStop(inner)))
         }
     }
 }
 
+/// the user defined rules on the new messages.  Returns the actions to run after storing.
+fn apply_rules(inner: &FetchInner, new_messages: &mut [MessageRow]) -> Vec<(usize, MessageRule)> {
+    if new_messages.is_empty() {
+        return Vec::default();
+    }
+    let engine = RuleEngine::new(&inner.subscriptionrepo.get_rules());
+    if engine.is_empty() {
+        return Vec::default();
+    }
+    let subs_path = subscription_path(&inner.subscriptionrepo, inner.fs_repo_id);
+    engine.apply(new_messages, &subs_path)
+}
+
+fn run_rules_after_store(
+    inner: &mut FetchInner,
+    new_messages: &[MessageRow],
+    after_store: &[(usize, MessageRule)],
+) {
+    if after_store.is_empty() {
+        return;
+    }
+    let subs_name = inner
+        .subscriptionrepo
+        .get_by_index(inner.fs_repo_id)
+        .map(|se| se.display_name)
+        .unwrap_or_default();
+    let mut tag_rules: Vec<(&MessageRow, &MessageRule)> = Vec::default();
+    for (n, rule) in after_store {
+        if let Some(msg) = new_messages.get(*n) {
+            if rule.action == RULE_ACTION_TAG {
+                tag_rules.push((msg, rule));
+            } else {
+                run_after_store(rule, msg, &subs_name);
+            }
+        }
+    }
+    assign_rule_tags(inner, &tag_rules);
+}
+
+/// the stored messages are found by their post id
+fn assign_rule_tags(inner: &mut FetchInner, tag_rules: &[(&MessageRow, &MessageRule)]) {
+    if tag_rules.is_empty() {
+        return;
+    }
+    inner.messgesrepo.cache_clear();
+    let stored: HashMap<String, isize> = inner
+        .messgesrepo
+        .get_by_subscription(inner.fs_repo_id)
+        .map(|m| (m.post_id.clone(), m.message_id))
+        .collect();
+    let mut tag_created = false;
+    for (msg, rule) in tag_rules {
+        if rule.action_arg.trim().is_empty() {
+            continue;
+        }
+        let msg_id = match stored.get(&msg.post_id) {
+            Some(id) => *id,
+            None => continue,
+        };
+        match inner.messgesrepo.get_or_insert_tag(&rule.action_arg) {
+            Ok((tag_id, is_new)) => {
+                tag_created |= is_new;
+                inner.messgesrepo.add_tag_links(&[msg_id as i32], tag_id);
+            }
+            Err(e) => warn!("rule {}: tag {} => {:?}", rule.rule_id, rule.action_arg, e),
+        }
+    }
+    if tag_created {
+        let _r = inner
+            .sourcetree_job_sender
+            .send(SJob::FillSubscriptionsAdapter);
+        let _r = inner.sourcetree_job_sender.send(SJob::GuiUpdateTreeAll);
+    } else {
+        let _r = inner
+            .sourcetree_job_sender
+            .send(SJob::GuiUpdateVirtualEntries);
+    }
+}
+
+/// new messages get the group of the same story from other subscriptions
+fn group_duplicates(inner: &FetchInner, new_messages: &mut [MessageRow]) {
+    if new_messages.is_empty() {
+        return;
+    }
+    let from_ts = new_messages
+        .iter()
+        .map(|m| m.entry_src_date)
+        .min()
+        .unwrap_or_default();
+    let to_ts = new_messages
+        .iter()
+        .map(|m| m.entry_src_date)
+        .max()
+        .unwrap_or_default();
+    let existing = inner.messgesrepo.get_duplicate_candidates(
+        inner.fs_repo_id,
+        from_ts - DUP_TIME_WINDOW_S,
+        to_ts + DUP_TIME_WINDOW_S,
+    );
+    if existing.is_empty() {
+        return;
+    }
+    for (msg_id, group) in assign_duplicate_groups(new_messages, &existing) {
+        inner.messgesrepo.update_dup_group(msg_id, group);
+    }
+}
+
+/// new messages of subscriptions with automatic enclosure download
+fn request_enclosure_autodownload(inner: &FetchInner, new_messages: &[MessageRow]) {
+    if new_messages.is_empty() {
+        return;
+    }
+    let autodl = inner
+        .subscriptionrepo
+        .get_by_index(inner.fs_repo_id)
+        .map(|se| se.enclosure_autodl)
+        .unwrap_or(false);
+    if !autodl {
+        return;
+    }
+    let urls: Vec<String> = new_messages
+        .iter()
+        .flat_map(|m| m.get_enclosures())
+        .map(|encl| encl.url)
+        .collect();
+    if !urls.is_empty() {
+        let _r = inner
+            .cjob_sender
+            .send(CJob::DownloadEnclosures(inner.fs_repo_id, urls));
+    }
+}
+
+/// new messages of subscriptions with full article mode get their web page content
+fn request_article_extraction(inner: &FetchInner, new_messages: &[MessageRow]) {
+    if new_messages.is_empty() {
+        return;
+    }
+    let full_article = inner
+        .subscriptionrepo
+        .get_by_index(inner.fs_repo_id)
+        .map(|se| se.full_article)
+        .unwrap_or(false);
+    if !full_article {
+        return;
+    }
+    let msg_ids: Vec<isize> = new_messages
+        .iter()
+        .filter(|m| m.message_id > 0 && !m.link.is_empty())
+        .map(|m| m.message_id)
+        .collect();
+    if !msg_ids.is_empty() {
+        let _r = inner.cjob_sender.send(CJob::ExtractArticles(msg_ids));
+    }
+}
+
 struct SetSourceUpdatedExt(FetchInner);
 impl Step<FetchInner> for SetSourceUpdatedExt {
     fn step(self: Box<Self>) -> StepResult<FetchInner> {
@@ -203,22 +586,58 @@ impl Step<FetchInner> for Final {
 }
 
 /// returns  list of content entries,   timestamp of creation, error_text
-/// titles are compressed
+/// titles are compressed, content hash is taken from the uncompressed content
 /// heap:  feed_rs::parser::parse consumes 20%
 pub fn feed_text_to_entries(
     text: String,
     source_repo_id: isize,
     url: String,
 ) -> (Vec<MessageRow>, i64, String) {
+    let (fce_list, created_ts, err_text, _header) =
+        feed_text_to_entries_header(text, source_repo_id, url, "");
+    (fce_list, created_ts, err_text)
+}
+
+/// What the feed tells about itself, apart from the entries
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FeedHeader {
+    pub websub: Option<WebSubLinks>,
+    pub meta: FeedMeta,
+}
+
+/// same as  feed_text_to_entries, additionally the feed header. That is  None  if the text could not be parsed.
+/// website_url: base for relative urls of the entries, if empty the link of the feed or the feed url is taken.
+pub fn feed_text_to_entries_header(
+    text: String,
+    source_repo_id: isize,
+    url: String,
+    website_url: &str,
+) -> (Vec<MessageRow>, i64, String, Option<FeedHeader>) {
+    let mut o_header: Option<FeedHeader> = None;
     let mut fce_list: Vec<MessageRow> = Vec::new();
     let mut created_ts: i64 = 0;
     let mut err_text = String::default();
     match feed_rs::parser::parse(text.as_bytes()) {
         Ok(feed) => {
+            o_header = Some(FeedHeader {
+                websub: feed_websub_links(&feed, &url),
+                meta: feed_meta_from_model(&feed, source_repo_id, timestamp_now()),
+            });
+            let feed_link = feed
+                .links
+                .iter()
+                .find(|l| !l.media_type.as_deref().unwrap_or_default().contains("xml"))
+                .map(|l| l.href.clone())
+                .unwrap_or_default();
+            let fallback_base =
+                resolve_base_url(&[website_url, &feed_link, &url]).unwrap_or_default();
             for e in feed.entries {
-                let (mut fce, err_t) = message_from_modelentry(&e);
+                let (mut fce, err_t) = message_from_modelentry_base(&e, &fallback_base);
                 fce.subscription_id = source_repo_id;
                 fce.title = compress(&fce.title);
+                if fce.content_hash.is_empty() {
+                    fce.content_hash = content_hash(&fce.content_text);
+                }
                 fce.content_text = compress(&fce.content_text);
                 fce.categories = compress(&fce.categories);
                 fce.author = compress(&fce.author);
@@ -245,7 +664,53 @@ pub fn feed_text_to_entries(
             err_text = format!("Parsing: {}  length={}   {}", &url, text.len(), detail);
         }
     };
-    (fce_list, created_ts, err_text)
+    (fce_list, created_ts, err_text, o_header)
+}
+
+pub fn feed_meta_from_model(feed: &feed_rs::model::Feed, subs_id: isize, now: i64) -> FeedMeta {
+    let text_of = |o_t: &Option<feed_rs::model::Text>| {
+        o_t.as_ref()
+            .map(|t| t.content.trim().to_string())
+            .unwrap_or_default()
+    };
+    let generator = match feed.generator {
+        Some(ref g) => match g.version {
+            Some(ref v) => format!("{} {}", g.content.trim(), v.trim()),
+            None => g.content.trim().to_string(),
+        },
+        None => String::default(),
+    };
+    let logo = feed
+        .logo
+        .as_ref()
+        .or(feed.icon.as_ref())
+        .map(|img| img.uri.clone())
+        .unwrap_or_default();
+    FeedMeta {
+        meta_id: -1,
+        subscription_id: subs_id,
+        fetch_date: now,
+        title: text_of(&feed.title),
+        description: text_of(&feed.description),
+        language: feed.language.clone().unwrap_or_default(),
+        generator,
+        authors: feed
+            .authors
+            .iter()
+            .map(|p| p.name.trim())
+            .filter(|n| !n.is_empty())
+            .collect::<Vec<&str>>()
+            .join(", "),
+        logo,
+        rights: text_of(&feed.rights),
+        categories: feed
+            .categories
+            .iter()
+            .map(|c| c.label.as_deref().unwrap_or(&c.term).trim())
+            .filter(|n| !n.is_empty())
+            .collect::<Vec<&str>>()
+            .join(", "),
+    }
 }
 
 //  modifies the message list, if a date entry can be interpreted
@@ -296,7 +761,7 @@ pub fn strange_datetime_recover(newmessages: &mut [MessageRow], dl_text: &str) -
 }
 
 ///
-///  takes the last of media[]  and brings it into enclosure_url
+///  takes the last of media[]  and brings it into enclosure_url,  all of them into the enclosures list
 ///
 ///   filter_by_iso8859_1().0;    // also removes umlauts
 ///  https://docs.rs/feed-rs/latest/feed_rs/model/struct.Entry.html#structfield.published
@@ -305,9 +770,16 @@ pub fn strange_datetime_recover(newmessages: &mut [MessageRow], dl_text: &str) -
 ///  if title  contains invalid chars (for instance  & ), the Option<title>  is empty
 /// returns  converted Message-Entry,  Error-Text
 pub fn message_from_modelentry(me: &Entry) -> (MessageRow, String) {
+    message_from_modelentry_base(me, "")
+}
+
+/// same as  message_from_modelentry, relative urls in link, content and enclosures are made absolute.
+/// The base is  xml:base,  the item link or the fallback, usually the web site of the subscription.
+pub fn message_from_modelentry_base(me: &Entry, fallback_base: &str) -> (MessageRow, String) {
     let mut msg = MessageRow::default();
     let mut published_ts: i64 = 0;
     let mut error_text = String::default();
+    let mut enclosures: Vec<Enclosure> = Vec::default();
     if let Some(publis) = me.published {
         let dt_utc: DateTime<Utc> = publis;
         published_ts = DateTime::<Local>::from(dt_utc).timestamp();
@@ -356,10 +828,32 @@ pub fn message_from_modelentry(me: &Entry) -> (MessageRow, String) {
             msg.content_text = b
         }
         if let Some(enc) = c.src {
+            enclosures.push(Enclosure {
+                url: enc.href.clone(),
+                mime_type: c.content_type.to_string(),
+                length: c.length.unwrap_or_default(),
+                ..Default::default()
+            });
             msg.enclosure_url = enc.href
         }
     }
+    me.links
+        .iter()
+        .filter(|l| l.rel.as_deref() == Some("enclosure"))
+        .for_each(|l| {
+            enclosures.push(Enclosure {
+                url: l.href.clone(),
+                mime_type: l.media_type.clone().unwrap_or_default(),
+                length: l.length.unwrap_or_default(),
+                ..Default::default()
+            })
+        });
     for media in &me.media {
+        let thumbnail_url: String = media
+            .thumbnails
+            .first()
+            .map(|th| th.image.uri.clone())
+            .unwrap_or_default();
         for cont in &media.content {
             if let Some(m_url) = &cont.url {
                 let u = m_url.clone();
@@ -367,6 +861,18 @@ pub fn message_from_modelentry(me: &Entry) -> (MessageRow, String) {
                     msg.enclosure_url =
                         format!("{}://{}{}", u.scheme(), u.domain().unwrap(), u.path());
                 }
+                let duration = cont.duration.or(media.duration).unwrap_or_default();
+                enclosures.push(Enclosure {
+                    url: u.to_string(),
+                    mime_type: cont
+                        .content_type
+                        .as_ref()
+                        .map(|m| m.to_string())
+                        .unwrap_or_default(),
+                    length: cont.size.unwrap_or_default(),
+                    duration: duration.as_secs(),
+                    thumbnail_url: thumbnail_url.clone(),
+                });
             }
         }
         if msg.content_text.is_empty() {
@@ -402,6 +908,23 @@ pub fn message_from_modelentry(me: &Entry) -> (MessageRow, String) {
         .join(", ");
     msg.author = authorlist;
     msg.categories = cate_list;
+    let xml_base = me.base.clone().unwrap_or_default();
+    if let Some(link_base) = resolve_base_url(&[&xml_base, fallback_base]) {
+        msg.link = resolve_url(&msg.link, &link_base);
+    }
+    if let Some(base) = resolve_base_url(&[&xml_base, &msg.link, fallback_base]) {
+        // the hash stays that of the delivered text, stored messages do not appear changed
+        msg.content_hash = content_hash(&msg.content_text);
+        msg.content_text = resolve_relative_urls(&msg.content_text, &base);
+        msg.enclosure_url = resolve_url(&msg.enclosure_url, &base);
+        for encl in enclosures.iter_mut() {
+            encl.url = resolve_url(&encl.url, &base);
+            encl.thumbnail_url = resolve_url(&encl.thumbnail_url, &base);
+        }
+    }
+    let mut seen_urls: HashSet<String> = HashSet::default();
+    enclosures.retain(|encl| seen_urls.insert(encl.url.clone()));
+    msg.set_enclosures(&enclosures);
     (msg, error_text)
 }
 
@@ -469,6 +992,40 @@ mod t_ {
         assert_eq!(fce.post_id, "1234");
     }
 
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::messages::t_::feed_meta_rss  --lib -- --exact --nocapture "
+    #[test]
+    fn feed_meta_rss() {
+        let rss_str = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/"><channel>
+              <title> Neu im Kino </title>
+              <description>Filme der Woche</description>
+              <language>de-de</language>
+              <generator>Hugo 0.120</generator>
+              <copyright>(c) Kino</copyright>
+              <category>Film</category><category>Kultur</category>
+              <image><url>https://kino.example/logo.png</url><title>t</title><link>https://kino.example/</link></image>
+              <item><title>Rama Dama</title></item>
+            </channel></rss>"#;
+        let (list, _ts, err, o_header) =
+            feed_text_to_entries_header(rss_str.to_string(), 7, "some-url".to_string(), "");
+        assert!(err.is_empty());
+        assert_eq!(list.len(), 1);
+        let header = o_header.unwrap();
+        assert!(header.websub.is_none());
+        let meta = header.meta;
+        assert_eq!(meta.subscription_id, 7);
+        assert_eq!(meta.title.as_str(), "Neu im Kino");
+        assert_eq!(meta.description.as_str(), "Filme der Woche");
+        assert_eq!(meta.language.as_str(), "de-de");
+        assert_eq!(meta.generator.as_str(), "Hugo 0.120");
+        assert_eq!(meta.rights.as_str(), "(c) Kino");
+        assert_eq!(meta.categories.as_str(), "Film, Kultur");
+        assert_eq!(meta.logo.as_str(), "https://kino.example/logo.png");
+        let (_l, _t, _e, o_header) =
+            feed_text_to_entries_header("no feed".to_string(), 7, "some-url".to_string(), "");
+        assert!(o_header.is_none());
+    }
+
     // #[ignore]
     #[test]
     fn message_from_modelentry_3() {
@@ -501,6 +1058,65 @@ mod t_ {
             fce.enclosure_url,
             "https://static.kino.de/rama-dama-1990-film-rcm1200x0u.jpg"
         );
+        let encl = fce.get_enclosures();
+        assert_eq!(encl.len(), 1);
+        assert_eq!(encl[0].mime_type, "image/jpeg");
+        assert_eq!(encl[0].length, 153553);
+    }
+
+    #[test]
+    fn message_from_modelentry_media_multiple() {
+        let rsstext = r#" <?xml version="1.0" encoding="UTF-8"?>
+	 	<rss xmlns:media="http://search.yahoo.com/mrss/" version="2.0">
+	 	  <channel>
+	 	    <title>Podcast</title>
+	 	    <item>
+	 	      <title>Episode 1</title>
+	 	      <guid>ep1</guid>
+	 	      <media:group>
+	 	        <media:content url="https://example.org/ep1.mp3" type="audio/mpeg" fileSize="1000" duration="61"/>
+	 	        <media:content url="https://example.org/ep1.ogg" type="audio/ogg" fileSize="900" duration="61"/>
+	 	        <media:thumbnail url="https://example.org/ep1.jpg"/>
+	 	      </media:group>
+	 	    </item>
+	 	  </channel>
+	 	</rss>"#;
+        let feeds = parser::parse(rsstext.as_bytes()).unwrap();
+        let first_entry = feeds.entries.first().unwrap();
+        let fce: MessageRow = message_from_modelentry(first_entry).0;
+        let encl = fce.get_enclosures();
+        assert_eq!(encl.len(), 2);
+        assert_eq!(encl[0].url, "https://example.org/ep1.mp3");
+        assert_eq!(encl[1].mime_type, "audio/ogg");
+        assert_eq!(encl[1].length, 900);
+        assert_eq!(encl[0].duration, 61);
+        assert_eq!(encl[0].thumbnail_url, "https://example.org/ep1.jpg");
+    }
+
+    #[test]
+    fn message_from_modelentry_media_duplicates() {
+        let rsstext = r#" <?xml version="1.0" encoding="UTF-8"?>
+	 	<rss xmlns:media="http://search.yahoo.com/mrss/" version="2.0">
+	 	  <channel>
+	 	    <title>Podcast</title>
+	 	    <item>
+	 	      <title>Episode 2</title>
+	 	      <guid>ep2</guid>
+	 	      <media:group>
+	 	        <media:content url="https://example.org/ep2.mp3" type="audio/mpeg" fileSize="1000"/>
+	 	        <media:content url="https://example.org/ep2.ogg" type="audio/ogg" fileSize="900"/>
+	 	        <media:content url="https://example.org/ep2.mp3" type="audio/mpeg" fileSize="1000"/>
+	 	      </media:group>
+	 	    </item>
+	 	  </channel>
+	 	</rss>"#;
+        let feeds = parser::parse(rsstext.as_bytes()).unwrap();
+        let first_entry = feeds.entries.first().unwrap();
+        let fce: MessageRow = message_from_modelentry(first_entry).0;
+        let encl = fce.get_enclosures();
+        assert_eq!(encl.len(), 2);
+        assert_eq!(encl[0].url, "https://example.org/ep2.mp3");
+        assert_eq!(encl[1].url, "https://example.org/ep2.ogg");
     }
 
     #[test]
@@ -568,4 +1184,50 @@ mod t_ {
         );
         assert!(fce.content_text.len() > 10);
     }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::messages::t_::relative_urls_resolved  --lib -- --exact --nocapture "
+    #[test]
+    fn relative_urls_resolved() {
+        let atom = r##"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Blog</title>
+  <link href="https://blog.example/"/>
+  <entry xml:base="https://blog.example/2024/05/">
+    <title>With base</title>
+    <id>tag:blog.example,1</id>
+    <link href="post.html"/>
+    <content type="html">&lt;img src="img/a.png"&gt; &lt;a href='/about'&gt;about&lt;/a&gt; &lt;a href="#top"&gt;top&lt;/a&gt; &lt;a href="mailto:x@y.z"&gt;m&lt;/a&gt;</content>
+  </entry>
+  <entry>
+    <title>Without base</title>
+    <id>tag:blog.example,2</id>
+    <link href="https://blog.example/2024/06/other.html"/>
+    <content type="html">&lt;img src="b.png"&gt; &lt;img src="//cdn.example/c.png"&gt;</content>
+  </entry>
+</feed>"##;
+        let feed = parser::parse(atom.as_bytes()).unwrap();
+        let fce0 = message_from_modelentry_base(&feed.entries[0], "https://blog.example/").0;
+        assert_eq!(fce0.link, "https://blog.example/2024/05/post.html");
+        assert_eq!(
+            fce0.content_text,
+            r##"<img src="https://blog.example/2024/05/img/a.png"> <a href='https://blog.example/about'>about</a> <a href="#top">top</a> <a href="mailto:x@y.z">m</a>"##
+        );
+        let fce1 = message_from_modelentry_base(&feed.entries[1], "https://blog.example/").0;
+        assert_eq!(
+            fce1.content_text,
+            r#"<img src="https://blog.example/2024/06/b.png"> <img src="https://cdn.example/c.png">"#
+        );
+        let (list, _, _, _) = feed_text_to_entries_header(
+            atom.to_string(),
+            3,
+            "https://blog.example/feed".to_string(),
+            "",
+        );
+        let content1 = decompress(&list[1].content_text);
+        assert!(content1.contains("https://blog.example/2024/06/b.png"));
+        assert_eq!(
+            list[1].content_hash,
+            content_hash(r#"<img src="b.png"> <img src="//cdn.example/c.png">"#)
+        );
+    }
 }
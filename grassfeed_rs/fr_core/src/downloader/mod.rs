@@ -1,7 +1,17 @@
+pub mod apply_rules;
+pub mod article;
 pub mod browserdrag;
 pub mod comprehensive;
 pub mod db_clean;
+pub mod duplicates;
+pub mod enclosures;
 pub mod icons;
 pub mod launch_web;
+pub mod linkcleaner;
 pub mod messages;
+pub mod pagemonitor;
+pub mod rules;
+pub mod scraper;
+pub mod siteresolver;
 pub mod util;
+pub mod websub;
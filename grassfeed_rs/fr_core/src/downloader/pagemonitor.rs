@@ -0,0 +1,313 @@
+use crate::db::message::compress;
+use crate::db::message::content_hash;
+use crate::db::message::MessageRow;
+use crate::util::sequence_diff;
+use crate::util::string_escape_url;
+use crate::util::text_diff_html;
+use crate::util::DiffStep;
+use serde::Deserialize;
+use serde::Serialize;
+use tl::Node;
+
+/// text of these is never shown
+const SKIP_TAGS: [&str; 7] = [
+    "script", "style", "noscript", "template", "head", "svg", "iframe",
+];
+/// these start a new line in the snapshot text
+const BLOCK_TAGS: [&str; 30] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "tr",
+];
+/// Limit for the line diff, the LCS table grows quadratic
+const SNAPSHOT_MAX_LINES: usize = 2000;
+/// unchanged lines shown around each change
+const DIFF_CONTEXT_LINES: usize = 1;
+
+/// Settings of a monitored web page
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct PageMonitorParams {
+    /// empty: the whole page body is watched
+    pub selector: String,
+}
+
+impl PageMonitorParams {
+    pub fn from_json(json: &str) -> Self {
+        if json.trim().is_empty() {
+            return PageMonitorParams::default();
+        }
+        match serde_json::from_str::<PageMonitorParams>(json) {
+            Ok(p) => p,
+            Err(e) => {
+                debug!("page monitor params {:?} : {:?}", json, e);
+                PageMonitorParams::default()
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Normalized plain text of the page, or of all elements matching the selector.
+/// One line per block element, whitespace collapsed, empty lines dropped.
+pub fn page_snapshot_text(page: &str, selector: &str) -> Result<String, String> {
+    let dom: tl::VDom = match tl::parse(page, tl::ParserOptions::default()) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("parsing : {e:?}")),
+    };
+    let parser = dom.parser();
+    let mut raw = String::default();
+    if selector.trim().is_empty() {
+        for handle in dom.children() {
+            if let Some(node) = handle.get(parser) {
+                render_text(node, parser, &mut raw);
+            }
+        }
+    } else {
+        let handles = match dom.query_selector(selector.trim()) {
+            Some(h) => h.collect::<Vec<_>>(),
+            None => return Err(format!("selector {selector:?}")),
+        };
+        if handles.is_empty() {
+            return Err(format!("nothing found for selector {selector:?}"));
+        }
+        for handle in handles {
+            if let Some(node) = handle.get(parser) {
+                render_text(node, parser, &mut raw);
+                raw.push('\n');
+            }
+        }
+    }
+    let lines = raw
+        .lines()
+        .map(|l| decode_entities(&l.split_whitespace().collect::<Vec<&str>>().join(" ")))
+        .filter(|l| !l.is_empty())
+        .take(SNAPSHOT_MAX_LINES)
+        .collect::<Vec<String>>();
+    Ok(lines.join("\n"))
+}
+
+/// The changed lines with some context, as html.  Changes within the lines are marked
+/// with  <ins> and <del>.   Returns the html, the number of added and removed lines.
+pub fn snapshot_diff_html(old_text: &str, new_text: &str) -> (String, usize, usize) {
+    let old_l: Vec<&str> = old_text.lines().collect();
+    let new_l: Vec<&str> = new_text.lines().collect();
+    // (Some(line), _, _) : unchanged line,   (None, removed, added) : changed block
+    let mut blocks: Vec<(Option<&str>, Vec<&str>, Vec<&str>)> = Vec::default();
+    for step in sequence_diff(&old_l, &new_l) {
+        if let DiffStep::Same(i) = step {
+            blocks.push((Some(old_l[i]), Vec::default(), Vec::default()));
+            continue;
+        }
+        if !matches!(blocks.last(), Some((None, _, _))) {
+            blocks.push((None, Vec::default(), Vec::default()));
+        }
+        let last = blocks.last_mut().unwrap();
+        match step {
+            DiffStep::Added(j) => last.2.push(new_l[j]),
+            DiffStep::Removed(i) => last.1.push(old_l[i]),
+            DiffStep::Same(_) => {}
+        }
+    }
+    let changed: Vec<usize> = blocks
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.0.is_none())
+        .map(|(idx, _)| idx)
+        .collect();
+    let is_shown = |idx: usize| {
+        changed
+            .iter()
+            .any(|c| idx + DIFF_CONTEXT_LINES >= *c && idx <= *c + DIFF_CONTEXT_LINES)
+    };
+    let mut html = String::default();
+    let mut num_added = 0;
+    let mut num_removed = 0;
+    let mut skipped = false;
+    for (idx, (o_same, removed, added)) in blocks.iter().enumerate() {
+        if !is_shown(idx) {
+            skipped = true;
+            continue;
+        }
+        if skipped && !html.is_empty() {
+            html.push_str("<p>&hellip;</p>\n");
+        }
+        skipped = false;
+        match o_same {
+            Some(line) => {
+                html.push_str(&format!("<p>{}</p>\n", string_escape_url(line.to_string())));
+            }
+            None => {
+                num_added += added.len();
+                num_removed += removed.len();
+                html.push_str(&format!(
+                    "<p>{}</p>\n",
+                    text_diff_html(&removed.join(" "), &added.join(" "))
+                ));
+            }
+        }
+    }
+    (html, num_added, num_removed)
+}
+
+/// snapshot text as html, one paragraph per line
+pub fn snapshot_to_html(text: &str) -> String {
+    text.lines()
+        .map(|l| format!("<p>{}</p>\n", string_escape_url(l.to_string())))
+        .collect()
+}
+
+/// Synthetic message for the monitored page.  The post id is unique for each change, since
+/// a page can return to an earlier state.
+pub fn monitor_message(
+    subs_id: isize,
+    page_url: &str,
+    title: &str,
+    content_html: &str,
+    now: i64,
+) -> MessageRow {
+    let hash = content_hash(content_html);
+    MessageRow {
+        message_id: -1,
+        subscription_id: subs_id,
+        title: compress(title),
+        post_id: format!("pagemonitor-{now}-{hash}"),
+        link: page_url.to_string(),
+        entry_src_date: now,
+        fetch_date: now,
+        content_hash: hash,
+        content_text: compress(content_html),
+        ..Default::default()
+    }
+}
+
+fn render_text(node: &Node, parser: &tl::Parser, out: &mut String) {
+    match node {
+        Node::Comment(_) => (),
+        Node::Raw(bytes) => {
+            out.push_str(&bytes.as_utf8_str());
+        }
+        Node::Tag(tag) => {
+            let name = tag.name().as_utf8_str().to_lowercase();
+            if SKIP_TAGS.contains(&name.as_str()) {
+                return;
+            }
+            let is_block = BLOCK_TAGS.contains(&name.as_str());
+            if is_block {
+                out.push('\n');
+            } else {
+                out.push(' ');
+            }
+            for handle in tag.children().top().iter() {
+                if let Some(child) = handle.get(parser) {
+                    render_text(child, parser, out);
+                }
+            }
+            if is_block {
+                out.push('\n');
+            } else {
+                out.push(' ');
+            }
+        }
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    const STATUS_PAGE: &str = r#"<html><head><title>Status</title><style>p {}</style></head>
+        <body><nav><a href="/">Home</a></nav>
+        <div id="status"><h2>Systems</h2><ul><li>API:  <b>operational</b></li>
+          <li>Web &amp; CDN: operational</li></ul></div>
+        <script>var x = 1;</script><footer>Updated 10:00</footer></body></html>"#;
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::pagemonitor::t_::snapshot_whole_page  --lib -- --exact --nocapture "
+    #[test]
+    fn snapshot_whole_page() {
+        let text = page_snapshot_text(STATUS_PAGE, "").unwrap();
+        assert_eq!(
+            text,
+            "Home\nSystems\nAPI: operational\nWeb & CDN: operational\nUpdated 10:00"
+        );
+    }
+
+    #[test]
+    fn snapshot_with_selector() {
+        let text = page_snapshot_text(STATUS_PAGE, "#status").unwrap();
+        assert_eq!(text, "Systems\nAPI: operational\nWeb & CDN: operational");
+        assert!(page_snapshot_text(STATUS_PAGE, ".missing").is_err());
+    }
+
+    #[test]
+    fn diff_changed_line() {
+        let old = "a\nb\nc\nd\ne\nstatus ok";
+        let new = "a\nb\nc\nd\ne\nstatus degraded\nnew line";
+        let (html, added, removed) = snapshot_diff_html(old, new);
+        assert_eq!((added, removed), (2, 1));
+        assert!(html.starts_with("<p>e</p>"));
+        assert!(html.contains("<del>ok</del>"));
+        assert!(html.contains("<ins>degraded</ins>"));
+        assert!(!html.contains("<p>a</p>"));
+    }
+
+    #[test]
+    fn diff_separates_distant_changes() {
+        let (html, added, removed) = snapshot_diff_html("1\n2\n3\n4\n5\n6", "0\n2\n3\n4\n5\n7");
+        assert_eq!((added, removed), (2, 2));
+        assert!(html.contains("&hellip;"));
+        assert!(!html.contains("<p>4</p>"));
+    }
+
+    #[test]
+    fn params_json() {
+        let p = PageMonitorParams {
+            selector: "#status".to_string(),
+        };
+        assert_eq!(PageMonitorParams::from_json(&p.to_json()), p);
+        assert_eq!(
+            PageMonitorParams::from_json(""),
+            PageMonitorParams::default()
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! User defined rules for new messages: one condition on a message field, one action.
+//! Actions that change the message are applied before it is stored, the others afterwards.
+
+use crate::db::message::decompress;
+use crate::db::message::MessageRow;
+use crate::db::message_rule::MessageRule;
+use crate::db::message_rule::RULE_ACTION_COMMAND;
+use crate::db::message_rule::RULE_ACTION_DELETE;
+use crate::db::message_rule::RULE_ACTION_FAVORITE;
+use crate::db::message_rule::RULE_ACTION_MARK_READ;
+use crate::db::message_rule::RULE_ACTION_NOTIFY;
+use crate::db::message_rule::RULE_ACTION_TAG;
+use crate::db::message_rule::RULE_FIELD_AUTHOR;
+use crate::db::message_rule::RULE_FIELD_CATEGORIES;
+use crate::db::message_rule::RULE_FIELD_CONTENT;
+use crate::db::message_rule::RULE_FIELD_HOST;
+use crate::db::message_rule::RULE_FIELD_TITLE;
+use crate::db::subscription_repo::ISubscriptionRepo;
+use regex::Regex;
+use url::Url;
+
+/// protects against loops in the parent chain
+const MAX_FOLDER_DEPTH: usize = 30;
+
+const NOTIFY_PROGRAM: &str = "notify-send";
+
+enum Matcher {
+    /// lower case
+    Plain(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(rule: &MessageRule) -> Result<Self, String> {
+        if rule.is_regex {
+            Regex::new(&rule.pattern)
+                .map(Matcher::Regex)
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Matcher::Plain(rule.pattern.to_lowercase()))
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Plain(p) => text.to_lowercase().contains(p.as_str()),
+            Matcher::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+/// None if the rule can be used, the error message otherwise
+pub fn check_rule(rule: &MessageRule) -> Option<String> {
+    if rule.pattern.trim().is_empty() {
+        return Some("empty pattern".to_string());
+    }
+    if rule.action == RULE_ACTION_TAG && rule.action_arg.trim().is_empty() {
+        return Some("empty tag name".to_string());
+    }
+    Matcher::new(rule).err()
+}
+
+/// The enabled and valid rules, in order
+pub struct RuleEngine {
+    rules: Vec<(MessageRule, Matcher)>,
+}
+
+impl RuleEngine {
+    pub fn new(rules: &[MessageRule]) -> Self {
+        let mut compiled: Vec<(MessageRule, Matcher)> = Vec::default();
+        for rule in rules.iter().filter(|r| r.enabled) {
+            if let Some(e) = check_rule(rule) {
+                warn!("rule {} '{}' skipped: {}", rule.rule_id, rule.pattern, e);
+                continue;
+            }
+            if let Ok(m) = Matcher::new(rule) {
+                compiled.push((rule.clone(), m));
+            }
+        }
+        RuleEngine { rules: compiled }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// subs_path: subscription id of the message, and the ids of the folders above
+    pub fn matching(&self, msg: &MessageRow, subs_path: &[isize]) -> Vec<&MessageRule> {
+        self.rules
+            .iter()
+            .filter(|(rule, _m)| {
+                rule.subscription_id <= 0 || subs_path.contains(&rule.subscription_id)
+            })
+            .filter(|(rule, m)| m.is_match(&field_text(msg, rule.field)))
+            .map(|(rule, _m)| rule)
+            .collect()
+    }
+
+    /// Marks the new messages read, deleted or favorite.
+    /// Returns list index and rule of the actions to run after storing.
+    pub fn apply(
+        &self,
+        new_list: &mut [MessageRow],
+        subs_path: &[isize],
+    ) -> Vec<(usize, MessageRule)> {
+        let mut after_store: Vec<(usize, MessageRule)> = Vec::default();
+        for (n, msg) in new_list.iter_mut().enumerate() {
+            let matched: Vec<MessageRule> =
+                self.matching(msg, subs_path).into_iter().cloned().collect();
+            for rule in matched {
+                match rule.action {
+                    RULE_ACTION_MARK_READ => msg.is_read = true,
+                    RULE_ACTION_DELETE => msg.is_deleted = true,
+                    RULE_ACTION_FAVORITE => msg.set_favorite(true),
+                    _ => after_store.push((n, rule)),
+                }
+            }
+        }
+        after_store
+    }
+}
+
+/// decompressed text of that field
+fn field_text(msg: &MessageRow, field: isize) -> String {
+    match field {
+        RULE_FIELD_TITLE => decompress(&msg.title),
+        RULE_FIELD_CONTENT => decompress(&msg.content_text),
+        RULE_FIELD_AUTHOR => decompress(&msg.author),
+        RULE_FIELD_CATEGORIES => decompress(&msg.categories),
+        RULE_FIELD_HOST => match Url::parse(&msg.link) {
+            Ok(u) => u.host_str().unwrap_or_default().to_string(),
+            Err(_) => String::default(),
+        },
+        _ => String::default(),
+    }
+}
+
+/// the subscription id, followed by the ids of the folders above it
+pub fn subscription_path(subs_repo: &dyn ISubscriptionRepo, subs_id: isize) -> Vec<isize> {
+    let mut path: Vec<isize> = vec![subs_id];
+    let mut current = subs_id;
+    for _ in 0..MAX_FOLDER_DEPTH {
+        match subs_repo.get_by_index(current) {
+            Some(entry) if entry.parent_subs_id > 0 && !path.contains(&entry.parent_subs_id) => {
+                path.push(entry.parent_subs_id);
+                current = entry.parent_subs_id;
+            }
+            _ => break,
+        }
+    }
+    path
+}
+
+/// Splits the command line at white space, then replaces the placeholders in each part:
+/// %t title   %l link   %a author   %s subscription name
+/// The message shall be compressed, as stored.
+pub fn expand_command(cmdline: &str, msg: &MessageRow, subs_name: &str) -> Vec<String> {
+    let title = decompress(&msg.title);
+    let author = decompress(&msg.author);
+    cmdline
+        .split_whitespace()
+        .map(|part| {
+            part.replace("%t", &title)
+                .replace("%l", &msg.link)
+                .replace("%a", &author)
+                .replace("%s", subs_name)
+        })
+        .collect()
+}
+
+/// notification or command for a stored message. Tags are assigned by the caller, who knows the message ids.
+pub fn run_after_store(rule: &MessageRule, msg: &MessageRow, subs_name: &str) {
+    if msg.is_deleted {
+        return;
+    }
+    let args: Vec<String> = match rule.action {
+        RULE_ACTION_NOTIFY => {
+            let summary = if rule.action_arg.trim().is_empty() {
+                subs_name.to_string()
+            } else {
+                rule.action_arg.clone()
+            };
+            vec![NOTIFY_PROGRAM.to_string(), summary, decompress(&msg.title)]
+        }
+        RULE_ACTION_COMMAND => expand_command(&rule.action_arg, msg, subs_name),
+        _ => return,
+    };
+    if args.is_empty() {
+        return;
+    }
+    match std::process::Command::new(&args[0])
+        .args(&args[1..])
+        .spawn()
+    {
+        // reaped in the background,  otherwise each finished process stays a zombie
+        Ok(mut child) => {
+            std::thread::spawn(move || {
+                let _r = child.wait();
+            });
+        }
+        Err(e) => warn!("rule {}: {:?} => {:?}", rule.rule_id, args, e),
+    }
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+    use crate::db::message::compress;
+
+    fn rule(field: isize, is_regex: bool, pattern: &str, action: isize) -> MessageRule {
+        MessageRule {
+            enabled: true,
+            field,
+            is_regex,
+            pattern: pattern.to_string(),
+            action,
+            ..Default::default()
+        }
+    }
+
+    fn msg(title: &str, categories: &str, link: &str) -> MessageRow {
+        MessageRow {
+            title: compress(title),
+            categories: compress(categories),
+            author: compress("Jane Doe"),
+            link: link.to_string(),
+            ..Default::default()
+        }
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::rules::t_::apply_actions  --lib -- --exact --nocapture "
+    #[test]
+    fn apply_actions() {
+        let mut favorite = rule(RULE_FIELD_TITLE, false, "grassfeed", RULE_ACTION_FAVORITE);
+        favorite.subscription_id = 5;
+        let engine = RuleEngine::new(&[
+            rule(
+                RULE_FIELD_CATEGORIES,
+                true,
+                r"(?i)^sports?\b",
+                RULE_ACTION_MARK_READ,
+            ),
+            favorite,
+            rule(RULE_FIELD_HOST, false, "ads.example", RULE_ACTION_DELETE),
+            rule(RULE_FIELD_AUTHOR, false, "jane", RULE_ACTION_NOTIFY),
+            rule(RULE_FIELD_TITLE, true, "(unclosed", RULE_ACTION_DELETE),
+            MessageRule {
+                enabled: false,
+                ..rule(RULE_FIELD_TITLE, false, "a", RULE_ACTION_DELETE)
+            },
+        ]);
+        let mut list = vec![
+            msg("Sport results", "Sports, Football", "https://a.example/1"),
+            msg("New GrassFeed release", "Software", "https://ads.example/2"),
+        ];
+        let after = engine.apply(&mut list, &[7, 5]);
+        assert!(list[0].is_read);
+        assert!(!list[0].is_favorite());
+        assert!(list[1].is_favorite());
+        assert!(list[1].is_deleted);
+        assert!(!list[1].is_read);
+        assert_eq!(after.len(), 2);
+        assert_eq!(after[1].0, 1);
+        assert_eq!(after[1].1.action, RULE_ACTION_NOTIFY);
+        let after = engine.apply(&mut list, &[8]);
+        assert!(after.iter().all(|(_n, r)| r.action != RULE_ACTION_FAVORITE));
+    }
+
+    #[test]
+    fn check_and_expand() {
+        assert!(check_rule(&rule(RULE_FIELD_TITLE, true, "a(", 0)).is_some());
+        assert!(check_rule(&rule(RULE_FIELD_TITLE, false, " ", 0)).is_some());
+        assert!(check_rule(&rule(RULE_FIELD_TITLE, false, "a(", 0)).is_none());
+        let mut tag = rule(RULE_FIELD_TITLE, false, "rust", RULE_ACTION_TAG);
+        tag.action_arg = "  ".to_string();
+        assert!(check_rule(&tag).is_some());
+        tag.action_arg = "Rust".to_string();
+        assert!(check_rule(&tag).is_none());
+        let m = msg("Some title", "", "https://a.example/x");
+        assert_eq!(
+            expand_command("echo %s: %t %l", &m, "Feed"),
+            vec!["echo", "Feed:", "Some title", "https://a.example/x"]
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use crate::db::message::compress;
+use crate::db::message::content_hash;
+use crate::db::message::MessageRow;
+use crate::util::db_time_to_display_nonnull;
+use chrono::DateTime;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::TimeZone;
+use serde::Deserialize;
+use serde::Serialize;
+use tl::HTMLTag;
+
+const DATE_FORMATS: [&str; 6] = [
+    "%Y-%m-%d",
+    "%d.%m.%Y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+];
+const DATETIME_FORMATS: [&str; 2] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"];
+
+/// CSS-like selectors for web sites without a feed.
+/// The title, link, date and body selectors apply inside each container.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ScraperSelectors {
+    pub container: String,
+    pub title: String,
+    /// empty: the first link inside the container
+    pub link: String,
+    /// empty: no date, the time of the first fetch is taken
+    pub date: String,
+    /// empty: the whole container is the message content
+    pub body: String,
+}
+
+impl ScraperSelectors {
+    pub fn from_json(json: &str) -> Option<Self> {
+        match serde_json::from_str::<ScraperSelectors>(json) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                debug!("scraper selectors {:?} : {:?}", json, e);
+                None
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        !self.container.trim().is_empty() && !self.title.trim().is_empty()
+    }
+}
+
+/// One item of the web page,  not compressed
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct ScrapedItem {
+    pub title: String,
+    pub link: String,
+    /// 0: no date found
+    pub date: i64,
+    pub body: String,
+}
+
+/// returns the items in page order, or an error text
+pub fn scrape_items(
+    page: &str,
+    selectors: &ScraperSelectors,
+    page_url: &str,
+) -> Result<Vec<ScrapedItem>, String> {
+    if !selectors.is_valid() {
+        return Err(format!("invalid selectors {selectors:?}"));
+    }
+    let dom: tl::VDom = match tl::parse(page, tl::ParserOptions::default()) {
+        Ok(d) => d,
+        Err(e) => return Err(format!("parsing {page_url} : {e:?}")),
+    };
+    let parser = dom.parser();
+    let containers = match dom.query_selector(selectors.container.trim()) {
+        Some(c) => c,
+        None => return Err(format!("container selector {:?}", selectors.container)),
+    };
+    let base_url = url::Url::parse(page_url).ok();
+    let mut items: Vec<ScrapedItem> = Vec::default();
+    for handle in containers {
+        let container = match handle.get(parser).and_then(|n| n.as_tag()) {
+            Some(t) => t,
+            None => continue,
+        };
+        let o_title_tag = select_first(container, parser, &selectors.title);
+        let title = match o_title_tag {
+            Some(t) => collapse_whitespace(&t.inner_text(parser)),
+            None => continue,
+        };
+        if title.is_empty() {
+            continue;
+        }
+        let link_tag = if selectors.link.trim().is_empty() {
+            if container.name().as_utf8_str().eq_ignore_ascii_case("a") {
+                Some(container)
+            } else {
+                select_first(container, parser, "a")
+            }
+        } else {
+            select_first(container, parser, &selectors.link)
+        };
+        let link = link_tag
+            .and_then(|t| attribute_value(t, "href"))
+            .map(|href| resolve_url(base_url.as_ref(), &href))
+            .unwrap_or_default();
+        let date = if selectors.date.trim().is_empty() {
+            0
+        } else {
+            select_first(container, parser, &selectors.date)
+                .and_then(|t| {
+                    attribute_value(t, "datetime")
+                        .and_then(|d| parse_scraped_date(&d))
+                        .or_else(|| parse_scraped_date(&t.inner_text(parser)))
+                })
+                .unwrap_or(0)
+        };
+        let body = if selectors.body.trim().is_empty() {
+            container.inner_html(parser)
+        } else {
+            select_first(container, parser, &selectors.body)
+                .map(|t| t.inner_html(parser))
+                .unwrap_or_default()
+        };
+        items.push(ScrapedItem {
+            title,
+            link,
+            date,
+            body: body.trim().to_string(),
+        });
+    }
+    Ok(items)
+}
+
+/// for the new-subscription dialog: one line per item, date  title  link
+pub fn scraper_preview_text(items: &[ScrapedItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "{}  {}  {}",
+                db_time_to_display_nonnull(item.date),
+                item.title,
+                item.link
+            )
+            .trim()
+            .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// content of the title tag,  empty if there is none
+pub fn page_title(page: &str) -> String {
+    let dom: tl::VDom = match tl::parse(page, tl::ParserOptions::default()) {
+        Ok(d) => d,
+        Err(_e) => return String::default(),
+    };
+    dom.query_selector("title")
+        .and_then(|mut iter| iter.next())
+        .and_then(|h| h.get(dom.parser()))
+        .map(|n| collapse_whitespace(&n.inner_text(dom.parser())))
+        .unwrap_or_default()
+}
+
+/// Converts the items into messages, like the feed entries.  Items without a date get the
+/// date of the already stored message with the same post id, or the current time.
+pub fn scraped_to_messages(
+    items: &[ScrapedItem],
+    subs_id: isize,
+    existing_dates: &dyn Fn(&str) -> Option<i64>,
+) -> Vec<MessageRow> {
+    let now = crate::util::timestamp_now();
+    items
+        .iter()
+        .map(|item| {
+            let post_id = if item.link.is_empty() {
+                format!("scraped-{}", content_hash(&item.title))
+            } else {
+                item.link.clone()
+            };
+            let entry_src_date = if item.date > 0 {
+                item.date
+            } else {
+                existing_dates(&post_id).unwrap_or(now)
+            };
+            MessageRow {
+                message_id: -1,
+                subscription_id: subs_id,
+                title: compress(&item.title),
+                post_id,
+                link: item.link.clone(),
+                entry_src_date,
+                fetch_date: now,
+                content_hash: content_hash(&item.body),
+                content_text: compress(&item.body),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn select_first<'b>(
+    container: &'b HTMLTag,
+    parser: &'b tl::Parser,
+    selector: &str,
+) -> Option<&'b HTMLTag<'b>> {
+    let selector = selector.trim();
+    if selector.is_empty() {
+        return None;
+    }
+    container
+        .query_selector(parser, selector)?
+        .filter_map(|h| h.get(parser))
+        .find_map(|n| n.as_tag())
+}
+
+fn attribute_value(tag: &HTMLTag, name: &str) -> Option<String> {
+    tag.attributes()
+        .get(name)
+        .flatten()
+        .map(|b| b.as_utf8_str().trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn resolve_url(base: Option<&url::Url>, href: &str) -> String {
+    match base.and_then(|b| b.join(href).ok()) {
+        Some(joined) => joined.to_string(),
+        None => href.to_string(),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// local timestamp in seconds
+pub fn parse_scraped_date(text: &str) -> Option<i64> {
+    let text = collapse_whitespace(text);
+    if text.is_empty() {
+        return None;
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(&text) {
+        return Some(dt.timestamp());
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(&text) {
+        return Some(dt.timestamp());
+    }
+    for format in DATETIME_FORMATS {
+        if let Ok(ndt) = NaiveDateTime::parse_from_str(&text, format) {
+            return Local
+                .from_local_datetime(&ndt)
+                .earliest()
+                .map(|d| d.timestamp());
+        }
+    }
+    for format in DATE_FORMATS {
+        if let Ok(nd) = NaiveDate::parse_from_str(&text, format) {
+            let ndt = nd.and_hms_opt(0, 0, 0)?;
+            return Local
+                .from_local_datetime(&ndt)
+                .earliest()
+                .map(|d| d.timestamp());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    const RELEASE_NOTES: &str = r#"<html><body><h1>Release notes</h1>
+        <div class="release"><h2>Version 2.1</h2><time datetime="2024-03-05T10:00:00Z">March 5</time>
+          <a href="/notes/2.1">details</a><div class="text"><p>Bug fixes</p></div></div>
+        <div class="release"><h2> Version
+            2.0 </h2><span class="date">2024-01-15</span>
+          <a href="https://example.org/notes/2.0">details</a><div class="text"><p>New UI</p></div></div>
+        <div class="release"><span>no title here</span></div>
+        </body></html>"#;
+
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors {
+            container: "div.release".to_string(),
+            title: "h2".to_string(),
+            link: String::default(),
+            date: "time, .date".to_string(),
+            body: ".text".to_string(),
+        }
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::scraper::t_::scrape_release_notes  --lib -- --exact --nocapture "
+    #[test]
+    fn scrape_release_notes() {
+        let items =
+            scrape_items(RELEASE_NOTES, &selectors(), "https://example.org/releases/").unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Version 2.1");
+        assert_eq!(items[0].link, "https://example.org/notes/2.1");
+        assert_eq!(items[0].date, 1709632800);
+        assert_eq!(items[0].body, "<p>Bug fixes</p>");
+        assert_eq!(items[1].title, "Version 2.0");
+        assert_eq!(items[1].link, "https://example.org/notes/2.0");
+        assert!(items[1].date > 0);
+    }
+
+    #[test]
+    fn preview_and_title() {
+        let items =
+            scrape_items(RELEASE_NOTES, &selectors(), "https://example.org/releases/").unwrap();
+        let preview = scraper_preview_text(&items);
+        assert_eq!(preview.lines().count(), 2);
+        assert!(preview.lines().next().unwrap().contains("Version 2.1"));
+        assert_eq!(
+            page_title("<html><head><title> Notes\n </title></head></html>"),
+            "Notes"
+        );
+        assert_eq!(page_title(RELEASE_NOTES), "");
+    }
+
+    #[test]
+    fn scrape_invalid_selectors() {
+        assert!(scrape_items(RELEASE_NOTES, &ScraperSelectors::default(), "").is_err());
+    }
+
+    #[test]
+    fn messages_keep_existing_date() {
+        let items = vec![
+            ScrapedItem {
+                title: "A".to_string(),
+                ..Default::default()
+            },
+            ScrapedItem {
+                title: "B".to_string(),
+                link: "https://example.org/b".to_string(),
+                ..Default::default()
+            },
+        ];
+        let existing = |post_id: &str| (post_id == "https://example.org/b").then_some(1000);
+        let msgs = scraped_to_messages(&items, 5, &existing);
+        assert_eq!(msgs.len(), 2);
+        assert!(msgs[0].post_id.starts_with("scraped-"));
+        assert!(msgs[0].entry_src_date > 1000);
+        assert_eq!(msgs[1].entry_src_date, 1000);
+        assert_eq!(msgs[1].subscription_id, 5);
+    }
+
+    #[test]
+    fn selectors_json_roundtrip() {
+        let s = selectors();
+        assert_eq!(ScraperSelectors::from_json(&s.to_json()), Some(s));
+        assert!(ScraperSelectors::from_json("").is_none());
+    }
+
+    #[test]
+    fn scraped_dates() {
+        assert_eq!(parse_scraped_date("2024-03-05T10:00:00Z"), Some(1709632800));
+        assert!(parse_scraped_date("5.3.2024").is_some());
+        assert!(parse_scraped_date("March 5, 2024").is_some());
+        assert!(parse_scraped_date("yesterday").is_none());
+    }
+}
@@ -0,0 +1,301 @@
+//! Feed addresses of popular platforms,  whose pages often do not announce them.
+//! The page url is mostly sufficient,  some resolvers look into the page content.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use url::Url;
+
+/// page url, page content  =>  feed url
+pub type ResolveFn = fn(&Url, &str) -> Option<String>;
+
+/// name, resolver.  The first one that gives a feed url wins.
+pub const SITE_RESOLVERS: [(&str, ResolveFn); 6] = [
+    ("youtube", resolve_youtube),
+    ("github", resolve_github),
+    ("gitlab", resolve_gitlab),
+    ("reddit", resolve_reddit),
+    ("stackexchange", resolve_stackexchange),
+    ("mastodon", resolve_mastodon),
+];
+
+const STACKEXCHANGE_HOSTS: [&str; 5] = [
+    "stackoverflow.com",
+    "superuser.com",
+    "serverfault.com",
+    "askubuntu.com",
+    "mathoverflow.net",
+];
+
+/// in order of reliability,  compiled once
+static YOUTUBE_CHANNEL_PATTERNS: Lazy<[Regex; 4]> = Lazy::new(|| {
+    [
+        r#"itemprop="channelId" content="(UC[0-9A-Za-z_-]{22})""#,
+        r#""externalId":"(UC[0-9A-Za-z_-]{22})""#,
+        r#"rel="canonical" href="https://www.youtube.com/channel/(UC[0-9A-Za-z_-]{22})""#,
+        r#""channelId":"(UC[0-9A-Za-z_-]{22})""#,
+    ]
+    .map(|p| Regex::new(p).unwrap())
+});
+
+static META_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<meta\s[^>]*>").unwrap());
+
+/// first path parts on github that are no user or organisation
+const GITHUB_RESERVED: [&str; 11] = [
+    "about",
+    "explore",
+    "login",
+    "marketplace",
+    "notifications",
+    "orgs",
+    "search",
+    "settings",
+    "sponsors",
+    "topics",
+    "trending",
+];
+
+/// Asks the resolvers in table order.  Returns the resolver name and the feed url.
+pub fn resolve_site_feed(page_url: &str, page_content: &str) -> Option<(String, String)> {
+    let url = Url::parse(page_url).ok()?;
+    url.host_str()?;
+    SITE_RESOLVERS.iter().find_map(|(name, resolve)| {
+        resolve(&url, page_content).map(|feed_url| (name.to_string(), feed_url))
+    })
+}
+
+fn path_parts(url: &Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|segs| segs.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// lower case host without the  www.  prefix
+fn plain_host(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    host.strip_prefix("www.").unwrap_or(&host).to_string()
+}
+
+fn query_value(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, v)| k == key && !v.is_empty())
+        .map(|(_k, v)| v.into_owned())
+}
+
+fn resolve_youtube(url: &Url, page_content: &str) -> Option<String> {
+    let host = plain_host(url);
+    if host != "youtube.com" && host != "m.youtube.com" {
+        return None;
+    }
+    const FEED_BASE: &str = "https://www.youtube.com/feeds/videos.xml";
+    let parts = path_parts(url);
+    match parts.as_slice() {
+        ["channel", channel_id, ..] => {
+            return Some(format!("{FEED_BASE}?channel_id={channel_id}"));
+        }
+        ["playlist"] => {
+            if let Some(list) = query_value(url, "list") {
+                return Some(format!("{FEED_BASE}?playlist_id={list}"));
+            }
+        }
+        ["user", user, ..] => {
+            return Some(format!("{FEED_BASE}?user={user}"));
+        }
+        _ => (),
+    }
+    // handles,  custom names and videos carry the channel id only inside the page.
+    // The page owner comes before the channels of recommended videos.
+    YOUTUBE_CHANNEL_PATTERNS.iter().find_map(|re| {
+        re.captures(page_content)
+            .map(|caps| format!("{FEED_BASE}?channel_id={}", &caps[1]))
+    })
+}
+
+fn resolve_github(url: &Url, _page_content: &str) -> Option<String> {
+    if plain_host(url) != "github.com" {
+        return None;
+    }
+    let parts = path_parts(url);
+    match parts.as_slice() {
+        [owner] if !GITHUB_RESERVED.contains(owner) => {
+            Some(format!("https://github.com/{owner}.atom"))
+        }
+        [owner, ..] if GITHUB_RESERVED.contains(owner) => None,
+        [owner, repo, "commits", branch, ..] => Some(format!(
+            "https://github.com/{owner}/{repo}/commits/{branch}.atom"
+        )),
+        [owner, repo, "tags", ..] => Some(format!("https://github.com/{owner}/{repo}/tags.atom")),
+        [owner, repo, ..] => Some(format!("https://github.com/{owner}/{repo}/releases.atom")),
+        _ => None,
+    }
+}
+
+/// gitlab.com and self hosted instances with  gitlab  in the host name
+fn resolve_gitlab(url: &Url, _page_content: &str) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    if !host.split('.').any(|p| p == "gitlab") {
+        return None;
+    }
+    // the project or group path ends before the  /-/  separator
+    let path = url.path().split("/-/").next().unwrap_or_default();
+    let path = path.trim_matches('/');
+    if path.is_empty() || path.starts_with("explore") || path.starts_with("users/sign_in") {
+        return None;
+    }
+    Some(format!("{}://{}/{}.atom", url.scheme(), host, path))
+}
+
+fn resolve_reddit(url: &Url, _page_content: &str) -> Option<String> {
+    let host = plain_host(url);
+    if host != "reddit.com" && !host.ends_with(".reddit.com") {
+        return None;
+    }
+    let parts = path_parts(url);
+    match parts.as_slice() {
+        ["r", sub, "comments", post_id, ..] => Some(format!(
+            "https://www.reddit.com/r/{sub}/comments/{post_id}/.rss"
+        )),
+        ["r", sub, ..] => Some(format!("https://www.reddit.com/r/{sub}/.rss")),
+        ["user" | "u", user, ..] => Some(format!("https://www.reddit.com/user/{user}/.rss")),
+        _ => None,
+    }
+}
+
+fn resolve_stackexchange(url: &Url, _page_content: &str) -> Option<String> {
+    let host = url.host_str()?.to_lowercase();
+    let plain = plain_host(url);
+    if !host.ends_with(".stackexchange.com") && !STACKEXCHANGE_HOSTS.contains(&plain.as_str()) {
+        return None;
+    }
+    let base = format!("https://{host}/feeds");
+    let parts = path_parts(url);
+    match parts.as_slice() {
+        ["questions", "tagged", tags, ..] => Some(format!("{base}/tag/{tags}")),
+        ["tags", tag, ..] => Some(format!("{base}/tag/{tag}")),
+        ["questions", id, ..] if id.chars().all(|c| c.is_ascii_digit()) => {
+            Some(format!("{base}/question/{id}"))
+        }
+        ["users", id, ..] if id.chars().all(|c| c.is_ascii_digit()) => {
+            Some(format!("{base}/user/{id}"))
+        }
+        _ => None,
+    }
+}
+
+/// The web app root element,  or the site name / application name meta tag.
+/// Mentioning the word in a post is not sufficient.
+fn is_mastodon_page(page_content: &str) -> bool {
+    let lower = page_content.to_lowercase();
+    if lower.contains(r#"id="mastodon""#) {
+        return true;
+    }
+    META_TAG.find_iter(&lower).any(|m| {
+        let tag = m.as_str();
+        (tag.contains("og:site_name") || tag.contains("application-name"))
+            && tag.contains("mastodon")
+    })
+}
+
+/// Mastodon runs on many hosts,  so the page needs to tell it is one
+fn resolve_mastodon(url: &Url, page_content: &str) -> Option<String> {
+    if !is_mastodon_page(page_content) {
+        return None;
+    }
+    let host = url.host_str()?;
+    let parts = path_parts(url);
+    let account = match parts.as_slice() {
+        [at_user] if at_user.starts_with('@') => at_user.trim_start_matches('@'),
+        ["users", user] => user,
+        _ => return None,
+    };
+    // remote accounts  @user@other.host  have their feed on their own instance
+    match account.split_once('@') {
+        Some((user, other_host)) if !user.is_empty() && !other_host.is_empty() => {
+            Some(format!("https://{other_host}/@{user}.rss"))
+        }
+        Some(_) => None,
+        None if account.is_empty() => None,
+        None => Some(format!("{}://{}/@{}.rss", url.scheme(), host, account)),
+    }
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+
+    // cargo test  --lib downloader::siteresolver::t_::url_only_resolvers
+    #[test]
+    fn url_only_resolvers() {
+        let expected = [
+            (
+                "https://www.youtube.com/channel/UCK8sQmJBp8GCxrOtXWBpyEA/videos",
+                "https://www.youtube.com/feeds/videos.xml?channel_id=UCK8sQmJBp8GCxrOtXWBpyEA",
+            ),
+            (
+                "https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+                "https://www.youtube.com/feeds/videos.xml?playlist_id=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+            ),
+            (
+                "https://github.com/rust-lang/rust",
+                "https://github.com/rust-lang/rust/releases.atom",
+            ),
+            (
+                "https://github.com/rust-lang/rust/commits/master",
+                "https://github.com/rust-lang/rust/commits/master.atom",
+            ),
+            ("https://github.com/rust-lang", "https://github.com/rust-lang.atom"),
+            (
+                "https://gitlab.com/gnome/gtk/-/tree/main",
+                "https://gitlab.com/gnome/gtk.atom",
+            ),
+            (
+                "https://old.reddit.com/r/rust/comments/abc123/some_title/",
+                "https://www.reddit.com/r/rust/comments/abc123/.rss",
+            ),
+            ("https://www.reddit.com/r/rust/", "https://www.reddit.com/r/rust/.rss"),
+            (
+                "https://stackoverflow.com/questions/tagged/rust",
+                "https://stackoverflow.com/feeds/tag/rust",
+            ),
+            (
+                "https://unix.stackexchange.com/questions/457584/gtk3-change-text-color",
+                "https://unix.stackexchange.com/feeds/question/457584",
+            ),
+        ];
+        for (page, feed) in expected {
+            assert_eq!(
+                resolve_site_feed(page, "").map(|(_n, f)| f),
+                Some(feed.to_string())
+            );
+        }
+        assert!(resolve_site_feed("https://github.com/topics/rust", "").is_none());
+        assert!(resolve_site_feed("https://www.youtube.com/@handle", "").is_none());
+        assert!(resolve_site_feed("https://example.com/@someone", "").is_none());
+        assert!(resolve_site_feed("no url", "").is_none());
+    }
+
+    // cargo test  --lib downloader::siteresolver::t_::mastodon_instance_marker
+    #[test]
+    fn mastodon_instance_marker() {
+        let mention = "<p>I moved to Mastodon, follow me there</p>";
+        assert!(resolve_site_feed("https://example.com/@someone", mention).is_none());
+        let app_root = r#"<div id="mastodon" data-props="{}"></div>"#;
+        assert_eq!(
+            resolve_site_feed("https://fosstodon.org/@someone", app_root).map(|(_n, f)| f),
+            Some("https://fosstodon.org/@someone.rss".to_string())
+        );
+        let meta = r#"<meta content="Mastodon" name="application-name">"#;
+        assert_eq!(
+            resolve_site_feed("https://fosstodon.org/@someone@mastodon.social", meta)
+                .map(|(_n, f)| f),
+            Some("https://mastodon.social/@someone.rss".to_string())
+        );
+        let youtube = r#"<meta itemprop="channelId" content="UCK8sQmJBp8GCxrOtXWBpyEA">"#;
+        assert_eq!(
+            resolve_site_feed("https://www.youtube.com/@handle", youtube).map(|(_n, f)| f),
+            Some(
+                "https://www.youtube.com/feeds/videos.xml?channel_id=UCK8sQmJBp8GCxrOtXWBpyEA"
+                    .to_string()
+            )
+        );
+    }
+}
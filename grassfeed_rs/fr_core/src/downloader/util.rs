@@ -1,5 +1,7 @@
 use feed_rs::parser;
 use lz4_compression::prelude;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use std::collections::HashMap;
 use tl::HTMLTag;
 use tl::Node;
@@ -200,9 +202,22 @@ pub fn workaround_https_declaration(wrong: &str) -> String {
     )
 }
 
-/// extract feed url via parser
+/// feed advertised by a web page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FeedCandidate {
+    pub url: String,
+    pub title: String,
+    /// rss or atom,  empty if unknown
+    pub feed_type: String,
+    /// titles of the latest items, one per line
+    pub preview: String,
+}
+
+/// extract all advertised feeds via parser,  comment feeds at the end
 /// if none found, we return    Error message ,  raw-text, comments
-pub fn extract_feed_from_website(page_content: &str) -> Result<String, (String, String)> {
+pub fn extract_feed_from_website(
+    page_content: &str,
+) -> Result<Vec<FeedCandidate>, (String, String)> {
     let mut rawtext: String = String::default();
     let dom: tl::VDom = match tl::parse(page_content, tl::ParserOptions::default()) {
         Ok(d) => d,
@@ -228,7 +243,8 @@ pub fn extract_feed_from_website(page_content: &str) -> Result<String, (String,
         })
         .collect();
 
-    let feeds_list: Vec<String> = link_tags
+    let mut candidates: Vec<FeedCandidate> = Vec::default();
+    link_tags
         .iter()
         .map(|t| {
             let attrmap: HashMap<String, String> = t
@@ -240,21 +256,78 @@ pub fn extract_feed_from_website(page_content: &str) -> Result<String, (String,
             attrmap
         })
         .filter(|attrmap| attrmap.get("rel").is_some())
-        .filter(|attrmap| {
-            if let Some(typ_e) = attrmap.get("type") {
-                typ_e.contains("rss") || typ_e.contains("atom")
+        .filter_map(|attrmap| {
+            let typ_e = attrmap.get("type")?;
+            let feed_type = if typ_e.contains("atom") {
+                "atom"
+            } else if typ_e.contains("rss") {
+                "rss"
             } else {
-                false
-            }
+                return None;
+            };
+            Some(FeedCandidate {
+                url: attrmap.get("href")?.clone(),
+                title: decode_title_entities(attrmap.get("title").map_or("", |t| t.as_str())),
+                feed_type: feed_type.to_string(),
+                preview: String::default(),
+            })
         })
-        .filter(|attrmap| !attrmap.get("href").unwrap().contains("comments"))
-        .filter_map(|attrmap| attrmap.get("href").cloned())
-        .collect();
-    if feeds_list.is_empty() {
+        .for_each(|c| {
+            if !candidates.iter().any(|known| known.url == c.url) {
+                candidates.push(c);
+            }
+        });
+    if candidates.is_empty() {
         return Err(("No feed-url found. ".to_string(), rawtext));
     }
-    let feed_url = feeds_list.first().unwrap().clone();
-    Ok(feed_url)
+    // comment feeds are rarely wanted,  keep them at the end
+    candidates.sort_by_key(|c| c.url.contains("comments") || c.title.contains("Comments Feed"));
+    Ok(candidates)
+}
+
+/// Titles of the first entries of the feed text, one per line.  Empty if it is no feed.
+pub fn feed_preview_titles(feed_text: &str, max_items: usize) -> String {
+    match parser::parse(feed_text.as_bytes()) {
+        Ok(feed) => feed
+            .entries
+            .iter()
+            .filter_map(|e| e.title.as_ref())
+            .map(|t| t.content.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .take(max_items)
+            .collect::<Vec<String>>()
+            .join("\n"),
+        Err(_e) => String::default(),
+    }
+}
+
+static RE_NUMERIC_ENTITY: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"&#(x[0-9a-fA-F]+|[0-9]+);").unwrap());
+
+/// the entities found in link titles,  named and numeric
+fn decode_title_entities(raw: &str) -> String {
+    let named = raw
+        .replace("&raquo;", "\u{bb}")
+        .replace("&laquo;", "\u{ab}")
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"");
+    RE_NUMERIC_ENTITY
+        .replace_all(&named, |caps: &regex::Captures| {
+            let code = &caps[1];
+            let o_value = match code.strip_prefix('x') {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => code.parse::<u32>().ok(),
+            };
+            o_value
+                .and_then(char::from_u32)
+                .map(|c| c.to_string())
+                .unwrap_or_default()
+        })
+        .replace("&amp;", "&")
+        .trim()
+        .to_string()
 }
 
 // extracts only the domain part of the site, no trailing slash
@@ -271,3 +344,72 @@ pub fn go_to_homepage(long_url: &str) -> Option<String> {
 
 //
 // using   html_parser::Dom;   for extract_icon_from_homepage() creates a stack overflow
+
+static RE_URL_ATTR: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)\b(src|href|poster)(\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+/// Rewrites relative  src, href  and  poster  attributes to absolute urls.
+/// Absolute urls, anchors and urls with a scheme stay as they are.
+pub fn resolve_relative_urls(html: &str, base: &str) -> String {
+    let base_url = match Url::parse(base) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => u,
+        _ => return html.to_string(),
+    };
+    RE_URL_ATTR
+        .replace_all(html, |caps: &regex::Captures| {
+            let (value, quote) = match caps.get(3) {
+                Some(v) => (v.as_str(), '"'),
+                None => (caps.get(4).map(|v| v.as_str()).unwrap_or_default(), '\''),
+            };
+            match resolve_relative_url(value, &base_url) {
+                Some(absolute) => format!("{}{}{quote}{absolute}{quote}", &caps[1], &caps[2]),
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+/// Relative urls are joined to the base, everything else is returned unchanged
+pub fn resolve_url(value: &str, base: &str) -> String {
+    match Url::parse(base) {
+        Ok(base_url) => resolve_relative_url(value, &base_url).unwrap_or(value.to_string()),
+        Err(_) => value.to_string(),
+    }
+}
+
+/// None if the url is absolute or does not need resolving
+fn resolve_relative_url(value: &str, base_url: &Url) -> Option<String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    match Url::parse(trimmed) {
+        Err(url::ParseError::RelativeUrlWithoutBase) => {
+            base_url.join(trimmed).ok().map(|u| u.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// The first absolute http url of the candidates, relative ones are resolved against the ones before
+pub fn resolve_base_url(candidates: &[&str]) -> Option<String> {
+    let mut base: Option<Url> = None;
+    for cand in candidates.iter().rev().map(|c| c.trim()) {
+        if cand.is_empty() {
+            continue;
+        }
+        let o_url = match Url::parse(cand) {
+            Ok(u) => Some(u),
+            Err(url::ParseError::RelativeUrlWithoutBase) => {
+                base.as_ref().and_then(|b| b.join(cand).ok())
+            }
+            Err(_) => None,
+        };
+        if let Some(u) = o_url {
+            if u.scheme() == "http" || u.scheme() == "https" {
+                base = Some(u);
+            }
+        }
+    }
+    base.map(|u| u.to_string())
+}
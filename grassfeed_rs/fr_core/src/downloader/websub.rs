@@ -0,0 +1,596 @@
+//! WebSub (PubSubHubbub) push subscriptions.
+//!
+//! Feeds announcing a hub get subscribed there, with a callback address of the embedded
+//! listener. The hub verifies the intent with a GET request, later it posts the changed feed
+//! content. The regular polling stays active, it catches up whatever the hub did not deliver.
+
+use crate::db::errorentry::ESRC;
+use crate::db::errors_repo::ErrorRepo;
+use crate::db::subscription_repo::ISubscriptionRepo;
+use crate::db::subscription_repo::SubscriptionRepo;
+use crate::util::timestamp_now;
+use crate::util::Step;
+use crate::util::StepResult;
+use crate::web::WebFetcherType;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hash::Hasher;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const WEBSUB_PATH: &str = "/websub/";
+/// we ask the hub for that duration, the hub may grant a different one
+pub const WEBSUB_LEASE_REQUEST_S: i64 = 10 * 86400;
+/// longer leases granted by the hub are shortened to this
+pub const WEBSUB_LEASE_MAX_S: i64 = 30 * 86400;
+/// renew the push subscription that long before the lease ends
+pub const WEBSUB_RENEW_MARGIN_S: i64 = 3600;
+/// after a subscription request, wait that long for the verification before asking again
+pub const WEBSUB_RETRY_S: i64 = 6 * 3600;
+const LISTENER_LOOP_MS: u64 = 100;
+const REQUEST_READ_TIMEOUT_S: u64 = 10;
+const REQUEST_MAX_BYTES: usize = 4_000_000;
+
+/// Hub and self link of a feed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WebSubLinks {
+    pub hub: String,
+    pub topic: String,
+}
+
+/// the self link is the topic, if the feed has none the feed url is taken
+pub fn feed_websub_links(feed: &feed_rs::model::Feed, feed_url: &str) -> Option<WebSubLinks> {
+    let rel_href = |rel: &str| {
+        feed.links
+            .iter()
+            .find(|l| l.rel.as_deref() == Some(rel))
+            .map(|l| l.href.trim().to_string())
+            .filter(|h| !h.is_empty())
+    };
+    let hub = rel_href("hub")?;
+    Some(WebSubLinks {
+        hub,
+        topic: rel_href("self").unwrap_or_else(|| feed_url.to_string()),
+    })
+}
+
+/// base: the address the hub can reach the listener at,  empty for  localhost
+pub fn callback_url(base: &str, port: u16, subs_id: isize, secret: &str) -> String {
+    let base = base.trim().trim_end_matches('/');
+    if base.is_empty() {
+        format!("http://localhost:{port}{WEBSUB_PATH}{subs_id}/{secret}")
+    } else {
+        format!("{base}{WEBSUB_PATH}{subs_id}/{secret}")
+    }
+}
+
+/// random enough to keep strangers from posting into our subscriptions
+pub fn new_secret() -> String {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_i64(timestamp_now());
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+pub struct WebSubInner {
+    pub subs_id: isize,
+    pub hub: String,
+    pub topic: String,
+    pub secret: String,
+    pub callback_base: String,
+    pub port: u16,
+    pub web_fetcher: WebFetcherType,
+    pub subscriptionrepo: SubscriptionRepo,
+    pub erro_repo: ErrorRepo,
+}
+
+impl std::fmt::Debug for WebSubInner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("")
+            .field("subs_id", &self.subs_id)
+            .field("hub", &self.hub)
+            .field("topic", &self.topic)
+            .finish()
+    }
+}
+
+impl PartialEq for WebSubInner {
+    fn eq(&self, other: &Self) -> bool {
+        self.subs_id == other.subs_id
+    }
+}
+
+pub struct WebSubStart(WebSubInner);
+impl WebSubStart {
+    pub fn new(i: WebSubInner) -> Self {
+        WebSubStart(i)
+    }
+}
+
+/// The secret is stored before the request, since the hub may verify before it answers.
+/// Until the verification arrives, the provisional lease prevents repeated requests.
+impl Step<WebSubInner> for WebSubStart {
+    fn step(self: Box<Self>) -> StepResult<WebSubInner> {
+        let mut inner = self.0;
+        if inner.secret.is_empty() {
+            inner.secret = new_secret();
+        }
+        inner.subscriptionrepo.update_websub_lease(
+            inner.subs_id,
+            inner.secret.clone(),
+            timestamp_now() + WEBSUB_RETRY_S,
+        );
+        StepResult::Continue(Box::new(WebSubRequest(inner)))
+    }
+}
+
+struct WebSubRequest(WebSubInner);
+impl Step<WebSubInner> for WebSubRequest {
+    fn step(self: Box<Self>) -> StepResult<WebSubInner> {
+        let inner = self.0;
+        let callback = callback_url(
+            &inner.callback_base,
+            inner.port,
+            inner.subs_id,
+            &inner.secret,
+        );
+        let lease = WEBSUB_LEASE_REQUEST_S.to_string();
+        let form = [
+            ("hub.mode", "subscribe"),
+            ("hub.topic", inner.topic.as_str()),
+            ("hub.callback", callback.as_str()),
+            ("hub.lease_seconds", lease.as_str()),
+        ];
+        let r = (*inner.web_fetcher).post_form(&inner.hub, &form);
+        if !(200..300).contains(&r.http_status) {
+            inner.erro_repo.add_error(
+                inner.subs_id,
+                ESRC::WebSub,
+                r.get_combined_error(),
+                inner.hub.clone(),
+                format!("subscribe {} : {}", inner.topic, r.error_description),
+            );
+        }
+        StepResult::Stop(inner)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallbackRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub body: String,
+}
+
+/// first line, headers, body by content length
+pub fn read_request<R: BufRead>(reader: &mut R) -> Result<CallbackRequest, String> {
+    let mut line = String::default();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("request line: {e:?}"))?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+    if method.is_empty() || target.is_empty() {
+        return Err(format!("bad request line {line:?}"));
+    }
+    let mut content_length: usize = 0;
+    loop {
+        let mut header = String::default();
+        let num = reader
+            .read_line(&mut header)
+            .map_err(|e| format!("header: {e:?}"))?;
+        if num == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().unwrap_or(0);
+            }
+        }
+    }
+    if content_length > REQUEST_MAX_BYTES {
+        return Err(format!("request too large: {content_length}"));
+    }
+    let mut body_bytes = vec![0; content_length];
+    reader
+        .read_exact(&mut body_bytes)
+        .map_err(|e| format!("body: {e:?}"))?;
+    let (path, query_str) = target.split_once('?').unwrap_or((&target, ""));
+    let query = url::form_urlencoded::parse(query_str.as_bytes())
+        .into_owned()
+        .collect::<HashMap<String, String>>();
+    Ok(CallbackRequest {
+        method: method.to_uppercase(),
+        path: path.to_string(),
+        query,
+        body: String::from_utf8_lossy(&body_bytes).to_string(),
+    })
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CallbackResponse {
+    pub status: u16,
+    pub body: String,
+    /// subscription id and the pushed feed content
+    pub push: Option<(isize, String)>,
+}
+
+impl CallbackResponse {
+    fn status(status: u16) -> Self {
+        CallbackResponse {
+            status,
+            ..Default::default()
+        }
+    }
+}
+
+/// Verification of intent (GET) or content distribution (POST)
+pub fn handle_callback(
+    req: &CallbackRequest,
+    subs_repo: &dyn ISubscriptionRepo,
+    now: i64,
+) -> CallbackResponse {
+    let o_ids = req
+        .path
+        .strip_prefix(WEBSUB_PATH)
+        .and_then(|rest| rest.split_once('/'))
+        .and_then(|(id_s, secret)| id_s.parse::<isize>().ok().map(|id| (id, secret)));
+    let (subs_id, secret) = match o_ids {
+        Some(ids) => ids,
+        None => return CallbackResponse::status(404),
+    };
+    let subs = match subs_repo.get_by_index(subs_id) {
+        Some(se) if !se.websub_secret.is_empty() && se.websub_secret == secret => se,
+        _ => return CallbackResponse::status(404),
+    };
+    let wanted =
+        !subs.websub_hub.is_empty() && !subs.isdeleted() && !subs_repo.is_paused_in_tree(subs_id);
+    match req.method.as_str() {
+        "GET" => {
+            let param = |name: &str| req.query.get(name).cloned().unwrap_or_default();
+            let challenge = param("hub.challenge");
+            match param("hub.mode").as_str() {
+                "subscribe" if wanted && param("hub.topic") == subs.websub_topic => {
+                    let lease_s = param("hub.lease_seconds")
+                        .parse::<i64>()
+                        .unwrap_or(WEBSUB_LEASE_REQUEST_S)
+                        .clamp(0, WEBSUB_LEASE_MAX_S);
+                    subs_repo.update_websub_lease(
+                        subs_id,
+                        subs.websub_secret,
+                        now.saturating_add(lease_s),
+                    );
+                    CallbackResponse {
+                        status: 200,
+                        body: challenge,
+                        push: None,
+                    }
+                }
+                "unsubscribe" if !wanted => CallbackResponse {
+                    status: 200,
+                    body: challenge,
+                    push: None,
+                },
+                "denied" => {
+                    debug!("websub denied {} : {:?}", subs_id, param("hub.reason"));
+                    subs_repo.update_websub_lease(
+                        subs_id,
+                        subs.websub_secret,
+                        now + WEBSUB_RETRY_S,
+                    );
+                    CallbackResponse::status(200)
+                }
+                _ => CallbackResponse::status(404),
+            }
+        }
+        "POST" if wanted => CallbackResponse {
+            status: 202,
+            body: String::default(),
+            push: Some((subs_id, req.body.clone())),
+        },
+        "POST" => CallbackResponse::status(410),
+        _ => CallbackResponse::status(405),
+    }
+}
+
+pub fn write_response<W: Write>(stream: &mut W, resp: &CallbackResponse) -> std::io::Result<()> {
+    let reason = match resp.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        410 => "Gone",
+        _ => "",
+    };
+    let text = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        resp.status,
+        reason,
+        resp.body.len(),
+        resp.body
+    );
+    stream.write_all(text.as_bytes())?;
+    stream.flush()
+}
+
+/// receives the feed content the hub pushed:  subscription id, content
+pub type PushReceiver = Box<dyn Fn(isize, String) + Send + Sync>;
+
+/// Embedded HTTP server for the hub callbacks, runs in its own thread.
+/// Each connection is handled in a thread of its own,  a slow client does not block the others.
+pub struct WebSubListener {
+    keeprunning: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<()>>,
+    port: u16,
+}
+
+impl WebSubListener {
+    /// port 0 takes any free port, see  port()
+    pub fn start(
+        port: u16,
+        subs_repo: SubscriptionRepo,
+        on_push: PushReceiver,
+    ) -> Result<WebSubListener, String> {
+        let listener = TcpListener::bind(("0.0.0.0", port))
+            .map_err(|e| format!("websub bind port {port} : {e:?}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("websub nonblocking: {e:?}"))?;
+        let bound_port = listener.local_addr().map(|a| a.port()).unwrap_or(port);
+        let keeprunning = Arc::new(AtomicBool::new(true));
+        let keeprunning_c = keeprunning.clone();
+        let on_push: Arc<PushReceiver> = Arc::new(on_push);
+        let handle = thread::Builder::new()
+            .name("websub".to_string())
+            .spawn(move || {
+                while keeprunning_c.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _addr)) => {
+                            let client_repo = SubscriptionRepo::by_existing_connection(
+                                subs_repo.get_connection(),
+                            );
+                            let on_push_c = on_push.clone();
+                            let r = thread::Builder::new()
+                                .name("websub_client".to_string())
+                                .spawn(move || {
+                                    if let Err(e) =
+                                        Self::handle_client(stream, &client_repo, &on_push_c)
+                                    {
+                                        debug!("websub client: {}", e);
+                                    }
+                                });
+                            if let Err(e) = r {
+                                warn!("websub client thread: {:?}", e);
+                            }
+                        }
+                        Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                            thread::sleep(Duration::from_millis(LISTENER_LOOP_MS));
+                        }
+                        Err(e) => {
+                            warn!("websub accept: {:?}", e);
+                            thread::sleep(Duration::from_millis(LISTENER_LOOP_MS));
+                        }
+                    }
+                }
+            })
+            .map_err(|e| format!("websub thread: {e:?}"))?;
+        Ok(WebSubListener {
+            keeprunning,
+            handle: Some(handle),
+            port: bound_port,
+        })
+    }
+
+    fn handle_client(
+        stream: TcpStream,
+        subs_repo: &SubscriptionRepo,
+        on_push: &PushReceiver,
+    ) -> Result<(), String> {
+        stream
+            .set_nonblocking(false)
+            .and_then(|_| {
+                stream.set_read_timeout(Some(Duration::from_secs(REQUEST_READ_TIMEOUT_S)))
+            })
+            .map_err(|e| format!("{e:?}"))?;
+        let mut reader = BufReader::new(&stream);
+        let resp = match read_request(&mut reader) {
+            Ok(req) => handle_callback(&req, subs_repo, timestamp_now()),
+            Err(e) => {
+                debug!("websub request: {}", e);
+                CallbackResponse::status(400)
+            }
+        };
+        let mut writer = &stream;
+        write_response(&mut writer, &resp).map_err(|e| format!("{e:?}"))?;
+        if let Some((subs_id, content)) = resp.push {
+            on_push(subs_id, content);
+        }
+        Ok(())
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    pub fn stop(&mut self) {
+        self.keeprunning.store(false, Ordering::Relaxed);
+        if let Some(h) = self.handle.take() {
+            if h.join().is_err() {
+                warn!("could not join websub thread");
+            }
+        }
+    }
+}
+
+impl Drop for WebSubListener {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod t_ {
+    use super::*;
+    use crate::db::subscription_entry::SubscriptionEntry;
+    use std::io::Read;
+    use std::sync::Mutex;
+
+    const ATOM_WITH_HUB: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom"><title>t</title><id>urn:x</id>
+        <updated>2024-01-01T00:00:00Z</updated>
+        <link rel="hub" href="https://hub.example.org/" />
+        <link rel="self" href="https://example.org/feed.xml" />
+        </feed>"#;
+
+    fn prepare_repo() -> SubscriptionRepo {
+        let mut sr = SubscriptionRepo::new_inmem();
+        sr.startup_int();
+        let _r = sr.store_entry(&SubscriptionEntry::default());
+        sr.update_websub_hub(
+            10,
+            "https://hub.example.org/".to_string(),
+            "https://example.org/feed.xml".to_string(),
+        );
+        sr.update_websub_lease(10, "abc".to_string(), 5);
+        sr
+    }
+
+    fn request(method: &str, target: &str, body: &str) -> CallbackRequest {
+        let text = format!(
+            "{method} {target} HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        read_request(&mut BufReader::new(text.as_bytes())).unwrap()
+    }
+
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  downloader::websub::t_::hub_links  --lib -- --exact --nocapture "
+    #[test]
+    fn hub_links() {
+        let feed = feed_rs::parser::parse(ATOM_WITH_HUB.as_bytes()).unwrap();
+        let links = feed_websub_links(&feed, "https://example.org/other").unwrap();
+        assert_eq!(links.hub.as_str(), "https://hub.example.org/");
+        assert_eq!(links.topic.as_str(), "https://example.org/feed.xml");
+    }
+
+    #[test]
+    fn parse_request() {
+        let req = request(
+            "GET",
+            "/websub/10/abc?hub.mode=subscribe&hub.topic=https%3A%2F%2Fexample.org%2Ffeed.xml",
+            "",
+        );
+        assert_eq!(req.path.as_str(), "/websub/10/abc");
+        assert_eq!(
+            req.query.get("hub.topic").unwrap().as_str(),
+            "https://example.org/feed.xml"
+        );
+        let req = request("POST", "/websub/10/abc", "<feed/>");
+        assert_eq!(req.body.as_str(), "<feed/>");
+    }
+
+    #[test]
+    fn verify_intent() {
+        let sr = prepare_repo();
+        let target = "/websub/10/abc?hub.mode=subscribe&hub.topic=https%3A%2F%2Fexample.org%2Ffeed.xml&hub.challenge=ch1&hub.lease_seconds=100";
+        let resp = handle_callback(&request("GET", target, ""), &sr, 1000);
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body.as_str(), "ch1");
+        assert_eq!(sr.get_by_index(10).unwrap().websub_lease, 1100);
+        let endless = target.replace("=100", "=9223372036854775807");
+        let resp = handle_callback(&request("GET", &endless, ""), &sr, 1000);
+        assert_eq!(resp.status, 200);
+        assert_eq!(
+            sr.get_by_index(10).unwrap().websub_lease,
+            1000 + WEBSUB_LEASE_MAX_S
+        );
+        let wrong_secret = target.replace("/abc?", "/xyz?");
+        let resp = handle_callback(&request("GET", &wrong_secret, ""), &sr, 1000);
+        assert_eq!(resp.status, 404);
+        let unsub = target.replace("=subscribe", "=unsubscribe");
+        let resp = handle_callback(&request("GET", &unsub, ""), &sr, 1000);
+        assert_eq!(resp.status, 404);
+    }
+
+    #[test]
+    fn content_push() {
+        let sr = prepare_repo();
+        let resp = handle_callback(&request("POST", "/websub/10/abc", "<feed/>"), &sr, 1000);
+        assert_eq!(resp.status, 202);
+        assert_eq!(resp.push, Some((10, "<feed/>".to_string())));
+        let resp = handle_callback(&request("POST", "/websub/11/abc", "<feed/>"), &sr, 1000);
+        assert_eq!(resp.status, 404);
+        sr.update_websub_hub(10, String::default(), String::default());
+        let resp = handle_callback(&request("POST", "/websub/10/abc", "<feed/>"), &sr, 1000);
+        assert_eq!(resp.status, 410);
+    }
+
+    #[test]
+    fn paused_not_wanted() {
+        let sr = prepare_repo();
+        sr.update_paused(10, true);
+        let resp = handle_callback(&request("POST", "/websub/10/abc", "<feed/>"), &sr, 1000);
+        assert_eq!(resp.status, 410);
+        assert!(resp.push.is_none());
+        let target = "/websub/10/abc?hub.mode=subscribe&hub.topic=https%3A%2F%2Fexample.org%2Ffeed.xml&hub.challenge=ch1";
+        let resp = handle_callback(&request("GET", target, ""), &sr, 1000);
+        assert_eq!(resp.status, 404);
+        assert_eq!(sr.get_by_index(10).unwrap().websub_lease, 5);
+        let unsub = target.replace("=subscribe", "=unsubscribe");
+        let resp = handle_callback(&request("GET", &unsub, ""), &sr, 1000);
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body.as_str(), "ch1");
+    }
+
+    #[test]
+    fn listener_receives_push() {
+        let sr = prepare_repo();
+        let received: Arc<Mutex<Vec<(isize, String)>>> = Arc::new(Mutex::new(Vec::default()));
+        let received_c = received.clone();
+        let mut listener = WebSubListener::start(
+            0,
+            SubscriptionRepo::by_existing_connection(sr.get_connection()),
+            Box::new(move |id, content| received_c.lock().unwrap().push((id, content))),
+        )
+        .unwrap();
+        // an idle client does not hold up the others
+        let _idle = TcpStream::connect(("127.0.0.1", listener.port())).unwrap();
+        thread::sleep(Duration::from_millis(LISTENER_LOOP_MS * 2));
+        let mut stream = TcpStream::connect(("127.0.0.1", listener.port())).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(REQUEST_READ_TIMEOUT_S / 2)))
+            .unwrap();
+        stream
+            .write_all(b"POST /websub/10/abc HTTP/1.1\r\nContent-Length: 7\r\n\r\n<feed/>")
+            .unwrap();
+        let mut answer = String::default();
+        stream.read_to_string(&mut answer).unwrap();
+        assert!(answer.starts_with("HTTP/1.1 202"));
+        listener.stop();
+        assert_eq!(*received.lock().unwrap(), vec![(10, "<feed/>".to_string())]);
+    }
+
+    #[test]
+    fn callback_address() {
+        assert_eq!(
+            callback_url("https://me.example.org/", 8090, 3, "s"),
+            "https://me.example.org/websub/3/s"
+        );
+        assert_eq!(
+            callback_url("", 8090, 3, "s"),
+            "http://localhost:8090/websub/3/s"
+        );
+        assert_ne!(new_secret(), new_secret());
+    }
+}
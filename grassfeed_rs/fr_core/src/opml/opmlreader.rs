@@ -1,6 +1,8 @@
 use crate::db::subscription_entry::SubscriptionEntry;
+use crate::db::subscription_entry::SOURCE_KIND_FEED;
 use crate::db::subscription_repo::ISubscriptionRepo;
 use crate::db::subscription_repo::SubscriptionRepo;
+use crate::downloader::linkcleaner::normalize_feed_url;
 use crate::util::db_time_to_display;
 use crate::util::remove_invalid_chars_from_input;
 use crate::util::timestamp_now;
@@ -13,6 +15,7 @@ use opml::Outline;
 use opml::OPML;
 use resources::gen_icons;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::ErrorKind;
 use std::io::Write;
@@ -61,39 +64,60 @@ impl OpmlReader {
         Ok(())
     }
 
-    pub fn transfer_to_db(&self, parent_folder_id: isize) {
-        self.root_outline
-            .outlines
-            .iter()
-            .enumerate()
-            .for_each(|(i, o)| {
-                OpmlReader::store_outline(
-                    self.subscription_repo.clone(),
-                    parent_folder_id,
-                    i as isize,
-                    o,
-                );
-            });
+    /// Subscriptions that exist already are not stored again.
+    /// returns the ids of the existing subscriptions for those skipped outlines
+    pub fn transfer_to_db(&self, parent_folder_id: isize) -> Vec<isize> {
+        let mut known = (*self.subscription_repo).borrow().get_feed_url_groups();
+        let mut skipped: Vec<isize> = Vec::default();
+        OpmlReader::store_outlines(
+            self.subscription_repo.clone(),
+            parent_folder_id,
+            &self.root_outline.outlines,
+            &mut known,
+            &mut skipped,
+        );
+        skipped
     }
 
-    /// outline, parent_repo_id, position
-    pub fn store_outline(
+    /// recursive.  known: normalized url -> subscription ids
+    fn store_outlines(
         subscription_repo: Rc<RefCell<dyn ISubscriptionRepo>>,
         parent_subs_id: isize,
-        position: isize,
-        outl: &Outline,
+        outlines: &[Outline],
+        known: &mut HashMap<String, Vec<isize>>,
+        skipped: &mut Vec<isize>,
     ) {
-        let db_entry = from_outline(outl, parent_subs_id, position);
-        let repo_id: isize = match (*subscription_repo).borrow().store_entry(&db_entry) {
-            Ok(r_entry) => r_entry.subs_id,
-            Err(e) => {
-                error!("store_outline {:?}", &e);
-                return;
+        let mut position: isize = 0;
+        for outl in outlines {
+            let db_entry = from_outline(outl, parent_subs_id, position);
+            let normalized = normalize_feed_url(&db_entry.url);
+            let check_known = !db_entry.is_folder && !normalized.is_empty();
+            if check_known {
+                if let Some(existing_id) = known.get(&normalized).and_then(|ids| ids.first()) {
+                    debug!("opml: skipping {}, exists as {}", db_entry.url, existing_id);
+                    skipped.push(*existing_id);
+                    continue;
+                }
             }
-        };
-        outl.outlines.iter().enumerate().for_each(|(folderpos, o)| {
-            OpmlReader::store_outline(subscription_repo.clone(), repo_id, folderpos as isize, o);
-        });
+            let repo_id: isize = match (*subscription_repo).borrow().store_entry(&db_entry) {
+                Ok(r_entry) => r_entry.subs_id,
+                Err(e) => {
+                    error!("store_outline {:?}", &e);
+                    continue;
+                }
+            };
+            if check_known {
+                known.insert(normalized, vec![repo_id]);
+            }
+            position += 1;
+            OpmlReader::store_outlines(
+                subscription_repo.clone(),
+                repo_id,
+                &outl.outlines,
+                known,
+                skipped,
+            );
+        }
     }
 
     pub fn transfer_from_db(&mut self) {
@@ -205,6 +229,21 @@ pub fn from_outline(o: &Outline, repo_parent_id: isize, folder_pos: isize) -> Su
         website_url: websit_url,
         last_selected_msg: -1,
         deleted: false,
+        enclosure_autodl: false,
+        enclosure_keep: 0,
+        full_article: false,
+        source_kind: SOURCE_KIND_FEED,
+        source_params: String::default(),
+        websub_hub: String::default(),
+        websub_topic: String::default(),
+        websub_secret: String::default(),
+        websub_lease: 0,
+        remote_content: false,
+        trash_parent_id: 0,
+        trash_position: 0,
+        trashed_at: 0,
+        paused: false,
+        keep_sorted: 0,
     }
 }
 
@@ -290,6 +329,26 @@ mod t_ {
         assert_eq!(e2.is_folder, false);
     }
 
+    //RUST_BACKTRACE=1 cargo watch -s "cargo test  opml::opmlreader::t_::opml_import_twice_skips_known  --lib -- --exact --nocapture"
+    #[test]
+    fn opml_import_twice_skips_known() {
+        let fsr = SubscriptionRepo::new_inmem();
+        fsr.scrub_all_subscriptions();
+        let fsrr = Rc::new(RefCell::new(fsr));
+        let mut opmlreader = OpmlReader::new(fsrr.clone());
+        let r = opmlreader.read_from_file(String::from("tests/data/simple_local.opml"));
+        assert!(r.is_ok());
+        assert!(opmlreader.transfer_to_db(0).is_empty());
+        let feeds_before = (*fsrr).borrow().get_all_nonfolder();
+        assert_eq!(feeds_before.len(), 3);
+        let skipped = opmlreader.transfer_to_db(0);
+        assert_eq!(skipped.len(), 3);
+        assert!(skipped
+            .iter()
+            .all(|id| feeds_before.iter().any(|fse| fse.subs_id == *id)));
+        assert_eq!((*fsrr).borrow().get_all_nonfolder().len(), 3);
+    }
+
     //cargo watch -s "cargo test  opml::opmlreader::t_::opml_write  --lib -- --exact --nocapture"
     // #[ignore]
     #[test]
@@ -3,6 +3,8 @@ use chrono::Local;
 use chrono::TimeZone;
 use chrono::Utc;
 use image::ImageFormat;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use resvg::tiny_skia::PixmapMut;
 use std::io::Cursor;
 use std::io::Read;
@@ -189,6 +191,78 @@ pub fn string_truncate(mut input: String, length: usize) -> String {
     input
 }
 
+/// Limit for the word diff, the LCS table grows quadratic
+const DIFF_MAX_WORDS: usize = 1500;
+
+static RE_HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// removes all html tags, collapses whitespace
+pub fn html_to_plain_words(html: &str) -> Vec<String> {
+    let plain = RE_HTML_TAG.replace_all(html, " ");
+    plain
+        .split_whitespace()
+        .take(DIFF_MAX_WORDS)
+        .map(|w| w.to_string())
+        .collect::<Vec<String>>()
+}
+
+/// One step through two compared sequences:  index into the old one, or into the new one for Added
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStep {
+    Same(usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// By longest common subsequence. Within a changed block the additions come first.
+pub fn sequence_diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffStep> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs: Vec<Vec<u16>> = vec![vec![0; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                std::cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+    let mut steps: Vec<DiffStep> = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            steps.push(DiffStep::Same(i));
+            i += 1;
+            j += 1;
+        } else if j < m && (i >= n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            steps.push(DiffStep::Added(j));
+            j += 1;
+        } else {
+            steps.push(DiffStep::Removed(i));
+            i += 1;
+        }
+    }
+    steps
+}
+
+/// Word based difference of two html texts, the tags are dropped.
+/// Removed words are  within  <del>, added words within <ins>
+pub fn text_diff_html(old_html: &str, new_html: &str) -> String {
+    let old_w = html_to_plain_words(old_html);
+    let new_w = html_to_plain_words(new_html);
+    sequence_diff(&old_w, &new_w)
+        .into_iter()
+        .map(|step| match step {
+            DiffStep::Same(i) => string_escape_url(old_w[i].clone()),
+            DiffStep::Added(j) => format!("<ins>{}</ins>", string_escape_url(new_w[j].clone())),
+            DiffStep::Removed(i) => {
+                format!("<del>{}</del>", string_escape_url(old_w[i].clone()))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 /// Retrieves a Url into a Binary.   Uses maxsize for maximum stored bytes.
 ///  returns the fetched buffer, the fetched size
 pub fn fetch_http_to_bin(url: String, maxsize: usize) -> (Vec<u8>, usize) {
@@ -380,6 +454,16 @@ mod t {
         );
     }
 
+    #[test]
+    fn text_diff_words() {
+        assert_eq!(
+            text_diff_html("<p>fixed in 1.2</p>", "<p>fixed in 1.3 and 1.2</p>"),
+            "fixed in <ins>1.3</ins> <ins>and</ins> 1.2"
+        );
+        assert_eq!(text_diff_html("a b", "a"), "a <del>b</del>");
+        assert_eq!(text_diff_html("x<y", "x<y"), "x&lt;y");
+    }
+
     #[test]
     fn string_truncate_reg() {
         let jap = String::from("Japan 無料ダウンロード");
@@ -21,10 +21,14 @@ impl HttpFetcher {
         let mut r_bytes: Vec<u8> = Vec::default();
         let mut web_content_length: i64 = -1;
         let mut web_last_modified: i64 = -1;
+        let mut r_final_url = String::default();
         let agent = ureq::builder().user_agent("ferris/1.0").build();
         match agent.get(url).call() {
             Ok(response) => {
                 r_status = response.status();
+                if response.get_url() != url {
+                    r_final_url = response.get_url().to_string();
+                }
                 if let Some(l_mod_str) = response.header("Last-Modified") {
                     match DateTime::parse_from_rfc2822(l_mod_str) {
                         Ok(parse_dt) => {
@@ -94,6 +98,7 @@ impl HttpFetcher {
             error_description: r_ed,
             content_length: web_content_length,
             timestamp: web_last_modified,
+            final_url: r_final_url,
         }
     }
 }
@@ -105,6 +110,30 @@ impl IHttpRequester for HttpFetcher {
     fn request_url_bin(&self, url: &str) -> HttpGetResult {
         self.request_url(url, true)
     }
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> HttpGetResult {
+        let mut result = HttpGetResult::default();
+        let agent = ureq::builder().user_agent("ferris/1.0").build();
+        match agent.post(url).send_form(form) {
+            Ok(response) => {
+                result.http_status = response.status() as i16;
+                result.content = response.into_string().unwrap_or_default();
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                result.http_status = status as i16;
+                result.error_description = response.status_text().to_string();
+                result.content = response.into_string().unwrap_or_default();
+            }
+            Err(ureq::Error::Transport(transp)) => {
+                result.http_err_val = ureq_error_kind_to_u8(transp.kind()) as i16;
+                result.error_description = format!(
+                    "transport:{:?}  {}",
+                    transp.kind(),
+                    transp.message().unwrap_or("")
+                );
+            }
+        }
+        result
+    }
 }
 
 pub fn ureq_error_kind_to_u8(e: ErrorKind) -> u8 {
@@ -57,6 +57,14 @@ impl IHttpRequester for FileFetcher {
         }
         r
     }
+
+    /// no remote side, every post is accepted
+    fn post_form(&self, _url: &str, _form: &[(&str, &str)]) -> HttpGetResult {
+        HttpGetResult {
+            http_status: 202,
+            ..Default::default()
+        }
+    }
 }
 
 pub fn file_to_bin(filename: &str) -> std::io::Result<Vec<u8>> {
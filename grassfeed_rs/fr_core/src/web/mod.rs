@@ -8,6 +8,8 @@ pub type WebFetcherType = Arc<Box<dyn IHttpRequester + Send + Sync + 'static>>;
 pub trait IHttpRequester {
     fn request_url(&self, url: &str) -> HttpGetResult;
     fn request_url_bin(&self, url: &str) -> HttpGetResult;
+    /// POST with a form encoded body, the response text goes into content
+    fn post_form(&self, url: &str, form: &[(&str, &str)]) -> HttpGetResult;
 }
 
 #[derive(Debug, Default)]
@@ -19,6 +21,8 @@ pub struct HttpGetResult {
     pub error_description: String,
     pub timestamp: i64,
     pub content_length: i64,
+    /// the url after following redirects,  empty if there were none
+    pub final_url: String,
 }
 
 impl HttpGetResult {
@@ -32,6 +32,7 @@ fn comprehensive_feed_download() {
     let fetcher: WebFetcherType = Arc::new(Box::new(FileFetcher::new(base_path)));
     let comp_inner = ComprehensiveInner {
         feed_url_edit: "gui_proc_rss2_v1.rss".to_string(),
+        redirected_url: String::default(),
         iconrepo: IconRepo::new_in_mem(),
         web_fetcher: fetcher,
         download_error_happened: false,
@@ -42,6 +43,9 @@ fn comprehensive_feed_download() {
         feed_title: String::default(),
         url_download_text: String::default(),
         icon_id: -1,
+        scraper: None,
+        scraper_preview: String::default(),
+        feed_candidates: Vec::default(),
     };
     let last = StepResult::start(Box::new(ComprStart::new(comp_inner)));
     assert_eq!(last.download_error_happened, false);
@@ -9,6 +9,8 @@ use fr_core::db::message::MessageRow;
 use fr_core::db::messages_repo::IMessagesRepo;
 use fr_core::db::messages_repo::MessagesRepo;
 use fr_core::db::subscription_entry::SubscriptionEntry;
+use fr_core::db::subscription_entry::SOURCE_KIND_SCRAPER;
+use fr_core::db::subscription_entry::SRC_REPO_ID_DELETED;
 use fr_core::db::subscription_repo::ISubscriptionRepo;
 use fr_core::db::subscription_repo::SubscriptionRepo;
 use fr_core::downloader::db_clean::AnalyzeFolderPositions;
@@ -18,6 +20,9 @@ use fr_core::downloader::db_clean::CleanerStart;
 use fr_core::downloader::db_clean::CorrectIconsDoublettes;
 use fr_core::downloader::db_clean::DeleteDoubleSameMessages;
 use fr_core::downloader::db_clean::DeleteUnusedIcons;
+use fr_core::downloader::db_clean::MarkUnconnectedMessages;
+use fr_core::downloader::db_clean::MergeDuplicateSubscriptions;
+use fr_core::downloader::db_clean::PurgeMessages;
 use fr_core::downloader::db_clean::ReduceTooManyMessages;
 use fr_core::downloader::db_clean::MAX_ERROR_LINES_PER_SUBSCRIPTION;
 use fr_core::downloader::db_clean::MAX_ERROR_LINE_AGE_S;
@@ -238,6 +243,144 @@ fn prepare_add_icons(inner: &CleanerInner) {
     });
 }
 
+// #[ignore]
+#[test]
+fn clean_trash_retention() {
+    setup();
+    let mut cleaner_i = prepare_cleaner_inner(-1);
+    cleaner_i.trash_retention_days = 7;
+    let day_s = 60 * 60 * 24;
+    for (subs_id, trashed_days_ago) in [(4, 2), (5, 9)] {
+        let subsrepo = &cleaner_i.subscriptionrepo;
+        subsrepo.update_parent_and_folder_position(subs_id, SRC_REPO_ID_DELETED, 0);
+        subsrepo.set_deleted_rec(subs_id, true);
+        subsrepo.update_trash_origin(subs_id, 1, 2, timestamp_now() - trashed_days_ago * day_s);
+    }
+    let sut = MarkUnconnectedMessages(cleaner_i);
+    if let StepResult::Continue(s) = Box::new(sut).step() {
+        let mut inner: CleanerInner = s.take();
+        // still in the trash: messages are kept
+        assert_eq!(
+            inner.messagesrepo.get_by_subscriptions(&[4], false).len(),
+            10
+        );
+        assert_eq!(
+            inner.messagesrepo.get_by_subscriptions(&[5], false).len(),
+            0
+        );
+    } else {
+        panic!()
+    }
+}
+
+// #[ignore]
+#[test]
+fn clean_purge_removes_tag_links() {
+    setup();
+    let mut cleaner_i = prepare_cleaner_inner(-1);
+    let subsrepo = &cleaner_i.subscriptionrepo;
+    subsrepo.update_parent_and_folder_position(5, SRC_REPO_ID_DELETED, 0);
+    subsrepo.set_deleted_rec(5, true);
+    let msg_ids = |inner: &mut CleanerInner, subs_id: isize| -> Vec<i32> {
+        inner
+            .messagesrepo
+            .get_by_subscriptions(&[subs_id], false)
+            .map(|m| m.message_id as i32)
+            .collect()
+    };
+    let keep_ids = msg_ids(&mut cleaner_i, 4);
+    let purge_ids = msg_ids(&mut cleaner_i, 5);
+    let (tag_id, _is_new) = cleaner_i.messagesrepo.get_or_insert_tag("later").unwrap();
+    cleaner_i
+        .messagesrepo
+        .add_tag_links(&[keep_ids[0], purge_ids[0], purge_ids[1]], tag_id);
+    assert_eq!(cleaner_i.messagesrepo.get_all_tag_links().len(), 3);
+    let sut = MarkUnconnectedMessages(cleaner_i);
+    let inner: CleanerInner = match Box::new(sut).step() {
+        StepResult::Continue(s) => s.take(),
+        _ => panic!(),
+    };
+    let sut = PurgeMessages(inner);
+    if let StepResult::Continue(s) = Box::new(sut).step() {
+        let inner: CleanerInner = s.take();
+        let links = inner.messagesrepo.get_all_tag_links();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].message_id, keep_ids[0] as isize);
+        assert_eq!(inner.messagesrepo.count_by_tag(tag_id), (1, 1));
+    } else {
+        panic!()
+    }
+}
+
+// #[ignore]
+#[test]
+fn clean_merge_duplicate_subscriptions() {
+    setup();
+    let cleaner_i = prepare_cleaner_inner(-1);
+    cleaner_i
+        .subscriptionrepo
+        .update_url(4, "https://www.example.com/feed/".to_string());
+    cleaner_i
+        .subscriptionrepo
+        .update_url(5, "http://example.com/feed".to_string());
+    let m_new = MessageRow {
+        subscription_id: 5,
+        post_id: "post-new".to_string(),
+        ..Default::default()
+    };
+    let _r = cleaner_i.messagesrepo.insert(&m_new);
+    let sut = MergeDuplicateSubscriptions(cleaner_i);
+    if let StepResult::Continue(s) = Box::new(sut).step() {
+        let mut inner: CleanerInner = s.take();
+        assert_eq!(
+            inner.messagesrepo.get_by_subscriptions(&[4], false).len(),
+            11
+        );
+        assert_eq!(
+            inner.messagesrepo.get_by_subscriptions(&[5], false).len(),
+            0
+        );
+        let subs5 = inner.subscriptionrepo.get_by_index(5).unwrap();
+        assert_eq!(subs5.parent_subs_id, SRC_REPO_ID_DELETED);
+        assert_eq!(subs5.trash_parent_id, 1);
+        let groups = inner.subscriptionrepo.get_feed_url_groups();
+        assert_eq!(groups.get("example.com/feed"), Some(&vec![4]));
+    } else {
+        panic!()
+    }
+}
+
+// #[ignore]
+#[test]
+fn clean_merge_keeps_scrapers_of_same_page() {
+    setup();
+    let cleaner_i = prepare_cleaner_inner(-1);
+    for (subs_id, selector) in [(4, "h2.title"), (5, "div.news a")] {
+        cleaner_i
+            .subscriptionrepo
+            .update_url(subs_id, "https://example.com/news".to_string());
+        cleaner_i.subscriptionrepo.update_source_kind(
+            subs_id,
+            SOURCE_KIND_SCRAPER,
+            format!("{{\"item\":\"{selector}\"}}"),
+        );
+    }
+    let sut = MergeDuplicateSubscriptions(cleaner_i);
+    if let StepResult::Continue(s) = Box::new(sut).step() {
+        let mut inner: CleanerInner = s.take();
+        assert_eq!(
+            inner.messagesrepo.get_by_subscriptions(&[4], false).len(),
+            10
+        );
+        let subs5 = inner.subscriptionrepo.get_by_index(5).unwrap();
+        assert_ne!(subs5.parent_subs_id, SRC_REPO_ID_DELETED);
+        let groups = inner.subscriptionrepo.get_feed_url_groups();
+        assert_eq!(groups.get("example.com/news"), None);
+    } else {
+        panic!()
+    }
+}
+
 fn prepare_db_with_errors_1(msgrepo: &MessagesRepo, subsrepo: &SubscriptionRepo) {
     let mut se = SubscriptionEntry::default();
     se.is_folder = true;
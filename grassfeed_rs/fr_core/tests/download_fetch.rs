@@ -9,6 +9,7 @@ use fr_core::db::messages_repo::IMessagesRepo;
 use fr_core::db::messages_repo::MessagesRepo;
 use fr_core::db::subscription_repo::ISubscriptionRepo;
 use fr_core::db::subscription_repo::SubscriptionRepo;
+use fr_core::downloader::linkcleaner::LinkCleaner;
 use fr_core::downloader::messages::FetchInner;
 use fr_core::downloader::messages::FetchStart;
 use fr_core::util::timestamp_now;
@@ -43,6 +44,7 @@ fn single_dl_regular() {
         messgesrepo: msgrepo,
         download_error_text: String::default(),
         erro_repo: erro_rep,
+        link_cleaner: LinkCleaner::default(),
     };
     let ts_now = timestamp_now();
     let date_copied_from_example =
@@ -92,6 +94,7 @@ fn download_with_create_date() {
         download_text: String::default(),
         download_error_text: String::default(),
         erro_repo: erro_rep,
+        link_cleaner: LinkCleaner::default(),
     };
     let ts_now = timestamp_now();
     let date_copied_from_example =
@@ -6,6 +6,7 @@ use fr_core::db::errors_repo::ErrorRepo;
 use fr_core::downloader::browserdrag::extract_feed_urls_sloppy;
 use fr_core::downloader::browserdrag::BrowserEvalStart;
 use fr_core::downloader::browserdrag::DragInner;
+use fr_core::downloader::util::extract_feed_from_website;
 use fr_core::util::StepResult;
 use fr_core::web::mockfilefetcher::FileFetcher;
 use fr_core::web::WebFetcherType;
@@ -94,6 +95,30 @@ fn analyse_nn_sloppy() {
     assert_eq!(found_feed_urls.len(), 3);
 }
 
+// #[ignore]
+#[test]
+fn all_feed_candidates() {
+    setup();
+    let read_page =
+        |n: &str| std::fs::read_to_string(format!("{}websites/{}", TD_BASE, n)).unwrap();
+    let candidates = extract_feed_from_website(&read_page("neweurope.html")).unwrap();
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(candidates[0].url, "https://www.neweurope.eu/feed/");
+    assert_eq!(candidates[0].title, "New Europe » Feed");
+    assert_eq!(candidates[0].feed_type, "rss");
+    assert!(candidates[1].title.ends_with("Comments Feed"));
+    assert!(candidates[2].title.ends_with("Comments Feed"));
+
+    let candidates = extract_feed_from_website(&read_page("stackexchange.html")).unwrap();
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].url, "/feeds/question/457584");
+    assert_eq!(candidates[0].feed_type, "atom");
+    assert_eq!(
+        candidates[0].title,
+        "Feed for question 'GTK+3 change text color in a label (raspberry pi)'"
+    );
+}
+
 // -------------------------------
 
 #[derive(Default, Debug, Clone)]
@@ -0,0 +1,135 @@
+use fr_core::controller::guiprocessor::Job;
+use fr_core::controller::sourcetree::SJob;
+use fr_core::db::errors_repo::ErrorRepo;
+use fr_core::downloader::browserdrag::BrowserEvalStart;
+use fr_core::downloader::browserdrag::DragInner;
+use fr_core::downloader::siteresolver::resolve_site_feed;
+use fr_core::util::StepResult;
+use fr_core::web::mockfilefetcher::FileFetcher;
+use fr_core::web::WebFetcherType;
+use std::sync::Arc;
+
+const SITES_BASE: &str = "tests/data/sites/";
+
+/// fixture, page url, resolver, feed url
+const SITES: [(&str, &str, &str, &str); 6] = [
+    (
+        "youtube_handle.html",
+        "https://www.youtube.com/@rustprogramming",
+        "youtube",
+        "https://www.youtube.com/feeds/videos.xml?channel_id=UCaYhcUwRBNscFNUKTjgPFiA",
+    ),
+    (
+        "github_repo.html",
+        "https://github.com/rust-lang/rust",
+        "github",
+        "https://github.com/rust-lang/rust/releases.atom",
+    ),
+    (
+        "gitlab_project.html",
+        "https://gitlab.gnome.org/GNOME/gtk/-/tree/main",
+        "gitlab",
+        "https://gitlab.gnome.org/GNOME/gtk.atom",
+    ),
+    (
+        "reddit_subreddit.html",
+        "https://www.reddit.com/r/rust/",
+        "reddit",
+        "https://www.reddit.com/r/rust/.rss",
+    ),
+    (
+        "mastodon_profile.html",
+        "https://fosstodon.org/@rust",
+        "mastodon",
+        "https://fosstodon.org/@rust.rss",
+    ),
+    (
+        "stackexchange_tag.html",
+        "https://stackoverflow.com/questions/tagged/rust",
+        "stackexchange",
+        "https://stackoverflow.com/feeds/tag/rust",
+    ),
+];
+
+fn read_fixture(filename: &str) -> String {
+    std::fs::read_to_string(format!("{}{}", SITES_BASE, filename)).unwrap()
+}
+
+fn drag_eval(dragged: &str, page_url: &str) -> DragInner {
+    let (stc_job_s, _stc_job_r) = flume::unbounded::<SJob>();
+    let (gp_sender, _gp_rec) = flume::unbounded::<Job>();
+    let fetcher: WebFetcherType = Arc::new(Box::new(FileFetcher::new(SITES_BASE.to_string())));
+    let mut drag_i = DragInner::new(
+        dragged.to_string(),
+        stc_job_s,
+        fetcher,
+        ErrorRepo::new_in_mem(),
+        gp_sender,
+    );
+    drag_i.testing_base_url = page_url.to_string();
+    StepResult::start(Box::new(BrowserEvalStart::new(drag_i)))
+}
+
+// #[ignore]
+#[test]
+fn resolve_fixture_pages() {
+    setup();
+    for (filename, page_url, resolver, feed_url) in SITES {
+        let page = read_fixture(filename);
+        let r = resolve_site_feed(page_url, &page);
+        assert_eq!(
+            r,
+            Some((resolver.to_string(), feed_url.to_string())),
+            "{filename}"
+        );
+    }
+}
+
+// #[ignore]
+#[test]
+fn page_content_needed() {
+    setup();
+    // without the page a handle or an arbitrary instance cannot be resolved
+    assert!(resolve_site_feed("https://www.youtube.com/@rustprogramming", "").is_none());
+    assert!(resolve_site_feed("https://fosstodon.org/@rust", "").is_none());
+    let yt_page = read_fixture("youtube_handle.html");
+    assert!(resolve_site_feed("https://fosstodon.org/@rust", &yt_page).is_none());
+}
+
+// #[ignore]
+#[test]
+fn drag_resolved_sites() {
+    setup();
+    for (filename, page_url, _resolver, feed_url) in SITES {
+        let last = drag_eval(filename, page_url);
+        assert_eq!(last.found_feed_url, feed_url.to_string(), "{filename}");
+    }
+}
+
+// #[ignore]
+#[test]
+fn drag_unreachable_page() {
+    setup();
+    // the page is blocked for us, the url is enough
+    let last = drag_eval("https://old.reddit.com/r/rust/", "");
+    assert!(!last.error_message.is_empty());
+    assert_eq!(last.found_feed_url, "https://www.reddit.com/r/rust/.rss");
+}
+
+// ------------------------------------
+
+mod logger_config;
+#[allow(unused_imports)]
+#[macro_use]
+extern crate log;
+use std::sync::Once;
+
+static TEST_SETUP: Once = Once::new();
+fn setup() {
+    TEST_SETUP.call_once(|| {
+        let _r = logger_config::setup_fern_logger(
+            logger_config::QuietFlags::Downloader as u64,
+            //  0,
+        );
+    });
+}
@@ -2,10 +2,12 @@ use feed_rs::parser;
 use flume::Receiver;
 use flume::Sender;
 use fr_core::config::init_system::GrassFeederConfig;
+use fr_core::controller::contentlist::filter_messages2;
 use fr_core::controller::contentlist::match_new_entries_to_existing;
 use fr_core::controller::contentlist::CJob;
 use fr_core::controller::contentlist::ContentList;
 use fr_core::controller::contentlist::IContentList;
+use fr_core::controller::messagefilter::MessageQuery;
 use fr_core::db::message::compress;
 use fr_core::db::message::MessageRow;
 use fr_core::db::message_state::MessageStateMap;
@@ -16,6 +18,7 @@ use fr_core::downloader::util::workaround_https_declaration;
 use fr_core::util;
 use fr_core::TD_BASE;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::RwLock;
 
@@ -109,6 +112,39 @@ fn test_new_entries_filter() {
     }
 }
 
+// a known message with different content hash leads to a content update, not to a new message
+#[test]
+fn test_new_entries_content_changed() {
+    setup();
+    let mut msg_repo = MessagesRepo::new_in_mem();
+    msg_repo.get_ctx().create_table();
+    let mut fce0 = MessageRow::new();
+    fce0.subscription_id = 6;
+    fce0.title = "Advisory".to_string();
+    fce0.post_id = "0x40".to_string();
+    fce0.entry_src_date = util::timestamp_now();
+    fce0.content_hash = fr_core::db::message::content_hash("affected: 1.2");
+    let _r = msg_repo.insert(&fce0);
+    let (job_sender, job_receiver) = flume::unbounded::<CJob>();
+    let exi_i = msg_repo.get_by_subscription(6);
+    let mut new_list: Vec<MessageRow> = vec![fce0.clone()];
+    let insert_list = match_new_entries_to_existing(&new_list, exi_i.clone(), job_sender.clone());
+    assert_eq!(insert_list.len(), 0);
+    assert!(job_receiver.is_empty());
+    new_list[0].content_hash = fr_core::db::message::content_hash("affected: 1.2, 1.3");
+    new_list[0].content_text = compress("affected: 1.2, 1.3");
+    let insert_list = match_new_entries_to_existing(&new_list, exi_i, job_sender);
+    assert_eq!(insert_list.len(), 0);
+    match job_receiver.recv().unwrap() {
+        CJob::DbUpdateContent(id, hash, content) => {
+            assert_eq!(id, 1);
+            assert_eq!(hash, new_list[0].content_hash);
+            assert_eq!(content, new_list[0].content_text);
+        }
+        _ => unimplemented!(),
+    }
+}
+
 #[test]
 fn parse_wissensmanufaktur() {
     setup();
@@ -202,12 +238,13 @@ fn t_messages_filter_multiple() {
         m_s_w.insert(3, false, 0, 0, compress("weerld"), 0, 0);
     }
     {
-        let r = fr_core::controller::contentlist::filter_messages2(&msgstate, &messagelist, "alle");
+        let q = MessageQuery::parse("alle").unwrap();
+        let r = filter_messages2(&msgstate, &messagelist, &q, &HashMap::new());
         assert_eq!(1, r.len());
     }
     {
-        let r =
-            fr_core::controller::contentlist::filter_messages2(&msgstate, &messagelist, "he|al");
+        let q = MessageQuery::parse("he|al").unwrap();
+        let r = filter_messages2(&msgstate, &messagelist, &q, &HashMap::new());
         debug!(" #R: {:?} ", r.len());
         assert_eq!(2, r.len());
     }
@@ -9,6 +9,7 @@ use fr_core::db::messages_repo::MessagesRepo;
 use fr_core::db::subscription_entry::SubscriptionEntry;
 use fr_core::db::subscription_repo::ISubscriptionRepo;
 use fr_core::db::subscription_repo::SubscriptionRepo;
+use fr_core::downloader::scraper::ScraperSelectors;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -141,6 +142,10 @@ impl IDownloader for DownloaderDummy {
     fn new_feedsource_request(&self, _fs_edit_url: &str) {
         unimplemented!()
     }
+
+    fn scraper_preview_request(&self, _page_url: &str, _selectors: ScraperSelectors) {
+        unimplemented!()
+    }
     fn shutdown(&mut self) {
         unimplemented!()
     }
@@ -173,4 +178,25 @@ impl IDownloader for DownloaderDummy {
     fn get_statistics(&self) -> [u32; DLKIND_MAX] {
         unimplemented!()
     }
+    fn download_enclosure(&self, _subs_id: isize, _url: String) {
+        unimplemented!()
+    }
+    fn set_conf_enclosure_folder(&mut self, _folder: String) {
+        unimplemented!()
+    }
+
+    fn extract_article(&self, _msg_id: isize) {
+        unimplemented!()
+    }
+
+    fn set_conf_websub(&mut self, _port: u16, _callback_base: String) {
+        unimplemented!()
+    }
+    fn set_conf_link_cleaning(&mut self, _enabled: bool, _extra_params: String) {
+        unimplemented!()
+    }
+
+    fn apply_rules_to_existing(&self) {
+        unimplemented!()
+    }
 }
@@ -6,8 +6,13 @@ use crate::tree_drag_common::dataset_simple_trio;
 use crate::tree_drag_common::dataset_some_tree;
 use crate::tree_drag_common::dataset_three_folders;
 use crate::tree_drag_common::prepare_subscription_move;
+use fr_core::controller::subscriptionmove::FolderSortOrder;
 use fr_core::controller::subscriptionmove::ISubscriptionMove;
+use fr_core::controller::subscriptionmove::TreeMoveCommand;
+use fr_core::controller::undo::UndoAction;
 use fr_core::db::subscription_entry::SubscriptionEntry;
+use fr_core::db::subscription_entry::SRC_REPO_ID_DELETED;
+use fr_core::db::subscription_state::ISubscriptionState;
 
 /// Dragging the first folder   between the second and third.   0 -> 2
 //  #[ignore]
@@ -253,6 +258,71 @@ fn drag_entry_on_other_entry() {
     }
 }
 
+#[test]
+fn undo_move_and_trash() {
+    setup();
+    let (mut fsc, r_fsource) = prepare_subscription_move(dataset_three_folders());
+    let positions = || -> Vec<(isize, isize, bool)> {
+        (*r_fsource)
+            .borrow()
+            .get_all_entries()
+            .iter()
+            .map(|e| (e.parent_subs_id, e.folder_position, e.isdeleted()))
+            .collect()
+    };
+    let first_down = UndoAction::TreeMove(1, (0, 0), (0, 2));
+    fsc.apply_undo(&first_down, true);
+    assert_eq!(
+        positions(),
+        vec![(0, 2, false), (0, 0, false), (0, 1, false)]
+    );
+    fsc.apply_undo(&first_down, false);
+    assert_eq!(
+        positions(),
+        vec![(0, 0, false), (0, 1, false), (0, 2, false)]
+    );
+
+    fsc.set_delete_subscription_id(Some(2));
+    fsc.move_subscription_to_trash();
+    assert_eq!(positions()[1], (SRC_REPO_ID_DELETED, 0, true));
+    assert_eq!(positions()[2], (0, 1, false));
+    fsc.apply_undo(&UndoAction::Trash(2, 0, 1), false);
+    assert_eq!(
+        positions(),
+        vec![(0, 0, false), (0, 1, false), (0, 2, false)]
+    );
+}
+
+#[test]
+fn trash_restore_and_delete() {
+    setup();
+    let (mut fsc, r_fsource) = prepare_subscription_move(dataset_three_folders());
+    let entry = |subs_id: isize| {
+        (*r_fsource)
+            .borrow()
+            .get_by_index(subs_id)
+            .map(|e| (e.parent_subs_id, e.folder_position, e.isdeleted()))
+    };
+    for subs_id in [2, 3] {
+        fsc.set_delete_subscription_id(Some(subs_id));
+        fsc.move_subscription_to_trash();
+    }
+    assert_eq!(entry(3), Some((SRC_REPO_ID_DELETED, 0, true)));
+    assert_eq!(entry(2), Some((SRC_REPO_ID_DELETED, 1, true)));
+    fsc.restore_from_trash(2);
+    assert_eq!(entry(2), Some((0, 1, false)));
+    assert_eq!((*r_fsource).borrow().get_by_index(2).unwrap().trashed_at, 0);
+    fsc.delete_from_trash(3);
+    assert_eq!(entry(3), None);
+    fsc.delete_from_trash(2); // not in the trash
+    assert_eq!(entry(2), Some((0, 1, false)));
+    fsc.set_delete_subscription_id(Some(1));
+    fsc.move_subscription_to_trash();
+    fsc.empty_trash();
+    assert_eq!(entry(1), None);
+    assert_eq!(entry(2), Some((0, 0, false)));
+}
+
 #[test]
 fn check_paths_simple() {
     setup();
@@ -264,6 +334,206 @@ fn check_paths_simple() {
     assert_eq!(stc.get_by_path(&vec![1]).unwrap().subs_id, 4);
 }
 
+/// folder1 [feed2d, feed3d],  folder4 [feed5d]
+#[test]
+fn keyboard_move_commands() {
+    setup();
+    let (fsc, r_fsource) = prepare_subscription_move(dataset_some_tree());
+    let entry = |id: isize| {
+        let e = (*r_fsource).borrow().get_by_index(id).unwrap();
+        (e.parent_subs_id, e.folder_position)
+    };
+    assert!(!fsc.move_by_command(2, TreeMoveCommand::Up));
+    assert!(fsc.move_by_command(2, TreeMoveCommand::Down));
+    assert_eq!(entry(2), (1, 1));
+    assert_eq!(entry(3), (1, 0));
+    assert!(!fsc.move_by_command(2, TreeMoveCommand::Down));
+    assert!(fsc.move_by_command(2, TreeMoveCommand::Up));
+    assert_eq!(entry(2), (1, 0));
+
+    // out of folder1, right after it
+    assert!(fsc.move_by_command(3, TreeMoveCommand::OutToParent));
+    assert_eq!(entry(3), (0, 1));
+    assert_eq!(entry(4), (0, 2));
+    assert!(!fsc.move_by_command(3, TreeMoveCommand::OutToParent));
+    // to the start of folder4
+    assert!(fsc.move_by_command(3, TreeMoveCommand::IntoNextFolder));
+    assert_eq!(entry(3), (4, 0));
+    assert_eq!(entry(5), (4, 1));
+    assert_eq!(fsc.get_by_path(&[1, 0]).unwrap().subs_id, 3);
+    // folder4 goes to the end of folder1
+    assert!(fsc.move_by_command(4, TreeMoveCommand::IntoPrevFolder));
+    assert_eq!(entry(4), (1, 1));
+    assert_eq!(fsc.get_by_path(&[0, 1, 1]).unwrap().subs_id, 5);
+    assert!(!fsc.move_by_command(1, TreeMoveCommand::IntoPrevFolder));
+    assert!(!fsc.move_by_command(1, TreeMoveCommand::IntoNextFolder));
+    let all = (*r_fsource).borrow().get_all_entries();
+    assert_eq!(all.len(), 5);
+    fsc.apply_undo(&UndoAction::TreeMove(4, (0, 1), (1, 1)), false);
+    assert_eq!(entry(4), (0, 1));
+}
+
+/// folder1 [feed2d, feed3d],  folder4 [feed5d],  stored positions with gaps
+#[test]
+fn keyboard_move_with_position_gaps() {
+    setup();
+    let (fsc, r_fsource) = prepare_subscription_move(dataset_some_tree());
+    let entry = |id: isize| {
+        let e = (*r_fsource).borrow().get_by_index(id).unwrap();
+        (e.parent_subs_id, e.folder_position)
+    };
+    {
+        let repo = (*r_fsource).borrow();
+        repo.update_folder_position(1, 3);
+        repo.update_folder_position(4, 7);
+        repo.update_folder_position(2, 2);
+        repo.update_folder_position(3, 5);
+    }
+    assert!(fsc.move_by_command(2, TreeMoveCommand::Down));
+    assert_eq!(entry(3), (1, 0));
+    assert_eq!(entry(2), (1, 1));
+    assert!(fsc.move_by_command(2, TreeMoveCommand::Up));
+    assert_eq!(entry(2), (1, 0));
+    assert_eq!(entry(3), (1, 1));
+    // right after folder1,  before folder4
+    assert!(fsc.move_by_command(3, TreeMoveCommand::OutToParent));
+    assert_eq!(entry(1), (0, 0));
+    assert_eq!(entry(3), (0, 1));
+    assert_eq!(entry(4), (0, 2));
+}
+
+/// folder1 [feed2d, feed3d, feed6d],  folder4 [feed5d]
+#[test]
+fn sort_folder_contents() {
+    setup();
+    let mut fs_list = dataset_some_tree();
+    let mut fse6 = SubscriptionEntry::from_new_url("Feed6d".to_string(), "feed6-url".to_string());
+    fse6.subs_id = 6;
+    fse6.parent_subs_id = 1;
+    fse6.folder_position = 2;
+    fs_list.push(fse6);
+    let (fsc, r_fsource) = prepare_subscription_move(fs_list);
+    let order = |parent: isize| {
+        (*r_fsource)
+            .borrow()
+            .get_children(parent)
+            .iter()
+            .map(|e| e.subs_id)
+            .collect::<Vec<isize>>()
+    };
+    (*r_fsource)
+        .borrow()
+        .update_displayname(2, "zeta".to_string());
+    fsc.sort_folder(1, FolderSortOrder::Name);
+    assert_eq!(order(1), vec![3, 6, 2]);
+    (*r_fsource).borrow().update_timestamps(6, 0, Some(100));
+    (*r_fsource).borrow().update_timestamps(2, 0, Some(200));
+    fsc.sort_folder(1, FolderSortOrder::LastUpdate);
+    assert_eq!(order(1), vec![2, 6, 3]);
+    {
+        let statemap = fsc.get_state_map();
+        let mut sm = statemap.borrow_mut();
+        sm.set_num_all_unread(2, 10, 1);
+        sm.set_num_all_unread(3, 10, 5);
+        sm.set_num_all_unread(6, 10, 0);
+    }
+    fsc.sort_folder(1, FolderSortOrder::Unread);
+    assert_eq!(order(1), vec![3, 2, 6]);
+    // folders stay on top
+    fsc.sort_folder(0, FolderSortOrder::Name);
+    assert_eq!(order(0), vec![1, 4]);
+    // a manual folder is not sorted again
+    assert!(!fsc.resort_if_kept_sorted(1));
+}
+
+/// folder1 [feed2d, feed3d],  folder4 [feed5d]
+#[test]
+fn keep_folder_sorted() {
+    setup();
+    let (fsc, r_fsource) = prepare_subscription_move(dataset_some_tree());
+    let order = |parent: isize| {
+        (*r_fsource)
+            .borrow()
+            .get_children(parent)
+            .iter()
+            .map(|e| e.subs_id)
+            .collect::<Vec<isize>>()
+    };
+    (*r_fsource)
+        .borrow()
+        .update_keep_sorted(1, FolderSortOrder::Name as isize);
+    assert!(!fsc.resort_if_kept_sorted(1));
+    (*r_fsource)
+        .borrow()
+        .update_displayname(2, "zeta".to_string());
+    assert!(fsc.resort_if_kept_sorted(1));
+    assert_eq!(order(1), vec![3, 2]);
+    // a moved entry does not stay where it was dropped
+    let entry5 = (*r_fsource).borrow().get_by_index(5).unwrap();
+    fsc.place_entry(entry5, 1, 0);
+    assert_eq!(order(1), vec![3, 5, 2]);
+    assert!(fsc.move_by_command(5, TreeMoveCommand::Up));
+    assert_eq!(order(1), vec![3, 5, 2]);
+    // back to manual order
+    (*r_fsource).borrow().update_keep_sorted(1, 0);
+    assert!(fsc.move_by_command(5, TreeMoveCommand::Up));
+    assert_eq!(order(1), vec![5, 3, 2]);
+}
+
+/// folder1 [feed2d, feed3d],  folder4 [feed5d]
+#[test]
+fn tree_filter_paths() {
+    setup();
+    let (fsc, _r_fsource) = prepare_subscription_move(dataset_some_tree());
+    let path_of = |id: isize| fsc.get_state_map().borrow().get_tree_path(id);
+    fsc.set_tree_filter_text("Feed3");
+    assert!(fsc.is_tree_filter_active());
+    assert_eq!(path_of(1), Some(vec![0]));
+    assert_eq!(path_of(3), Some(vec![0, 0]));
+    assert_eq!(path_of(2), None);
+    assert_eq!(path_of(4), None);
+    assert_eq!(fsc.get_by_path(&[0, 0]).unwrap().subs_id, 3);
+    assert_eq!(fsc.get_tree_filter_match(), Some(3));
+    // the paths of the filtered tree cannot be dragged
+    assert!(!fsc.on_subscription_drag(0, vec![0, 0], vec![0, 1]));
+    // a matching folder shows its entries
+    fsc.set_tree_filter_text("folder4");
+    assert_eq!(path_of(4), Some(vec![0]));
+    assert_eq!(path_of(5), Some(vec![0, 0]));
+    assert_eq!(fsc.get_tree_filter_match(), Some(4));
+    // by url
+    fsc.set_tree_filter_text("feed4-url");
+    assert_eq!(path_of(4), Some(vec![1]));
+    assert_eq!(path_of(5), Some(vec![1, 0]));
+    assert_eq!(fsc.get_tree_filter_match(), Some(3));
+    fsc.set_tree_filter_text("");
+    {
+        let statemap = fsc.get_state_map();
+        let mut sm = statemap.borrow_mut();
+        sm.set_num_all_unread(2, 3, 0);
+        sm.set_num_all_unread(3, 3, 0);
+        sm.set_num_all_unread(5, 3, 2);
+    }
+    fsc.set_tree_filter_unread(true);
+    assert_eq!(path_of(1), None);
+    assert_eq!(path_of(4), Some(vec![0]));
+    assert_eq!(path_of(5), Some(vec![0, 0]));
+    fsc.set_tree_filter_unread(false);
+    // the selected subscription stays, with the folders above it
+    fsc.get_tree_filter().borrow_mut().keep_visible = 3;
+    fsc.set_tree_filter_unread(true);
+    assert_eq!(path_of(1), Some(vec![0]));
+    assert_eq!(path_of(3), Some(vec![0, 0]));
+    assert_eq!(path_of(2), None);
+    assert_eq!(path_of(5), Some(vec![1, 0]));
+    fsc.get_tree_filter().borrow_mut().keep_visible = 0;
+    fsc.set_tree_filter_unread(false);
+    assert!(!fsc.is_tree_filter_active());
+    assert_eq!(path_of(2), Some(vec![0, 0]));
+    assert_eq!(path_of(5), Some(vec![1, 0]));
+    assert_eq!(fsc.get_tree_filter_match(), None);
+}
+
 // ------------------------------------
 
 mod unzipper;
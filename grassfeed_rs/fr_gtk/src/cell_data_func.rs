@@ -21,13 +21,16 @@ pub trait BoldFuncDiscriminator {
     fn column_nr() -> i32;
 
     fn attrlist(act_bits: u32) -> AttrList {
-        let (fontsize, is_read, is_folder, _is_transparent) =
+        let (fontsize, is_read, is_folder, is_transparent) =
             FontAttributes::from_activation_bits(act_bits);
         let r = AttrList::new();
         if !is_read && !is_folder {
             add_font_weight(&r);
         }
         add_font_size(&r, fontsize);
+        if is_transparent {
+            add_font_dimmed(&r);
+        }
         r
     }
 }
@@ -56,6 +59,19 @@ pub fn add_font_size(r: &AttrList, fontsize: u32) {
     // do nothing. Downgrade of functionality with gtk-0.14.   Later   add newer gtk packages
 }
 
+#[cfg(not(feature = "legacy3gtk14"))]
+pub fn add_font_dimmed(r: &AttrList) {
+    r.insert(Attribute::from(AttrInt::new_foreground_alpha(DIMMED_ALPHA)));
+}
+
+#[cfg(feature = "legacy3gtk14")]
+pub fn add_font_dimmed(r: &AttrList) {
+    // do nothing. Downgrade of functionality with gtk-0.14.   Later   add newer gtk packages
+}
+
+/// about 45% of full opacity
+const DIMMED_ALPHA: u16 = 30000;
+
 #[derive(Default)]
 pub struct TreeBoldDiscr {}
 
@@ -4,7 +4,10 @@ use gtk::NotebookBuilder;
 #[cfg(not(feature = "legacy3gtk14"))]
 use gtk::builders::NotebookBuilder;
 
+use crate::rules_dialog::create_rules_dialog;
+use crate::saved_search_dialog::create_saved_search_dialog;
 use crate::statistics_list::create_statistic_listview;
+use crate::tags_dialog::create_tags_dialog;
 use crate::util::*;
 use flume::Sender;
 use gtk::gdk_pixbuf::InterpType;
@@ -15,6 +18,7 @@ use gtk::traits::TextBufferExt;
 use gtk::AboutDialog;
 use gtk::Adjustment;
 use gtk::Align;
+use gtk::CheckButton;
 use gtk::ComboBoxText;
 use gtk::Dialog;
 use gtk::Entry;
@@ -51,6 +55,8 @@ use resources::parameter::ICON_SIZE_LIMIT_BYTES;
 use resources::parameter::STORE_MESSAGES_PER_SUBSCRIPTION;
 use rust_i18n;
 use rust_i18n::t;
+use std::cell::RefCell;
+use std::rc::Rc;
 use ui_gtk::dialogdatadistributor::DialogDataDistributor;
 use ui_gtk::iconloader::IconLoader;
 use ui_gtk::GtkObjectsType;
@@ -65,6 +71,7 @@ const ICON_RESCALE_SIZE: i32 = 24;
 const ICON_DIALOG_COLUMNS: i32 = 40;
 
 const CLEANER_STEPS_MAX: f64 = 12.0;
+const TRASH_RETENTION_DAYS_MAX: f64 = 3650.0;
 
 const NONE_ADJ: Option<&Adjustment> = None;
 const NONE_TEXTTAGTABLE: Option<&TextTagTable> = None;
@@ -85,6 +92,9 @@ pub fn create_dialogs(
     create_opml_export_dialog(gui_event_sender.clone(), gtk_obj_a.clone());
     create_about_dialog(gtk_obj_a.clone(), ddd);
     create_subscription_statistic_dialog(gtk_obj_a.clone(), ddd);
+    create_rules_dialog(gui_event_sender.clone(), gtk_obj_a.clone(), ddd);
+    create_tags_dialog(gui_event_sender.clone(), gtk_obj_a.clone(), ddd);
+    create_saved_search_dialog(gui_event_sender.clone(), gtk_obj_a.clone(), ddd);
 }
 
 fn create_icons_dialog(gtk_obj_a: GtkObjectsType, ddd: &mut DialogDataDistributor) {
@@ -284,6 +294,113 @@ pub fn create_new_subscription_dialog(
     }
     box3h.pack_end(&image_icon, false, false, 0);
     box1v.pack_start(&box3h, false, false, 1);
+
+    let box_dup = gtk::Box::new(Orientation::Horizontal, 1);
+    let label_dup = Label::new(None);
+    label_dup.set_halign(Align::Start);
+    box_dup.pack_start(&label_dup, true, true, 1);
+    let b_show_existing = gtk::Button::with_label(&t!("D_NEW_SUBSCRIPTION_SHOW_EXISTING"));
+    box_dup.pack_end(&b_show_existing, false, false, 0);
+    label_dup.show();
+    b_show_existing.show();
+    box_dup.set_no_show_all(true);
+    box1v.pack_start(&box_dup, false, false, 1);
+    let ev_se = g_ev_se.clone();
+    let dialog_c = dialog.clone();
+    b_show_existing.connect_clicked(move |_b| {
+        let _r = ev_se.send(GuiEvents::DialogData(
+            "new-feedsource-show-existing".to_string(),
+            Vec::default(),
+        ));
+        dialog_c.response(ResponseType::Cancel);
+    });
+
+    let box_cand = gtk::Box::new(Orientation::Vertical, 1);
+    let label_cand = Label::new(Some(&t!("D_NEW_SUBSCRIPTION_CANDIDATES")));
+    label_cand.set_halign(Align::Start);
+    box_cand.pack_start(&label_cand, false, false, 1);
+    let box_cand_list = gtk::Box::new(Orientation::Vertical, 1);
+    let scrolledwindow_cand = ScrolledWindow::new(NONE_ADJ, NONE_ADJ);
+    scrolledwindow_cand.set_min_content_height(150);
+    scrolledwindow_cand.set_shadow_type(ShadowType::EtchedIn);
+    scrolledwindow_cand.add(&box_cand_list);
+    box_cand.pack_start(&scrolledwindow_cand, true, true, 1);
+    label_cand.show();
+    scrolledwindow_cand.show_all();
+    box_cand.set_no_show_all(true);
+    box1v.pack_start(&box_cand, true, true, 1);
+    // check button, feed-url, title
+    let candidates: Rc<RefCell<Vec<(CheckButton, String, String)>>> =
+        Rc::new(RefCell::new(Vec::default()));
+
+    let expander = gtk::Expander::new(Some(&t!("D_NEW_SUBSCRIPTION_SCRAPER")));
+    box1v.pack_start(&expander, false, false, 1);
+    let box4v = gtk::Box::new(Orientation::Vertical, 1);
+    expander.add(&box4v);
+    let grid1 = Grid::new();
+    grid1.set_column_spacing(4);
+    box4v.pack_start(&grid1, false, false, 1);
+    let selector_labels = [
+        t!("D_NEW_SUBSCRIPTION_SCRAPER_CONTAINER"),
+        t!("D_NEW_SUBSCRIPTION_SCRAPER_TITLE"),
+        t!("D_NEW_SUBSCRIPTION_SCRAPER_LINK"),
+        t!("D_NEW_SUBSCRIPTION_SCRAPER_DATE"),
+        t!("D_NEW_SUBSCRIPTION_SCRAPER_BODY"),
+    ];
+    let mut selector_entries: Vec<Entry> = Vec::default();
+    for (line, label_text) in selector_labels.iter().enumerate() {
+        let label = Label::new(Some(label_text));
+        label.set_halign(Align::Start);
+        grid1.attach(&label, 0, line as i32, 1, 1);
+        let entry = Entry::new();
+        entry.set_hexpand(true);
+        grid1.attach(&entry, 1, line as i32, 1, 1);
+        selector_entries.push(entry);
+    }
+    let b_preview = gtk::Button::with_label(&t!("D_NEW_SUBSCRIPTION_SCRAPER_PREVIEW"));
+    b_preview.set_halign(Align::End);
+    box4v.pack_start(&b_preview, false, false, 1);
+    let label_preview = Label::new(None);
+    label_preview.set_halign(Align::Start);
+    label_preview.set_valign(Align::Start);
+    label_preview.set_selectable(true);
+    let scrolledwindow1 = ScrolledWindow::new(NONE_ADJ, NONE_ADJ);
+    scrolledwindow1.set_min_content_height(120);
+    scrolledwindow1.set_shadow_type(ShadowType::EtchedIn);
+    scrolledwindow1.add(&label_preview);
+    box4v.pack_start(&scrolledwindow1, true, true, 1);
+    let ev_se = g_ev_se.clone();
+    let ent1_c = entry_url.clone();
+    let sel_ent_c = selector_entries.clone();
+    b_preview.connect_clicked(move |_b| {
+        let mut payload = vec![AValue::ASTR(ent1_c.text().as_str().to_string())];
+        sel_ent_c
+            .iter()
+            .for_each(|e| payload.push(AValue::ASTR(e.text().as_str().to_string())));
+        let _r = ev_se.send(GuiEvents::DialogData(
+            "scraper-preview".to_string(),
+            payload,
+        ));
+    });
+
+    let expander2 = gtk::Expander::new(Some(&t!("D_NEW_SUBSCRIPTION_MONITOR")));
+    box1v.pack_start(&expander2, false, false, 1);
+    let grid2 = Grid::new();
+    grid2.set_column_spacing(4);
+    expander2.add(&grid2);
+    let label_m1 = Label::new(Some(&t!("D_NEW_SUBSCRIPTION_MONITOR_ACTIVE")));
+    label_m1.set_halign(Align::Start);
+    grid2.attach(&label_m1, 0, 0, 1, 1);
+    let sw_monitor = Switch::new();
+    sw_monitor.set_halign(Align::Start);
+    grid2.attach(&sw_monitor, 1, 0, 1, 1);
+    let label_m2 = Label::new(Some(&t!("D_NEW_SUBSCRIPTION_MONITOR_SELECTOR")));
+    label_m2.set_halign(Align::Start);
+    grid2.attach(&label_m2, 0, 1, 1, 1);
+    let entry_monitor = Entry::new();
+    entry_monitor.set_hexpand(true);
+    grid2.attach(&entry_monitor, 1, 1, 1, 1);
+
     let ev_se = g_ev_se.clone();
     entry_url.connect_text_notify(move |entry_url| {
         let e_text = entry_url.text().as_str().to_string();
@@ -303,22 +420,41 @@ pub fn create_new_subscription_dialog(
     entry_name.set_activates_default(true);
     let ent1_c = entry_url.clone();
     let ent2_c = entry_name.clone();
+    let sel_ent_c = selector_entries.clone();
+    let sw_monitor_c = sw_monitor.clone();
+    let entry_monitor_c = entry_monitor.clone();
+    let candidates_c = candidates.clone();
     let ev_se = g_ev_se;
     dialog.connect_response(move |dialog, rt| {
         match rt {
             ResponseType::Ok => {
                 let f2txt = ent2_c.text().as_str().to_string();
-                let payload = vec![
+                let mut payload = vec![
                     AValue::ASTR(ent1_c.text().as_str().to_string()),
                     AValue::ASTR(f2txt),
                 ];
+                sel_ent_c
+                    .iter()
+                    .for_each(|e| payload.push(AValue::ASTR(e.text().as_str().to_string())));
+                payload.push(AValue::ABOOL(sw_monitor_c.is_active()));
+                payload.push(AValue::ASTR(entry_monitor_c.text().as_str().to_string()));
+                candidates_c
+                    .borrow()
+                    .iter()
+                    .filter(|(cb, _, _)| cb.is_active())
+                    .for_each(|(_, url, title)| {
+                        payload.push(AValue::ASTR(url.clone())); // 9.. : further selected feeds
+                        payload.push(AValue::ASTR(title.clone()));
+                    });
                 let _r = ev_se.send(GuiEvents::DialogData("new-feedsource".to_string(), payload));
                 ent1_c.buffer().set_text("");
                 ent2_c.buffer().set_text("");
+                sel_ent_c.iter().for_each(|e| e.buffer().set_text(""));
             }
             ResponseType::Cancel | ResponseType::DeleteEvent => {
                 ent1_c.buffer().set_text("");
                 ent2_c.buffer().set_text("");
+                sel_ent_c.iter().for_each(|e| e.buffer().set_text(""));
             }
             _ => {
                 warn!("new-subscription:response unexpected {}", rt);
@@ -332,9 +468,21 @@ pub fn create_new_subscription_dialog(
     });
     let ent2_c = entry_name.clone();
     let label3_c = label3.clone();
+    let label_preview_c = label_preview.clone();
+    let box_dup_c = box_dup.clone();
+    let box_cand_c = box_cand.clone();
+    let box_cand_list_c = box_cand_list.clone();
+    let candidates_c = candidates.clone();
     dialog.connect_show(move |dialog| {
         ent2_c.set_text("");
         label3_c.set_text("");
+        label_preview_c.set_text("");
+        box_dup_c.hide();
+        box_cand_list_c.foreach(|w| box_cand_list_c.remove(w));
+        candidates_c.borrow_mut().clear();
+        box_cand_c.hide();
+        sw_monitor.set_active(false);
+        entry_monitor.buffer().set_text("");
         dialog.set_response_sensitive(ResponseType::Ok, false);
     });
     let entry_name_c = entry_name.clone();
@@ -366,6 +514,61 @@ pub fn create_new_subscription_dialog(
         if let Some(s) = dialogdata.get(4).unwrap().str() {
             ent1_c.set_text(&s); // 4: feed-url
         }
+        if let Some(s) = dialogdata.get(5).and_then(|av| av.str()) {
+            label_preview.set_text(&s); // 5: scraper preview
+        }
+        match dialogdata.get(6).and_then(|av| av.str()) {
+            Some(s) if !s.is_empty() => {
+                label_dup.set_text(&s); // 6: duplicate warning
+                box_dup.show();
+            }
+            _ => box_dup.hide(),
+        }
+        if dialogdata.len() > DIALOG_NEW_SUBSCRIPTION_CANDIDATES_START {
+            box_cand_list.foreach(|w| box_cand_list.remove(w));
+            candidates.borrow_mut().clear();
+            dialogdata[DIALOG_NEW_SUBSCRIPTION_CANDIDATES_START..]
+                .chunks(DIALOG_NEW_SUBSCRIPTION_VALUES_PER_CANDIDATE)
+                .for_each(|chunk| {
+                    let get_s = |i: usize| chunk.get(i).and_then(|av| av.str()).unwrap_or_default();
+                    let (url, title, feed_type, preview) = (get_s(0), get_s(1), get_s(2), get_s(3));
+                    let mut cb_text = if title.is_empty() {
+                        url.clone()
+                    } else {
+                        title.clone()
+                    };
+                    if !feed_type.is_empty() {
+                        cb_text = format!("{}  ({})", cb_text, feed_type.to_uppercase());
+                    }
+                    let cb = CheckButton::with_label(&cb_text);
+                    cb.set_tooltip_text(Some(&url));
+                    box_cand_list.pack_start(&cb, false, false, 0);
+                    if !preview.is_empty() {
+                        let label_items = Label::new(Some(&preview));
+                        label_items.set_halign(Align::Start);
+                        label_items.set_margin_start(28);
+                        label_items.set_sensitive(false);
+                        box_cand_list.pack_start(&label_items, false, false, 0);
+                    }
+                    let ent1_cc = ent1_c.clone();
+                    let candidates_cc = candidates.clone();
+                    let url_c = url.clone();
+                    cb.connect_toggled(move |cb| {
+                        // the first chosen feed goes into the url entry,  the others are added along
+                        let num_active = candidates_cc
+                            .borrow()
+                            .iter()
+                            .filter(|(c, _, _)| c.is_active())
+                            .count();
+                        if cb.is_active() && num_active == 1 {
+                            ent1_cc.set_text(&url_c);
+                        }
+                    });
+                    candidates.borrow_mut().push((cb, url, title));
+                });
+            box_cand_list.show_all();
+            box_cand.show();
+        }
     });
     let mut ret = (*gtk_obj_a).write().unwrap();
     ret.set_dialog(DIALOG_NEW_SUBSCRIPTION, &dialog);
@@ -489,17 +692,52 @@ fn create_subscription_edit_dialog(
     entry2.set_activates_default(true);
     entry2.set_max_length(MAX_LENGTH_NEW_SOURCE_URL);
     grid1.attach(&entry2, 1, line, 1, 1);
-    //    line += 1;
+    line += 1;
+
+    let label3 = Label::new(Some(&t!("D_EDIT_SUBSCRIPTION_ENCLOSURE_AUTODL")));
+    grid1.attach(&label3, 0, line, 1, 1);
+    let sw_enclosure_autodl = Switch::new();
+    sw_enclosure_autodl.set_halign(Align::Start);
+    grid1.attach(&sw_enclosure_autodl, 1, line, 1, 1);
+    line += 1;
+
+    let label4 = Label::new(Some(&t!("D_EDIT_SUBSCRIPTION_ENCLOSURE_KEEP")));
+    grid1.attach(&label4, 0, line, 1, 1);
+    let spinb_enclosure_keep = SpinButton::with_range(0.0, 1000.0, 1.0);
+    spinb_enclosure_keep.set_halign(Align::Start);
+    grid1.attach(&spinb_enclosure_keep, 1, line, 1, 1);
+    line += 1;
+
+    let label5 = Label::new(Some(&t!("D_EDIT_SUBSCRIPTION_FULL_ARTICLE")));
+    grid1.attach(&label5, 0, line, 1, 1);
+    let sw_full_article = Switch::new();
+    sw_full_article.set_halign(Align::Start);
+    grid1.attach(&sw_full_article, 1, line, 1, 1);
+    line += 1;
+
+    let label6 = Label::new(Some(&t!("D_EDIT_SUBSCRIPTION_REMOTE_CONTENT")));
+    grid1.attach(&label6, 0, line, 1, 1);
+    let sw_remote_content = Switch::new();
+    sw_remote_content.set_halign(Align::Start);
+    grid1.attach(&sw_remote_content, 1, line, 1, 1);
 
     let ev_se = g_ev_se;
     let entry1c = entry1.clone();
     let entry2c = entry2.clone();
+    let sw_enclosure_autodl_c = sw_enclosure_autodl.clone();
+    let spinb_enclosure_keep_c = spinb_enclosure_keep.clone();
+    let sw_full_article_c = sw_full_article.clone();
+    let sw_remote_content_c = sw_remote_content.clone();
     dialog.connect_response(move |dialog, rt| {
         match rt {
             ResponseType::Ok => {
                 let av = vec![
                     AValue::ASTR(entry1c.text().to_string()),
                     AValue::ASTR(entry2c.text().to_string()),
+                    AValue::ABOOL(sw_enclosure_autodl_c.state()), // 2
+                    AValue::AI32(spinb_enclosure_keep_c.value() as i32), // 3
+                    AValue::ABOOL(sw_full_article_c.state()),     // 4
+                    AValue::ABOOL(sw_remote_content_c.state()),   // 5
                 ];
                 let _r = ev_se.send(GuiEvents::DialogData(
                     "subscription-edit-ok".to_string(),
@@ -530,6 +768,18 @@ fn create_subscription_edit_dialog(
             entry2c.set_text(&s); //   1: url
                                   //          url = s;
         }
+        if let Some(av) = dialogdata.get(9) {
+            sw_enclosure_autodl.set_state(av.boo()); //   9: enclosure auto download
+        }
+        if let Some(keep) = dialogdata.get(10).and_then(|av| av.int()) {
+            spinb_enclosure_keep.set_value(keep as f64); //   10: enclosure keep count
+        }
+        if let Some(av) = dialogdata.get(11) {
+            sw_full_article.set_state(av.boo()); //   11: full article from web page
+        }
+        if let Some(av) = dialogdata.get(12) {
+            sw_remote_content.set_state(av.boo()); //   12: load remote images
+        }
     });
     let mut ret = (*gtk_obj_a).write().unwrap();
     ret.set_dialog(DIALOG_SUBS_EDIT, &dialog);
@@ -576,6 +826,15 @@ fn create_folder_edit_dialog(
     entry1.set_expand(true);
     entry1.set_activates_default(true);
     grid1.attach(&entry1, 1, 0, 1, 1);
+    let label2 = Label::new(Some(&t!("D_EDIT_FOLDER_KEEP_SORTED")));
+    grid1.attach(&label2, 0, 1, 1, 1);
+    // the row number is the sort order of the folder
+    let cbt_keep_sorted = ComboBoxText::new();
+    cbt_keep_sorted.append_text(&t!("D_EDIT_FOLDER_SORT_MANUAL"));
+    cbt_keep_sorted.append_text(&t!("CM_SUB_SORT_NAME"));
+    cbt_keep_sorted.append_text(&t!("CM_SUB_SORT_UNREAD"));
+    cbt_keep_sorted.append_text(&t!("CM_SUB_SORT_LAST_UPDATE"));
+    grid1.attach(&cbt_keep_sorted, 1, 1, 1, 1);
 
     /*
        let box2v = gtk::Box::new(Orientation::Vertical, 0);
@@ -585,10 +844,16 @@ fn create_folder_edit_dialog(
 
     let ev_se = g_ev_se;
     let entry1c = entry1.clone();
+    let cbt_keep_sorted_c = cbt_keep_sorted.clone();
     dialog.connect_response(move |dialog, rt| {
         match rt {
             ResponseType::Ok => {
-                let av = vec![AValue::ASTR(entry1c.text().to_string()), AValue::None];
+                let keep_sorted = cbt_keep_sorted_c.active().unwrap_or(0) as i32;
+                let av = vec![
+                    AValue::ASTR(entry1c.text().to_string()),
+                    AValue::None,
+                    AValue::AI32(keep_sorted),
+                ];
                 let _r = ev_se.send(GuiEvents::DialogData("folder-edit".to_string(), av));
             }
             ResponseType::Cancel | ResponseType::DeleteEvent => {
@@ -609,6 +874,8 @@ fn create_folder_edit_dialog(
         if let Some(s) = dialogdata.first().unwrap().str() {
             entry1c.set_text(&s);
         }
+        let keep_sorted = dialogdata.get(1).and_then(|v| v.int()).unwrap_or(0);
+        cbt_keep_sorted.set_active(Some(keep_sorted.clamp(0, 3) as u32));
     });
     let mut ret = (*gtk_obj_a).write().unwrap();
     ret.set_dialog(DIALOG_FOLDER_EDIT, &dialog);
@@ -706,6 +973,18 @@ fn create_settings_dialog(
     let spinb_fontsize_manual = SpinButton::with_range(FONTSIZE_MIN, FONTSIZE_MAX, 1.0);
     let scale_bright = Scale::with_range(Orientation::Horizontal, 0.0, 255.0, 1.0);
     let sw_browser_cache_clear = Switch::new();
+    let sw_mark_unread_on_update = Switch::new();
+    let entry_enclosure_player = Entry::new();
+    let entry_enclosure_folder = Entry::new();
+    let spinb_websub_port = SpinButton::with_range(0.0, u16::MAX as f64, 1.0);
+    let entry_websub_callback = Entry::new();
+    let sw_remote_content = Switch::new();
+    let entry_allowed_tags = Entry::new();
+    entry_allowed_tags.set_placeholder_text(Some(&t!("D_SETTINGS_ALLOWED_TAGS_DEFAULT")));
+    let sw_link_cleaning = Switch::new();
+    let entry_link_params = Entry::new();
+    entry_link_params.set_placeholder_text(Some(&t!("D_SETTINGS_LINK_CLEAN_PARAMS_DEFAULT")));
+    let spinb_trash_retention = SpinButton::with_range(0.0, TRASH_RETENTION_DAYS_MAX, 1.0);
     {
         let grid1 = Grid::new();
         grid1.set_vexpand(true);
@@ -803,6 +1082,59 @@ fn create_settings_dialog(
         grid2.attach(&label2_5, 0, line, 1, 1);
         grid2.attach(&sw_browser_cache_clear, 1, line, 1, 1);
         sw_browser_cache_clear.set_halign(Align::Start);
+
+        line += 1;
+        let label2_6 = Label::new(Some(&t!("D_SETTINGS_MARK_UNREAD_ON_UPDATE")));
+        grid2.attach(&label2_6, 0, line, 1, 1);
+        grid2.attach(&sw_mark_unread_on_update, 1, line, 1, 1);
+        sw_mark_unread_on_update.set_halign(Align::Start);
+
+        line += 1;
+        let label2_7 = Label::new(Some(&t!("D_SETTINGS_ENCLOSURE_PLAYER")));
+        grid2.attach(&label2_7, 0, line, 1, 1);
+        grid2.attach(&entry_enclosure_player, 1, line, 1, 1);
+
+        line += 1;
+        let label2_8 = Label::new(Some(&t!("D_SETTINGS_ENCLOSURE_FOLDER")));
+        grid2.attach(&label2_8, 0, line, 1, 1);
+        grid2.attach(&entry_enclosure_folder, 1, line, 1, 1);
+
+        line += 1;
+        let label2_9 = Label::new(Some(&t!("D_SETTINGS_WEBSUB_PORT")));
+        grid2.attach(&label2_9, 0, line, 1, 1);
+        grid2.attach(&spinb_websub_port, 1, line, 1, 1);
+
+        line += 1;
+        let label2_10 = Label::new(Some(&t!("D_SETTINGS_WEBSUB_CALLBACK")));
+        grid2.attach(&label2_10, 0, line, 1, 1);
+        grid2.attach(&entry_websub_callback, 1, line, 1, 1);
+
+        line += 1;
+        let label2_11 = Label::new(Some(&t!("D_SETTINGS_REMOTE_CONTENT")));
+        grid2.attach(&label2_11, 0, line, 1, 1);
+        grid2.attach(&sw_remote_content, 1, line, 1, 1);
+        sw_remote_content.set_halign(Align::Start);
+
+        line += 1;
+        let label2_12 = Label::new(Some(&t!("D_SETTINGS_ALLOWED_TAGS")));
+        grid2.attach(&label2_12, 0, line, 1, 1);
+        grid2.attach(&entry_allowed_tags, 1, line, 1, 1);
+
+        line += 1;
+        let label2_13 = Label::new(Some(&t!("D_SETTINGS_LINK_CLEANING")));
+        grid2.attach(&label2_13, 0, line, 1, 1);
+        grid2.attach(&sw_link_cleaning, 1, line, 1, 1);
+        sw_link_cleaning.set_halign(Align::Start);
+
+        line += 1;
+        let label2_14 = Label::new(Some(&t!("D_SETTINGS_LINK_CLEAN_PARAMS")));
+        grid2.attach(&label2_14, 0, line, 1, 1);
+        grid2.attach(&entry_link_params, 1, line, 1, 1);
+
+        line += 1;
+        let label2_15 = Label::new(Some(&t!("D_SETTINGS_TRASH_RETENTION")));
+        grid2.attach(&label2_15, 0, line, 1, 1);
+        grid2.attach(&spinb_trash_retention, 1, line, 1, 1);
         if false {
             line += 1;
             let label2_5 = Label::new(Some(&t!("D_SETTINGS_SYSTRAY_ICON_ENABLE")));
@@ -862,6 +1194,16 @@ fn create_settings_dialog(
     let spinb_fontsize_manual_c = spinb_fontsize_manual.clone();
     let scale_bright_c = scale_bright.clone();
     let sw_browser_cache_clear_c = sw_browser_cache_clear.clone();
+    let sw_mark_unread_on_update_c = sw_mark_unread_on_update.clone();
+    let entry_enclosure_player_c = entry_enclosure_player.clone();
+    let entry_enclosure_folder_c = entry_enclosure_folder.clone();
+    let spinb_websub_port_c = spinb_websub_port.clone();
+    let entry_websub_callback_c = entry_websub_callback.clone();
+    let sw_remote_content_c = sw_remote_content.clone();
+    let entry_allowed_tags_c = entry_allowed_tags.clone();
+    let sw_link_cleaning_c = sw_link_cleaning.clone();
+    let spinb_trash_retention_c = spinb_trash_retention.clone();
+    let entry_link_params_c = entry_link_params.clone();
     dialog.connect_response(move |dialog, rt| {
         match rt {
             ResponseType::Ok => {
@@ -885,6 +1227,26 @@ fn create_settings_dialog(
                 av.push(AValue::AI32(spinb_fontsize_manual_c.value() as i32)); // 8 : ManualFontSizeEnable
                 av.push(AValue::AU32(scale_bright_c.value() as u32)); // 9 : Browser BG
                 av.push(AValue::ABOOL(sw_browser_cache_clear_c.state())); // 10 : browser cache cleanup
+                av.push(AValue::ABOOL(sw_mark_unread_on_update_c.state())); // 11 : mark unread on content change
+                av.push(AValue::ASTR(
+                    entry_enclosure_player_c.text().as_str().to_string(),
+                )); // 12 : enclosure player
+                av.push(AValue::ASTR(
+                    entry_enclosure_folder_c.text().as_str().to_string(),
+                )); // 13 : enclosure folder
+                av.push(AValue::AU32(spinb_websub_port_c.value() as u32)); // 14 : WebSub port
+                av.push(AValue::ASTR(
+                    entry_websub_callback_c.text().as_str().to_string(),
+                )); // 15 : WebSub callback url
+                av.push(AValue::ABOOL(sw_remote_content_c.state())); // 16 : load remote images
+                av.push(AValue::ASTR(
+                    entry_allowed_tags_c.text().as_str().to_string(),
+                )); // 17 : allowed html tags
+                av.push(AValue::ABOOL(sw_link_cleaning_c.state())); // 18 : remove tracking parameters
+                av.push(AValue::ASTR(
+                    entry_link_params_c.text().as_str().to_string(),
+                )); // 19 : additional tracking parameters
+                av.push(AValue::AU32(spinb_trash_retention_c.value() as u32)); // 20 : trash retention days
                 let _r = ev_se.send(GuiEvents::DialogData("settings".to_string(), av));
             }
             ResponseType::Cancel | ResponseType::DeleteEvent => {
@@ -925,6 +1287,33 @@ fn create_settings_dialog(
         scale_bright.set_value(browser_bg as f64);
         sw_browser_cache_clear.set_state(dialogdata.get(10).unwrap().boo()); // 10 : browser cache cleanup
                                                                              //  sw_subs_db_cleanup.set_state(dialogdata.get(11).unwrap().boo()); // 11 : DB cleanup
+        if let Some(av) = dialogdata.get(11) {
+            sw_mark_unread_on_update.set_state(av.boo()); // 11 : mark unread on content change
+        }
+        if let Some(player) = dialogdata.get(12).and_then(|av| av.str()) {
+            entry_enclosure_player.buffer().set_text(&player); // 12 : enclosure player
+        }
+        if let Some(folder) = dialogdata.get(13).and_then(|av| av.str()) {
+            entry_enclosure_folder.buffer().set_text(&folder); // 13 : enclosure folder
+        }
+        spinb_websub_port.set_value(dd_get_uint(dialogdata, 14, 0) as f64); // 14 : WebSub port
+        if let Some(callback) = dialogdata.get(15).and_then(|av| av.str()) {
+            entry_websub_callback.buffer().set_text(&callback); // 15 : WebSub callback url
+        }
+        if let Some(av) = dialogdata.get(16) {
+            sw_remote_content.set_state(av.boo()); // 16 : load remote images
+        }
+        if let Some(tags) = dialogdata.get(17).and_then(|av| av.str()) {
+            entry_allowed_tags.buffer().set_text(&tags); // 17 : allowed html tags
+        }
+        if let Some(av) = dialogdata.get(18) {
+            sw_link_cleaning.set_state(av.boo()); // 18 : remove tracking parameters
+        }
+        if let Some(params) = dialogdata.get(19).and_then(|av| av.str()) {
+            entry_link_params.buffer().set_text(&params); // 19 : additional tracking parameters
+        }
+        let trash_retention = dd_get_uint(dialogdata, 20, 30); // 20 : trash retention days
+        spinb_trash_retention.set_value(trash_retention as f64);
     });
     let textview_d = textview3.clone();
     ddd.set_dialog_distribute(DIALOG_SETTINGS_CHECK, move |dialogdata| {
@@ -1109,9 +1498,42 @@ fn create_subscription_statistic_dialog(
         l.set_halign(Align::Start);
     }
 
+    let frame_meta = gtk::Frame::new(Some(&t!("D_SUBS_STAT_FEED_INFO")));
+    let grid_meta = Grid::new();
+    grid_meta.set_column_spacing(5);
+    grid_meta.set_margin(4);
+    frame_meta.add(&grid_meta);
+    gri.attach(&frame_meta, 3, 0, 1, line + 1);
+    let mut meta_values: Vec<Label> = Vec::default();
+    for (meta_line, key) in [
+        t!("D_SUBS_STAT_FEED_TITLE"),
+        t!("D_SUBS_STAT_FEED_DESCRIPTION"),
+        t!("D_SUBS_STAT_FEED_LANGUAGE"),
+        t!("D_SUBS_STAT_FEED_GENERATOR"),
+        t!("D_SUBS_STAT_FEED_AUTHORS"),
+        t!("D_SUBS_STAT_FEED_LOGO"),
+        t!("D_SUBS_STAT_FEED_RIGHTS"),
+        t!("D_SUBS_STAT_FEED_CATEGORIES"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let label_a = Label::new(Some(key.as_str()));
+        label_a.set_halign(Align::Start);
+        label_a.set_valign(Align::Start);
+        grid_meta.attach(&label_a, 0, meta_line as i32, 1, 1);
+        let label_b = Label::new(None);
+        label_b.set_halign(Align::Start);
+        label_b.set_line_wrap(true);
+        label_b.set_max_width_chars(40);
+        label_b.set_selectable(true);
+        grid_meta.attach(&label_b, 1, meta_line as i32, 1, 1);
+        meta_values.push(label_b);
+    }
+
     line += 1;
     let scrolledwindow1 = ScrolledWindow::new(NONE_ADJ, NONE_ADJ);
-    gri.attach(&scrolledwindow1, 0, line, 3, 1);
+    gri.attach(&scrolledwindow1, 0, line, 4, 1);
 
     scrolledwindow1.set_widget_name("scrolledwindow_0");
     scrolledwindow1.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic); // scrollbar-h, scrollbar-v
@@ -1161,6 +1583,13 @@ fn create_subscription_statistic_dialog(
         if let Some(s) = dialogdata.get(8).unwrap().str() {
             label6b.set_text(&s); // num-favorites
         }
+        for (n, label_b) in meta_values.iter().enumerate() {
+            let s = dialogdata
+                .get(9 + n) // 9..16 : feed title, description, language, generator, authors, logo, rights, categories
+                .and_then(|av| av.str())
+                .unwrap_or_default();
+            label_b.set_text(&s);
+        }
     });
     let mut ret = (*gtk_obj_a).write().unwrap();
     ret.set_dialog(DIALOG_SUBSCRIPTION_STATISTIC, &dialog);
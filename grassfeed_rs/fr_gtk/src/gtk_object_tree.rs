@@ -44,6 +44,7 @@ use gtk::ResizeMode;
 use gtk::ScrolledWindow;
 use gtk::SearchEntry;
 use gtk::ShadowType;
+use gtk::ToggleButton;
 use gtk::ToggleToolButton;
 use gtk::ToolButton;
 use gtk::Toolbar;
@@ -192,7 +193,13 @@ impl GtkGuiBuilder for GtkObjectTree {
             ddd,
         );
         scrolledwindow_0.add(&sourcetree);
-        paned_1.pack1(&scrolledwindow_0, false, FRAME_SHRINK);
+        let box_tree_v = gtk::Box::new(Orientation::Vertical, 0);
+        box_tree_v.add(&create_tree_filter(
+            gui_event_sender.clone(),
+            gtk_obj_a.clone(),
+        ));
+        box_tree_v.add(&scrolledwindow_0);
+        paned_1.pack1(&box_tree_v, false, FRAME_SHRINK);
         paned_1.set_resize_mode(ResizeMode::Queue); // is it needed ?
         let esw = EvSenderWrapper(gui_event_sender.clone());
         paned_1.connect_position_notify(move |paned| {
@@ -424,20 +431,64 @@ fn connect_keyboard(g_ev_se: Sender<GuiEvents>, gtk_obj_a: GtkObjectsType) {
     let win = o_win.unwrap();
     let esw = EvSenderWrapper(g_ev_se);
     win.connect_key_press_event(move |_win, key| {
-        let mut entry_has_focus: bool = false;
-        if let Some(searchentry) = (*gtk_obj_a).read().unwrap().get_searchentry(SEARCH_ENTRY_0) {
-            entry_has_focus = searchentry.has_focus();
+        let keyval = key.keyval();
+        let keystate = key.state();
+        if keystate.intersects(gdk::ModifierType::CONTROL_MASK)
+            && (*keyval) as i32 == gdk_sys::GDK_KEY_f
+        {
+            if let Some(tree_filter) = (*gtk_obj_a).read().unwrap().get_searchentry(SEARCH_ENTRY_1)
+            {
+                tree_filter.grab_focus();
+            }
+            return Inhibit(true);
         }
+        let entry_has_focus = [SEARCH_ENTRY_0, SEARCH_ENTRY_1].iter().any(|idx| {
+            (*gtk_obj_a)
+                .read()
+                .unwrap()
+                .get_searchentry(*idx)
+                .map(|searchentry| searchentry.has_focus())
+                .unwrap_or(false)
+        });
         if entry_has_focus {
             return Inhibit(false);
         }
-        let keyval = key.keyval();
-        let keystate = key.state();
         // trace!("            keypress: {:?} {:?} ", keyval, keystate);
         if keystate.intersects(gdk::ModifierType::CONTROL_MASK) {
-            // debug!("! CONTROL_MASK Ctrl- ");
+            // Shift turns the keyval into the upper case letter
+            let o_menu_name = match (*keyval) as i32 {
+                gdk_sys::GDK_KEY_z if !keystate.intersects(gdk::ModifierType::SHIFT_MASK) => {
+                    Some("M_UNDO")
+                }
+                gdk_sys::GDK_KEY_z | gdk_sys::GDK_KEY_Z => Some("M_REDO"),
+                _ => None,
+            };
+            if let Some(menu_name) = o_menu_name {
+                esw.sendw(GuiEvents::MenuActivate(menu_name.to_string()));
+                return Inhibit(true);
+            }
         } else if keystate.intersects(gdk::ModifierType::MOD1_MASK) {
-            // debug!("! MOD1_MASK   Alt- ");
+            // Alt-Cursor moves the selected subscription inside the tree
+            let tree_has_focus = (*gtk_obj_a)
+                .read()
+                .unwrap()
+                .get_tree_view(TREEVIEW0 as usize)
+                .map(|tv| tv.has_focus())
+                .unwrap_or(false);
+            let o_command = match (*keyval) as i32 {
+                gdk_sys::GDK_KEY_Up => Some("tree-move-up"),
+                gdk_sys::GDK_KEY_Down => Some("tree-move-down"),
+                gdk_sys::GDK_KEY_Right | gdk_sys::GDK_KEY_Page_Up => {
+                    Some("tree-move-into-prev-folder")
+                }
+                gdk_sys::GDK_KEY_Page_Down => Some("tree-move-into-next-folder"),
+                gdk_sys::GDK_KEY_Left => Some("tree-move-out"),
+                _ => None,
+            };
+            if let (true, Some(command)) = (tree_has_focus, o_command) {
+                esw.sendw(GuiEvents::TreeEvent(TREEVIEW0, -1, command.to_string()));
+                return Inhibit(true);
+            }
         } else if keystate.intersects(gdk::ModifierType::MOD4_MASK) {
             // debug!("! MOD4_MASK   Win-Right- ");
         } else if keystate.intersects(gdk::ModifierType::MOD5_MASK) {
@@ -508,6 +559,15 @@ pub fn create_menubar(
                 .unwrap();
         });
 
+        let m_rules = MenuItem::with_label(&t!("M_RULES"));
+        m_rules.set_widget_name("M_RULES");
+        menu_file.add(&m_rules);
+        let se = g_ev_se.clone();
+        m_rules.connect_activate(move |_m| {
+            se.send(GuiEvents::MenuActivate(_m.widget_name().to_string()))
+                .unwrap();
+        });
+
         let m_file_quit = MenuItem::with_label(&t!("M_FILE_QUIT"));
         m_file_quit.set_widget_name("M_FILE_QUIT");
         menu_file.add(&m_file_quit);
@@ -517,6 +577,22 @@ pub fn create_menubar(
                 .unwrap();
         });
     }
+    {
+        let m_item = MenuItem::with_label(&t!("M_EDIT"));
+        m_item.set_widget_name("M_EDIT");
+        menubar.append(&m_item);
+        let menu = Menu::new();
+        m_item.set_submenu(Some(&menu));
+        for name in ["M_UNDO", "M_REDO"] {
+            let m_undo = MenuItem::with_label(&t!(name));
+            m_undo.set_widget_name(name);
+            menu.add(&m_undo);
+            let esw = EvSenderWrapper(g_ev_se.clone());
+            m_undo.connect_activate(move |_m| {
+                esw.sendw(GuiEvents::MenuActivate(_m.widget_name().to_string()));
+            });
+        }
+    }
     if false {
         let m_item = MenuItem::with_label(&t!("M_OPTIONS"));
         m_item.set_widget_name("M_OPTIONS");
@@ -696,6 +772,56 @@ pub fn create_toolbar(
     }
 }
 
+/// Search entry and unread toggle above the subscription tree.  Enter jumps to the first match.
+fn create_tree_filter(g_ev_se: Sender<GuiEvents>, gtk_obj_a: GtkObjectsType) -> gtk::Box {
+    let box_filter_h = gtk::Box::new(Orientation::Horizontal, 0);
+    let searchentry: SearchEntry = SearchEntry::new();
+    searchentry.set_tooltip_text(Some(&t!("TREE_FILTER_TEXT")));
+    searchentry.set_hexpand(true);
+    box_filter_h.pack_start(&searchentry, true, true, 0);
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    searchentry.connect_search_changed(move |se: &SearchEntry| {
+        esw.sendw(GuiEvents::SearchEntryTextChanged(
+            SEARCH_ENTRY_1,
+            se.buffer().text(),
+        ));
+    });
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let gtk_obj_ac = gtk_obj_a.clone();
+    searchentry.connect_activate(move |_se: &SearchEntry| {
+        esw.sendw(GuiEvents::TreeEvent(
+            TREEVIEW0,
+            -1,
+            "tree-filter-jump".to_string(),
+        ));
+        if let Some(tv) = (*gtk_obj_ac)
+            .read()
+            .unwrap()
+            .get_tree_view(TREEVIEW0 as usize)
+        {
+            tv.grab_focus();
+        }
+    });
+    searchentry.connect_stop_search(move |se: &SearchEntry| {
+        se.set_text("");
+    });
+    let toggle_unread = ToggleButton::with_label(&t!("TREE_FILTER_UNREAD"));
+    toggle_unread.set_tooltip_text(Some(&t!("TREE_FILTER_UNREAD_TOOLTIP")));
+    box_filter_h.pack_start(&toggle_unread, false, false, 0);
+    let esw = EvSenderWrapper(g_ev_se);
+    toggle_unread.connect_toggled(move |tb| {
+        esw.sendw(GuiEvents::ToolBarToggle(
+            "tree-filter-unread".to_string(),
+            tb.is_active(),
+        ));
+    });
+    {
+        let mut ret = (*gtk_obj_a).write().unwrap();
+        ret.set_searchentry(SEARCH_ENTRY_1, &searchentry);
+    }
+    box_filter_h
+}
+
 pub fn create_browser_toolbar(g_ev_se: Sender<GuiEvents>, containing_box: &gtk::Box) {
     let toolbar = Toolbar::new();
     toolbar.set_height_request(16);
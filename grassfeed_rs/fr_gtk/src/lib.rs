@@ -12,7 +12,10 @@ pub mod dialogs;
 pub mod gtk_object_tree;
 pub mod load_css;
 pub mod messagelist;
+pub mod rules_dialog;
+pub mod saved_search_dialog;
 pub mod statistics_list;
+pub mod tags_dialog;
 pub mod treeview2;
 pub mod util;
 
@@ -65,6 +65,7 @@ pub fn create_listview(
         u32::static_type(),      // 5 : db-id
         gtk::glib::Type::STRING, // tooltip
         bool::static_type(),     // 7 : Is-Favorite
+        gtk::glib::Type::STRING, // 8 : tag chips, markup
     ];
     let title_column: TreeViewColumn;
     let date_column: TreeViewColumn;
@@ -116,6 +117,18 @@ pub fn create_listview(
         col.set_fixed_width(col1width);
         title_column = col;
     }
+    {
+        let cellrendtext = CellRendererText::new();
+        let col = TreeViewColumn::new();
+        CellLayoutExt::pack_start(&col, &cellrendtext, false);
+        CellLayoutExt::add_attribute(&col, &cellrendtext, "markup", 8);
+        col.set_visible(true);
+        col.set_title(&t!("MSGLIST_TOP_TAGS"));
+        col.set_sizing(gtk::TreeViewColumnSizing::Autosize);
+        col.set_resizable(true);
+        col.set_expand(false);
+        content_tree_view.append_column(&col);
+    }
     {
         let col = TreeViewColumn::new(); // is-read
         let cellrendpixbuf = CellRendererPixbuf::new();
@@ -380,6 +393,50 @@ fn show_context_menu_message(
         });
     }
 
+    let mi_open_enclosure = MenuItem::with_label(&t!("CM_MSG_OPEN_ENCLOSURE"));
+    let esc = EvSenderCache(
+        g_ev_se.clone(),
+        GuiEvents::ListSelectedAction(0, "open-enclosure".to_string(), repoid_listpos.to_owned()),
+    );
+    mi_open_enclosure.connect_activate(move |_menuiten| {
+        esc.send();
+    });
+
+    let mi_download_enclosure = MenuItem::with_label(&t!("CM_MSG_DOWNLOAD_ENCLOSURE"));
+    let esc = EvSenderCache(
+        g_ev_se.clone(),
+        GuiEvents::ListSelectedAction(
+            0,
+            "download-enclosure".to_string(),
+            repoid_listpos.to_owned(),
+        ),
+    );
+    mi_download_enclosure.connect_activate(move |_menuiten| {
+        esc.send();
+    });
+
+    let mi_extract_article = MenuItem::with_label(&t!("CM_MSG_EXTRACT_ARTICLE"));
+    let esc = EvSenderCache(
+        g_ev_se.clone(),
+        GuiEvents::ListSelectedAction(0, "extract-article".to_string(), repoid_listpos.to_owned()),
+    );
+    mi_extract_article.connect_activate(move |_menuiten| {
+        esc.send();
+    });
+
+    let mi_load_remote = MenuItem::with_label(&t!("CM_MSG_LOAD_REMOTE_CONTENT"));
+    let esc = EvSenderCache(
+        g_ev_se.clone(),
+        GuiEvents::ListSelectedAction(
+            0,
+            "load-remote-content".to_string(),
+            repoid_listpos.to_owned(),
+        ),
+    );
+    mi_load_remote.connect_activate(move |_menuiten| {
+        esc.send();
+    });
+
     let mi_mark_favorite = MenuItem::with_label(&t!("CM_MSG_MARK_FAVORITE"));
     let esc = EvSenderCache(
         g_ev_se.clone(),
@@ -390,15 +447,29 @@ fn show_context_menu_message(
     });
     let mi_unmark_favorite = MenuItem::with_label(&t!("CM_MSG_UNMARK_FAVORITE"));
     let esc = EvSenderCache(
-        g_ev_se,
+        g_ev_se.clone(),
         GuiEvents::ListSelectedAction(0, "unmark-favorite".to_string(), repoid_listpos.to_owned()),
     );
     mi_unmark_favorite.connect_activate(move |_menuiten| {
         esc.send();
     });
+    let mi_tags = MenuItem::with_label(&t!("CM_MSG_TAGS"));
+    let esc = EvSenderCache(
+        g_ev_se,
+        GuiEvents::ListSelectedAction(0, "edit-tags".to_string(), repoid_listpos.to_owned()),
+    );
+    mi_tags.connect_activate(move |_menuiten| {
+        esc.send();
+    });
 
     let menu: gtk::Menu = Menu::new();
     menu.append(&mi_open_browser);
+    menu.append(&mi_open_enclosure);
+    menu.append(&mi_download_enclosure);
+    menu.append(&mi_extract_article);
+    if repoid_listpos.len() == 1 {
+        menu.append(&mi_load_remote);
+    }
     if repoid_listpos.len() == 1 {
         menu.append(&mi_copy_link);
     }
@@ -407,6 +478,7 @@ fn show_context_menu_message(
     menu.append(&mi_delete);
     menu.append(&mi_mark_favorite);
     menu.append(&mi_unmark_favorite);
+    menu.append(&mi_tags);
     menu.show_all();
     let c_ev_time = gtk::current_event_time();
     menu.popup_easy(ev_button, c_ev_time);
@@ -0,0 +1,459 @@
+use flume::Sender;
+use glib::StaticType;
+use glib::ToValue;
+use gtk::prelude::*;
+use gtk::Adjustment;
+use gtk::Align;
+use gtk::Button;
+use gtk::CellRendererText;
+use gtk::CellRendererToggle;
+use gtk::CheckButton;
+use gtk::ComboBoxText;
+use gtk::Dialog;
+use gtk::Entry;
+use gtk::Grid;
+use gtk::Label;
+use gtk::ListStore;
+use gtk::ResponseType;
+use gtk::ScrolledWindow;
+use gtk::ShadowType;
+use gtk::TreeIter;
+use gtk::TreeView;
+use gtk::TreeViewColumn;
+use gtk::Window;
+use gui_layer::abstract_ui::AValue;
+use gui_layer::abstract_ui::GuiEvents;
+use resources::id::DIALOG_RULES;
+use resources::id::DIALOG_RULES_VALUES_PER_RULE;
+use rust_i18n;
+use rust_i18n::t;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use ui_gtk::dialogdatadistributor::DialogDataDistributor;
+use ui_gtk::GtkObjectsType;
+
+const TYPESTRING_TEXT: &str = "text";
+const TYPESTRING_ACTIVE: &str = "active";
+const NONE_ADJ: Option<&Adjustment> = None;
+
+const NUM_FIELDS: i32 = 5;
+const NUM_ACTIONS: i32 = 6;
+
+// list store columns, the first ones in the order of the dialog data
+const COL_ENABLED: u32 = 0;
+const COL_FIELD: u32 = 1;
+const COL_IS_REGEX: u32 = 2;
+const COL_PATTERN: u32 = 3;
+const COL_SUBS_ID: u32 = 4;
+const COL_ACTION: u32 = 5;
+const COL_ARG: u32 = 6;
+const COL_FIELD_NAME: u32 = 7;
+const COL_SUBS_NAME: u32 = 8;
+const COL_ACTION_NAME: u32 = 9;
+
+fn field_name(n: i32) -> String {
+    t!(&format!("D_RULES_FIELD_{n}"))
+}
+
+fn action_name(n: i32) -> String {
+    t!(&format!("D_RULES_ACTION_{n}"))
+}
+
+fn active_id_i32(cbt: &ComboBoxText) -> i32 {
+    cbt.active_id()
+        .and_then(|s| s.parse::<i32>().ok())
+        .unwrap_or(0)
+}
+
+/// the edit widgets below the list, for the selected rule
+#[derive(Clone)]
+struct RuleForm {
+    enabled: CheckButton,
+    field: ComboBoxText,
+    is_regex: CheckButton,
+    pattern: Entry,
+    scope: ComboBoxText,
+    action: ComboBoxText,
+    arg: Entry,
+    /// suppresses the write back while the widgets are filled
+    loading: Rc<Cell<bool>>,
+}
+
+impl RuleForm {
+    fn load(&self, store: &ListStore, iter: &TreeIter) {
+        self.loading.set(true);
+        let get_i32 = |col: u32| store.value(iter, col as i32).get::<i32>().unwrap_or(0);
+        let get_str = |col: u32| {
+            store
+                .value(iter, col as i32)
+                .get::<String>()
+                .unwrap_or_default()
+        };
+        self.enabled.set_active(
+            store
+                .value(iter, COL_ENABLED as i32)
+                .get::<bool>()
+                .unwrap_or(false),
+        );
+        self.field
+            .set_active_id(Some(&get_i32(COL_FIELD).to_string()));
+        self.is_regex.set_active(
+            store
+                .value(iter, COL_IS_REGEX as i32)
+                .get::<bool>()
+                .unwrap_or(false),
+        );
+        self.pattern.set_text(&get_str(COL_PATTERN));
+        self.scope
+            .set_active_id(Some(&get_i32(COL_SUBS_ID).to_string()));
+        self.action
+            .set_active_id(Some(&get_i32(COL_ACTION).to_string()));
+        self.arg.set_text(&get_str(COL_ARG));
+        self.loading.set(false);
+    }
+
+    fn save(&self, store: &ListStore, iter: &TreeIter) {
+        if self.loading.get() {
+            return;
+        }
+        let field = active_id_i32(&self.field);
+        let action = active_id_i32(&self.action);
+        let scope_name = self
+            .scope
+            .active_text()
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        store.set(
+            iter,
+            &[
+                (COL_ENABLED, &self.enabled.is_active()),
+                (COL_FIELD, &field),
+                (COL_IS_REGEX, &self.is_regex.is_active()),
+                (COL_PATTERN, &self.pattern.text().to_string()),
+                (COL_SUBS_ID, &active_id_i32(&self.scope)),
+                (COL_ACTION, &action),
+                (COL_ARG, &self.arg.text().to_string()),
+                (COL_FIELD_NAME, &field_name(field)),
+                (COL_SUBS_NAME, &scope_name),
+                (COL_ACTION_NAME, &action_name(action)),
+            ],
+        );
+    }
+}
+
+fn add_text_column(view: &TreeView, title: &str, col_nr: u32, expand: bool) {
+    let cellrendtext = CellRendererText::new();
+    let col = TreeViewColumn::new();
+    CellLayoutExt::pack_start(&col, &cellrendtext, true);
+    CellLayoutExt::add_attribute(&col, &cellrendtext, TYPESTRING_TEXT, col_nr as i32);
+    col.set_title(title);
+    col.set_resizable(true);
+    col.set_expand(expand);
+    view.append_column(&col);
+}
+
+pub fn create_rules_dialog(
+    g_ev_se: Sender<GuiEvents>,
+    gtk_obj_a: GtkObjectsType,
+    ddd: &mut DialogDataDistributor,
+) {
+    let dialog = Dialog::with_buttons::<Window>(
+        Some(&t!("D_RULES_TITLE")),
+        (*gtk_obj_a).read().unwrap().get_window().as_ref(),
+        gtk::DialogFlags::MODAL,
+        &[
+            (&t!("D_BUTTON_OK"), ResponseType::Ok),
+            (&t!("D_BUTTON_CANCEL"), ResponseType::Cancel),
+        ],
+    );
+    dialog.set_width_request(700);
+    dialog.set_height_request(450);
+    dialog.set_default_response(ResponseType::Ok);
+    let grid = Grid::new();
+    dialog.content_area().add(&grid);
+    grid.set_vexpand(true);
+    grid.set_hexpand(true);
+    grid.set_column_spacing(5);
+    grid.set_row_spacing(3);
+
+    let list_store = ListStore::new(&[
+        bool::static_type(),   // 0 enabled
+        i32::static_type(),    // 1 field
+        bool::static_type(),   // 2 is regex
+        String::static_type(), // 3 pattern
+        i32::static_type(),    // 4 subscription id
+        i32::static_type(),    // 5 action
+        String::static_type(), // 6 action argument
+        String::static_type(), // 7 field name
+        String::static_type(), // 8 subscription name
+        String::static_type(), // 9 action name
+    ]);
+    let tree_view = TreeView::with_model(&list_store);
+    tree_view.set_headers_visible(true);
+    tree_view.set_enable_search(false);
+    let cellrendtoggle = CellRendererToggle::new();
+    {
+        let col = TreeViewColumn::new();
+        CellLayoutExt::pack_start(&col, &cellrendtoggle, false);
+        CellLayoutExt::add_attribute(&col, &cellrendtoggle, TYPESTRING_ACTIVE, COL_ENABLED as i32);
+        col.set_title(&t!("D_RULES_ENABLED"));
+        tree_view.append_column(&col);
+    }
+    add_text_column(&tree_view, &t!("D_RULES_FIELD"), COL_FIELD_NAME, false);
+    add_text_column(&tree_view, &t!("D_RULES_PATTERN"), COL_PATTERN, true);
+    add_text_column(
+        &tree_view,
+        &t!("D_RULES_SUBSCRIPTION"),
+        COL_SUBS_NAME,
+        false,
+    );
+    add_text_column(&tree_view, &t!("D_RULES_ACTION"), COL_ACTION_NAME, false);
+    add_text_column(&tree_view, &t!("D_RULES_ACTION_ARG"), COL_ARG, false);
+
+    let mut line = 0;
+    let scrolledwindow = ScrolledWindow::new(NONE_ADJ, NONE_ADJ);
+    scrolledwindow.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Automatic);
+    scrolledwindow.set_vexpand(true);
+    scrolledwindow.set_hexpand(true);
+    scrolledwindow.set_shadow_type(ShadowType::EtchedIn);
+    scrolledwindow.add(&tree_view);
+    grid.attach(&scrolledwindow, 0, line, 4, 1);
+    line += 1;
+
+    let button_add = Button::with_label(&t!("D_RULES_ADD"));
+    button_add.set_halign(Align::Start);
+    grid.attach(&button_add, 0, line, 1, 1);
+    let button_remove = Button::with_label(&t!("D_RULES_REMOVE"));
+    button_remove.set_halign(Align::Start);
+    grid.attach(&button_remove, 1, line, 1, 1);
+    line += 1;
+
+    let form = RuleForm {
+        enabled: CheckButton::with_label(&t!("D_RULES_ENABLED")),
+        field: ComboBoxText::new(),
+        is_regex: CheckButton::with_label(&t!("D_RULES_REGEX")),
+        pattern: Entry::new(),
+        scope: ComboBoxText::new(),
+        action: ComboBoxText::new(),
+        arg: Entry::new(),
+        loading: Rc::new(Cell::new(false)),
+    };
+    for n in 0..NUM_FIELDS {
+        form.field.append(Some(&n.to_string()), &field_name(n));
+    }
+    for n in 0..NUM_ACTIONS {
+        form.action.append(Some(&n.to_string()), &action_name(n));
+    }
+    form.pattern.set_hexpand(true);
+    form.arg
+        .set_tooltip_text(Some(&t!("D_RULES_ACTION_ARG_HINT")));
+
+    let label_field = Label::new(Some(&t!("D_RULES_FIELD")));
+    grid.attach(&label_field, 0, line, 1, 1);
+    grid.attach(&form.field, 1, line, 1, 1);
+    grid.attach(&form.is_regex, 2, line, 1, 1);
+    grid.attach(&form.enabled, 3, line, 1, 1);
+    line += 1;
+    let label_pattern = Label::new(Some(&t!("D_RULES_PATTERN")));
+    grid.attach(&label_pattern, 0, line, 1, 1);
+    grid.attach(&form.pattern, 1, line, 3, 1);
+    line += 1;
+    let label_scope = Label::new(Some(&t!("D_RULES_SUBSCRIPTION")));
+    grid.attach(&label_scope, 0, line, 1, 1);
+    grid.attach(&form.scope, 1, line, 3, 1);
+    line += 1;
+    let label_action = Label::new(Some(&t!("D_RULES_ACTION")));
+    grid.attach(&label_action, 0, line, 1, 1);
+    grid.attach(&form.action, 1, line, 1, 1);
+    grid.attach(&form.arg, 2, line, 2, 1);
+    line += 1;
+    for l in [&label_field, &label_pattern, &label_scope, &label_action] {
+        l.set_halign(Align::Start);
+    }
+    let cb_apply_existing = CheckButton::with_label(&t!("D_RULES_APPLY_EXISTING"));
+    grid.attach(&cb_apply_existing, 0, line, 4, 1);
+
+    {
+        let form_c = form.clone();
+        let store_c = list_store.clone();
+        tree_view.selection().connect_changed(move |sel| {
+            if let Some((_model, iter)) = sel.selected() {
+                form_c.load(&store_c, &iter);
+            }
+        });
+    }
+    {
+        let form_c = form.clone();
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        cellrendtoggle.connect_toggled(move |_r, path| {
+            if let Some(iter) = store_c.iter(&path) {
+                let enabled = store_c
+                    .value(&iter, COL_ENABLED as i32)
+                    .get::<bool>()
+                    .unwrap_or(false);
+                store_c.set_value(&iter, COL_ENABLED, &(!enabled).to_value());
+            }
+            if let Some((_model, iter)) = view_c.selection().selected() {
+                form_c.load(&store_c, &iter);
+            }
+        });
+    }
+    let on_change: Rc<dyn Fn()> = {
+        let form_c = form.clone();
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        Rc::new(move || {
+            if let Some((_model, iter)) = view_c.selection().selected() {
+                form_c.save(&store_c, &iter);
+            }
+        })
+    };
+    {
+        let oc = on_change.clone();
+        form.enabled.connect_toggled(move |_| oc());
+        let oc = on_change.clone();
+        form.is_regex.connect_toggled(move |_| oc());
+        let oc = on_change.clone();
+        form.field.connect_changed(move |_| oc());
+        let oc = on_change.clone();
+        form.scope.connect_changed(move |_| oc());
+        let oc = on_change.clone();
+        form.action.connect_changed(move |_| oc());
+        let oc = on_change.clone();
+        form.pattern.connect_changed(move |_| oc());
+        let oc = on_change;
+        form.arg.connect_changed(move |_| oc());
+    }
+    {
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        let form_c = form.clone();
+        button_add.connect_clicked(move |_b| {
+            let iter = store_c.append();
+            store_c.set(
+                &iter,
+                &[
+                    (COL_ENABLED, &true),
+                    (COL_FIELD, &0),
+                    (COL_IS_REGEX, &false),
+                    (COL_PATTERN, &String::default()),
+                    (COL_SUBS_ID, &0),
+                    (COL_ACTION, &0),
+                    (COL_ARG, &String::default()),
+                    (COL_FIELD_NAME, &field_name(0)),
+                    (COL_SUBS_NAME, &t!("D_RULES_SUBSCRIPTION_ALL")),
+                    (COL_ACTION_NAME, &action_name(0)),
+                ],
+            );
+            view_c.selection().select_iter(&iter);
+            form_c.pattern.grab_focus();
+        });
+    }
+    {
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        button_remove.connect_clicked(move |_b| {
+            if let Some((_model, iter)) = view_c.selection().selected() {
+                store_c.remove(&iter);
+            }
+        });
+    }
+    {
+        let store_c = list_store.clone();
+        let cb_apply_c = cb_apply_existing.clone();
+        let ev_se = g_ev_se;
+        dialog.connect_response(move |dialog, rt| {
+            if rt == ResponseType::Ok {
+                let mut payload: Vec<AValue> = vec![AValue::ABOOL(cb_apply_c.is_active())]; // 0: apply to existing
+                if let Some(iter) = store_c.iter_first() {
+                    loop {
+                        let get_i32 =
+                            |col: u32| store_c.value(&iter, col as i32).get::<i32>().unwrap_or(0);
+                        let get_str = |col: u32| {
+                            store_c
+                                .value(&iter, col as i32)
+                                .get::<String>()
+                                .unwrap_or_default()
+                        };
+                        let get_bool = |col: u32| {
+                            store_c
+                                .value(&iter, col as i32)
+                                .get::<bool>()
+                                .unwrap_or(false)
+                        };
+                        payload.push(AValue::ABOOL(get_bool(COL_ENABLED)));
+                        payload.push(AValue::AI32(get_i32(COL_FIELD)));
+                        payload.push(AValue::ABOOL(get_bool(COL_IS_REGEX)));
+                        payload.push(AValue::ASTR(get_str(COL_PATTERN)));
+                        payload.push(AValue::AI32(get_i32(COL_SUBS_ID)));
+                        payload.push(AValue::AI32(get_i32(COL_ACTION)));
+                        payload.push(AValue::ASTR(get_str(COL_ARG)));
+                        if !store_c.iter_next(&iter) {
+                            break;
+                        }
+                    }
+                }
+                let _r = ev_se.send(GuiEvents::DialogData("rules".to_string(), payload));
+            }
+            dialog.hide();
+        });
+    }
+    dialog.connect_delete_event(|dia, _| {
+        dia.hide();
+        gtk::Inhibit(true)
+    });
+
+    ddd.set_dialog_distribute(DIALOG_RULES, move |dialogdata| {
+        cb_apply_existing.set_active(false);
+        list_store.clear();
+        form.scope.remove_all();
+        let all_name = t!("D_RULES_SUBSCRIPTION_ALL");
+        form.scope.append(Some("0"), &all_name);
+        let mut names: HashMap<i32, String> = HashMap::new();
+        names.insert(0, all_name);
+        let num_subs = dialogdata.first().and_then(|av| av.uint()).unwrap_or(0) as usize; // 0: number of subscriptions
+        for n in 0..num_subs {
+            let o_id = dialogdata.get(1 + n * 2).and_then(|av| av.int());
+            let o_name = dialogdata.get(2 + n * 2).and_then(|av| av.str());
+            if let (Some(id), Some(name)) = (o_id, o_name) {
+                form.scope.append(Some(&id.to_string()), &name);
+                names.insert(id, name);
+            }
+        }
+        let rules_start = 1 + num_subs * 2;
+        for rule in
+            dialogdata[rules_start.min(dialogdata.len())..].chunks(DIALOG_RULES_VALUES_PER_RULE)
+        {
+            if rule.len() < DIALOG_RULES_VALUES_PER_RULE {
+                break;
+            }
+            let field = rule[1].int().unwrap_or(0);
+            let subs_id = rule[4].int().unwrap_or(0);
+            let action = rule[5].int().unwrap_or(0);
+            let subs_name = names.get(&subs_id).cloned().unwrap_or(subs_id.to_string());
+            let iter = list_store.append();
+            list_store.set(
+                &iter,
+                &[
+                    (COL_ENABLED, &rule[0].boo()),
+                    (COL_FIELD, &field),
+                    (COL_IS_REGEX, &rule[2].boo()),
+                    (COL_PATTERN, &rule[3].str().unwrap_or_default()),
+                    (COL_SUBS_ID, &subs_id),
+                    (COL_ACTION, &action),
+                    (COL_ARG, &rule[6].str().unwrap_or_default()),
+                    (COL_FIELD_NAME, &field_name(field)),
+                    (COL_SUBS_NAME, &subs_name),
+                    (COL_ACTION_NAME, &action_name(action)),
+                ],
+            );
+        }
+        if let Some(iter) = list_store.iter_first() {
+            tree_view.selection().select_iter(&iter);
+        }
+    });
+    let mut ret = (*gtk_obj_a).write().unwrap();
+    ret.set_dialog(DIALOG_RULES, &dialog);
+}
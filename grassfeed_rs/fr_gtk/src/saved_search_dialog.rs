@@ -0,0 +1,175 @@
+use flume::Sender;
+use gtk::prelude::*;
+use gtk::Align;
+use gtk::CheckButton;
+use gtk::ComboBoxText;
+use gtk::Dialog;
+use gtk::Entry;
+use gtk::Grid;
+use gtk::Label;
+use gtk::ResponseType;
+use gtk::SpinButton;
+use gtk::Window;
+use gui_layer::abstract_ui::AValue;
+use gui_layer::abstract_ui::GuiEvents;
+use resources::id::DIALOG_SAVED_SEARCH;
+use resources::id::DIALOG_SAVED_SEARCH_VALUES;
+use rust_i18n;
+use rust_i18n::t;
+use std::cell::Cell;
+use std::rc::Rc;
+use ui_gtk::dialogdatadistributor::DialogDataDistributor;
+use ui_gtk::GtkObjectsType;
+
+const AGE_DAYS_MAX: f64 = 3650.0;
+
+fn active_id_i32(cbt: &ComboBoxText) -> i32 {
+    cbt.active_id()
+        .and_then(|s| s.parse::<i32>().ok())
+        .unwrap_or(0)
+}
+
+/// fills the combo from  number, (id, name) for each,  returns the index after them
+fn fill_id_combo(cbt: &ComboBoxText, first: &str, dialogdata: &[AValue], start: usize) -> usize {
+    cbt.remove_all();
+    cbt.append(Some("0"), first);
+    let num = dialogdata.get(start).and_then(|av| av.uint()).unwrap_or(0) as usize;
+    for n in 0..num {
+        let o_id = dialogdata.get(start + 1 + n * 2).and_then(|av| av.int());
+        let o_name = dialogdata.get(start + 2 + n * 2).and_then(|av| av.str());
+        if let (Some(id), Some(name)) = (o_id, o_name) {
+            cbt.append(Some(&id.to_string()), &name);
+        }
+    }
+    start + 1 + num * 2
+}
+
+pub fn create_saved_search_dialog(
+    g_ev_se: Sender<GuiEvents>,
+    gtk_obj_a: GtkObjectsType,
+    ddd: &mut DialogDataDistributor,
+) {
+    let dialog = Dialog::with_buttons::<Window>(
+        Some(&t!("D_SEARCH_TITLE")),
+        (*gtk_obj_a).read().unwrap().get_window().as_ref(),
+        gtk::DialogFlags::MODAL,
+        &[
+            (&t!("D_BUTTON_OK"), ResponseType::Ok),
+            (&t!("D_BUTTON_CANCEL"), ResponseType::Cancel),
+        ],
+    );
+    dialog.set_width_request(450);
+    dialog.set_default_response(ResponseType::Ok);
+    let grid = Grid::new();
+    dialog.content_area().add(&grid);
+    grid.set_hexpand(true);
+    grid.set_column_spacing(5);
+    grid.set_row_spacing(3);
+
+    let entry_name = Entry::new();
+    let entry_text = Entry::new();
+    let combo_tag = ComboBoxText::new();
+    let cb_unread = CheckButton::with_label(&t!("D_SEARCH_UNREAD"));
+    let cb_favorite = CheckButton::with_label(&t!("D_SEARCH_FAVORITE"));
+    let spin_age = SpinButton::with_range(0.0, AGE_DAYS_MAX, 1.0);
+    let entry_from = Entry::new();
+    let entry_to = Entry::new();
+    let combo_scope = ComboBoxText::new();
+    entry_name.set_hexpand(true);
+    entry_name.set_activates_default(true);
+    entry_text.set_activates_default(true);
+    for e in [&entry_from, &entry_to] {
+        e.set_placeholder_text(Some(&t!("D_SEARCH_DATE_HINT")));
+    }
+
+    let mut line = 0;
+    let rows: [(&str, &gtk::Widget); 7] = [
+        ("D_SEARCH_NAME", entry_name.upcast_ref()),
+        ("D_SEARCH_TEXT", entry_text.upcast_ref()),
+        ("D_SEARCH_TAG", combo_tag.upcast_ref()),
+        ("D_SEARCH_AGE_DAYS", spin_age.upcast_ref()),
+        ("D_SEARCH_DATE_FROM", entry_from.upcast_ref()),
+        ("D_SEARCH_DATE_TO", entry_to.upcast_ref()),
+        ("D_SEARCH_SUBSCRIPTION", combo_scope.upcast_ref()),
+    ];
+    for (label_key, widget) in rows {
+        let label = Label::new(Some(&t!(label_key)));
+        label.set_halign(Align::Start);
+        grid.attach(&label, 0, line, 1, 1);
+        grid.attach(widget, 1, line, 1, 1);
+        line += 1;
+    }
+    grid.attach(&cb_unread, 0, line, 2, 1);
+    line += 1;
+    grid.attach(&cb_favorite, 0, line, 2, 1);
+
+    let search_id: Rc<Cell<i32>> = Rc::new(Cell::new(0));
+    {
+        let search_id_c = search_id.clone();
+        let entry_name = entry_name.clone();
+        let entry_text = entry_text.clone();
+        let combo_tag = combo_tag.clone();
+        let cb_unread = cb_unread.clone();
+        let cb_favorite = cb_favorite.clone();
+        let spin_age = spin_age.clone();
+        let entry_from = entry_from.clone();
+        let entry_to = entry_to.clone();
+        let combo_scope = combo_scope.clone();
+        let ev_se = g_ev_se;
+        dialog.connect_response(move |dialog, rt| {
+            if rt == ResponseType::Ok {
+                let payload = vec![
+                    AValue::AI32(search_id_c.get()),             // 0
+                    AValue::ASTR(entry_name.text().to_string()), // 1
+                    AValue::ASTR(entry_text.text().to_string()), // 2
+                    AValue::AI32(active_id_i32(&combo_tag)),     // 3
+                    AValue::ABOOL(cb_unread.is_active()),        // 4
+                    AValue::ABOOL(cb_favorite.is_active()),      // 5
+                    AValue::AI32(spin_age.value_as_int()),       // 6
+                    AValue::ASTR(entry_from.text().to_string()), // 7
+                    AValue::ASTR(entry_to.text().to_string()),   // 8
+                    AValue::AI32(active_id_i32(&combo_scope)),   // 9
+                ];
+                let _r = ev_se.send(GuiEvents::DialogData("saved-search".to_string(), payload));
+            }
+            dialog.hide();
+        });
+    }
+    dialog.connect_delete_event(|dia, _| {
+        dia.hide();
+        gtk::Inhibit(true)
+    });
+
+    ddd.set_dialog_distribute(DIALOG_SAVED_SEARCH, move |dialogdata| {
+        if dialogdata.len() < DIALOG_SAVED_SEARCH_VALUES {
+            return;
+        }
+        let get_str = |n: usize| dialogdata[n].str().unwrap_or_default();
+        let get_i32 = |n: usize| dialogdata[n].int().unwrap_or(0);
+        search_id.set(get_i32(0));
+        entry_name.set_text(&get_str(1));
+        entry_text.set_text(&get_str(2));
+        cb_unread.set_active(dialogdata[4].boo());
+        cb_favorite.set_active(dialogdata[5].boo());
+        spin_age.set_value(get_i32(6) as f64);
+        entry_from.set_text(&get_str(7));
+        entry_to.set_text(&get_str(8));
+        let subs_start = fill_id_combo(
+            &combo_tag,
+            &t!("D_SEARCH_TAG_ANY"),
+            dialogdata,
+            DIALOG_SAVED_SEARCH_VALUES,
+        );
+        fill_id_combo(
+            &combo_scope,
+            &t!("D_RULES_SUBSCRIPTION_ALL"),
+            dialogdata,
+            subs_start,
+        );
+        combo_tag.set_active_id(Some(&get_i32(3).to_string()));
+        combo_scope.set_active_id(Some(&get_i32(9).to_string()));
+        entry_name.grab_focus();
+    });
+    let mut ret = (*gtk_obj_a).write().unwrap();
+    ret.set_dialog(DIALOG_SAVED_SEARCH, &dialog);
+}
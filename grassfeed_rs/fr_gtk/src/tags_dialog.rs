@@ -0,0 +1,334 @@
+use flume::Sender;
+use glib::StaticType;
+use glib::ToValue;
+use gtk::prelude::*;
+use gtk::Adjustment;
+use gtk::Align;
+use gtk::Button;
+use gtk::CellRendererText;
+use gtk::CellRendererToggle;
+use gtk::ColorButton;
+use gtk::Dialog;
+use gtk::Entry;
+use gtk::Grid;
+use gtk::ListStore;
+use gtk::ResponseType;
+use gtk::ScrolledWindow;
+use gtk::ShadowType;
+use gtk::TreeView;
+use gtk::TreeViewColumn;
+use gtk::Window;
+use gui_layer::abstract_ui::AValue;
+use gui_layer::abstract_ui::GuiEvents;
+use resources::id::DIALOG_MESSAGE_TAGS;
+use resources::id::DIALOG_MESSAGE_TAGS_VALUES_PER_TAG;
+use rust_i18n;
+use rust_i18n::t;
+use std::cell::RefCell;
+use std::rc::Rc;
+use ui_gtk::dialogdatadistributor::DialogDataDistributor;
+use ui_gtk::GtkObjectsType;
+
+const NONE_ADJ: Option<&Adjustment> = None;
+const NEW_TAG_COLOR: &str = "#3465a4";
+
+// same values as  contentlist::TAG_STATE_*
+const STATE_UNASSIGNED: i32 = 0;
+const STATE_ASSIGNED: i32 = 1;
+const STATE_SOME: i32 = 2;
+const STATE_DELETE: i32 = -1;
+
+// list store columns
+const COL_TAG_ID: u32 = 0;
+const COL_NAME: u32 = 1;
+const COL_COLOR: u32 = 2;
+const COL_ASSIGNED: u32 = 3;
+const COL_INCONSISTENT: u32 = 4;
+
+fn rgba_to_hex(c: &gtk::gdk::RGBA) -> String {
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        (c.red() * 255.0).round() as u8,
+        (c.green() * 255.0).round() as u8,
+        (c.blue() * 255.0).round() as u8
+    )
+}
+
+pub fn create_tags_dialog(
+    g_ev_se: Sender<GuiEvents>,
+    gtk_obj_a: GtkObjectsType,
+    ddd: &mut DialogDataDistributor,
+) {
+    let dialog = Dialog::with_buttons::<Window>(
+        Some(&t!("D_TAGS_TITLE")),
+        (*gtk_obj_a).read().unwrap().get_window().as_ref(),
+        gtk::DialogFlags::MODAL,
+        &[
+            (&t!("D_BUTTON_OK"), ResponseType::Ok),
+            (&t!("D_BUTTON_CANCEL"), ResponseType::Cancel),
+        ],
+    );
+    dialog.set_width_request(360);
+    dialog.set_height_request(350);
+    dialog.set_default_response(ResponseType::Ok);
+    let grid = Grid::new();
+    dialog.content_area().add(&grid);
+    grid.set_vexpand(true);
+    grid.set_hexpand(true);
+    grid.set_column_spacing(5);
+    grid.set_row_spacing(3);
+
+    let list_store = ListStore::new(&[
+        i32::static_type(),    // 0 tag id,  0: new
+        String::static_type(), // 1 name
+        String::static_type(), // 2 color
+        bool::static_type(),   // 3 assigned
+        bool::static_type(),   // 4 assigned to some of the messages
+    ]);
+    let tree_view = TreeView::with_model(&list_store);
+    tree_view.set_headers_visible(false);
+    tree_view.set_enable_search(false);
+    let cellrendtoggle = CellRendererToggle::new();
+    {
+        let col = TreeViewColumn::new();
+        CellLayoutExt::pack_start(&col, &cellrendtoggle, false);
+        CellLayoutExt::add_attribute(&col, &cellrendtoggle, "active", COL_ASSIGNED as i32);
+        CellLayoutExt::add_attribute(
+            &col,
+            &cellrendtoggle,
+            "inconsistent",
+            COL_INCONSISTENT as i32,
+        );
+        tree_view.append_column(&col);
+    }
+    let cellrendtext = CellRendererText::new();
+    cellrendtext.set_editable(true);
+    cellrendtext.set_foreground(Some("#ffffff"));
+    {
+        let col = TreeViewColumn::new();
+        CellLayoutExt::pack_start(&col, &cellrendtext, true);
+        CellLayoutExt::add_attribute(&col, &cellrendtext, "text", COL_NAME as i32);
+        CellLayoutExt::add_attribute(&col, &cellrendtext, "background", COL_COLOR as i32);
+        col.set_expand(true);
+        tree_view.append_column(&col);
+    }
+    let scrolledwindow = ScrolledWindow::new(NONE_ADJ, NONE_ADJ);
+    scrolledwindow.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+    scrolledwindow.set_vexpand(true);
+    scrolledwindow.set_hexpand(true);
+    scrolledwindow.set_shadow_type(ShadowType::EtchedIn);
+    scrolledwindow.add(&tree_view);
+    grid.attach(&scrolledwindow, 0, 0, 3, 1);
+
+    let color_button = ColorButton::new();
+    color_button.set_tooltip_text(Some(&t!("D_TAGS_COLOR")));
+    color_button.set_halign(Align::Start);
+    grid.attach(&color_button, 0, 1, 1, 1);
+    let button_delete = Button::with_label(&t!("D_TAGS_DELETE"));
+    button_delete.set_halign(Align::Start);
+    grid.attach(&button_delete, 1, 1, 1, 1);
+    let entry_new = Entry::new();
+    entry_new.set_placeholder_text(Some(&t!("D_TAGS_NEW")));
+    entry_new.set_hexpand(true);
+    grid.attach(&entry_new, 0, 2, 2, 1);
+    let button_add = Button::with_label(&t!("D_TAGS_ADD"));
+    grid.attach(&button_add, 2, 2, 1, 1);
+
+    let deleted_ids: Rc<RefCell<Vec<i32>>> = Rc::new(RefCell::new(Vec::default()));
+    {
+        let store_c = list_store.clone();
+        cellrendtoggle.connect_toggled(move |_r, path| {
+            if let Some(iter) = store_c.iter(&path) {
+                let assigned = store_c
+                    .value(&iter, COL_ASSIGNED as i32)
+                    .get::<bool>()
+                    .unwrap_or(false);
+                let some = store_c
+                    .value(&iter, COL_INCONSISTENT as i32)
+                    .get::<bool>()
+                    .unwrap_or(false);
+                store_c.set_value(&iter, COL_ASSIGNED, &(some || !assigned).to_value());
+                store_c.set_value(&iter, COL_INCONSISTENT, &false.to_value());
+            }
+        });
+    }
+    {
+        let store_c = list_store.clone();
+        cellrendtext.connect_edited(move |_r, path, new_text| {
+            if new_text.trim().is_empty() {
+                return;
+            }
+            if let Some(iter) = store_c.iter(&path) {
+                store_c.set_value(&iter, COL_NAME, &new_text.trim().to_value());
+            }
+        });
+    }
+    {
+        let store_c = list_store.clone();
+        let button_c = color_button.clone();
+        tree_view.selection().connect_changed(move |sel| {
+            if let Some((_model, iter)) = sel.selected() {
+                let color = store_c
+                    .value(&iter, COL_COLOR as i32)
+                    .get::<String>()
+                    .unwrap_or_default();
+                if let Ok(rgba) = gtk::gdk::RGBA::parse(&color) {
+                    button_c.set_rgba(&rgba);
+                }
+            }
+        });
+    }
+    {
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        color_button.connect_color_set(move |cb| {
+            if let Some((_model, iter)) = view_c.selection().selected() {
+                store_c.set_value(&iter, COL_COLOR, &rgba_to_hex(&cb.rgba()).to_value());
+            }
+        });
+    }
+    {
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        let deleted_c = deleted_ids.clone();
+        button_delete.connect_clicked(move |_b| {
+            if let Some((_model, iter)) = view_c.selection().selected() {
+                let tag_id = store_c
+                    .value(&iter, COL_TAG_ID as i32)
+                    .get::<i32>()
+                    .unwrap_or(0);
+                if tag_id > 0 {
+                    deleted_c.borrow_mut().push(tag_id);
+                }
+                store_c.remove(&iter);
+            }
+        });
+    }
+    let add_new: Rc<dyn Fn()> = {
+        let store_c = list_store.clone();
+        let view_c = tree_view.clone();
+        let entry_c = entry_new.clone();
+        Rc::new(move || {
+            let name = entry_c.text().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let iter = store_c.append();
+            store_c.set(
+                &iter,
+                &[
+                    (COL_TAG_ID, &0),
+                    (COL_NAME, &name),
+                    (COL_COLOR, &NEW_TAG_COLOR.to_string()),
+                    (COL_ASSIGNED, &true),
+                    (COL_INCONSISTENT, &false),
+                ],
+            );
+            view_c.selection().select_iter(&iter);
+            entry_c.set_text("");
+        })
+    };
+    {
+        let an = add_new.clone();
+        button_add.connect_clicked(move |_b| an());
+        let an = add_new;
+        entry_new.connect_activate(move |_e| an());
+    }
+    {
+        let store_c = list_store.clone();
+        let deleted_c = deleted_ids.clone();
+        let entry_c = entry_new.clone();
+        let ev_se = g_ev_se;
+        dialog.connect_response(move |dialog, rt| {
+            if rt == ResponseType::Ok {
+                if !entry_c.text().trim().is_empty() {
+                    let iter = store_c.append(); // typed, but not added yet
+                    store_c.set(
+                        &iter,
+                        &[
+                            (COL_TAG_ID, &0),
+                            (COL_NAME, &entry_c.text().trim().to_string()),
+                            (COL_COLOR, &NEW_TAG_COLOR.to_string()),
+                            (COL_ASSIGNED, &true),
+                            (COL_INCONSISTENT, &false),
+                        ],
+                    );
+                }
+                let mut payload: Vec<AValue> = Vec::default();
+                for tag_id in deleted_c.borrow().iter() {
+                    payload.push(AValue::AI32(*tag_id));
+                    payload.push(AValue::None);
+                    payload.push(AValue::None);
+                    payload.push(AValue::AI32(STATE_DELETE));
+                }
+                if let Some(iter) = store_c.iter_first() {
+                    loop {
+                        let get_str = |col: u32| {
+                            store_c
+                                .value(&iter, col as i32)
+                                .get::<String>()
+                                .unwrap_or_default()
+                        };
+                        let get_bool = |col: u32| {
+                            store_c
+                                .value(&iter, col as i32)
+                                .get::<bool>()
+                                .unwrap_or(false)
+                        };
+                        let state = if get_bool(COL_INCONSISTENT) {
+                            STATE_SOME
+                        } else if get_bool(COL_ASSIGNED) {
+                            STATE_ASSIGNED
+                        } else {
+                            STATE_UNASSIGNED
+                        };
+                        payload.push(AValue::AI32(
+                            store_c
+                                .value(&iter, COL_TAG_ID as i32)
+                                .get::<i32>()
+                                .unwrap_or(0),
+                        ));
+                        payload.push(AValue::ASTR(get_str(COL_NAME)));
+                        payload.push(AValue::ASTR(get_str(COL_COLOR)));
+                        payload.push(AValue::AI32(state));
+                        if !store_c.iter_next(&iter) {
+                            break;
+                        }
+                    }
+                }
+                let _r = ev_se.send(GuiEvents::DialogData("message-tags".to_string(), payload));
+            }
+            dialog.hide();
+        });
+    }
+    dialog.connect_delete_event(|dia, _| {
+        dia.hide();
+        gtk::Inhibit(true)
+    });
+
+    ddd.set_dialog_distribute(DIALOG_MESSAGE_TAGS, move |dialogdata| {
+        list_store.clear();
+        deleted_ids.borrow_mut().clear();
+        entry_new.set_text("");
+        for tag in dialogdata.chunks(DIALOG_MESSAGE_TAGS_VALUES_PER_TAG) {
+            if tag.len() < DIALOG_MESSAGE_TAGS_VALUES_PER_TAG {
+                break;
+            }
+            let state = tag[3].int().unwrap_or(STATE_UNASSIGNED);
+            let iter = list_store.append();
+            list_store.set(
+                &iter,
+                &[
+                    (COL_TAG_ID, &tag[0].int().unwrap_or(0)),
+                    (COL_NAME, &tag[1].str().unwrap_or_default()),
+                    (COL_COLOR, &tag[2].str().unwrap_or_default()),
+                    (COL_ASSIGNED, &(state == STATE_ASSIGNED)),
+                    (COL_INCONSISTENT, &(state == STATE_SOME)),
+                ],
+            );
+        }
+        entry_new.grab_focus();
+    });
+    let mut ret = (*gtk_obj_a).write().unwrap();
+    ret.set_dialog(DIALOG_MESSAGE_TAGS, &dialog);
+}
@@ -15,11 +15,13 @@ use gtk::CellRendererSpinner;
 use gtk::CellRendererText;
 use gtk::Menu;
 use gtk::MenuItem;
+use gtk::MessageDialog;
 use gtk::TreeStore;
 use gtk::TreeView;
 use gtk::TreeViewColumn;
 use gtk::TreeViewGridLines;
 use gui_layer::abstract_ui::GuiEvents;
+use gui_layer::gui_values::FontAttributes;
 use resources::id::*;
 use rust_i18n;
 use rust_i18n::t;
@@ -162,6 +164,7 @@ pub fn create_treeview(
         let (posx, posy) = ev_but.position();
         let treeview: gtk::TreeView = p_tv.clone().dynamic_cast::<gtk::TreeView>().unwrap();
         let mut is_folder: bool = false;
+        let mut is_paused: bool = false;
         if let Some((Some(t_path), _o_tvc, _x, _y)) = treeview.path_at_pos(posx as i32, posy as i32)
         {
             if let Some(t_model) = treeview.model() {
@@ -175,10 +178,22 @@ pub fn create_treeview(
                     .value(&t_iter, TREE0_COL_ISFOLDER)
                     .get::<bool>()
                     .unwrap();
+                if let Ok(act_bits) = t_model
+                    .value(&t_iter, TREE0_COL_FONT_ATTRIBUTES)
+                    .get::<u32>()
+                {
+                    is_paused = FontAttributes::from_activation_bits(act_bits).3;
+                }
             }
         }
         if ev_but.button() == MOUSE_BUTTON_RIGHT {
-            show_context_menu_source(ev_but.button(), source_repo_id, ev_se_3.clone(), is_folder);
+            show_context_menu_source(
+                ev_but.button(),
+                source_repo_id,
+                ev_se_3.clone(),
+                is_folder,
+                is_paused,
+            );
         }
         gtk::Inhibit(false)
     });
@@ -317,6 +332,7 @@ fn show_context_menu_source(
     subscription_id: i32,
     g_ev_se: Sender<GuiEvents>,
     is_folder: bool,
+    is_paused: bool,
 ) {
     let menu: gtk::Menu = Menu::new();
     let mi_addfeed = MenuItem::with_label(&t!("CM_SUB_ADD_FEED"));
@@ -366,6 +382,38 @@ fn show_context_menu_source(
             "feedsource-edit-dialog".to_string(),
         ));
     });
+    let mi_pause = match is_paused {
+        true => MenuItem::with_label(&t!("CM_SUB_RESUME")),
+        false => MenuItem::with_label(&t!("CM_SUB_PAUSE")),
+    };
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    mi_pause.connect_activate(move |_menuiten| {
+        esw.sendw(GuiEvents::TreeEvent(
+            TV_ID,
+            subscription_id,
+            "feedsource-pause-toggle".to_string(),
+        ));
+    });
+    let menu_sort = Menu::new();
+    let sort_items: [(&str, &str); 3] = [
+        ("CM_SUB_SORT_NAME", "folder-sort-name"),
+        ("CM_SUB_SORT_UNREAD", "folder-sort-unread"),
+        ("CM_SUB_SORT_LAST_UPDATE", "folder-sort-last-update"),
+    ];
+    for (label_key, command) in sort_items {
+        let mi_sort_by = MenuItem::with_label(&t!(label_key));
+        let esw = EvSenderWrapper(g_ev_se.clone());
+        mi_sort_by.connect_activate(move |_menuiten| {
+            esw.sendw(GuiEvents::TreeEvent(
+                TV_ID,
+                subscription_id,
+                command.to_string(),
+            ));
+        });
+        menu_sort.append(&mi_sort_by);
+    }
+    let mi_sort = MenuItem::with_label(&t!("CM_SUB_SORT"));
+    mi_sort.set_submenu(Some(&menu_sort));
     let esw = EvSenderWrapper(g_ev_se.clone());
     let mi_del = MenuItem::with_label(&t!("CM_SUB_DELETE"));
     mi_del.connect_activate(move |_menuiten| {
@@ -376,6 +424,64 @@ fn show_context_menu_source(
         ));
     });
 
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let mi_add_search = MenuItem::with_label(&t!("CM_SUB_ADD_SEARCH"));
+    mi_add_search.connect_activate(move |_menuiten| {
+        esw.sendw(GuiEvents::TreeEvent(
+            TV_ID,
+            subscription_id,
+            "saved-search-new-dialog".to_string(),
+        ));
+    });
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let mi_edit_search = MenuItem::with_label(&t!("CM_SUB_EDIT_SEARCH"));
+    mi_edit_search.connect_activate(move |_menuiten| {
+        esw.sendw(GuiEvents::TreeEvent(
+            TV_ID,
+            subscription_id,
+            "saved-search-edit-dialog".to_string(),
+        ));
+    });
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let mi_del_search = MenuItem::with_label(&t!("CM_SUB_DELETE_SEARCH"));
+    mi_del_search.connect_activate(move |_menuiten| {
+        esw.sendw(GuiEvents::TreeEvent(
+            TV_ID,
+            subscription_id,
+            "saved-search-delete".to_string(),
+        ));
+    });
+
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let mi_trash_restore = MenuItem::with_label(&t!("CM_TRASH_RESTORE"));
+    mi_trash_restore.connect_activate(move |_menuiten| {
+        esw.sendw(GuiEvents::TreeEvent(
+            TV_ID,
+            subscription_id,
+            "trash-restore".to_string(),
+        ));
+    });
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let mi_trash_delete = MenuItem::with_label(&t!("CM_TRASH_DELETE"));
+    mi_trash_delete.connect_activate(move |_menuiten| {
+        confirm_tree_event(
+            &t!("CM_TRASH_DELETE_CONFIRM"),
+            EvSenderWrapper(esw.0.clone()),
+            subscription_id,
+            "trash-delete",
+        );
+    });
+    let esw = EvSenderWrapper(g_ev_se.clone());
+    let mi_trash_empty = MenuItem::with_label(&t!("CM_TRASH_EMPTY"));
+    mi_trash_empty.connect_activate(move |_menuiten| {
+        confirm_tree_event(
+            &t!("CM_TRASH_EMPTY_CONFIRM"),
+            EvSenderWrapper(esw.0.clone()),
+            subscription_id,
+            "trash-empty",
+        );
+    });
+
     let esw = EvSenderWrapper(g_ev_se);
     let mi_stats = MenuItem::with_label(&t!("CM_SUBS_STATISTICS"));
     mi_stats.connect_activate(move |_menuiten| {
@@ -390,14 +496,53 @@ fn show_context_menu_source(
         menu.append(&mi_mark_all);
         menu.append(&mi_update);
         menu.append(&mi_edit);
-        if !is_folder {
+        menu.append(&mi_pause);
+        if is_folder {
+            menu.append(&mi_sort);
+        } else {
             menu.append(&mi_stats);
         }
         menu.append(&mi_del);
     }
-    menu.append(&mi_addfeed);
-    menu.append(&mi_afo);
+    if subscription_id < TREE0_VIRTUAL_ID_SEARCH_BASE {
+        menu.append(&mi_edit_search);
+        menu.append(&mi_del_search);
+    } else if subscription_id < TREE0_VIRTUAL_ID_TRASH_BASE {
+        menu.append(&mi_trash_restore);
+        menu.append(&mi_trash_delete);
+    }
+    if subscription_id == TREE0_VIRTUAL_ID_TRASH {
+        menu.append(&mi_trash_empty);
+    }
+    if subscription_id > TREE0_VIRTUAL_ID_TAGS {
+        menu.append(&mi_addfeed);
+        menu.append(&mi_afo);
+    }
+    menu.append(&mi_add_search);
     menu.show_all();
     let c_ev_time = gtk::current_event_time();
     menu.popup_easy(ev_button, c_ev_time);
 }
+
+/// permanent deletions are sent only after the user agreed
+fn confirm_tree_event(question: &str, esw: EvSenderWrapper, subscription_id: i32, command: &str) {
+    let dialog = MessageDialog::new(
+        None::<&gtk::Window>,
+        gtk::DialogFlags::MODAL,
+        gtk::MessageType::Question,
+        gtk::ButtonsType::OkCancel,
+        question,
+    );
+    let command = command.to_string();
+    dialog.connect_response(move |dia, rt| {
+        if rt == gtk::ResponseType::Ok {
+            esw.sendw(GuiEvents::TreeEvent(
+                TV_ID,
+                subscription_id,
+                command.clone(),
+            ));
+        }
+        dia.close();
+    });
+    dialog.show_all();
+}
@@ -115,6 +115,7 @@ impl FontAttributes {
     }
 
     /// returns  font_size, is_read, is_folder, transparent
+    ///  transparent: text is shown dimmed, used for paused subscriptions
     pub fn from_activation_bits(bits: u32) -> (u32, bool, bool, bool) {
         (
             (bits & Self::BITMASK_FONTSIZE),
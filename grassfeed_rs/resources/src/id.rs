@@ -18,14 +18,31 @@ pub const LISTVIEW0: u8 = 1;
 pub const LISTVIEW1: u8 = 2;
 
 pub const TREE0_COL_REPO_ID: i32 = 5;
+pub const TREE0_COL_FONT_ATTRIBUTES: i32 = 6;
 pub const TREE0_COL_STATUS: i32 = 7;
 pub const TREE0_COL_STATUS_EXPANDED: u32 = 1;
 pub const TREE0_COL_TOOLTIP: i32 = 8;
 pub const TREE0_COL_ISFOLDER: i32 = 4;
 
+/// tree entries that are no subscriptions,  see  virtualnode
+pub const TREE0_VIRTUAL_ID_TAGS: i32 = -10;
+pub const TREE0_VIRTUAL_ID_SEARCHES: i32 = -11;
+pub const TREE0_VIRTUAL_ID_TRASH: i32 = -12;
+/// tags are below,  down to the trash base
+pub const TREE0_VIRTUAL_ID_TAG_BASE: i32 = -1000;
+/// deleted subscriptions are below,  down to the search base
+pub const TREE0_VIRTUAL_ID_TRASH_BASE: i32 = -1_000_000;
+/// saved searches are below
+pub const TREE0_VIRTUAL_ID_SEARCH_BASE: i32 = -2_000_000;
+
 pub const DIALOG_ICONS: u8 = 0;
 pub const DIALOG_NEW_FOLDER: u8 = 1;
 pub const DIALOG_NEW_SUBSCRIPTION: u8 = 2;
+
+/// index of the first feed candidate in the new-subscription dialog data
+pub const DIALOG_NEW_SUBSCRIPTION_CANDIDATES_START: usize = 7;
+/// feed-url, title, feed-type, preview
+pub const DIALOG_NEW_SUBSCRIPTION_VALUES_PER_CANDIDATE: usize = 4;
 pub const DIALOG_FS_DELETE: u8 = 3;
 pub const DIALOG_SUBS_EDIT: u8 = 4;
 pub const DIALOG_FOLDER_EDIT: u8 = 5;
@@ -36,6 +53,18 @@ pub const DIALOG_ABOUT: u8 = 9;
 pub const DIALOG_TREE0COL1: u8 = 10;
 pub const DIALOG_SETTINGS_CHECK: u8 = 11;
 pub const DIALOG_SUBSCRIPTION_STATISTIC: u8 = 12;
+pub const DIALOG_RULES: u8 = 13;
+
+/// enabled, field, is-regex, pattern, subscription-id, action, action-argument
+pub const DIALOG_RULES_VALUES_PER_RULE: usize = 7;
+
+pub const DIALOG_MESSAGE_TAGS: u8 = 14;
+/// tag-id, name, color, assigned-state
+pub const DIALOG_MESSAGE_TAGS_VALUES_PER_TAG: usize = 4;
+
+pub const DIALOG_SAVED_SEARCH: u8 = 15;
+/// search-id, name, text, tag-id, only-unread, only-favorite, age-days, date-from, date-to, subscription-id
+pub const DIALOG_SAVED_SEARCH_VALUES: usize = 10;
 
 pub const DIALOG_TEXTVIEW_ERR: u8 = 0;
 pub const DIALOG_TEXTVIEW_CLEAN: u8 = 1;
@@ -66,6 +95,8 @@ pub const SCROLLEDWINDOW_1: u8 = 1;
 pub const SCROLLEDWINDOW_2: u8 = 2;
 
 pub const SEARCH_ENTRY_0: u8 = 0;
+/// filter of the subscription tree
+pub const SEARCH_ENTRY_1: u8 = 1;
 
 pub const TOOLBUTTON_RELOAD_ALL: u8 = 0;
 
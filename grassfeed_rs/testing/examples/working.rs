@@ -62,6 +62,7 @@ fn startup_minihttpserver(port: usize, current_folder: &String) -> MiniHttpServe
         tcp_address: format!("127.0.0.1:{}", port).to_string(),
         binary_max_size: 1000000,
         download_throttling_kbps: 20,
+        post_requests: None,
     };
     debug!("CONF={:?}", conf);
     let mut msc = MiniHttpServerController::new(Arc::new(conf));
@@ -25,6 +25,7 @@ fn http_serve_short() {
         tcp_address: addr,
         binary_max_size: 100,
         download_throttling_kbps: 10,
+        post_requests: None,
     };
     let mut msc = minisrv::MiniHttpServerController::new(Arc::new(conf));
     msc.start();
@@ -18,6 +18,7 @@ use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
@@ -144,17 +145,28 @@ impl MiniHttpServer {
 
     pub fn handle_client(&self, mut stream: TcpStream) -> std::io::Result<()> {
         let mut request_line = String::new(); // Get only the first line of the request, since this is a static HTTP 1.0 server.
-        {
-            let mut bf = BufReader::new(&stream);
-            bf.read_line(&mut request_line)?;
-        }
+        let mut bf = BufReader::new(&stream);
+        bf.read_line(&mut request_line)?;
         let r = parse_request(&mut request_line);
         if r.is_err() {
             error!("Bad request: {}", &request_line);
             return Err(Error::from(ErrorKind::InvalidData));
         }
         let request = r.unwrap();
-        let mut response: Response = self.build_response(&request);
+        let mut response: Response = match &self.config.post_requests {
+            Some(posted) if request.method == "POST" => {
+                let body = read_post_body(&mut bf)?;
+                posted.lock().unwrap().push((request.path.clone(), body));
+                let mut r = Response::new();
+                r.status = StatusCode::ACCEPTED;
+                let text = "202 Accepted";
+                r.headers.content_length = text.len() as u64;
+                r.body = Some(text.as_bytes().to_vec());
+                r
+            }
+            _ => self.build_response(&request),
+        };
+        drop(bf);
         log_request(&request, &response.status);
         let resp_string = format_response(&response);
         let bytes_formatted = response_string_to_bytes(resp_string, response.body);
@@ -434,6 +446,25 @@ pub fn check_request_dir(htdocs_dir: &str, path: &str) -> (bool, bool, String) {
     (false, false, path.to_string())
 }
 
+/// the headers are skipped, the body is read by content-length
+fn read_post_body<R: BufRead>(reader: &mut R) -> std::io::Result<String> {
+    let mut content_length: usize = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse::<usize>().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
 #[derive(Default, Debug)]
 pub struct ServerConfig {
     pub htdocs_dir: String,
@@ -443,6 +474,8 @@ pub struct ServerConfig {
     pub binary_max_size: u64,
     ///  -1 switches  throttling off
     pub download_throttling_kbps: i64,
+    /// if set, POST requests are answered with 202 and collected here:  path, body
+    pub post_requests: Option<Arc<Mutex<Vec<(String, String)>>>>,
 }
 
 #[derive(Debug)]
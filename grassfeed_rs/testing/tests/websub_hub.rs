@@ -0,0 +1,133 @@
+use fr_core::db::errors_repo::ErrorRepo;
+use fr_core::db::subscription_entry::SubscriptionEntry;
+use fr_core::db::subscription_repo::ISubscriptionRepo;
+use fr_core::db::subscription_repo::SubscriptionRepo;
+use fr_core::downloader::websub::WebSubInner;
+use fr_core::downloader::websub::WebSubListener;
+use fr_core::downloader::websub::WebSubStart;
+use fr_core::util::timestamp_now;
+use fr_core::util::StepResult;
+use fr_core::web::httpfetcher::HttpFetcher;
+use fr_core::web::WebFetcherType;
+use std::collections::HashMap;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::Mutex;
+use testing::minihttpserver::minisrv::MiniHttpServerController;
+use testing::minihttpserver::minisrv::ServerConfig;
+
+const HUB_PORT: usize = 8391;
+const TOPIC: &str = "https://example.org/feed.xml";
+
+type Posted = Arc<Mutex<Vec<(String, String)>>>;
+
+/// the mini server plays the hub:  it accepts the subscription requests and keeps them
+fn start_hub(posted: Posted) -> MiniHttpServerController {
+    let conf = ServerConfig {
+        htdocs_dir: String::from("tests/ms_htdocs/"),
+        index_file: String::from("index.html"),
+        tcp_address: format!("127.0.0.1:{HUB_PORT}"),
+        binary_max_size: 1000,
+        download_throttling_kbps: -1,
+        post_requests: Some(posted),
+    };
+    let mut msc = MiniHttpServerController::new(Arc::new(conf));
+    msc.start();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    msc
+}
+
+// #[ignore]
+#[test]
+fn websub_subscribe_verify_push() {
+    setup();
+    let posted: Posted = Arc::new(Mutex::new(Vec::default()));
+    let mut hub = start_hub(posted.clone());
+    let hub_url = format!("{}/hub", hub.get_address());
+    let mut subs_repo = SubscriptionRepo::new_inmem();
+    subs_repo.startup_int();
+    let _r = subs_repo.store_entry(&SubscriptionEntry::default()); // id 10
+    subs_repo.update_websub_hub(10, hub_url.clone(), TOPIC.to_string());
+    let pushed: Arc<Mutex<Vec<(isize, String)>>> = Arc::new(Mutex::new(Vec::default()));
+    let pushed_c = pushed.clone();
+    let mut listener = WebSubListener::start(
+        0,
+        SubscriptionRepo::by_existing_connection(subs_repo.get_connection()),
+        Box::new(move |id, content| pushed_c.lock().unwrap().push((id, content))),
+    )
+    .unwrap();
+    let fetcher: WebFetcherType = Arc::new(Box::new(HttpFetcher {}));
+    let erro_repo = ErrorRepo::new_in_mem();
+    let inner = WebSubInner {
+        subs_id: 10,
+        hub: hub_url,
+        topic: TOPIC.to_string(),
+        secret: String::default(),
+        callback_base: String::default(),
+        port: listener.port(),
+        web_fetcher: fetcher.clone(),
+        subscriptionrepo: SubscriptionRepo::by_existing_connection(subs_repo.get_connection()),
+        erro_repo: ErrorRepo::by_connection(erro_repo.get_connection()),
+    };
+    let _inner = StepResult::start(Box::new(WebSubStart::new(inner)));
+    assert_eq!(erro_repo.get_by_subscription(10), Vec::default());
+
+    // subscribe: the hub got the form
+    let (path, body) = posted.lock().unwrap().first().cloned().unwrap();
+    assert_eq!(path.as_str(), "/hub");
+    let form = url::form_urlencoded::parse(body.as_bytes())
+        .into_owned()
+        .collect::<HashMap<String, String>>();
+    assert_eq!(form.get("hub.mode").unwrap().as_str(), "subscribe");
+    assert_eq!(form.get("hub.topic").unwrap().as_str(), TOPIC);
+    let callback = form.get("hub.callback").unwrap().clone();
+    let secret = subs_repo.get_by_index(10).unwrap().websub_secret;
+    assert!(callback.ends_with(&format!("/websub/10/{secret}")));
+
+    // verify: the hub asks the callback for the challenge
+    let verify_url = format!(
+        "{callback}?hub.mode=subscribe&hub.topic={}&hub.challenge=ch42&hub.lease_seconds=500",
+        url::form_urlencoded::byte_serialize(TOPIC.as_bytes()).collect::<String>()
+    );
+    let r = fetcher.request_url(&verify_url);
+    assert_eq!(r.http_status, 200);
+    assert_eq!(r.content.as_str(), "ch42");
+    let lease = subs_repo.get_by_index(10).unwrap().websub_lease;
+    assert!(lease > timestamp_now() + 400 && lease <= timestamp_now() + 500);
+
+    // push: the hub posts the changed feed
+    let callback_path = callback.split_once(&listener.port().to_string()).unwrap().1;
+    let content = "<feed xmlns=\"http://www.w3.org/2005/Atom\"/>";
+    let mut stream = TcpStream::connect(("127.0.0.1", listener.port())).unwrap();
+    stream
+        .write_all(
+            format!(
+                "POST {callback_path} HTTP/1.1\r\nContent-Type: application/atom+xml\r\nContent-Length: {}\r\n\r\n{content}",
+                content.len()
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let mut answer = String::default();
+    stream.read_to_string(&mut answer).unwrap();
+    assert!(answer.starts_with("HTTP/1.1 202"));
+    listener.stop();
+    hub.stop();
+    assert_eq!(*pushed.lock().unwrap(), vec![(10, content.to_string())]);
+}
+
+// ------------------------------------
+
+#[allow(unused_imports)]
+#[macro_use]
+extern crate log;
+use std::sync::Once;
+
+static TEST_SETUP: Once = Once::new();
+fn setup() {
+    TEST_SETUP.call_once(|| {
+        let _r = testing::logger_config_local::setup_logger();
+    });
+}